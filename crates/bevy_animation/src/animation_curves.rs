@@ -306,6 +306,14 @@ pub struct AnimatableCurveEvaluator<A: Animatable> {
     property: Box<dyn AnimatableProperty<Property = A>>,
 }
 
+impl<A: Animatable> AnimatableCurveEvaluator<A> {
+    /// Removes the most recently sampled value from the evaluation stack and
+    /// returns it, without writing it to any entity.
+    pub(crate) fn pop_value(&mut self) -> Option<A> {
+        self.evaluator.stack.pop().map(|element| element.value)
+    }
+}
+
 impl<P, C> AnimatableCurve<P, C>
 where
     P: AnimatableProperty,
@@ -621,22 +629,22 @@ impl AnimationCurveEvaluator for WeightsCurveEvaluator {
 }
 
 #[derive(Reflect)]
-struct BasicAnimationCurveEvaluator<A>
+pub(crate) struct BasicAnimationCurveEvaluator<A>
 where
     A: Animatable,
 {
-    stack: Vec<BasicAnimationCurveEvaluatorStackElement<A>>,
+    pub(crate) stack: Vec<BasicAnimationCurveEvaluatorStackElement<A>>,
     blend_register: Option<(A, f32)>,
 }
 
 #[derive(Reflect)]
-struct BasicAnimationCurveEvaluatorStackElement<A>
+pub(crate) struct BasicAnimationCurveEvaluatorStackElement<A>
 where
     A: Animatable,
 {
-    value: A,
-    weight: f32,
-    graph_node: AnimationNodeIndex,
+    pub(crate) value: A,
+    pub(crate) weight: f32,
+    pub(crate) graph_node: AnimationNodeIndex,
 }
 
 impl<A> Default for BasicAnimationCurveEvaluator<A>
@@ -655,7 +663,7 @@ impl<A> BasicAnimationCurveEvaluator<A>
 where
     A: Animatable,
 {
-    fn combine(
+    pub(crate) fn combine(
         &mut self,
         graph_node: AnimationNodeIndex,
         additive: bool,
@@ -727,7 +735,7 @@ where
         }
     }
 
-    fn push_blend_register(
+    pub(crate) fn push_blend_register(
         &mut self,
         weight: f32,
         graph_node: AnimationNodeIndex,
@@ -803,7 +811,7 @@ pub trait AnimationCurve: Debug + Send + Sync + 'static {
 
 /// The [`EvaluatorId`] is used to look up the [`AnimationCurveEvaluator`] for an [`AnimatableProperty`].
 /// For a given animated property, this ID should always be the same to allow things like animation blending to occur.
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum EvaluatorId<'a> {
     /// Corresponds to a specific field on a specific component type.
     /// The `TypeId` should correspond to the component type, and the `usize`
@@ -0,0 +1,735 @@
+//! Blend spaces, which blend clips placed in a one- or two-dimensional
+//! parameter space.
+
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use bevy_asset::Assets;
+use bevy_ecs::system::{Query, Res};
+use bevy_math::{FloatOrd, Vec2};
+use bevy_platform::hash::FixedHasher;
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::{
+        AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType,
+        ThreadedAnimationGraphs,
+    },
+    state_machine::{active_nodes, AnimationParameter},
+    AnimationClip, AnimationPlayer, RepeatAnimation,
+};
+
+/// A set of animations placed at points in a one- or two-dimensional parameter
+/// space, blended according to where a sample point lies in that space.
+///
+/// This is the payload of an [`AnimationNodeType::BlendSpace`] node. Each
+/// [`BlendSpacePoint`] refers to a child of that node. The coordinates of the
+/// sample point are read from the float parameters of the [`AnimationPlayer`]
+/// named by [`x_parameter`](Self::x_parameter) and
+/// [`y_parameter`](Self::y_parameter), which are typically set every frame
+/// with [`AnimationPlayer::set_float`].
+///
+/// One-dimensional blend spaces interpolate linearly between the two points
+/// closest to the sample on either side. Two-dimensional blend spaces are
+/// triangulated, and the points of the triangle that contains the sample are
+/// weighted by its barycentric coordinates. Samples outside of the points are
+/// clamped to the nearest edge.
+///
+/// Clips placed directly at points are played automatically, repeating
+/// forever. When [`sync`](Self::sync) is set, their speeds are adjusted so that
+/// they all complete their cycles at the same time, keeping them in phase even
+/// when they have different durations, like the footsteps of a walk and a run.
+#[derive(Clone, Debug, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, Default)]
+pub struct AnimationBlendSpace {
+    /// The name of the float parameter that provides the x coordinate of the sample.
+    pub x_parameter: String,
+    /// The name of the float parameter that provides the y coordinate of the
+    /// sample, or `None` for a one-dimensional blend space.
+    pub y_parameter: Option<String>,
+    /// The points of this blend space.
+    pub points: Vec<BlendSpacePoint>,
+    /// Whether the clips at the points play in phase with each other.
+    pub sync: bool,
+}
+
+/// An animation placed in an [`AnimationBlendSpace`].
+#[derive(Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug)]
+pub struct BlendSpacePoint {
+    /// The node that is played at this point.
+    ///
+    /// This must be a child of the blend space node.
+    pub node: AnimationNodeIndex,
+    /// The position of this point in the parameter space.
+    ///
+    /// The y coordinate is ignored in one-dimensional blend spaces.
+    pub position: Vec2,
+}
+
+/// The triangulation of the points of a two-dimensional
+/// [`AnimationBlendSpace`].
+///
+/// The triangulation remembers a hash of the positions it was computed from,
+/// so that it can be recomputed when points are added, removed or moved.
+#[derive(Clone, Debug, Default, Reflect)]
+#[reflect(Clone, Debug, Default)]
+pub struct BlendSpaceTriangulation {
+    /// The triangles, as triples of indices into [`AnimationBlendSpace::points`].
+    pub triangles: Vec<[usize; 3]>,
+    positions_hash: u64,
+}
+
+impl BlendSpaceTriangulation {
+    /// Triangulates the points of the given blend space.
+    pub fn new(blend_space: &AnimationBlendSpace) -> Self {
+        let positions: Vec<Vec2> = blend_space
+            .points
+            .iter()
+            .map(|point| point.position)
+            .collect();
+        Self {
+            triangles: triangulate(&positions),
+            positions_hash: blend_space.positions_hash(),
+        }
+    }
+
+    /// Returns true if this triangulation was computed from the current
+    /// positions of the points of the given blend space.
+    pub fn is_current(&self, blend_space: &AnimationBlendSpace) -> bool {
+        self.positions_hash == blend_space.positions_hash()
+    }
+}
+
+impl AnimationBlendSpace {
+    /// Creates a one-dimensional blend space sampled by the given parameter.
+    ///
+    /// The clips of the blend space are synchronized.
+    pub fn new_1d(x_parameter: impl Into<String>) -> Self {
+        Self {
+            x_parameter: x_parameter.into(),
+            y_parameter: None,
+            points: Vec::new(),
+            sync: true,
+        }
+    }
+
+    /// Creates a two-dimensional blend space sampled by the given parameters.
+    ///
+    /// The clips of the blend space are synchronized.
+    pub fn new_2d(x_parameter: impl Into<String>, y_parameter: impl Into<String>) -> Self {
+        Self {
+            x_parameter: x_parameter.into(),
+            y_parameter: Some(y_parameter.into()),
+            points: Vec::new(),
+            sync: true,
+        }
+    }
+
+    /// Places the given node at a point of this blend space.
+    pub fn add_point(&mut self, node: AnimationNodeIndex, position: Vec2) -> &mut Self {
+        self.points.push(BlendSpacePoint { node, position });
+        self
+    }
+
+    /// Returns true if this blend space has two dimensions.
+    pub fn is_2d(&self) -> bool {
+        self.y_parameter.is_some()
+    }
+
+    /// Returns a hash of the positions of the points, in order.
+    fn positions_hash(&self) -> u64 {
+        let mut hasher = FixedHasher.build_hasher();
+        self.points.len().hash(&mut hasher);
+        for point in &self.points {
+            FloatOrd(point.position.x).hash(&mut hasher);
+            FloatOrd(point.position.y).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Computes the weight of each point for the given sample.
+    ///
+    /// `triangles` is the triangulation of the points, as computed by
+    /// [`triangulate`], and is only used for two-dimensional blend spaces. If
+    /// it refers to points that don't exist, the points are triangulated again.
+    /// The returned weights are in the same order as the points and sum to 1,
+    /// unless there are no points.
+    pub fn weights(&self, sample: Vec2, triangles: &[[usize; 3]]) -> Vec<f32> {
+        let mut weights = vec![0.0; self.points.len()];
+        if self.points.len() <= 1 {
+            weights.fill(1.0);
+            return weights;
+        }
+
+        if !self.is_2d() {
+            self.weights_1d(sample.x, &mut weights);
+            return weights;
+        }
+
+        let positions: Vec<Vec2> = self.points.iter().map(|point| point.position).collect();
+
+        let retriangulated;
+        let triangles = if triangles
+            .iter()
+            .flatten()
+            .any(|&index| index >= positions.len())
+        {
+            retriangulated = triangulate(&positions);
+            &retriangulated
+        } else {
+            triangles
+        };
+
+        // Inside the triangulation, weight the corners of the containing triangle.
+        for &[a, b, c] in triangles {
+            if let Some([u, v, w]) = barycentric(sample, positions[a], positions[b], positions[c]) {
+                weights[a] = u;
+                weights[b] = v;
+                weights[c] = w;
+                return weights;
+            }
+        }
+
+        // Outside of it, or if the points are collinear, interpolate along
+        // the closest edge.
+        let edges: Vec<(usize, usize)> = if triangles.is_empty() {
+            (0..positions.len())
+                .flat_map(|a| (a + 1..positions.len()).map(move |b| (a, b)))
+                .collect()
+        } else {
+            triangles
+                .iter()
+                .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+                .collect()
+        };
+        let (a, b, t) = edges
+            .into_iter()
+            .map(|(a, b)| {
+                let t = project_onto_segment(sample, positions[a], positions[b]);
+                (a, b, t)
+            })
+            .min_by_key(|&(a, b, t)| {
+                FloatOrd(sample.distance_squared(positions[a].lerp(positions[b], t)))
+            })
+            .unwrap();
+        weights[a] = 1.0 - t;
+        weights[b] = t;
+        weights
+    }
+
+    fn weights_1d(&self, x: f32, weights: &mut [f32]) {
+        let mut below: Option<(usize, f32)> = None;
+        let mut above: Option<(usize, f32)> = None;
+        for (index, point) in self.points.iter().enumerate() {
+            let position = point.position.x;
+            if position <= x && below.is_none_or(|(_, best)| position > best) {
+                below = Some((index, position));
+            }
+            if position >= x && above.is_none_or(|(_, best)| position < best) {
+                above = Some((index, position));
+            }
+        }
+
+        match (below, above) {
+            (Some((below, below_x)), Some((above, above_x))) if above_x > below_x => {
+                let t = (x - below_x) / (above_x - below_x);
+                weights[below] = 1.0 - t;
+                weights[above] = t;
+            }
+            (Some((index, _)), _) | (None, Some((index, _))) => weights[index] = 1.0,
+            (None, None) => {}
+        }
+    }
+}
+
+/// Computes the Delaunay triangulation of a set of points.
+///
+/// The returned triangles are triples of indices into `points`. If fewer than
+/// three of the points are distinct, or all of them are collinear, no triangles
+/// are returned.
+pub fn triangulate(points: &[Vec2]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Start with a triangle that contains all of the points, whose corners are
+    // stored after the real points.
+    let min = points.iter().copied().reduce(Vec2::min).unwrap();
+    let max = points.iter().copied().reduce(Vec2::max).unwrap();
+    let center = (min + max) * 0.5;
+    let extent = (max - min).max_element().max(1.0) * 20.0;
+    let mut vertices = points.to_vec();
+    vertices.extend([
+        center + Vec2::new(-extent, -extent),
+        center + Vec2::new(extent, -extent),
+        center + Vec2::new(0.0, extent),
+    ]);
+    let super_triangle = [points.len(), points.len() + 1, points.len() + 2];
+
+    // Bowyer-Watson: insert the points one at a time, replacing the triangles
+    // whose circumcircles contain them.
+    let mut triangles = vec![super_triangle];
+    for (index, &point) in points.iter().enumerate() {
+        let (bad, good): (Vec<_>, Vec<_>) = triangles
+            .into_iter()
+            .partition(|&[a, b, c]| in_circumcircle(point, vertices[a], vertices[b], vertices[c]));
+        triangles = good;
+
+        // The boundary of the hole consists of the edges that belong to only
+        // one of the removed triangles.
+        let edges: Vec<(usize, usize)> = bad
+            .iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .collect();
+        for &(a, b) in &edges {
+            let shared = edges
+                .iter()
+                .filter(|&&(c, d)| (c, d) == (a, b) || (c, d) == (b, a))
+                .count()
+                > 1;
+            if !shared {
+                triangles.push([a, b, index]);
+            }
+        }
+    }
+
+    triangles.retain(|triangle| {
+        triangle.iter().all(|&vertex| vertex < points.len())
+            && triangle_area(
+                points[triangle[0]],
+                points[triangle[1]],
+                points[triangle[2]],
+            )
+            .abs()
+                > f32::EPSILON
+    });
+    triangles
+}
+
+/// Returns twice the signed area of a triangle, which is positive if its
+/// corners are in counterclockwise order.
+fn triangle_area(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/// Returns true if `point` lies strictly inside the circumcircle of a triangle.
+fn in_circumcircle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let (a, b, c) = if triangle_area(a, b, c) < 0.0 {
+        (a, c, b)
+    } else {
+        (a, b, c)
+    };
+    let (a, b, c) = (a - point, b - point, c - point);
+    let determinant = a.length_squared() * b.perp_dot(c) - b.length_squared() * a.perp_dot(c)
+        + c.length_squared() * a.perp_dot(b);
+    determinant > 0.0
+}
+
+/// Returns the barycentric coordinates of `point` in a triangle, if it lies
+/// inside of it.
+fn barycentric(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> Option<[f32; 3]> {
+    const EPSILON: f32 = 1e-5;
+
+    let area = triangle_area(a, b, c);
+    if area.abs() <= f32::EPSILON {
+        return None;
+    }
+    let u = triangle_area(point, b, c) / area;
+    let v = triangle_area(a, point, c) / area;
+    let w = 1.0 - u - v;
+    (u >= -EPSILON && v >= -EPSILON && w >= -EPSILON).then(|| {
+        let [u, v, w] = [u.max(0.0), v.max(0.0), w.max(0.0)];
+        let sum = u + v + w;
+        [u / sum, v / sum, w / sum]
+    })
+}
+
+/// Returns the interpolation factor of the point closest to `point` on the
+/// segment from `a` to `b`.
+fn project_onto_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let segment = b - a;
+    let length_squared = segment.length_squared();
+    if length_squared <= f32::EPSILON {
+        return 0.0;
+    }
+    ((point - a).dot(segment) / length_squared).clamp(0.0, 1.0)
+}
+
+/// A system that computes the weights of the points of every active
+/// [`AnimationBlendSpace`], and plays and synchronizes their clips.
+pub fn update_blend_spaces(
+    animation_clips: Res<Assets<AnimationClip>>,
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    threaded_animation_graphs: Res<ThreadedAnimationGraphs>,
+    mut players: Query<(&mut AnimationPlayer, &AnimationGraphHandle)>,
+) {
+    players
+        .par_iter_mut()
+        .for_each(|(mut player, graph_handle)| {
+            let Some(animation_graph) = animation_graphs.get(graph_handle) else {
+                return;
+            };
+            let threaded_animation_graph = threaded_animation_graphs.0.get(&graph_handle.id());
+
+            for node_index in active_nodes(animation_graph, &player) {
+                let Some(AnimationNodeType::BlendSpace(blend_space)) =
+                    animation_graph.get(node_index).map(|node| &node.node_type)
+                else {
+                    continue;
+                };
+
+                // The triangulation is only rebuilt along with the threaded
+                // graph, so the points can have changed since.
+                let retriangulated;
+                let triangles = match threaded_animation_graph
+                    .and_then(|threaded| threaded.blend_space_triangles.get(&node_index))
+                {
+                    Some(triangulation) if triangulation.is_current(blend_space) => {
+                        triangulation.triangles.as_slice()
+                    }
+                    _ if blend_space.is_2d() => {
+                        retriangulated = BlendSpaceTriangulation::new(blend_space);
+                        retriangulated.triangles.as_slice()
+                    }
+                    _ => &[],
+                };
+                update_blend_space(
+                    &mut player,
+                    animation_graph,
+                    &animation_clips,
+                    blend_space,
+                    triangles,
+                );
+            }
+        });
+}
+
+/// Updates the weights and clips of a single blend space.
+fn update_blend_space(
+    player: &mut AnimationPlayer,
+    graph: &AnimationGraph,
+    clips: &Assets<AnimationClip>,
+    blend_space: &AnimationBlendSpace,
+    triangles: &[[usize; 3]],
+) {
+    let parameter = |name: &str| match player.parameter(name) {
+        Some(AnimationParameter::Float(value)) => value,
+        _ => 0.0,
+    };
+    let sample = Vec2::new(
+        parameter(&blend_space.x_parameter),
+        blend_space.y_parameter.as_deref().map_or(0.0, parameter),
+    );
+    let weights = blend_space.weights(sample, triangles);
+
+    // The clips at the points, with their weights and durations.
+    let mut point_clips = Vec::new();
+    for (point, &weight) in blend_space.points.iter().zip(&weights) {
+        // Graphs built at runtime aren't validated, so points can refer to
+        // nodes that don't exist.
+        let Some(node) = graph.get(point.node) else {
+            continue;
+        };
+        player.node_weights.insert(point.node, weight);
+
+        let AnimationNodeType::Clip(ref handle) = node.node_type else {
+            continue;
+        };
+        if !player.is_playing_animation(point.node) {
+            player.play(point.node).set_repeat(RepeatAnimation::Forever);
+        }
+        if let Some(clip) = clips.get(handle)
+            && clip.duration > 0.0
+        {
+            point_clips.push((point.node, weight, clip.duration));
+        }
+    }
+
+    if !blend_space.sync {
+        return;
+    }
+
+    // Play every clip at a speed that makes it complete a cycle in the
+    // weighted average of the durations, so that the clips stay in phase.
+    let total_weight: f32 = point_clips.iter().map(|&(_, weight, _)| weight).sum();
+    if total_weight <= 0.0 {
+        return;
+    }
+    let cycle_duration = point_clips
+        .iter()
+        .map(|&(_, weight, duration)| weight * duration)
+        .sum::<f32>()
+        / total_weight;
+
+    // The clip with the highest weight leads, and clips that drifted away from
+    // it, like ones that just started, are moved to its phase.
+    let &(leader, _, leader_duration) = point_clips
+        .iter()
+        .max_by_key(|&&(_, weight, _)| FloatOrd(weight))
+        .unwrap();
+    let phase = player
+        .animation(leader)
+        .map_or(0.0, |animation| animation.seek_time() / leader_duration);
+
+    for (node, _, duration) in point_clips {
+        let Some(animation) = player.animation_mut(node) else {
+            continue;
+        };
+        animation.set_speed(duration / cycle_duration);
+
+        let offset = (animation.seek_time() / duration - phase).rem_euclid(1.0);
+        if offset.min(1.0 - offset) > 1e-3 {
+            animation.set_seek_time(phase * duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+
+    use super::*;
+    use crate::graph::ThreadedAnimationGraph;
+
+    fn assert_weights(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual_weight, expected_weight) in actual.iter().zip(expected) {
+            assert!(
+                (actual_weight - expected_weight).abs() < 1e-5,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    fn blend_space(
+        mut blend_space: AnimationBlendSpace,
+        positions: &[Vec2],
+    ) -> AnimationBlendSpace {
+        for (index, &position) in positions.iter().enumerate() {
+            blend_space.add_point(AnimationNodeIndex::new(index), position);
+        }
+        blend_space
+    }
+
+    #[test]
+    fn blend_space_1d_interpolates_between_neighbors() {
+        let blend_space = blend_space(
+            AnimationBlendSpace::new_1d("speed"),
+            &[
+                Vec2::new(0.0, 0.0),
+                Vec2::new(4.0, 0.0),
+                Vec2::new(1.0, 0.0),
+            ],
+        );
+
+        assert_weights(
+            &blend_space.weights(Vec2::new(0.5, 0.0), &[]),
+            &[0.5, 0.0, 0.5],
+        );
+        assert_weights(
+            &blend_space.weights(Vec2::new(2.5, 0.0), &[]),
+            &[0.0, 0.5, 0.5],
+        );
+        assert_weights(
+            &blend_space.weights(Vec2::new(1.0, 0.0), &[]),
+            &[0.0, 0.0, 1.0],
+        );
+        // Samples outside of the points are clamped.
+        assert_weights(
+            &blend_space.weights(Vec2::new(-1.0, 0.0), &[]),
+            &[1.0, 0.0, 0.0],
+        );
+        assert_weights(
+            &blend_space.weights(Vec2::new(9.0, 0.0), &[]),
+            &[0.0, 1.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn blend_space_2d_uses_barycentric_weights() {
+        let positions = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, -1.0),
+        ];
+        let blend_space = blend_space(AnimationBlendSpace::new_2d("x", "y"), &positions);
+        let triangles = triangulate(&positions);
+        assert_eq!(triangles.len(), 4);
+
+        assert_weights(
+            &blend_space.weights(Vec2::new(0.0, 0.0), &triangles),
+            &[1.0, 0.0, 0.0, 0.0, 0.0],
+        );
+        assert_weights(
+            &blend_space.weights(Vec2::new(0.25, 0.5), &triangles),
+            &[0.25, 0.5, 0.0, 0.25, 0.0],
+        );
+        // Samples outside of the points are clamped to the closest edge.
+        assert_weights(
+            &blend_space.weights(Vec2::new(1.0, 1.0), &triangles),
+            &[0.0, 0.5, 0.0, 0.5, 0.0],
+        );
+        assert_weights(
+            &blend_space.weights(Vec2::new(0.0, 3.0), &triangles),
+            &[0.0, 1.0, 0.0, 0.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn blend_space_2d_handles_stale_triangles() {
+        let positions = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ];
+        let triangles = triangulate(&positions);
+        // A point was removed after the triangulation was computed.
+        let blend_space = blend_space(AnimationBlendSpace::new_2d("x", "y"), &positions[..3]);
+
+        assert_weights(
+            &blend_space.weights(Vec2::new(0.25, 0.25), &triangles),
+            &[0.5, 0.25, 0.25],
+        );
+    }
+
+    #[test]
+    fn triangulation_of_a_grid() {
+        let positions: Vec<Vec2> = (0..3)
+            .flat_map(|y| (0..3).map(move |x| Vec2::new(x as f32, y as f32)))
+            .collect();
+        let triangles = triangulate(&positions);
+        assert_eq!(triangles.len(), 8);
+
+        let area: f32 = triangles
+            .iter()
+            .map(|&[a, b, c]| triangle_area(positions[a], positions[b], positions[c]).abs() / 2.0)
+            .sum();
+        assert!((area - 4.0).abs() < 1e-5);
+
+        // Collinear points can't be triangulated.
+        assert!(triangulate(&[Vec2::ZERO, Vec2::X, Vec2::X * 2.0]).is_empty());
+    }
+
+    #[test]
+    fn synchronized_clips_stay_in_phase() {
+        let mut clips = Assets::<AnimationClip>::default();
+        let mut walk_clip = AnimationClip::default();
+        walk_clip.set_duration(1.0);
+        let mut run_clip = AnimationClip::default();
+        run_clip.set_duration(0.5);
+
+        let mut graph = AnimationGraph::new();
+        let node = graph.add_blend_space(AnimationBlendSpace::new_1d("speed"), 1.0, graph.root);
+        let walk = graph.add_blend_space_clip(clips.add(walk_clip), Vec2::new(1.0, 0.0), node);
+        let run = graph.add_blend_space_clip(clips.add(run_clip), Vec2::new(3.0, 0.0), node);
+        let blend_space = graph.blend_space(node).unwrap().clone();
+
+        let mut player = AnimationPlayer::default();
+        player.set_float("speed", 2.5);
+        update_blend_space(&mut player, &graph, &clips, &blend_space, &[]);
+
+        assert_eq!(player.node_weight(walk), 0.25);
+        assert_eq!(player.node_weight(run), 0.75);
+        // The cycle lasts 0.625 seconds.
+        assert_eq!(player.animation(walk).unwrap().speed(), 1.6);
+        assert_eq!(player.animation(run).unwrap().speed(), 0.8);
+        assert_eq!(
+            player.animation(walk).unwrap().repeat_mode(),
+            RepeatAnimation::Forever
+        );
+
+        // A clip that drifted away is moved back to the phase of the leader.
+        player.animation_mut(run).unwrap().set_seek_time(0.25);
+        player.animation_mut(walk).unwrap().set_seek_time(0.1);
+        update_blend_space(&mut player, &graph, &clips, &blend_space, &[]);
+        assert_eq!(player.animation(run).unwrap().seek_time(), 0.25);
+        assert_eq!(player.animation(walk).unwrap().seek_time(), 0.5);
+    }
+
+    #[test]
+    fn points_with_missing_nodes_are_skipped() {
+        let mut clips = Assets::<AnimationClip>::default();
+        let mut graph = AnimationGraph::new();
+        let node = graph.add_blend_space(AnimationBlendSpace::new_1d("speed"), 1.0, graph.root);
+        let walk = graph.add_blend_space_clip(clips.add(AnimationClip::default()), Vec2::X, node);
+        graph
+            .blend_space_mut(node)
+            .unwrap()
+            .add_point(AnimationNodeIndex::new(42), Vec2::new(3.0, 0.0));
+        let blend_space = graph.blend_space(node).unwrap().clone();
+
+        let mut player = AnimationPlayer::default();
+        player.set_float("speed", 2.0);
+        update_blend_space(&mut player, &graph, &clips, &blend_space, &[]);
+
+        assert_eq!(player.node_weight(walk), 0.5);
+        assert!(player.is_playing_animation(walk));
+        assert!(!player.is_playing_animation(AnimationNodeIndex::new(42)));
+    }
+
+    #[test]
+    fn moved_points_are_triangulated_again() {
+        let mut world = World::new();
+        let clip = world
+            .get_resource_or_init::<Assets<AnimationClip>>()
+            .add(AnimationClip::default());
+
+        let mut graph = AnimationGraph::new();
+        let node = graph.add_blend_space(AnimationBlendSpace::new_2d("x", "y"), 1.0, graph.root);
+        let points: Vec<_> = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(1.0, 1.5),
+        ]
+        .into_iter()
+        .map(|position| graph.add_blend_space_clip(clip.clone(), position, node))
+        .collect();
+        let threaded_graph = ThreadedAnimationGraph::from_graph(&graph);
+        let graph = world
+            .get_resource_or_init::<Assets<AnimationGraph>>()
+            .add(graph);
+        world
+            .get_resource_or_init::<ThreadedAnimationGraphs>()
+            .0
+            .insert(graph.id(), threaded_graph);
+
+        // Move a point without threading the graph again.
+        let mut graphs = world.resource_mut::<Assets<AnimationGraph>>();
+        let blend_space = graphs
+            .get_mut(&graph)
+            .unwrap()
+            .blend_space_mut(node)
+            .unwrap();
+        blend_space.points[3].position = Vec2::new(-1.0, -1.0);
+        let blend_space = blend_space.clone();
+        let threaded_graphs = world.resource::<ThreadedAnimationGraphs>();
+        let stale = &threaded_graphs.0[&graph.id()].blend_space_triangles[&node];
+        assert!(!stale.is_current(&blend_space));
+
+        let sample = Vec2::new(0.0, -0.3);
+        let expected = blend_space.weights(
+            sample,
+            &BlendSpaceTriangulation::new(&blend_space).triangles,
+        );
+        assert_weights(&expected, &[0.4, 0.3, 0.0, 0.3]);
+        assert_ne!(blend_space.weights(sample, &stale.triangles), expected);
+
+        let mut player = AnimationPlayer::default();
+        player.set_float("x", sample.x);
+        player.set_float("y", sample.y);
+        let player = world.spawn((player, AnimationGraphHandle(graph))).id();
+        world.run_system_once(update_blend_spaces).unwrap();
+
+        let player = world.get::<AnimationPlayer>(player).unwrap();
+        let weights: Vec<f32> = points
+            .iter()
+            .map(|&point| player.node_weight(point))
+            .collect();
+        assert_weights(&weights, &expected);
+    }
+}
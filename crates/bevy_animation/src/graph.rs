@@ -18,6 +18,7 @@ use bevy_ecs::{
     resource::Resource,
     system::{Res, ResMut},
 };
+use bevy_math::Vec2;
 use bevy_platform::collections::HashMap;
 use bevy_reflect::{prelude::ReflectDefault, Reflect};
 use derive_more::derive::From;
@@ -31,7 +32,11 @@ use smallvec::SmallVec;
 use thiserror::Error;
 use tracing::warn;
 
-use crate::{AnimationClip, AnimationTargetId};
+use crate::{
+    blend_space::{AnimationBlendSpace, BlendSpacePoint, BlendSpaceTriangulation},
+    state_machine::AnimationStateMachine,
+    AnimationClip, AnimationTargetId,
+};
 
 /// A graph structure that describes how animation clips are to be blended
 /// together.
@@ -44,12 +49,16 @@ use crate::{AnimationClip, AnimationTargetId};
 /// the root and blends the animations together in a bottom-up fashion to
 /// produce the final pose.
 ///
-/// There are three types of nodes: *blend nodes*, *add nodes*, and *clip
-/// nodes*, all of which can have an associated weight. Blend nodes and add
-/// nodes have no associated animation clip and combine the animations of their
-/// children according to those children's weights. Clip nodes specify an
-/// animation clip to play. When a graph is created, it starts with only a
-/// single blend node, the root node.
+/// There are five types of nodes: *blend nodes*, *add nodes*, *clip nodes*,
+/// *state machine nodes*, and *blend space nodes*, all of which can have an
+/// associated weight. Blend nodes and add nodes have no associated animation
+/// clip and combine the animations of their children according to those
+/// children's weights. Clip nodes specify an animation clip to play. State
+/// machine nodes play one of their children at a time, cross-fading between
+/// them as described by an [`AnimationStateMachine`]. Blend space nodes weight
+/// their children by their distance to a point in a parameter space, as
+/// described by an [`AnimationBlendSpace`]. When a graph is created, it starts
+/// with only a single blend node, the root node.
 ///
 /// For example, consider the following graph:
 ///
@@ -161,9 +170,10 @@ pub type AnimationNodeIndex = NodeIndex<u32>;
 /// An individual node within an animation graph.
 ///
 /// The [`AnimationGraphNode::node_type`] field specifies the type of node: one
-/// of a *clip node*, a *blend node*, or an *add node*. Clip nodes, the leaves
-/// of the graph, contain animation clips to play. Blend and add nodes describe
-/// how to combine their children to produce a final animation.
+/// of a *clip node*, a *blend node*, an *add node*, a *state machine node*, or a
+/// *blend space node*. Clip nodes, the leaves of the graph, contain animation
+/// clips to play. The other types of nodes describe how to combine their
+/// children to produce a final animation.
 #[derive(Clone, Reflect, Debug)]
 #[reflect(Clone)]
 pub struct AnimationGraphNode {
@@ -233,6 +243,24 @@ pub enum AnimationNodeType {
     /// top of a running animation to produce an animation of a character
     /// attacking while running.
     Add,
+
+    /// A *state machine node*, which plays one of its children at a time.
+    ///
+    /// Each child is the root of the subgraph of one state. The children are
+    /// blended like those of a blend node, except that their weights are
+    /// multiplied by the current weights of their states, which the
+    /// [`AnimationPlayer`](crate::AnimationPlayer) updates as transitions fire.
+    /// See [`AnimationStateMachine`] for more details.
+    StateMachine(AnimationStateMachine),
+
+    /// A *blend space node*, which weights its children by their positions
+    /// in a parameter space.
+    ///
+    /// The children are blended like those of a blend node, except that their
+    /// weights are multiplied by the weights that the blend space computes
+    /// from the parameters of the [`AnimationPlayer`](crate::AnimationPlayer).
+    /// See [`AnimationBlendSpace`] for more details.
+    BlendSpace(AnimationBlendSpace),
 }
 
 /// An [`AssetLoader`] that can load [`AnimationGraph`]s as assets.
@@ -276,6 +304,27 @@ pub enum AnimationGraphLoadError {
     type and determine how to migrate any SerializedAnimationClip::AssetId animation clips"
     )]
     GraphContainsLegacyAssetId,
+    /// The deserialized graph referred to a node that doesn't exist, as its
+    /// root or from a state machine or blend space.
+    #[error("The deserialized AnimationGraph refers to node {}, which doesn't exist", .0.index())]
+    InvalidNodeIndex(AnimationNodeIndex),
+    /// A state machine in the deserialized graph referred to a state that
+    /// doesn't exist, as its initial state or from a transition.
+    #[error("A state machine in the deserialized AnimationGraph refers to state {0}, which doesn't exist")]
+    InvalidStateIndex(usize),
+    /// A state machine or blend space in the deserialized graph referred to a
+    /// node that isn't one of its children.
+    #[error(
+        "Node {} of the deserialized AnimationGraph refers to node {}, which isn't one of its children",
+        .parent.index(),
+        .child.index()
+    )]
+    InvalidChildNode {
+        /// The state machine or blend space node.
+        parent: AnimationNodeIndex,
+        /// The node it referred to.
+        child: AnimationNodeIndex,
+    },
 }
 
 /// Acceleration structures for animation graphs that allows Bevy to evaluate
@@ -369,6 +418,9 @@ pub struct ThreadedAnimationGraph {
     /// A 1 in bit position N indicates that this node doesn't animate any
     /// targets of mask group N.
     pub computed_masks: Vec<u64>,
+
+    /// The triangulations of the points of two-dimensional blend space nodes.
+    pub blend_space_triangles: HashMap<AnimationNodeIndex, BlendSpaceTriangulation>,
 }
 
 /// A version of [`AnimationGraph`] suitable for serializing as an asset.
@@ -390,6 +442,44 @@ pub struct SerializedAnimationGraph {
     pub mask_groups: HashMap<AnimationTargetId, AnimationMask>,
 }
 
+impl SerializedAnimationGraph {
+    /// Checks that the root, and the nodes referred to by state machines and
+    /// blend spaces, are all part of the graph, that state machines and blend
+    /// spaces only refer to their own children, and that state machines only
+    /// refer to their own states.
+    pub(crate) fn validate_indices(&self) -> Result<(), AnimationGraphLoadError> {
+        if self.root.index() >= self.graph.node_count() {
+            return Err(AnimationGraphLoadError::InvalidNodeIndex(self.root));
+        }
+
+        for parent in self.graph.node_indices() {
+            let children: Vec<AnimationNodeIndex> = match self.graph[parent].node_type {
+                SerializedAnimationNodeType::StateMachine(ref state_machine) => {
+                    state_machine.validate_state_indices()?;
+                    state_machine
+                        .states
+                        .iter()
+                        .map(|state| state.node)
+                        .collect()
+                }
+                SerializedAnimationNodeType::BlendSpace(ref blend_space) => {
+                    blend_space.points.iter().map(|point| point.node).collect()
+                }
+                _ => continue,
+            };
+            for child in children {
+                if child.index() >= self.graph.node_count() {
+                    return Err(AnimationGraphLoadError::InvalidNodeIndex(child));
+                }
+                if !self.graph.contains_edge(parent, child) {
+                    return Err(AnimationGraphLoadError::InvalidChildNode { parent, child });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A version of [`AnimationGraphNode`] suitable for serializing as an asset.
 ///
 /// See the comments in [`SerializedAnimationGraph`] for more information.
@@ -413,6 +503,10 @@ pub enum SerializedAnimationNodeType {
     Blend,
     /// Corresponds to [`AnimationNodeType::Add`].
     Add,
+    /// Corresponds to [`AnimationNodeType::StateMachine`].
+    StateMachine(AnimationStateMachine),
+    /// Corresponds to [`AnimationNodeType::BlendSpace`].
+    BlendSpace(AnimationBlendSpace),
 }
 
 /// A type to facilitate migration from the legacy format of [`SerializedAnimationGraph`] to the
@@ -638,6 +732,122 @@ impl AnimationGraph {
         node_index
     }
 
+    /// Adds a state machine node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The state machine node will be placed under the supplied `parent` node.
+    /// The subgraphs of its states should be added as its children, after
+    /// which they can be registered as states through
+    /// [`AnimationGraph::state_machine_mut`]. The node will have no mask.
+    pub fn add_state_machine(
+        &mut self,
+        state_machine: AnimationStateMachine,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::StateMachine(state_machine),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Returns the [`AnimationStateMachine`] of the state machine node with
+    /// the given index.
+    ///
+    /// If no such node exists, or it isn't a state machine node, returns `None`.
+    pub fn state_machine(&self, node: AnimationNodeIndex) -> Option<&AnimationStateMachine> {
+        match self.get(node)?.node_type {
+            AnimationNodeType::StateMachine(ref state_machine) => Some(state_machine),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the [`AnimationStateMachine`] of the
+    /// state machine node with the given index.
+    ///
+    /// If no such node exists, or it isn't a state machine node, returns `None`.
+    pub fn state_machine_mut(
+        &mut self,
+        node: AnimationNodeIndex,
+    ) -> Option<&mut AnimationStateMachine> {
+        match self.get_mut(node)?.node_type {
+            AnimationNodeType::StateMachine(ref mut state_machine) => Some(state_machine),
+            _ => None,
+        }
+    }
+
+    /// Adds a blend space node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The blend space node will be placed under the supplied `parent` node.
+    /// Clips can be placed in it with [`AnimationGraph::add_blend_space_clip`].
+    /// The node will have no mask.
+    pub fn add_blend_space(
+        &mut self,
+        blend_space: AnimationBlendSpace,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::BlendSpace(blend_space),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Adds an [`AnimationClip`] to the blend space node `blend_space`, placing
+    /// it at the given position, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `blend_space` isn't a blend space node.
+    pub fn add_blend_space_clip(
+        &mut self,
+        clip: Handle<AnimationClip>,
+        position: Vec2,
+        blend_space: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.add_clip(clip, 1.0, blend_space);
+        self.blend_space_mut(blend_space)
+            .expect("the parent of a blend space clip should be a blend space node")
+            .points
+            .push(BlendSpacePoint {
+                node: node_index,
+                position,
+            });
+        node_index
+    }
+
+    /// Returns the [`AnimationBlendSpace`] of the blend space node with the
+    /// given index.
+    ///
+    /// If no such node exists, or it isn't a blend space node, returns `None`.
+    pub fn blend_space(&self, node: AnimationNodeIndex) -> Option<&AnimationBlendSpace> {
+        match self.get(node)?.node_type {
+            AnimationNodeType::BlendSpace(ref blend_space) => Some(blend_space),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the [`AnimationBlendSpace`] of the blend
+    /// space node with the given index.
+    ///
+    /// If no such node exists, or it isn't a blend space node, returns `None`.
+    pub fn blend_space_mut(
+        &mut self,
+        node: AnimationNodeIndex,
+    ) -> Option<&mut AnimationBlendSpace> {
+        match self.get_mut(node)?.node_type {
+            AnimationNodeType::BlendSpace(ref mut blend_space) => Some(blend_space),
+            _ => None,
+        }
+    }
+
     /// Adds an edge from the edge `from` to `to`, making `to` a child of
     /// `from`.
     ///
@@ -792,6 +1002,7 @@ impl AssetLoader for AnimationGraphAssetLoader {
         let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
         let serialized_animation_graph = SerializedAnimationGraph::deserialize(&mut deserializer)
             .map_err(|err| deserializer.span_error(err))?;
+        serialized_animation_graph.validate_indices()?;
 
         // Load all `AssetPath`s to convert from a `SerializedAnimationGraph` to a real
         // `AnimationGraph`. This is effectively a `DiGraph::map`, but this allows us to return
@@ -832,6 +1043,12 @@ impl AssetLoader for AnimationGraphAssetLoader {
                     },
                     SerializedAnimationNodeType::Blend => AnimationNodeType::Blend,
                     SerializedAnimationNodeType::Add => AnimationNodeType::Add,
+                    SerializedAnimationNodeType::StateMachine(ref state_machine) => {
+                        AnimationNodeType::StateMachine(state_machine.clone())
+                    }
+                    SerializedAnimationNodeType::BlendSpace(ref blend_space) => {
+                        AnimationNodeType::BlendSpace(blend_space.clone())
+                    }
                 },
                 mask: serialized_node.mask,
                 weight: serialized_node.weight,
@@ -877,6 +1094,12 @@ impl TryFrom<AnimationGraph> for SerializedAnimationGraph {
                     },
                     AnimationNodeType::Blend => SerializedAnimationNodeType::Blend,
                     AnimationNodeType::Add => SerializedAnimationNodeType::Add,
+                    AnimationNodeType::StateMachine(ref state_machine) => {
+                        SerializedAnimationNodeType::StateMachine(state_machine.clone())
+                    }
+                    AnimationNodeType::BlendSpace(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace(blend_space.clone())
+                    }
                 },
             });
         }
@@ -928,6 +1151,7 @@ pub(crate) fn thread_animation_graphs(
                     animation_graph.root,
                     0,
                 );
+                threaded_animation_graph.triangulate_blend_spaces(animation_graph);
 
                 // Write in the threaded graph.
                 threaded_animation_graphs
@@ -944,12 +1168,23 @@ pub(crate) fn thread_animation_graphs(
 }
 
 impl ThreadedAnimationGraph {
+    /// Threads the given graph, as [`thread_animation_graphs`] does.
+    #[cfg(test)]
+    pub(crate) fn from_graph(animation_graph: &AnimationGraph) -> Self {
+        let mut threaded_animation_graph = Self::default();
+        threaded_animation_graph.init(animation_graph);
+        threaded_animation_graph.build_from(&animation_graph.graph, animation_graph.root, 0);
+        threaded_animation_graph.triangulate_blend_spaces(animation_graph);
+        threaded_animation_graph
+    }
+
     /// Removes all the data in this [`ThreadedAnimationGraph`], keeping the
     /// memory around for later reuse.
     fn clear(&mut self) {
         self.threaded_graph.clear();
         self.sorted_edge_ranges.clear();
         self.sorted_edges.clear();
+        self.blend_space_triangles.clear();
     }
 
     /// Prepares the [`ThreadedAnimationGraph`] for recursion.
@@ -968,6 +1203,19 @@ impl ThreadedAnimationGraph {
         self.computed_masks.extend(iter::repeat_n(0, node_count));
     }
 
+    /// Triangulates the points of all two-dimensional blend spaces in the graph.
+    fn triangulate_blend_spaces(&mut self, animation_graph: &AnimationGraph) {
+        for node_index in animation_graph.nodes() {
+            if let AnimationNodeType::BlendSpace(ref blend_space) =
+                animation_graph[node_index].node_type
+                && blend_space.is_2d()
+            {
+                self.blend_space_triangles
+                    .insert(node_index, BlendSpaceTriangulation::new(blend_space));
+            }
+        }
+    }
+
     /// Recursively constructs the [`ThreadedAnimationGraph`] for the subtree
     /// rooted at the given node.
     ///
@@ -0,0 +1,624 @@
+//! Inverse kinematics constraints, which procedurally adjust animated bones so
+//! that they reach for, or look at, other entities.
+//!
+//! The constraints run after the animations have been applied and before the
+//! transforms are propagated. They work on the animated pose of the current
+//! frame, and each one has a weight that blends between that pose and the
+//! solved one. Two-bone constraints are solved first, then chains, then
+//! look-at constraints, so that for example a head can look at something from
+//! the position a spine chain put it in.
+
+use bevy_ecs::prelude::*;
+use bevy_math::{Dir3, Quat, Vec3};
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+/// Rotates the two parents of this bone, for example the upper arm and the
+/// forearm of a hand, so that this bone reaches the target.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Clone, Debug)]
+pub struct TwoBoneIk {
+    /// The entity to reach.
+    #[entities]
+    pub target: Entity,
+
+    /// The entity towards which the middle joint, such as the elbow or the
+    /// knee, bends.
+    ///
+    /// If this is `None`, the joint bends in the same direction as in the
+    /// animated pose.
+    #[entities]
+    pub pole: Option<Entity>,
+
+    /// How much the solved pose overrides the animated one, from 0 to 1.
+    pub weight: f32,
+}
+
+impl TwoBoneIk {
+    /// Creates a [`TwoBoneIk`] that fully reaches the given target.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            pole: None,
+            weight: 1.0,
+        }
+    }
+
+    /// Sets the entity towards which the middle joint bends.
+    pub fn with_pole(mut self, pole: Entity) -> Self {
+        self.pole = Some(pole);
+        self
+    }
+
+    /// Sets how much the solved pose overrides the animated one.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Rotates a chain of ancestors of this bone, for example the vertebrae of a
+/// spine or the segments of a tail, so that this bone reaches the target.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Clone, Debug)]
+pub struct IkChain {
+    /// The entity to reach.
+    #[entities]
+    pub target: Entity,
+
+    /// The number of ancestors of this bone that are rotated.
+    pub length: usize,
+
+    /// The algorithm used to solve the chain.
+    pub solver: IkSolver,
+
+    /// The maximum number of iterations of the solver.
+    pub iterations: u32,
+
+    /// The distance to the target under which the solver stops iterating.
+    pub tolerance: f32,
+
+    /// How much the solved pose overrides the animated one, from 0 to 1.
+    pub weight: f32,
+}
+
+impl IkChain {
+    /// Creates an [`IkChain`] of the given length that fully reaches the
+    /// given target using [FABRIK].
+    ///
+    /// [FABRIK]: IkSolver::Fabrik
+    pub fn new(target: Entity, length: usize) -> Self {
+        Self {
+            target,
+            length,
+            solver: IkSolver::Fabrik,
+            iterations: 10,
+            tolerance: 0.001,
+            weight: 1.0,
+        }
+    }
+
+    /// Sets the algorithm used to solve the chain.
+    pub fn with_solver(mut self, solver: IkSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Sets the maximum number of iterations of the solver.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets how much the solved pose overrides the animated one.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// An algorithm that solves an [`IkChain`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Clone, Debug, Default, PartialEq)]
+pub enum IkSolver {
+    /// Forward And Backward Reaching Inverse Kinematics, which spreads the
+    /// rotation evenly along the chain.
+    #[default]
+    Fabrik,
+    /// Cyclic Coordinate Descent, which favors rotating the joints closest to
+    /// the end of the chain.
+    Ccd,
+}
+
+/// Rotates this bone so that one of its axes points at the target, for
+/// example to make a head look at something or a gun aim at it.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Clone, Debug)]
+pub struct LookAt {
+    /// The entity to look at.
+    #[entities]
+    pub target: Entity,
+
+    /// The local axis of the bone that points at the target.
+    pub forward: Dir3,
+
+    /// The maximum angle, in radians, by which the bone turns away from its
+    /// animated orientation.
+    pub max_angle: f32,
+
+    /// How much the solved pose overrides the animated one, from 0 to 1.
+    pub weight: f32,
+}
+
+impl LookAt {
+    /// Creates a [`LookAt`] that fully turns the local forward axis of the
+    /// bone, -Z, towards the target.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            forward: Dir3::NEG_Z,
+            max_angle: core::f32::consts::PI,
+            weight: 1.0,
+        }
+    }
+
+    /// Sets the local axis of the bone that points at the target.
+    pub fn with_forward(mut self, forward: Dir3) -> Self {
+        self.forward = forward;
+        self
+    }
+
+    /// Sets the maximum angle, in radians, by which the bone turns away from
+    /// its animated orientation.
+    pub fn with_max_angle(mut self, max_angle: f32) -> Self {
+        self.max_angle = max_angle;
+        self
+    }
+
+    /// Sets how much the solved pose overrides the animated one.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// The system set in which the inverse kinematics constraints are solved,
+/// after the animations have been applied and before the transforms are
+/// propagated.
+///
+/// Within it, [`solve_two_bone_ik`] runs first, then [`solve_ik_chains`], then
+/// [`solve_look_at`].
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IkSystems;
+
+/// The local transforms of the bones that constraints read and write.
+type Bones<'w, 's> = Query<'w, 's, (&'static mut Transform, Option<&'static ChildOf>)>;
+
+/// A system that solves all [`TwoBoneIk`] constraints.
+pub fn solve_two_bone_ik(constraints: Query<(Entity, &TwoBoneIk)>, mut bones: Bones) {
+    for (end, constraint) in &constraints {
+        if constraint.weight <= 0.0 {
+            continue;
+        }
+        let Some(joints) = ancestors(end, 2, &bones) else {
+            continue;
+        };
+        let Some(target) = global_transform(constraint.target, &bones) else {
+            continue;
+        };
+        let pole = constraint
+            .pole
+            .and_then(|pole| global_transform(pole, &bones))
+            .map(|pole| pole.translation());
+        let Some((parent, positions)) = joint_positions(&joints, &bones) else {
+            continue;
+        };
+
+        let positions = solve_two_bone(
+            [positions[0], positions[1], positions[2]],
+            target.translation(),
+            pole,
+        );
+        apply_positions(&joints, &positions, parent, constraint.weight, &mut bones);
+    }
+}
+
+/// A system that solves all [`IkChain`] constraints.
+pub fn solve_ik_chains(constraints: Query<(Entity, &IkChain)>, mut bones: Bones) {
+    for (end, constraint) in &constraints {
+        if constraint.weight <= 0.0 || constraint.length == 0 {
+            continue;
+        }
+        let Some(joints) = ancestors(end, constraint.length, &bones) else {
+            continue;
+        };
+        let Some(target) = global_transform(constraint.target, &bones) else {
+            continue;
+        };
+        let Some((parent, mut positions)) = joint_positions(&joints, &bones) else {
+            continue;
+        };
+
+        match constraint.solver {
+            IkSolver::Fabrik => solve_fabrik(
+                &mut positions,
+                target.translation(),
+                constraint.iterations,
+                constraint.tolerance,
+            ),
+            IkSolver::Ccd => solve_ccd(
+                &mut positions,
+                target.translation(),
+                constraint.iterations,
+                constraint.tolerance,
+            ),
+        }
+        apply_positions(&joints, &positions, parent, constraint.weight, &mut bones);
+    }
+}
+
+/// A system that solves all [`LookAt`] constraints.
+pub fn solve_look_at(constraints: Query<(Entity, &LookAt)>, mut bones: Bones) {
+    for (entity, constraint) in &constraints {
+        if constraint.weight <= 0.0 {
+            continue;
+        }
+        let Some(target) = global_transform(constraint.target, &bones) else {
+            continue;
+        };
+        let parent = parent_global_transform(entity, &bones);
+        let Ok((mut transform, _)) = bones.get_mut(entity) else {
+            continue;
+        };
+
+        let global = parent * *transform;
+        let rotation = global.rotation();
+        let forward = rotation * constraint.forward.as_vec3();
+        let Some(direction) = (target.translation() - global.translation()).try_normalize() else {
+            continue;
+        };
+
+        let mut arc = Quat::from_rotation_arc(forward, direction);
+        let (axis, angle) = arc.to_axis_angle();
+        if angle > constraint.max_angle {
+            arc = Quat::from_axis_angle(axis, constraint.max_angle.max(0.0));
+        }
+
+        let solved = (parent.rotation().inverse() * arc * rotation).normalize();
+        transform.rotation = transform.rotation.slerp(solved, constraint.weight.min(1.0));
+    }
+}
+
+/// Returns the given bone preceded by its `count` nearest ancestors, from the
+/// farthest one to the bone itself.
+fn ancestors(entity: Entity, count: usize, bones: &Bones) -> Option<Vec<Entity>> {
+    let mut joints = vec![entity];
+    let mut current = entity;
+    for _ in 0..count {
+        let (_, child_of) = bones.get(current).ok()?;
+        current = child_of?.parent();
+        bones.get(current).ok()?;
+        joints.push(current);
+    }
+    joints.reverse();
+    Some(joints)
+}
+
+/// Computes the global transform of an entity from the local transforms of
+/// its ancestors.
+///
+/// This doesn't use [`GlobalTransform`], which isn't propagated yet and would
+/// lag one frame behind the animation.
+fn global_transform(entity: Entity, bones: &Bones) -> Option<GlobalTransform> {
+    let (transform, child_of) = bones.get(entity).ok()?;
+    Some(parent_from(child_of, bones) * *transform)
+}
+
+/// Computes the global transform of the parent of an entity, or the identity
+/// if it has none.
+fn parent_global_transform(entity: Entity, bones: &Bones) -> GlobalTransform {
+    bones
+        .get(entity)
+        .map(|(_, child_of)| parent_from(child_of, bones))
+        .unwrap_or_default()
+}
+
+/// Composes the local transforms of a parent and all of its ancestors.
+fn parent_from(child_of: Option<&ChildOf>, bones: &Bones) -> GlobalTransform {
+    let mut global = GlobalTransform::IDENTITY;
+    let mut parent = child_of.map(ChildOf::parent);
+    while let Some(entity) = parent
+        && let Ok((transform, child_of)) = bones.get(entity)
+    {
+        global = GlobalTransform::from(*transform) * global;
+        parent = child_of.map(ChildOf::parent);
+    }
+    global
+}
+
+/// Returns the global transform of the parent of the first joint, and the
+/// global positions of all the joints.
+fn joint_positions(joints: &[Entity], bones: &Bones) -> Option<(GlobalTransform, Vec<Vec3>)> {
+    let parent = parent_global_transform(joints[0], bones);
+    let mut global = parent;
+    let mut positions = Vec::with_capacity(joints.len());
+    for &joint in joints {
+        let (transform, _) = bones.get(joint).ok()?;
+        global = global * *transform;
+        positions.push(global.translation());
+    }
+    Some((parent, positions))
+}
+
+/// Rotates each joint but the last one so that its child moves towards the
+/// given global position, blending the solved rotations with the animated
+/// ones by `weight`.
+fn apply_positions(
+    joints: &[Entity],
+    positions: &[Vec3],
+    mut parent: GlobalTransform,
+    weight: f32,
+    bones: &mut Bones,
+) {
+    let weight = weight.min(1.0);
+    for (index, pair) in joints.windows(2).enumerate() {
+        let Ok((child, _)) = bones.get(pair[1]) else {
+            return;
+        };
+        let child_translation = child.translation;
+        let Ok((mut transform, _)) = bones.get_mut(pair[0]) else {
+            return;
+        };
+
+        let global = parent * *transform;
+        let current = global.affine().transform_vector3(child_translation);
+        let desired = positions[index + 1] - global.translation();
+        if let (Some(current), Some(desired)) = (current.try_normalize(), desired.try_normalize()) {
+            let arc = Quat::from_rotation_arc(current, desired);
+            let solved = (parent.rotation().inverse() * arc * global.rotation()).normalize();
+            transform.rotation = transform.rotation.slerp(solved, weight);
+        }
+        parent = parent * *transform;
+    }
+}
+
+/// Returns the positions of the three joints of a limb whose end reaches as
+/// close to `target` as possible, bending towards `pole` if any.
+fn solve_two_bone([root, mid, end]: [Vec3; 3], target: Vec3, pole: Option<Vec3>) -> [Vec3; 3] {
+    let upper = root.distance(mid);
+    let lower = mid.distance(end);
+    let to_target = target - root;
+    let Some(direction) = to_target.try_normalize() else {
+        return [root, mid, end];
+    };
+    let distance = to_target
+        .length()
+        .clamp((upper - lower).abs(), upper + lower);
+
+    // The direction in which the middle joint bends, perpendicular to the
+    // direction of the target.
+    let hint = pole.map_or(mid - root, |pole| pole - root);
+    let bend = hint
+        .reject_from_normalized(direction)
+        .try_normalize()
+        .or_else(|| {
+            (mid - root)
+                .reject_from_normalized(direction)
+                .try_normalize()
+        })
+        .unwrap_or_else(|| direction.any_orthonormal_vector());
+
+    // The law of cosines gives the angle between the upper bone and the
+    // target.
+    let cos = if upper * distance > 0.0 {
+        ((upper * upper + distance * distance - lower * lower) / (2.0 * upper * distance))
+            .clamp(-1.0, 1.0)
+    } else {
+        1.0
+    };
+    let sin = (1.0 - cos * cos).sqrt();
+
+    [
+        root,
+        root + upper * (direction * cos + bend * sin),
+        root + direction * distance,
+    ]
+}
+
+/// Moves the joints of a chain so that its last joint reaches as close to
+/// `target` as possible, using FABRIK.
+fn solve_fabrik(positions: &mut [Vec3], target: Vec3, iterations: u32, tolerance: f32) {
+    let lengths: Vec<f32> = positions
+        .windows(2)
+        .map(|pair| pair[0].distance(pair[1]))
+        .collect();
+    let root = positions[0];
+    let last = positions.len() - 1;
+
+    // Stretch towards unreachable targets.
+    if root.distance(target) >= lengths.iter().sum::<f32>() {
+        let direction = (target - root).normalize_or_zero();
+        for index in 1..positions.len() {
+            positions[index] = positions[index - 1] + direction * lengths[index - 1];
+        }
+        return;
+    }
+
+    for _ in 0..iterations {
+        if positions[last].distance(target) <= tolerance {
+            break;
+        }
+
+        positions[last] = target;
+        for index in (0..last).rev() {
+            let direction = (positions[index] - positions[index + 1]).normalize_or_zero();
+            positions[index] = positions[index + 1] + direction * lengths[index];
+        }
+
+        positions[0] = root;
+        for index in 1..positions.len() {
+            let direction = (positions[index] - positions[index - 1]).normalize_or_zero();
+            positions[index] = positions[index - 1] + direction * lengths[index - 1];
+        }
+    }
+}
+
+/// Moves the joints of a chain so that its last joint reaches as close to
+/// `target` as possible, using CCD.
+fn solve_ccd(positions: &mut [Vec3], target: Vec3, iterations: u32, tolerance: f32) {
+    let last = positions.len() - 1;
+    for _ in 0..iterations {
+        if positions[last].distance(target) <= tolerance {
+            break;
+        }
+
+        for index in (0..last).rev() {
+            let pivot = positions[index];
+            let (Some(to_end), Some(to_target)) = (
+                (positions[last] - pivot).try_normalize(),
+                (target - pivot).try_normalize(),
+            ) else {
+                continue;
+            };
+            let arc = Quat::from_rotation_arc(to_end, to_target);
+            for position in &mut positions[index + 1..] {
+                *position = pivot + arc * (*position - pivot);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::FRAC_PI_4;
+
+    use bevy_ecs::{prelude::*, system::RunSystemOnce};
+    use bevy_math::{Quat, Vec3};
+    use bevy_transform::components::Transform;
+
+    use super::{
+        solve_ccd, solve_fabrik, solve_ik_chains, solve_look_at, solve_two_bone, solve_two_bone_ik,
+        IkChain, LookAt, TwoBoneIk,
+    };
+
+    fn chain(count: usize) -> Vec<Vec3> {
+        (0..count).map(|index| Vec3::Y * index as f32).collect()
+    }
+
+    #[test]
+    fn two_bone_reaches_and_bends_towards_pole() {
+        let [root, mid, end] = solve_two_bone(
+            [Vec3::ZERO, Vec3::Y, Vec3::Y * 2.0],
+            Vec3::new(1.0, 1.0, 0.0),
+            Some(Vec3::new(0.0, 0.0, 5.0)),
+        );
+        assert_eq!(root, Vec3::ZERO);
+        assert!(end.abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-5));
+        assert!((root.distance(mid) - 1.0).abs() < 1e-5);
+        assert!((mid.distance(end) - 1.0).abs() < 1e-5);
+        assert!(mid.z > 0.5);
+
+        // Out of reach, the limb is straight.
+        let [_, mid, end] =
+            solve_two_bone([Vec3::ZERO, Vec3::Y, Vec3::Y * 2.0], Vec3::X * 5.0, None);
+        assert!(mid.abs_diff_eq(Vec3::X, 1e-5));
+        assert!(end.abs_diff_eq(Vec3::X * 2.0, 1e-5));
+    }
+
+    #[test]
+    fn chains_reach_target() {
+        let target = Vec3::new(1.5, 1.5, 0.5);
+
+        let mut positions = chain(4);
+        solve_fabrik(&mut positions, target, 20, 1e-4);
+        assert!(positions[3].distance(target) < 1e-3);
+
+        let mut positions = chain(4);
+        solve_ccd(&mut positions, target, 20, 1e-4);
+        assert!(positions[3].distance(target) < 1e-3);
+
+        for pair in positions.windows(2) {
+            assert!((pair[0].distance(pair[1]) - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn two_bone_ik_rotates_bones() {
+        let mut world = World::new();
+        let target = world.spawn(Transform::from_xyz(1.0, 1.0, 0.0)).id();
+        let root = world
+            .spawn(Transform::from_xyz(0.0, 0.0, 0.0))
+            .with_children(|parent| {
+                parent
+                    .spawn(Transform::from_xyz(0.0, 1.0, 0.0))
+                    .with_child((Transform::from_xyz(0.0, 1.0, 0.0), TwoBoneIk::new(target)));
+            })
+            .id();
+
+        world.run_system_once(solve_two_bone_ik).unwrap();
+
+        let mid = world.entity(root).get::<Children>().unwrap()[0];
+        let root_rotation = world.entity(root).get::<Transform>().unwrap().rotation;
+        let mid_rotation = world.entity(mid).get::<Transform>().unwrap().rotation;
+        let end = root_rotation * (Vec3::Y + mid_rotation * Vec3::Y);
+        assert!(end.abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-4));
+        assert_ne!(mid_rotation, Quat::IDENTITY);
+    }
+
+    /// Returns the global translation of an entity, from the local transforms
+    /// of its ancestors.
+    fn global_translation(world: &World, entity: Entity) -> Vec3 {
+        let mut transform = *world.get::<Transform>(entity).unwrap();
+        let mut current = entity;
+        while let Some(child_of) = world.get::<ChildOf>(current) {
+            current = child_of.parent();
+            transform = *world.get::<Transform>(current).unwrap() * transform;
+        }
+        transform.translation
+    }
+
+    #[test]
+    fn ik_chains_rotate_bones() {
+        let mut world = World::new();
+        let target = Vec3::new(1.5, 1.5, 0.5);
+        let target_entity = world.spawn(Transform::from_translation(target)).id();
+        let mut bone = world.spawn(Transform::default()).id();
+        for _ in 0..3 {
+            bone = world
+                .spawn((Transform::from_xyz(0.0, 1.0, 0.0), ChildOf(bone)))
+                .id();
+        }
+        world
+            .entity_mut(bone)
+            .insert(IkChain::new(target_entity, 3).with_iterations(20));
+
+        world.run_system_once(solve_ik_chains).unwrap();
+
+        assert!(global_translation(&world, bone).distance(target) < 1e-2);
+    }
+
+    #[test]
+    fn look_at_is_clamped_and_weighted() {
+        let mut world = World::new();
+        let target = world.spawn(Transform::from_xyz(1.0, 0.0, 0.0)).id();
+        let clamped = world
+            .spawn((
+                Transform::default(),
+                LookAt::new(target).with_max_angle(FRAC_PI_4),
+            ))
+            .id();
+        let blended = world
+            .spawn((Transform::default(), LookAt::new(target).with_weight(0.5)))
+            .id();
+
+        world.run_system_once(solve_look_at).unwrap();
+
+        // The target is 90 degrees away from the forward axis, -Z, so both
+        // bones turn halfway towards it.
+        let halfway = Vec3::new(1.0, 0.0, -1.0).normalize();
+        for bone in [clamped, blended] {
+            let rotation = world.get::<Transform>(bone).unwrap().rotation;
+            assert!((rotation * Vec3::NEG_Z).abs_diff_eq(halfway, 1e-4));
+        }
+    }
+}
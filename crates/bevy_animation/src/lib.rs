@@ -11,8 +11,12 @@ extern crate alloc;
 
 pub mod animatable;
 pub mod animation_curves;
+pub mod blend_space;
 pub mod gltf_curves;
 pub mod graph;
+pub mod ik;
+pub mod root_motion;
+pub mod state_machine;
 pub mod transition;
 mod util;
 
@@ -37,7 +41,7 @@ use bevy_ecs::{prelude::*, world::EntityMutExcept};
 use bevy_math::FloatOrd;
 use bevy_platform::{collections::HashMap, hash::NoOpHash};
 use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
-use bevy_time::Time;
+use bevy_time::DomainTime;
 use bevy_transform::TransformSystems;
 use bevy_utils::{PreHashMap, PreHashMapExt, TypeIdMap};
 use serde::{Deserialize, Serialize};
@@ -51,14 +55,19 @@ use uuid::Uuid;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        animatable::*, animation_curves::*, graph::*, transition::*, AnimationClip,
-        AnimationPlayer, AnimationPlugin, VariableCurve,
+        animatable::*, animation_curves::*, blend_space::*, graph::*, ik::*, root_motion::*,
+        state_machine::*, transition::*, AnimationClip, AnimationPlayer, AnimationPlugin,
+        VariableCurve,
     };
 }
 
 use crate::{
     animation_curves::AnimationCurve,
+    blend_space::update_blend_spaces,
     graph::{AnimationGraph, AnimationGraphAssetLoader, AnimationNodeIndex},
+    ik::{solve_ik_chains, solve_look_at, solve_two_bone_ik, IkSystems},
+    root_motion::extract_root_motion,
+    state_machine::{advance_state_machines, ActiveStateMachine, AnimationParameter},
     transition::{advance_transitions, expire_completed_transitions},
 };
 use alloc::sync::Arc;
@@ -684,6 +693,15 @@ impl ActiveAnimation {
 #[reflect(Component, Default, Clone)]
 pub struct AnimationPlayer {
     active_animations: HashMap<AnimationNodeIndex, ActiveAnimation>,
+    /// The named parameters that drive the transitions of state machines.
+    parameters: HashMap<String, AnimationParameter>,
+    /// The current state of each state machine node in the graph.
+    state_machines: HashMap<AnimationNodeIndex, ActiveStateMachine>,
+    /// The weights that state machines and blend spaces assigned to their
+    /// children.
+    ///
+    /// Nodes not in this map have a weight of 1.0.
+    node_weights: HashMap<AnimationNodeIndex, f32>,
 }
 
 // This is needed since `#[derive(Clone)]` does not generate optimized `clone_from`.
@@ -691,11 +709,17 @@ impl Clone for AnimationPlayer {
     fn clone(&self) -> Self {
         Self {
             active_animations: self.active_animations.clone(),
+            parameters: self.parameters.clone(),
+            state_machines: self.state_machines.clone(),
+            node_weights: self.node_weights.clone(),
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.active_animations.clone_from(&source.active_animations);
+        self.parameters.clone_from(&source.parameters);
+        self.state_machines.clone_from(&source.state_machines);
+        self.node_weights.clone_from(&source.node_weights);
     }
 }
 
@@ -822,14 +846,23 @@ impl AnimationPlayer {
 
     /// Stops playing the given animation, removing it from the list of playing
     /// animations.
+    ///
+    /// This doesn't change the state of state machines: stopping the animation
+    /// of their current state leaves them in that state. Use
+    /// [`stop_all`](Self::stop_all) to restart them.
     pub fn stop(&mut self, animation: AnimationNodeIndex) -> &mut Self {
         self.active_animations.remove(&animation);
         self
     }
 
     /// Stops all currently-playing animations.
+    ///
+    /// State machines are stopped too, and start over from their initial
+    /// state the next time they're advanced.
     pub fn stop_all(&mut self) -> &mut Self {
         self.active_animations.clear();
+        self.state_machines.clear();
+        self.node_weights.clear();
         self
     }
 
@@ -936,6 +969,57 @@ impl AnimationPlayer {
     pub fn animation_mut(&mut self, animation: AnimationNodeIndex) -> Option<&mut ActiveAnimation> {
         self.active_animations.get_mut(&animation)
     }
+
+    /// Sets the value of a named parameter used by state machine transitions.
+    pub fn set_parameter(
+        &mut self,
+        name: impl Into<String>,
+        value: AnimationParameter,
+    ) -> &mut Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+
+    /// Sets a bool parameter used by state machine transitions.
+    pub fn set_bool(&mut self, name: impl Into<String>, value: bool) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Bool(value))
+    }
+
+    /// Sets a float parameter used by state machine transitions.
+    pub fn set_float(&mut self, name: impl Into<String>, value: f32) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Float(value))
+    }
+
+    /// Sets a trigger used by state machine transitions.
+    ///
+    /// The trigger stays set until a transition that depends on it fires.
+    pub fn set_trigger(&mut self, name: impl Into<String>) -> &mut Self {
+        self.set_parameter(name, AnimationParameter::Trigger)
+    }
+
+    /// Clears a named parameter, returning its previous value if it was set.
+    pub fn remove_parameter(&mut self, name: &str) -> Option<AnimationParameter> {
+        self.parameters.remove(name)
+    }
+
+    /// Returns the value of a named parameter, if it's set.
+    pub fn parameter(&self, name: &str) -> Option<AnimationParameter> {
+        self.parameters.get(name).copied()
+    }
+
+    /// Returns the state of the state machine node with the given index, if
+    /// it has started.
+    pub fn state_machine(&self, node: AnimationNodeIndex) -> Option<&ActiveStateMachine> {
+        self.state_machines.get(&node)
+    }
+
+    /// Returns the weight that a state machine or blend space assigned to the
+    /// given node.
+    ///
+    /// This is 1.0 for nodes that aren't the children of either.
+    fn node_weight(&self, node: AnimationNodeIndex) -> f32 {
+        self.node_weights.get(&node).copied().unwrap_or(1.0)
+    }
 }
 
 /// A system that triggers untargeted animation events for the currently-playing animations.
@@ -960,7 +1044,10 @@ fn trigger_untargeted_animation_events(
                 .get(*index)
                 .and_then(|node| match &node.node_type {
                     AnimationNodeType::Clip(handle) => Some(handle),
-                    AnimationNodeType::Blend | AnimationNodeType::Add => None,
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::Add
+                    | AnimationNodeType::StateMachine(_)
+                    | AnimationNodeType::BlendSpace(_) => None,
                 })
                 .and_then(|id| clips.get(id))
             else {
@@ -981,19 +1068,21 @@ fn trigger_untargeted_animation_events(
 }
 
 /// A system that advances the time for all playing animations.
+///
+/// Players in a [`TimeDomain`](bevy_time::TimeDomain) advance with the time of their domain.
 pub fn advance_animations(
-    time: Res<Time>,
+    domain_time: DomainTime,
     animation_clips: Res<Assets<AnimationClip>>,
     animation_graphs: Res<Assets<AnimationGraph>>,
-    mut players: Query<(&mut AnimationPlayer, &AnimationGraphHandle)>,
+    mut players: Query<(Entity, &mut AnimationPlayer, &AnimationGraphHandle)>,
 ) {
-    let delta_seconds = time.delta_secs();
     players
         .par_iter_mut()
-        .for_each(|(mut player, graph_handle)| {
+        .for_each(|(entity, mut player, graph_handle)| {
             let Some(animation_graph) = animation_graphs.get(graph_handle) else {
                 return;
             };
+            let delta_seconds = domain_time.delta_secs(entity);
 
             // Tick animations, and schedule them.
 
@@ -1085,8 +1174,12 @@ pub fn animate_targets(
                 };
 
                 match animation_graph_node.node_type {
-                    AnimationNodeType::Blend => {
-                        // This is a blend node.
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::StateMachine(_)
+                    | AnimationNodeType::BlendSpace(_) => {
+                        // This is a blend node, or a state machine or blend
+                        // space node, which blends its children according to
+                        // the weights it assigned them.
                         for edge_index in threaded_animation_graph.sorted_edge_ranges
                             [animation_graph_node_index.index()]
                         .clone()
@@ -1099,7 +1192,8 @@ pub fn animate_targets(
                         }
 
                         if let Err(err) = evaluation_state.push_blend_register_all(
-                            animation_graph_node.weight,
+                            animation_graph_node.weight
+                                * animation_player.node_weight(animation_graph_node_index),
                             animation_graph_node_index,
                         ) {
                             warn!("Animation blending failed: {:?}", err);
@@ -1120,7 +1214,8 @@ pub fn animate_targets(
                         }
 
                         if let Err(err) = evaluation_state.push_blend_register_all(
-                            animation_graph_node.weight,
+                            animation_graph_node.weight
+                                * animation_player.node_weight(animation_graph_node_index),
                             animation_graph_node_index,
                         ) {
                             warn!("Animation blending failed: {:?}", err);
@@ -1136,9 +1231,12 @@ pub fn animate_targets(
                             continue;
                         };
 
+                        let node_weight = animation_player.node_weight(animation_graph_node_index);
+
                         // If the weight is zero or the current animation target is
                         // masked out, stop here.
                         if active_animation.weight == 0.0
+                            || node_weight == 0.0
                             || (target_mask
                                 & threaded_animation_graph.computed_masks
                                     [animation_graph_node_index.index()])
@@ -1178,7 +1276,8 @@ pub fn animate_targets(
                             continue;
                         };
 
-                        let weight = active_animation.weight * animation_graph_node.weight;
+                        let weight =
+                            active_animation.weight * animation_graph_node.weight * node_weight;
                         let seek_time = active_animation.seek_time;
 
                         for curve in curves {
@@ -1237,6 +1336,8 @@ impl Plugin for AnimationPlugin {
                 (
                     graph::thread_animation_graphs.before(AssetEventSystems),
                     advance_transitions,
+                    advance_state_machines,
+                    update_blend_spaces,
                     advance_animations,
                     // TODO: `animate_targets` can animate anything, so
                     // ambiguity testing currently considers it ambiguous with
@@ -1247,6 +1348,10 @@ impl Plugin for AnimationPlugin {
                     animate_targets
                         .before(bevy_mesh::InheritWeightSystems)
                         .ambiguous_with_all(),
+                    extract_root_motion,
+                    (solve_two_bone_ik, solve_ik_chains, solve_look_at)
+                        .chain()
+                        .in_set(IkSystems),
                     trigger_untargeted_animation_events,
                     expire_completed_transitions,
                 )
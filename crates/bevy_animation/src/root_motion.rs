@@ -0,0 +1,642 @@
+//! Root motion, which moves an entity by the motion of the root bone of its
+//! animations instead of letting the root bone drift away from it.
+//!
+//! Add a [`RootMotion`] component to an entity with an [`AnimationPlayer`] to
+//! designate the [`AnimationTarget`] whose motion should be extracted. Every
+//! frame, after the animations have been applied, the horizontal translation
+//! and yaw of that target are removed from its [`Transform`], and the motion
+//! they described since the last frame, blended through the animation graph
+//! like any other animated value, is written to [`RootMotionDelta`].
+
+use core::f32::consts::{PI, TAU};
+
+use bevy_asset::Assets;
+use bevy_ecs::prelude::*;
+use bevy_math::{Affine3A, BVec3, EulerRot, Quat, Vec3};
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+use crate::{
+    animated_field,
+    animation_curves::{
+        AnimatableCurveEvaluator, AnimatableProperty, AnimatedField, AnimationCurve,
+        AnimationCurveEvaluator, BasicAnimationCurveEvaluator,
+        BasicAnimationCurveEvaluatorStackElement, EvaluatorId,
+    },
+    graph::{
+        AnimationGraph, AnimationGraphHandle, AnimationNodeIndex, AnimationNodeType,
+        ThreadedAnimationGraph, ThreadedAnimationGraphs,
+    },
+    ActiveAnimation, AnimationClip, AnimationPlayer, AnimationTarget, AnimationTargetId,
+    VariableCurve,
+};
+
+/// Extracts the motion of an animation target, typically the root bone of a
+/// skeleton, so that it can move the entity with the [`AnimationPlayer`]
+/// instead.
+///
+/// The extracted motion is removed from the target's [`Transform`] after the
+/// animations are applied, and written to the [`RootMotionDelta`] of this
+/// entity.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Clone, Debug)]
+#[require(RootMotionDelta)]
+pub struct RootMotion {
+    /// The animation target whose motion is extracted.
+    pub target: AnimationTargetId,
+
+    /// The axes of the target's translation to extract, in the space of the
+    /// target's parent.
+    ///
+    /// Defaults to the horizontal axes X and Z, so that the root keeps the
+    /// vertical motion of jumps and crouches.
+    pub translation_axes: BVec3,
+
+    /// Whether to extract the rotation of the target around the Y axis.
+    pub yaw: bool,
+
+    /// Whether to apply the extracted motion to the [`Transform`] of this
+    /// entity.
+    ///
+    /// If this is false, the motion is only written to [`RootMotionDelta`],
+    /// for example so that a character controller can apply it.
+    pub apply_to_transform: bool,
+}
+
+impl RootMotion {
+    /// Creates a [`RootMotion`] that extracts the horizontal translation and
+    /// the yaw of the given target, without applying them.
+    pub fn new(target: AnimationTargetId) -> Self {
+        Self {
+            target,
+            translation_axes: BVec3::new(true, false, true),
+            yaw: true,
+            apply_to_transform: false,
+        }
+    }
+
+    /// Sets the axes of the target's translation to extract.
+    pub fn with_translation_axes(mut self, translation_axes: BVec3) -> Self {
+        self.translation_axes = translation_axes;
+        self
+    }
+
+    /// Sets whether to extract the rotation of the target around the Y axis.
+    pub fn with_yaw(mut self, yaw: bool) -> Self {
+        self.yaw = yaw;
+        self
+    }
+
+    /// Makes the extracted motion move the [`Transform`] of this entity.
+    pub fn applied_to_transform(mut self) -> Self {
+        self.apply_to_transform = true;
+        self
+    }
+}
+
+/// The motion extracted by [`RootMotion`] during the last update.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Clone, Debug, Default, PartialEq)]
+pub struct RootMotionDelta {
+    /// The translation, in the local space of the entity with the
+    /// [`RootMotion`] component.
+    pub translation: Vec3,
+    /// The rotation around the local Y axis, in radians.
+    pub yaw: f32,
+}
+
+/// A system that extracts the motion of [`RootMotion`] targets after the
+/// animations have been applied, and optionally moves their players with it.
+pub fn extract_root_motion(
+    clips: Res<Assets<AnimationClip>>,
+    graphs: Res<Assets<AnimationGraph>>,
+    threaded_animation_graphs: Res<ThreadedAnimationGraphs>,
+    mut players: Query<
+        (
+            &AnimationPlayer,
+            &AnimationGraphHandle,
+            &RootMotion,
+            &mut RootMotionDelta,
+        ),
+        Without<AnimationTarget>,
+    >,
+    targets: Query<(Entity, &AnimationTarget)>,
+    mut transforms: Query<(&mut Transform, Option<&ChildOf>)>,
+) {
+    for (_, _, _, mut delta) in &mut players {
+        delta.set_if_neq(RootMotionDelta::default());
+    }
+
+    for (target_entity, target) in &targets {
+        let player_entity = target.player;
+        let Ok((player, graph_handle, root_motion, mut delta)) = players.get_mut(player_entity)
+        else {
+            continue;
+        };
+        if target.id != root_motion.target {
+            continue;
+        }
+        let Some(graph) = graphs.get(graph_handle) else {
+            continue;
+        };
+        let Some(threaded_graph) = threaded_animation_graphs.0.get(&graph_handle.id()) else {
+            continue;
+        };
+
+        let (mut translation, yaw) = sample_root_motion(
+            &clips,
+            graph,
+            threaded_graph,
+            player,
+            target.id,
+            root_motion.yaw,
+        );
+        translation = Vec3::select(root_motion.translation_axes, translation, Vec3::ZERO);
+
+        // Pin the extracted part of the target in place.
+        let Ok((mut target_transform, child_of)) = transforms.get_mut(target_entity) else {
+            continue;
+        };
+        let parent = child_of.map(ChildOf::parent);
+        let target_transform = &mut *target_transform;
+        target_transform.translation = Vec3::select(
+            root_motion.translation_axes,
+            Vec3::ZERO,
+            target_transform.translation,
+        );
+        if root_motion.yaw {
+            let (target_yaw, _, _) = target_transform.rotation.to_euler(EulerRot::YXZ);
+            target_transform.rotation =
+                Quat::from_rotation_y(-target_yaw) * target_transform.rotation;
+        }
+
+        // The curves animate the target relative to its parent, which isn't
+        // necessarily the player.
+        if let Some(parent) = parent
+            && parent != player_entity
+        {
+            translation =
+                parent_to_player(parent, player_entity, &transforms).transform_vector3(translation);
+        }
+
+        delta.set_if_neq(RootMotionDelta { translation, yaw });
+
+        if root_motion.apply_to_transform
+            && let Ok((mut player_transform, _)) = transforms.get_mut(player_entity)
+        {
+            let player_transform = &mut *player_transform;
+            player_transform.translation +=
+                player_transform.rotation * (player_transform.scale * translation);
+            player_transform.rotate_local_y(yaw);
+        }
+    }
+}
+
+/// Computes the transform from the space of `parent` to the space of
+/// `player` from the local transforms of their ancestors.
+///
+/// This doesn't use [`GlobalTransform`], which isn't propagated yet and would
+/// lag one frame behind the animation.
+fn parent_to_player(
+    parent: Entity,
+    player: Entity,
+    transforms: &Query<(&mut Transform, Option<&ChildOf>)>,
+) -> Affine3A {
+    let mut relative = GlobalTransform::IDENTITY;
+    let mut current = Some(parent);
+    while let Some(entity) = current
+        && entity != player
+        && let Ok((transform, child_of)) = transforms.get(entity)
+    {
+        relative = GlobalTransform::from(*transform) * relative;
+        current = child_of.map(ChildOf::parent);
+    }
+    if current == Some(player) {
+        return relative.affine();
+    }
+
+    // The player isn't an ancestor of the parent, so go through the world.
+    let mut player_global = GlobalTransform::IDENTITY;
+    let mut current = Some(player);
+    while let Some(entity) = current
+        && let Ok((transform, child_of)) = transforms.get(entity)
+    {
+        player_global = GlobalTransform::from(*transform) * player_global;
+        current = child_of.map(ChildOf::parent);
+    }
+    player_global.affine().inverse() * relative.affine()
+}
+
+/// Returns the translation and yaw of the given target between the previous
+/// and the current seek times of the active animations, blended through the
+/// graph in the same way as the animated values.
+fn sample_root_motion(
+    clips: &Assets<AnimationClip>,
+    graph: &AnimationGraph,
+    threaded_graph: &ThreadedAnimationGraph,
+    player: &AnimationPlayer,
+    target: AnimationTargetId,
+    extract_yaw: bool,
+) -> (Vec3, f32) {
+    let translation_field = animated_field!(Transform::translation);
+    let rotation_field = animated_field!(Transform::rotation);
+    let fields = RootFields {
+        translation: translation_field.evaluator_id(),
+        rotation: rotation_field.evaluator_id(),
+        extract_yaw,
+    };
+
+    let target_mask = graph.mask_groups.get(&target).copied().unwrap_or_default();
+
+    let mut translations = BasicAnimationCurveEvaluator::<Vec3>::default();
+    let mut yaws = BasicAnimationCurveEvaluator::<f32>::default();
+
+    for &node_index in threaded_graph.threaded_graph.iter() {
+        let Some(node) = graph.get(node_index) else {
+            continue;
+        };
+        let children = threaded_graph.sorted_edge_ranges[node_index.index()]
+            .clone()
+            .map(|edge_index| threaded_graph.sorted_edges[edge_index as usize]);
+
+        match node.node_type {
+            AnimationNodeType::Blend
+            | AnimationNodeType::StateMachine(_)
+            | AnimationNodeType::BlendSpace(_)
+            | AnimationNodeType::Add => {
+                let additive = matches!(node.node_type, AnimationNodeType::Add);
+                for child in children {
+                    // Combining never fails for the basic evaluator.
+                    let _ = translations.combine(child, additive);
+                    let _ = yaws.combine(child, additive);
+                }
+
+                let weight = node.weight * player.node_weight(node_index);
+                let _ = translations.push_blend_register(weight, node_index);
+                let _ = yaws.push_blend_register(weight, node_index);
+            }
+
+            AnimationNodeType::Clip(ref clip_handle) => {
+                let Some(active_animation) = player.active_animations.get(&node_index) else {
+                    continue;
+                };
+                let node_weight = player.node_weight(node_index);
+                if active_animation.weight == 0.0
+                    || node_weight == 0.0
+                    || (target_mask & threaded_graph.computed_masks[node_index.index()]) != 0
+                {
+                    continue;
+                }
+                let Some(curves) = clips
+                    .get(clip_handle)
+                    .and_then(|clip| Some((clip.curves_for_target(target)?, clip.duration)))
+                else {
+                    continue;
+                };
+
+                let (translation, yaw) = fields.clip_motion(curves.0, curves.1, active_animation);
+                let weight = active_animation.weight * node.weight * node_weight;
+                translations
+                    .stack
+                    .push(BasicAnimationCurveEvaluatorStackElement {
+                        value: translation,
+                        weight,
+                        graph_node: node_index,
+                    });
+                yaws.stack.push(BasicAnimationCurveEvaluatorStackElement {
+                    value: yaw,
+                    weight,
+                    graph_node: node_index,
+                });
+            }
+        }
+    }
+
+    (
+        translations
+            .stack
+            .pop()
+            .map_or(Vec3::ZERO, |element| element.value),
+        yaws.stack.pop().map_or(0.0, |element| element.value),
+    )
+}
+
+/// The evaluator IDs of the animated fields that root motion is extracted
+/// from.
+struct RootFields<'a> {
+    translation: EvaluatorId<'a>,
+    rotation: EvaluatorId<'a>,
+    extract_yaw: bool,
+}
+
+impl RootFields<'_> {
+    /// Returns the translation and yaw described by the root curves of a clip
+    /// since the last time it was advanced.
+    ///
+    /// If the yaw is extracted, the translation is relative to the heading of
+    /// the root at the last seek time rather than to the clip, since that
+    /// heading has already been applied to the player.
+    fn clip_motion(
+        &self,
+        curves: &[VariableCurve],
+        duration: f32,
+        active_animation: &ActiveAnimation,
+    ) -> (Vec3, f32) {
+        let Some(last_seek_time) = active_animation.last_seek_time else {
+            return (Vec3::ZERO, 0.0);
+        };
+        if active_animation.paused {
+            return (Vec3::ZERO, 0.0);
+        }
+        let seek_time = active_animation.seek_time;
+
+        let mut translation_curve = None;
+        let mut rotation_curve = None;
+        for curve in curves {
+            let evaluator_id = curve.0.evaluator_id();
+            if evaluator_id == self.translation {
+                translation_curve = Some(&*curve.0);
+            } else if self.extract_yaw && evaluator_id == self.rotation {
+                rotation_curve = Some(&*curve.0);
+            }
+        }
+        let mut translation_sampler = translation_curve.map(CurveSampler::new);
+        let mut rotation_sampler = rotation_curve.map(CurveSampler::new);
+        let mut position = |time| {
+            translation_sampler
+                .as_mut()
+                .map_or(Vec3::ZERO, |sampler| sampler.sample(time))
+        };
+        let mut yaw_at = |time| {
+            rotation_sampler
+                .as_mut()
+                .map_or(0.0, |sampler| yaw_of(sampler.sample(time)))
+        };
+        let heading = |yaw: f32| Quat::from_rotation_y(-yaw);
+
+        let last_yaw = yaw_at(last_seek_time);
+        let seek_yaw = yaw_at(seek_time);
+        if active_animation.just_completed && !active_animation.is_finished() {
+            // The clip wrapped around, so add the motion up to the end to the
+            // motion from the start, turned by the yaw up to the end.
+            let (start, end) = loop_bounds(active_animation, duration);
+            let start_yaw = yaw_at(start);
+            let yaw_to_end = wrap_angle(yaw_at(end) - last_yaw);
+            let translation = heading(last_yaw) * (position(end) - position(last_seek_time))
+                + Quat::from_rotation_y(yaw_to_end)
+                    * (heading(start_yaw) * (position(seek_time) - position(start)));
+            let yaw = yaw_to_end + wrap_angle(seek_yaw - start_yaw);
+            (translation, yaw)
+        } else {
+            let translation = heading(last_yaw) * (position(seek_time) - position(last_seek_time));
+            let yaw = wrap_angle(seek_yaw - last_yaw);
+            (translation, yaw)
+        }
+    }
+}
+
+/// Returns the times at which a looping clip leaves and re-enters its domain,
+/// depending on the direction it plays in.
+fn loop_bounds(active_animation: &ActiveAnimation, duration: f32) -> (f32, f32) {
+    if active_animation.speed < 0.0 {
+        (duration, 0.0)
+    } else {
+        (0.0, duration)
+    }
+}
+
+/// Samples a curve at several times, reusing the same evaluator.
+struct CurveSampler<'a> {
+    curve: &'a dyn AnimationCurve,
+    evaluator: Box<dyn AnimationCurveEvaluator>,
+}
+
+impl<'a> CurveSampler<'a> {
+    fn new(curve: &'a dyn AnimationCurve) -> Self {
+        Self {
+            curve,
+            evaluator: curve.create_evaluator(),
+        }
+    }
+
+    /// Samples the curve, which animates a value of type `A`, at the given
+    /// time, returning the zero value if the curve can't be sampled.
+    fn sample<A>(&mut self, time: f32) -> A
+    where
+        A: crate::animatable::Animatable + Default,
+    {
+        if self
+            .curve
+            .apply(&mut *self.evaluator, time, 1.0, AnimationNodeIndex::new(0))
+            .is_err()
+        {
+            return A::default();
+        }
+        self.evaluator
+            .downcast_mut::<AnimatableCurveEvaluator<A>>()
+            .and_then(AnimatableCurveEvaluator::pop_value)
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the rotation around the Y axis of the given rotation.
+fn yaw_of(rotation: Quat) -> f32 {
+    rotation.to_euler(EulerRot::YXZ).0
+}
+
+/// Wraps an angle difference to the range `[-π, π]`.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::FRAC_PI_2;
+
+    use bevy_asset::{Assets, Handle};
+    use bevy_ecs::{prelude::*, system::RunSystemOnce};
+    use bevy_math::{ops, Quat, Vec3};
+    use bevy_transform::components::Transform;
+
+    use super::{extract_root_motion, sample_root_motion, RootMotion, RootMotionDelta};
+    use crate::{
+        animated_field,
+        animation_curves::{AnimatableCurve, AnimatableKeyframeCurve, AnimatedField},
+        graph::{
+            AnimationGraph, AnimationGraphHandle, ThreadedAnimationGraph, ThreadedAnimationGraphs,
+        },
+        AnimationClip, AnimationPlayer, AnimationTarget, AnimationTargetId,
+    };
+
+    fn walk_clip(target: AnimationTargetId, velocity: Vec3) -> AnimationClip {
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                animated_field!(Transform::translation),
+                AnimatableKeyframeCurve::new([(0.0, Vec3::ZERO), (1.0, velocity)]).unwrap(),
+            ),
+        );
+        clip
+    }
+
+    #[test]
+    fn loops_accumulate_motion() {
+        let target = AnimationTargetId::from_name(&"root".into());
+        let mut clips = Assets::<AnimationClip>::default();
+        let clip = clips.add(walk_clip(target, Vec3::new(0.0, 0.0, 2.0)));
+        let (graph, node) = AnimationGraph::from_clip(clip);
+        let threaded_graph = ThreadedAnimationGraph::from_graph(&graph);
+
+        let mut player = AnimationPlayer::default();
+        player.play(node).repeat();
+        player
+            .active_animations
+            .get_mut(&node)
+            .unwrap()
+            .seek_to(0.8);
+
+        let advance = |player: &mut AnimationPlayer| {
+            player
+                .active_animations
+                .get_mut(&node)
+                .unwrap()
+                .update(0.4, 1.0);
+            sample_root_motion(&clips, &graph, &threaded_graph, player, target, true)
+        };
+
+        let (translation, yaw) = advance(&mut player);
+        assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.0, 0.8), 1e-5));
+        assert_eq!(yaw, 0.0);
+
+        let (translation, _) = advance(&mut player);
+        assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.0, 0.8), 1e-5));
+    }
+
+    #[test]
+    fn blends_motion_and_yaw() {
+        let target = AnimationTargetId::from_name(&"root".into());
+        let mut clips = Assets::<AnimationClip>::default();
+        let forward = clips.add(walk_clip(target, Vec3::new(0.0, 0.0, 1.0)));
+        let mut turn = walk_clip(target, Vec3::new(1.0, 0.0, 0.0));
+        turn.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                animated_field!(Transform::rotation),
+                AnimatableKeyframeCurve::new([
+                    (0.0, Quat::IDENTITY),
+                    (1.0, Quat::from_rotation_y(1.0)),
+                ])
+                .unwrap(),
+            ),
+        );
+        let turn: Handle<AnimationClip> = clips.add(turn);
+
+        let mut graph = AnimationGraph::new();
+        let forward = graph.add_clip(forward, 1.0, graph.root);
+        let turn = graph.add_clip(turn, 1.0, graph.root);
+        let threaded_graph = ThreadedAnimationGraph::from_graph(&graph);
+
+        let mut player = AnimationPlayer::default();
+        player.play(forward).set_weight(3.0);
+        player.play(turn);
+        for active_animation in player.active_animations.values_mut() {
+            active_animation.update(0.5, 1.0);
+        }
+
+        let (translation, yaw) =
+            sample_root_motion(&clips, &graph, &threaded_graph, &player, target, true);
+        assert!(translation.abs_diff_eq(Vec3::new(0.125, 0.0, 0.375), 1e-5));
+        assert!((yaw - 0.125).abs() < 1e-5);
+
+        // Masking the root out of the turning clip leaves only the other one.
+        graph.add_target_to_mask_group(target, 0);
+        graph.get_mut(turn).unwrap().add_mask_group(0);
+        let threaded_graph = ThreadedAnimationGraph::from_graph(&graph);
+        let (translation, yaw) =
+            sample_root_motion(&clips, &graph, &threaded_graph, &player, target, true);
+        assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.0, 0.5), 1e-5));
+        assert_eq!(yaw, 0.0);
+    }
+
+    #[test]
+    fn turning_walk_follows_its_path() {
+        // The root walks a quarter circle of radius 1 around (1, 0, 0),
+        // always facing the direction it walks in.
+        let target = AnimationTargetId::from_name(&"root".into());
+        let times = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let path = |time: f32| {
+            let angle = time * FRAC_PI_2;
+            Vec3::new(1.0 - ops::cos(angle), 0.0, ops::sin(angle))
+        };
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                animated_field!(Transform::translation),
+                AnimatableKeyframeCurve::new(times.map(|time| (time, path(time)))).unwrap(),
+            ),
+        );
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                animated_field!(Transform::rotation),
+                AnimatableKeyframeCurve::new(
+                    times.map(|time| (time, Quat::from_rotation_y(time * FRAC_PI_2))),
+                )
+                .unwrap(),
+            ),
+        );
+
+        let mut world = World::new();
+        let clip = world
+            .get_resource_or_init::<Assets<AnimationClip>>()
+            .add(clip);
+        let (graph, node) = AnimationGraph::from_clip(clip);
+        let threaded_graph = ThreadedAnimationGraph::from_graph(&graph);
+        let graph = world
+            .get_resource_or_init::<Assets<AnimationGraph>>()
+            .add(graph);
+        world
+            .get_resource_or_init::<ThreadedAnimationGraphs>()
+            .0
+            .insert(graph.id(), threaded_graph);
+
+        let mut player = AnimationPlayer::default();
+        player.play(node).repeat();
+        let player = world
+            .spawn((
+                player,
+                AnimationGraphHandle(graph),
+                RootMotion::new(target).applied_to_transform(),
+                Transform::default(),
+            ))
+            .id();
+        world.spawn((
+            AnimationTarget { id: target, player },
+            Transform::default(),
+            ChildOf(player),
+        ));
+
+        // Play the clip one and a half times, wrapping around once.
+        for _ in 0..6 {
+            world
+                .get_mut::<AnimationPlayer>(player)
+                .unwrap()
+                .active_animations
+                .get_mut(&node)
+                .unwrap()
+                .update(0.25, 1.0);
+            world.run_system_once(extract_root_motion).unwrap();
+        }
+
+        let transform = world.get::<Transform>(player).unwrap();
+        let expected = path(1.0) + Quat::from_rotation_y(FRAC_PI_2) * path(0.5);
+        assert!(transform.translation.abs_diff_eq(expected, 1e-4));
+        assert!(transform
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_y(3.0 * FRAC_PI_2 / 2.0), 1e-4));
+        let delta = world.get::<RootMotionDelta>(player).unwrap();
+        assert!((delta.yaw - FRAC_PI_2 / 4.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,941 @@
+//! Animation state machines, which switch between subgraphs of an
+//! [`AnimationGraph`] in response to named parameters.
+
+use core::iter;
+
+use bevy_asset::Assets;
+use bevy_ecs::{
+    entity::Entity,
+    system::{Query, Res},
+};
+use bevy_math::{curve::EaseFunction, Curve};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_time::DomainTime;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::{
+        AnimationGraph, AnimationGraphHandle, AnimationGraphLoadError, AnimationNodeIndex,
+        AnimationNodeType,
+    },
+    AnimationClip, AnimationPlayer, RepeatAnimation,
+};
+
+/// A state machine that plays one of its states at a time, cross-fading
+/// between states when a transition fires.
+///
+/// This is the payload of an [`AnimationNodeType::StateMachine`] node. Each
+/// [`AnimationState`] refers to a child of that node: the root of the subgraph
+/// that is played while the state is active. During evaluation, the node blends
+/// its children like a [blend node], with the weights of the children replaced
+/// by the weights of their states, so that inactive states contribute nothing.
+///
+/// Transitions are checked in order every frame, and the first one whose
+/// conditions hold fires. While a cross-fade is in progress no other transition
+/// can fire. The parameters that conditions refer to are set on the
+/// [`AnimationPlayer`], with methods like [`AnimationPlayer::set_float`].
+///
+/// The [`AnimationPlayer`] keeps track of which state each state machine is in,
+/// and starts and stops the clips of the states as they are entered and left.
+/// Clips in a state machine shouldn't be played manually.
+///
+/// [blend node]: AnimationNodeType::Blend
+#[derive(Clone, Debug, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, Default)]
+pub struct AnimationStateMachine {
+    /// The states of this state machine.
+    ///
+    /// States are referred to by their index in this list.
+    pub states: Vec<AnimationState>,
+    /// The index of the state that the state machine starts in.
+    pub initial_state: usize,
+    /// The transitions between states, in order of priority.
+    pub transitions: Vec<AnimationStateTransition>,
+}
+
+/// A single state of an [`AnimationStateMachine`].
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug)]
+pub struct AnimationState {
+    /// The name of the state.
+    pub name: String,
+    /// The root of the subgraph played in this state.
+    ///
+    /// This must be a child of the state machine node.
+    pub node: AnimationNodeIndex,
+    /// Whether the clips of this state repeat forever or stop after playing once.
+    pub repeat: bool,
+}
+
+/// A transition between two states of an [`AnimationStateMachine`].
+#[derive(Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug)]
+pub struct AnimationStateTransition {
+    /// The state this transition leaves, or `None` if it can fire from any other state.
+    pub from: Option<usize>,
+    /// The state this transition enters.
+    pub to: usize,
+    /// The conditions that must all hold for this transition to fire.
+    pub conditions: Vec<AnimationCondition>,
+    /// The fraction of the source state that must have played before this transition can fire.
+    ///
+    /// This is measured relative to the duration of the longest clip in the
+    /// state, so a value of 1.0 waits for that clip to play to the end once.
+    pub exit_time: Option<f32>,
+    /// How long the cross-fade to the new state lasts, in seconds.
+    pub duration: f32,
+    /// The easing of the cross-fade, mapping its progress to the weight of the new state.
+    pub curve: EaseFunction,
+}
+
+/// A condition on a named parameter of an [`AnimationPlayer`], used by
+/// [`AnimationStateTransition`]s.
+///
+/// Parameters that aren't set never satisfy [`Greater`](Self::Greater),
+/// [`Less`](Self::Less) or [`Trigger`](Self::Trigger), and read as `false`
+/// for [`Bool`](Self::Bool).
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum AnimationCondition {
+    /// The bool parameter with the given name has the given value.
+    Bool(String, bool),
+    /// The float parameter with the given name is greater than the given value.
+    Greater(String, f32),
+    /// The float parameter with the given name is less than the given value.
+    Less(String, f32),
+    /// The trigger with the given name has been set.
+    ///
+    /// Triggers are reset once a transition that depends on them fires.
+    Trigger(String),
+}
+
+/// The value of a named parameter of an [`AnimationPlayer`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum AnimationParameter {
+    /// A boolean parameter.
+    Bool(bool),
+    /// A float parameter.
+    Float(f32),
+    /// A trigger, which stays set until a transition consumes it.
+    Trigger,
+}
+
+/// The state of an [`AnimationStateMachine`] on a particular [`AnimationPlayer`].
+#[derive(Clone, Copy, Debug, Reflect)]
+#[reflect(Clone, Debug)]
+pub struct ActiveStateMachine {
+    current_state: usize,
+    time_in_state: f32,
+    transition: Option<ActiveStateTransition>,
+}
+
+/// A cross-fade in progress.
+#[derive(Clone, Copy, Debug, Reflect)]
+#[reflect(Clone, Debug)]
+struct ActiveStateTransition {
+    from: usize,
+    elapsed: f32,
+    duration: f32,
+    curve: EaseFunction,
+}
+
+impl AnimationStateMachine {
+    /// Creates a new state machine with no states or transitions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state that plays the subgraph rooted at `node`, and returns its index.
+    ///
+    /// The clips of the state repeat forever; use [`Self::add_state_once`]
+    /// for states that should only play once.
+    pub fn add_state(&mut self, name: impl Into<String>, node: AnimationNodeIndex) -> usize {
+        self.push_state(name.into(), node, true)
+    }
+
+    /// Adds a state that plays the subgraph rooted at `node` once, and returns
+    /// its index.
+    pub fn add_state_once(&mut self, name: impl Into<String>, node: AnimationNodeIndex) -> usize {
+        self.push_state(name.into(), node, false)
+    }
+
+    fn push_state(&mut self, name: String, node: AnimationNodeIndex, repeat: bool) -> usize {
+        self.states.push(AnimationState { name, node, repeat });
+        self.states.len() - 1
+    }
+
+    /// Adds a transition with a lower priority than all existing ones.
+    pub fn add_transition(&mut self, transition: AnimationStateTransition) -> &mut Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    /// Returns the index of the state with the given name, if any.
+    pub fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|state| state.name == name)
+    }
+
+    /// Checks that the initial state and the transitions refer to states of
+    /// this state machine.
+    ///
+    /// The initial state of a state machine without any states isn't checked.
+    pub(crate) fn validate_state_indices(&self) -> Result<(), AnimationGraphLoadError> {
+        let initial_state = (!self.states.is_empty()).then_some(self.initial_state);
+        let transition_states = self
+            .transitions
+            .iter()
+            .flat_map(|transition| transition.from.into_iter().chain([transition.to]));
+        match initial_state
+            .into_iter()
+            .chain(transition_states)
+            .find(|&state| state >= self.states.len())
+        {
+            Some(state) => Err(AnimationGraphLoadError::InvalidStateIndex(state)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl AnimationStateTransition {
+    /// Creates an instant transition between two states without any conditions.
+    pub fn new(from: usize, to: usize) -> Self {
+        Self {
+            from: Some(from),
+            to,
+            conditions: Vec::new(),
+            exit_time: None,
+            duration: 0.0,
+            curve: EaseFunction::Linear,
+        }
+    }
+
+    /// Creates an instant transition from any other state, without any conditions.
+    pub fn from_any(to: usize) -> Self {
+        Self {
+            from: None,
+            ..Self::new(0, to)
+        }
+    }
+
+    /// Adds a condition that must hold for this transition to fire.
+    pub fn with_condition(mut self, condition: AnimationCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Sets the [exit time](Self::exit_time) of this transition.
+    pub fn with_exit_time(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+
+    /// Sets the duration of the cross-fade, in seconds.
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the easing of the cross-fade.
+    pub fn with_curve(mut self, curve: EaseFunction) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    fn can_leave(&self, state: usize) -> bool {
+        match self.from {
+            Some(from) => from == state,
+            None => self.to != state,
+        }
+    }
+}
+
+impl AnimationCondition {
+    fn holds(&self, parameters: &HashMap<String, AnimationParameter>) -> bool {
+        match self {
+            AnimationCondition::Bool(name, value) => {
+                let current = matches!(parameters.get(name), Some(AnimationParameter::Bool(true)));
+                current == *value
+            }
+            AnimationCondition::Greater(name, threshold) => matches!(
+                parameters.get(name),
+                Some(AnimationParameter::Float(value)) if value > threshold
+            ),
+            AnimationCondition::Less(name, threshold) => matches!(
+                parameters.get(name),
+                Some(AnimationParameter::Float(value)) if value < threshold
+            ),
+            AnimationCondition::Trigger(name) => {
+                matches!(parameters.get(name), Some(AnimationParameter::Trigger))
+            }
+        }
+    }
+}
+
+impl ActiveStateMachine {
+    /// Returns the index of the state the state machine is in.
+    ///
+    /// During a cross-fade, this is the state being faded in.
+    pub fn current_state(&self) -> usize {
+        self.current_state
+    }
+
+    /// Returns how long the current state has been active, in seconds.
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// Returns the state being faded out, if a cross-fade is in progress.
+    pub fn previous_state(&self) -> Option<usize> {
+        self.transition.map(|transition| transition.from)
+    }
+
+    /// Returns whether the states this refers to exist in the given state machine.
+    fn fits(&self, machine: &AnimationStateMachine) -> bool {
+        let states = machine.states.len();
+        self.current_state < states
+            && self
+                .transition
+                .is_none_or(|transition| transition.from < states)
+    }
+
+    /// Returns the weight of the current state, which is less than 1.0 during a cross-fade.
+    pub fn blend_factor(&self) -> f32 {
+        self.transition.map_or(1.0, |transition| {
+            if transition.duration > 0.0 {
+                transition
+                    .curve
+                    .sample_clamped(transition.elapsed / transition.duration)
+            } else {
+                1.0
+            }
+        })
+    }
+}
+
+/// A system that advances the [`AnimationStateMachine`]s of every
+/// [`AnimationPlayer`], firing transitions and starting and stopping the
+/// clips of the states.
+pub fn advance_state_machines(
+    domain_time: DomainTime,
+    animation_clips: Res<Assets<AnimationClip>>,
+    animation_graphs: Res<Assets<AnimationGraph>>,
+    mut players: Query<(Entity, &mut AnimationPlayer, &AnimationGraphHandle)>,
+) {
+    players
+        .par_iter_mut()
+        .for_each(|(entity, mut player, graph_handle)| {
+            let Some(animation_graph) = animation_graphs.get(graph_handle) else {
+                return;
+            };
+            let delta_seconds = domain_time.delta_secs(entity);
+
+            advance_player_state_machines(
+                &mut player,
+                animation_graph,
+                &animation_clips,
+                delta_seconds,
+            );
+        });
+}
+
+/// Advances all the active state machines of a player by `delta_seconds`.
+fn advance_player_state_machines(
+    player: &mut AnimationPlayer,
+    animation_graph: &AnimationGraph,
+    clips: &Assets<AnimationClip>,
+    delta_seconds: f32,
+) {
+    // Walk the graph from the root, only descending into the active
+    // states of each state machine, so that state machines nested in
+    // inactive states stay dormant. Parents are advanced before their
+    // children.
+    let mut visited = HashSet::new();
+    let mut stack = vec![animation_graph.root];
+    while let Some(node_index) = stack.pop() {
+        if !visited.insert(node_index) {
+            continue;
+        }
+
+        let Some(node) = animation_graph.get(node_index) else {
+            continue;
+        };
+        let AnimationNodeType::StateMachine(ref machine) = node.node_type else {
+            stack.extend(
+                animation_graph
+                    .graph
+                    .neighbors_directed(node_index, Direction::Outgoing),
+            );
+            continue;
+        };
+
+        advance_state_machine(
+            player,
+            animation_graph,
+            clips,
+            node_index,
+            machine,
+            delta_seconds,
+        );
+
+        if let Some(active) = player.state_machines.get(&node_index) {
+            stack.extend(
+                iter::once(active.current_state)
+                    .chain(active.previous_state())
+                    .filter_map(|state| machine.states.get(state))
+                    .map(|state| state.node),
+            );
+        }
+    }
+}
+
+/// Returns the nodes of a graph that are reachable from its root, without
+/// descending into the inactive states of state machines.
+pub(crate) fn active_nodes(
+    animation_graph: &AnimationGraph,
+    player: &AnimationPlayer,
+) -> Vec<AnimationNodeIndex> {
+    let mut nodes = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![animation_graph.root];
+    while let Some(node_index) = stack.pop() {
+        if !visited.insert(node_index) {
+            continue;
+        }
+        let Some(node) = animation_graph.get(node_index) else {
+            continue;
+        };
+        nodes.push(node_index);
+
+        match node.node_type {
+            AnimationNodeType::StateMachine(ref machine) => {
+                if let Some(active) = player.state_machines.get(&node_index) {
+                    stack.extend(
+                        iter::once(active.current_state)
+                            .chain(active.previous_state())
+                            .filter_map(|state| machine.states.get(state))
+                            .map(|state| state.node),
+                    );
+                }
+            }
+            _ => stack.extend(
+                animation_graph
+                    .graph
+                    .neighbors_directed(node_index, Direction::Outgoing),
+            ),
+        }
+    }
+    nodes
+}
+
+/// Advances a single state machine by `delta_seconds`.
+fn advance_state_machine(
+    player: &mut AnimationPlayer,
+    graph: &AnimationGraph,
+    clips: &Assets<AnimationClip>,
+    node_index: AnimationNodeIndex,
+    machine: &AnimationStateMachine,
+    delta_seconds: f32,
+) {
+    let Some(initial_state) = machine.states.get(machine.initial_state) else {
+        return;
+    };
+
+    // Start over if the player is in a state that no longer exists, for
+    // example because the graph was reloaded with fewer states.
+    let Some(mut active) = player
+        .state_machines
+        .get(&node_index)
+        .copied()
+        .filter(|active| active.fits(machine))
+    else {
+        enter_state(player, graph, initial_state);
+        player.state_machines.insert(
+            node_index,
+            ActiveStateMachine {
+                current_state: machine.initial_state,
+                time_in_state: 0.0,
+                transition: None,
+            },
+        );
+        update_state_weights(player, machine, node_index);
+        return;
+    };
+
+    active.time_in_state += delta_seconds;
+    if let Some(transition) = active.transition.as_mut() {
+        transition.elapsed += delta_seconds;
+        if transition.elapsed >= transition.duration {
+            let from = transition.from;
+            active.transition = None;
+            exit_state(player, graph, machine, from, active.current_state);
+        }
+    }
+
+    if active.transition.is_none()
+        && let Some((transition, to_state)) = machine.transitions.iter().find_map(|transition| {
+            let to_state = machine.states.get(transition.to)?;
+            let fires = transition.can_leave(active.current_state)
+                && transition
+                    .conditions
+                    .iter()
+                    .all(|condition| condition.holds(&player.parameters))
+                && transition.exit_time.is_none_or(|exit_time| {
+                    let duration = machine
+                        .states
+                        .get(active.current_state)
+                        .map_or(0.0, |state| state_duration(graph, clips, state));
+                    active.time_in_state >= exit_time * duration
+                });
+            fires.then_some((transition, to_state))
+        })
+    {
+        for condition in &transition.conditions {
+            if let AnimationCondition::Trigger(name) = condition {
+                player.parameters.remove(name);
+            }
+        }
+
+        let from = active.current_state;
+        active.current_state = transition.to;
+        active.time_in_state = 0.0;
+        // A state restarting in place can't be cross-faded with itself.
+        enter_state(player, graph, to_state);
+        if from != transition.to {
+            if transition.duration > 0.0 {
+                active.transition = Some(ActiveStateTransition {
+                    from,
+                    elapsed: 0.0,
+                    duration: transition.duration,
+                    curve: transition.curve,
+                });
+            } else {
+                exit_state(player, graph, machine, from, transition.to);
+            }
+        }
+    }
+
+    player.state_machines.insert(node_index, active);
+    update_state_weights(player, machine, node_index);
+}
+
+/// Writes the weights of all states of a state machine into the player.
+fn update_state_weights(
+    player: &mut AnimationPlayer,
+    machine: &AnimationStateMachine,
+    node_index: AnimationNodeIndex,
+) {
+    let Some(active) = player.state_machines.get(&node_index).copied() else {
+        return;
+    };
+    let blend_factor = active.blend_factor();
+
+    for (index, state) in machine.states.iter().enumerate() {
+        let weight = if index == active.current_state {
+            blend_factor
+        } else if active.previous_state() == Some(index) {
+            1.0 - blend_factor
+        } else {
+            0.0
+        };
+        player.node_weights.insert(state.node, weight);
+    }
+}
+
+/// Starts all clips of a state from the beginning.
+fn enter_state(player: &mut AnimationPlayer, graph: &AnimationGraph, state: &AnimationState) {
+    let repeat = if state.repeat {
+        RepeatAnimation::Forever
+    } else {
+        RepeatAnimation::Never
+    };
+
+    let mut nodes = Vec::new();
+    collect_state_nodes(graph, state.node, false, &mut nodes);
+    for node in nodes {
+        if graph
+            .get(node)
+            .is_some_and(|node| matches!(node.node_type, AnimationNodeType::Clip(_)))
+        {
+            player.start(node).set_repeat(repeat);
+        }
+    }
+}
+
+/// Stops all clips of the state `from` that aren't also part of the state `to`,
+/// and resets the state machines nested in it.
+fn exit_state(
+    player: &mut AnimationPlayer,
+    graph: &AnimationGraph,
+    machine: &AnimationStateMachine,
+    from: usize,
+    to: usize,
+) {
+    let Some(from) = machine.states.get(from) else {
+        return;
+    };
+
+    let mut kept = Vec::new();
+    if let Some(to) = machine.states.get(to) {
+        collect_state_nodes(graph, to.node, true, &mut kept);
+    }
+    let kept: HashSet<_> = kept.into_iter().collect();
+
+    let mut nodes = Vec::new();
+    collect_state_nodes(graph, from.node, true, &mut nodes);
+    for node in nodes.into_iter().filter(|node| !kept.contains(node)) {
+        player.active_animations.remove(&node);
+        player.state_machines.remove(&node);
+    }
+}
+
+/// Returns the duration of the longest loaded clip in a state.
+fn state_duration(
+    graph: &AnimationGraph,
+    clips: &Assets<AnimationClip>,
+    state: &AnimationState,
+) -> f32 {
+    let mut nodes = Vec::new();
+    collect_state_nodes(graph, state.node, false, &mut nodes);
+    nodes
+        .into_iter()
+        .filter_map(|node| match graph.get(node)?.node_type {
+            AnimationNodeType::Clip(ref handle) => clips.get(handle),
+            _ => None,
+        })
+        .map(|clip| clip.duration)
+        .fold(0.0, f32::max)
+}
+
+/// Collects `root` and its descendants into `nodes`, skipping `root` if it
+/// isn't in the graph.
+///
+/// The descendants of nested state machines are only collected if
+/// `include_nested` is true, since those manage their own clips.
+fn collect_state_nodes(
+    graph: &AnimationGraph,
+    root: AnimationNodeIndex,
+    include_nested: bool,
+    nodes: &mut Vec<AnimationNodeIndex>,
+) {
+    let Some(node) = graph.get(root) else {
+        return;
+    };
+    nodes.push(root);
+    if !include_nested && matches!(node.node_type, AnimationNodeType::StateMachine(_)) {
+        return;
+    }
+    for child in graph.graph.neighbors_directed(root, Direction::Outgoing) {
+        collect_state_nodes(graph, child, include_nested, nodes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Vec2;
+    use petgraph::graph::DiGraph;
+
+    use super::*;
+    use crate::{
+        blend_space::AnimationBlendSpace,
+        graph::{
+            SerializedAnimationGraph, SerializedAnimationGraphNode, SerializedAnimationNodeType,
+        },
+    };
+
+    struct TestGraph {
+        graph: AnimationGraph,
+        clips: Assets<AnimationClip>,
+        machine: AnimationNodeIndex,
+        idle: AnimationNodeIndex,
+        run: AnimationNodeIndex,
+    }
+
+    fn test_graph(fade_duration: f32) -> TestGraph {
+        let mut clips = Assets::<AnimationClip>::default();
+        let mut idle_clip = AnimationClip::default();
+        idle_clip.set_duration(1.0);
+        let mut run_clip = AnimationClip::default();
+        run_clip.set_duration(2.0);
+
+        let mut graph = AnimationGraph::new();
+        let machine = graph.add_state_machine(AnimationStateMachine::new(), 1.0, graph.root);
+        let idle = graph.add_clip(clips.add(idle_clip), 1.0, machine);
+        let run = graph.add_clip(clips.add(run_clip), 1.0, machine);
+
+        let state_machine = graph.state_machine_mut(machine).unwrap();
+        let idle_state = state_machine.add_state("idle", idle);
+        let run_state = state_machine.add_state("run", run);
+        state_machine
+            .add_transition(
+                AnimationStateTransition::new(idle_state, run_state)
+                    .with_condition(AnimationCondition::Greater("speed".into(), 0.5))
+                    .with_duration(fade_duration),
+            )
+            .add_transition(
+                AnimationStateTransition::new(run_state, idle_state)
+                    .with_condition(AnimationCondition::Trigger("stop".into()))
+                    .with_exit_time(1.0),
+            );
+
+        TestGraph {
+            graph,
+            clips,
+            machine,
+            idle,
+            run,
+        }
+    }
+
+    impl TestGraph {
+        fn step(&self, player: &mut AnimationPlayer, delta_seconds: f32) {
+            advance_player_state_machines(player, &self.graph, &self.clips, delta_seconds);
+        }
+    }
+
+    #[test]
+    fn state_machine_cross_fades_between_states() {
+        let test = test_graph(0.5);
+        let mut player = AnimationPlayer::default();
+
+        test.step(&mut player, 0.1);
+        test.step(&mut player, 0.1);
+        let state = player.state_machine(test.machine).unwrap();
+        assert_eq!(state.current_state(), 0);
+        assert!(player.is_playing_animation(test.idle));
+        assert!(!player.is_playing_animation(test.run));
+        assert_eq!(player.node_weight(test.idle), 1.0);
+        assert_eq!(player.node_weight(test.run), 0.0);
+
+        player.set_float("speed", 1.0);
+        test.step(&mut player, 0.1);
+        let state = player.state_machine(test.machine).unwrap();
+        assert_eq!(state.current_state(), 1);
+        assert_eq!(state.previous_state(), Some(0));
+        assert!(player.is_playing_animation(test.idle));
+        assert!(player.is_playing_animation(test.run));
+        assert_eq!(player.node_weight(test.run), 0.0);
+
+        test.step(&mut player, 0.25);
+        assert_eq!(player.node_weight(test.idle), 0.5);
+        assert_eq!(player.node_weight(test.run), 0.5);
+
+        test.step(&mut player, 0.3);
+        let state = player.state_machine(test.machine).unwrap();
+        assert_eq!(state.previous_state(), None);
+        assert!(!player.is_playing_animation(test.idle));
+        assert_eq!(player.node_weight(test.idle), 0.0);
+        assert_eq!(player.node_weight(test.run), 1.0);
+    }
+
+    #[test]
+    fn state_machine_waits_for_exit_time_and_consumes_triggers() {
+        let test = test_graph(0.0);
+        let mut player = AnimationPlayer::default();
+        player.set_float("speed", 1.0);
+
+        test.step(&mut player, 0.1);
+        test.step(&mut player, 0.1);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().current_state(),
+            1
+        );
+        // Instant transitions stop the previous state right away.
+        assert!(!player.is_playing_animation(test.idle));
+
+        player.set_trigger("stop");
+        test.step(&mut player, 1.0);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().current_state(),
+            1
+        );
+        assert_eq!(player.parameter("stop"), Some(AnimationParameter::Trigger));
+
+        test.step(&mut player, 1.5);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().current_state(),
+            0
+        );
+        assert_eq!(player.parameter("stop"), None);
+        assert!(player.is_playing_animation(test.idle));
+        assert!(!player.is_playing_animation(test.run));
+    }
+
+    #[test]
+    fn state_machine_round_trips_through_ron() {
+        let test = test_graph(0.25);
+        let machine = test.graph.state_machine(test.machine).unwrap();
+
+        let serialized = ron::to_string(machine).unwrap();
+        let deserialized: AnimationStateMachine = ron::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.state_index("run"), Some(1));
+        assert_eq!(deserialized.states[1].node, test.run);
+        assert_eq!(deserialized.transitions.len(), 2);
+        assert_eq!(
+            deserialized.transitions[1].conditions,
+            vec![AnimationCondition::Trigger("stop".into())]
+        );
+        assert_eq!(deserialized.transitions[0].duration, 0.25);
+    }
+
+    #[test]
+    fn dangling_state_nodes_are_rejected_on_load() {
+        let mut machine = AnimationStateMachine::new();
+        machine.add_state("idle", AnimationNodeIndex::new(1));
+        machine.add_state("missing", AnimationNodeIndex::new(7));
+
+        let mut graph = DiGraph::new();
+        let root = graph.add_node(SerializedAnimationGraphNode {
+            node_type: SerializedAnimationNodeType::StateMachine(machine),
+            mask: 0,
+            weight: 1.0,
+        });
+        let idle = graph.add_node(SerializedAnimationGraphNode {
+            node_type: SerializedAnimationNodeType::Blend,
+            mask: 0,
+            weight: 1.0,
+        });
+        graph.add_edge(root, idle, ());
+        let serialized = SerializedAnimationGraph {
+            graph,
+            root,
+            mask_groups: HashMap::default(),
+        };
+
+        assert!(matches!(
+            serialized.validate_indices(),
+            Err(AnimationGraphLoadError::InvalidNodeIndex(node)) if node.index() == 7
+        ));
+    }
+
+    #[test]
+    fn states_and_points_of_other_nodes_are_rejected_on_load() {
+        let node = |node_type| SerializedAnimationGraphNode {
+            node_type,
+            mask: 0,
+            weight: 1.0,
+        };
+        let clip = AnimationNodeIndex::new(3);
+        let mut machine = AnimationStateMachine::new();
+        machine.add_state("idle", clip);
+        let mut blend_space = AnimationBlendSpace::new_1d("speed");
+        blend_space.add_point(clip, Vec2::ZERO);
+
+        // The clip is a sibling of the state machine and the blend space, not
+        // one of their children.
+        let mut graph = DiGraph::new();
+        let root = graph.add_node(node(SerializedAnimationNodeType::Blend));
+        let machine = graph.add_node(node(SerializedAnimationNodeType::StateMachine(machine)));
+        let blend_space =
+            graph.add_node(node(SerializedAnimationNodeType::BlendSpace(blend_space)));
+        let clip = graph.add_node(node(SerializedAnimationNodeType::Blend));
+        for child in [machine, blend_space, clip] {
+            graph.add_edge(root, child, ());
+        }
+        let mut serialized = SerializedAnimationGraph {
+            graph,
+            root,
+            mask_groups: HashMap::default(),
+        };
+        assert!(matches!(
+            serialized.validate_indices(),
+            Err(AnimationGraphLoadError::InvalidChildNode { parent, child })
+                if parent == machine && child == clip
+        ));
+
+        serialized.graph.add_edge(machine, clip, ());
+        assert!(matches!(
+            serialized.validate_indices(),
+            Err(AnimationGraphLoadError::InvalidChildNode { parent, child })
+                if parent == blend_space && child == clip
+        ));
+
+        serialized.graph.add_edge(blend_space, clip, ());
+        assert!(serialized.validate_indices().is_ok());
+    }
+
+    #[test]
+    fn dangling_state_indices_are_rejected_on_load() {
+        let mut machine = AnimationStateMachine::new();
+        let idle = machine.add_state("idle", AnimationNodeIndex::new(0));
+        machine.add_transition(AnimationStateTransition::new(idle, 3));
+        assert!(matches!(
+            machine.validate_state_indices(),
+            Err(AnimationGraphLoadError::InvalidStateIndex(3))
+        ));
+
+        machine.transitions.clear();
+        machine.initial_state = 2;
+        assert!(matches!(
+            machine.validate_state_indices(),
+            Err(AnimationGraphLoadError::InvalidStateIndex(2))
+        ));
+    }
+
+    #[test]
+    fn stopping_all_animations_restarts_state_machines() {
+        let test = test_graph(0.0);
+        let mut player = AnimationPlayer::default();
+        player.set_float("speed", 1.0);
+        test.step(&mut player, 0.1);
+        test.step(&mut player, 0.1);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().current_state(),
+            1
+        );
+
+        player.set_float("speed", 0.0).stop_all();
+        assert!(player.state_machine(test.machine).is_none());
+        test.step(&mut player, 0.1);
+        let state = player.state_machine(test.machine).unwrap();
+        assert_eq!(state.current_state(), 0);
+        assert!(player.is_playing_animation(test.idle));
+        assert!(!player.is_playing_animation(test.run));
+        assert_eq!(player.node_weight(test.idle), 1.0);
+        assert_eq!(player.node_weight(test.run), 0.0);
+    }
+
+    #[test]
+    fn removed_states_reset_the_player() {
+        let mut test = test_graph(0.5);
+        let mut player = AnimationPlayer::default();
+        player.set_float("speed", 1.0);
+        test.step(&mut player, 0.1);
+        test.step(&mut player, 0.1);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().previous_state(),
+            Some(0)
+        );
+
+        // Edit the graph so that the state the player is in disappears.
+        let machine = test.graph.state_machine_mut(test.machine).unwrap();
+        machine.states.truncate(1);
+        machine.transitions.clear();
+        test.step(&mut player, 0.1);
+        let state = player.state_machine(test.machine).unwrap();
+        assert_eq!(state.current_state(), 0);
+        assert_eq!(state.previous_state(), None);
+        assert!(player.is_playing_animation(test.idle));
+    }
+
+    #[test]
+    fn missing_nodes_are_skipped_at_runtime() {
+        let mut test = test_graph(0.0);
+        let machine = test.graph.state_machine_mut(test.machine).unwrap();
+        machine.add_state("missing", AnimationNodeIndex::new(42));
+        machine.initial_state = 2;
+
+        let mut player = AnimationPlayer::default();
+        test.step(&mut player, 0.1);
+        test.step(&mut player, 0.1);
+        assert_eq!(
+            player.state_machine(test.machine).unwrap().current_state(),
+            2
+        );
+        assert!(!player.is_playing_animation(test.idle));
+        assert!(active_nodes(&test.graph, &player).contains(&test.machine));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{App, Plugin, PreUpdate};
+use bevy_ecs::{
+    intern::Interned,
+    schedule::{IntoScheduleConfigs, ScheduleLabel, SystemSet},
+    world::{run_async_world_jobs, AsyncWorld},
+};
+
+/// Adds the [`AsyncWorld`] resource and applies the jobs queued through it once per frame.
+///
+/// This allows tasks spawned on the [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool)
+/// to await access to the [`World`](bevy_ecs::world::World), for example to write multi-frame
+/// gameplay sequences as straight-line async code.
+///
+/// By default, the jobs are applied in [`PreUpdate`], in the [`AsyncWorldSystems`] set.
+/// Use [`AsyncWorldPlugin::new`] to choose a different schedule.
+pub struct AsyncWorldPlugin {
+    schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl AsyncWorldPlugin {
+    /// Constructs the plugin. Jobs queued through the [`AsyncWorld`] will be applied in the specified schedule.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for AsyncWorldPlugin {
+    fn default() -> Self {
+        Self::new(PreUpdate)
+    }
+}
+
+/// The [`SystemSet`] in which the jobs queued through the [`AsyncWorld`] are applied.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct AsyncWorldSystems;
+
+impl Plugin for AsyncWorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AsyncWorld>().add_systems(
+            self.schedule,
+            run_async_world_jobs.in_set(AsyncWorldSystems),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Update;
+    use bevy_ecs::{resource::Resource, system::ResMut};
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[test]
+    fn jobs_are_applied_before_update() {
+        let mut app = App::new();
+        app.add_plugins(AsyncWorldPlugin::default())
+            .init_resource::<Counter>()
+            .add_systems(Update, |mut counter: ResMut<Counter>| counter.0 += 1);
+
+        let async_world = app.world().resource::<AsyncWorld>().clone();
+        let mut future = pin!(async_world.run(|world| world.resource::<Counter>().0));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+
+        app.update();
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(0));
+    }
+}
@@ -38,6 +38,9 @@ pub struct PlaybackSettings {
     /// Volume to play at.
     pub volume: Volume,
     /// Speed to play at.
+    ///
+    /// For entities in a [`TimeDomain`](bevy_time::TimeDomain), this is scaled by the relative
+    /// speed of the domain.
     pub speed: f32,
     /// Create the sink in paused state.
     /// Useful for "deferred playback", if you want to prepare
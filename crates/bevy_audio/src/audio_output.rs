@@ -1,10 +1,12 @@
 use crate::{
-    AudioPlayer, Decodable, DefaultSpatialScale, GlobalVolume, PlaybackMode, PlaybackSettings,
+    effects::BoxedSource, AudioBusGains, AudioBusTarget, AudioMixer, AudioPlayer, BusGain,
+    BusRoute, Decodable, DefaultSpatialScale, GlobalVolume, PlaybackMode, PlaybackSettings,
     SpatialAudioSink, SpatialListener,
 };
 use bevy_asset::{Asset, Assets};
 use bevy_ecs::{prelude::*, system::SystemParam};
 use bevy_math::Vec3;
+use bevy_time::DomainTime;
 use bevy_transform::prelude::GlobalTransform;
 use rodio::{OutputStream, OutputStreamHandle, Sink, Source, SpatialSink};
 use tracing::warn;
@@ -98,12 +100,15 @@ pub(crate) fn play_queued_audio_system<Source: Asset + Decodable>(
     audio_output: Res<AudioOutput>,
     audio_sources: Res<Assets<Source>>,
     global_volume: Res<GlobalVolume>,
+    mixer: Res<AudioMixer>,
+    bus_gains: Res<AudioBusGains>,
     query_nonplaying: Query<
         (
             Entity,
             &AudioPlayer<Source>,
             &PlaybackSettings,
             Option<&GlobalTransform>,
+            Option<&AudioBusTarget>,
         ),
         (Without<AudioSink>, Without<SpatialAudioSink>),
     >,
@@ -118,10 +123,12 @@ pub(crate) fn play_queued_audio_system<Source: Asset + Decodable>(
         return;
     };
 
-    for (entity, source_handle, settings, maybe_emitter_transform) in &query_nonplaying {
+    for (entity, source_handle, settings, maybe_emitter_transform, bus_target) in &query_nonplaying
+    {
         let Some(audio_source) = audio_sources.get(&source_handle.0) else {
             continue;
         };
+        let bus_route = bus_target.and_then(|bus_target| bus_gains.route(&mixer, &bus_target.0));
         // audio data is available (has loaded), begin playback and insert sink component
         if settings.spatial {
             let (left_ear, right_ear) = ear_positions.get();
@@ -157,49 +164,7 @@ pub(crate) fn play_queued_audio_system<Source: Asset + Decodable>(
                 }
             };
 
-            let decoder = audio_source.decoder();
-
-            match settings.mode {
-                PlaybackMode::Loop => match (settings.start_position, settings.duration) {
-                    // custom start position and duration
-                    (Some(start_position), Some(duration)) => sink.append(
-                        decoder
-                            .skip_duration(start_position)
-                            .take_duration(duration)
-                            .repeat_infinite(),
-                    ),
-
-                    // custom start position
-                    (Some(start_position), None) => {
-                        sink.append(decoder.skip_duration(start_position).repeat_infinite());
-                    }
-
-                    // custom duration
-                    (None, Some(duration)) => {
-                        sink.append(decoder.take_duration(duration).repeat_infinite());
-                    }
-
-                    // full clip
-                    (None, None) => sink.append(decoder.repeat_infinite()),
-                },
-                PlaybackMode::Once | PlaybackMode::Despawn | PlaybackMode::Remove => {
-                    match (settings.start_position, settings.duration) {
-                        (Some(start_position), Some(duration)) => sink.append(
-                            decoder
-                                .skip_duration(start_position)
-                                .take_duration(duration),
-                        ),
-
-                        (Some(start_position), None) => {
-                            sink.append(decoder.skip_duration(start_position));
-                        }
-
-                        (None, Some(duration)) => sink.append(decoder.take_duration(duration)),
-
-                        (None, None) => sink.append(decoder),
-                    }
-                }
-            }
+            append_playback(&sink, audio_source.decoder(), settings, bus_route.as_ref());
 
             let mut sink = SpatialAudioSink::new(sink);
 
@@ -234,49 +199,7 @@ pub(crate) fn play_queued_audio_system<Source: Asset + Decodable>(
                 }
             };
 
-            let decoder = audio_source.decoder();
-
-            match settings.mode {
-                PlaybackMode::Loop => match (settings.start_position, settings.duration) {
-                    // custom start position and duration
-                    (Some(start_position), Some(duration)) => sink.append(
-                        decoder
-                            .skip_duration(start_position)
-                            .take_duration(duration)
-                            .repeat_infinite(),
-                    ),
-
-                    // custom start position
-                    (Some(start_position), None) => {
-                        sink.append(decoder.skip_duration(start_position).repeat_infinite());
-                    }
-
-                    // custom duration
-                    (None, Some(duration)) => {
-                        sink.append(decoder.take_duration(duration).repeat_infinite());
-                    }
-
-                    // full clip
-                    (None, None) => sink.append(decoder.repeat_infinite()),
-                },
-                PlaybackMode::Once | PlaybackMode::Despawn | PlaybackMode::Remove => {
-                    match (settings.start_position, settings.duration) {
-                        (Some(start_position), Some(duration)) => sink.append(
-                            decoder
-                                .skip_duration(start_position)
-                                .take_duration(duration),
-                        ),
-
-                        (Some(start_position), None) => {
-                            sink.append(decoder.skip_duration(start_position));
-                        }
-
-                        (None, Some(duration)) => sink.append(decoder.take_duration(duration)),
-
-                        (None, None) => sink.append(decoder),
-                    }
-                }
-            }
+            append_playback(&sink, audio_source.decoder(), settings, bus_route.as_ref());
 
             let mut sink = AudioSink::new(sink);
 
@@ -306,6 +229,118 @@ pub(crate) fn play_queued_audio_system<Source: Asset + Decodable>(
     }
 }
 
+/// Applies the playback mode, start position and duration of the settings to a decoder, and
+/// appends it to a sink.
+fn append_playback<S, K>(
+    sink: &K,
+    decoder: S,
+    settings: &PlaybackSettings,
+    bus_route: Option<&BusRoute>,
+) where
+    S: Source + Send + 'static,
+    S::Item: rodio::Sample + Send + Sync,
+    f32: rodio::cpal::FromSample<S::Item>,
+    K: AppendSource,
+{
+    match settings.mode {
+        PlaybackMode::Loop => match (settings.start_position, settings.duration) {
+            // custom start position and duration
+            (Some(start_position), Some(duration)) => sink.append_source(
+                decoder
+                    .skip_duration(start_position)
+                    .take_duration(duration)
+                    .repeat_infinite(),
+                bus_route,
+            ),
+
+            // custom start position
+            (Some(start_position), None) => {
+                sink.append_source(
+                    decoder.skip_duration(start_position).repeat_infinite(),
+                    bus_route,
+                );
+            }
+
+            // custom duration
+            (None, Some(duration)) => {
+                sink.append_source(decoder.take_duration(duration).repeat_infinite(), bus_route);
+            }
+
+            // full clip
+            (None, None) => sink.append_source(decoder.repeat_infinite(), bus_route),
+        },
+        PlaybackMode::Once | PlaybackMode::Despawn | PlaybackMode::Remove => {
+            match (settings.start_position, settings.duration) {
+                (Some(start_position), Some(duration)) => sink.append_source(
+                    decoder
+                        .skip_duration(start_position)
+                        .take_duration(duration),
+                    bus_route,
+                ),
+
+                (Some(start_position), None) => {
+                    sink.append_source(decoder.skip_duration(start_position), bus_route);
+                }
+
+                (None, Some(duration)) => {
+                    sink.append_source(decoder.take_duration(duration), bus_route);
+                }
+
+                (None, None) => sink.append_source(decoder, bus_route),
+            }
+        }
+    }
+}
+
+/// A sink that sources can be appended to, either directly or through a bus.
+trait AppendSource {
+    /// Appends a source, which is only boxed if it plays through a bus, to apply the effects of
+    /// the bus.
+    fn append_source<S>(&self, source: S, bus_route: Option<&BusRoute>)
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+        f32: rodio::cpal::FromSample<S::Item>;
+}
+
+impl AppendSource for Sink {
+    fn append_source<S>(&self, source: S, bus_route: Option<&BusRoute>)
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+        f32: rodio::cpal::FromSample<S::Item>,
+    {
+        match bus_route {
+            Some(bus_route) => append_to_sink(self, bus_route.apply(source)),
+            None => self.append(source),
+        }
+    }
+}
+
+impl AppendSource for SpatialSink {
+    fn append_source<S>(&self, source: S, bus_route: Option<&BusRoute>)
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+        f32: rodio::cpal::FromSample<S::Item>,
+    {
+        match bus_route {
+            Some(bus_route) => append_to_spatial_sink(self, bus_route.apply(source)),
+            None => self.append(source),
+        }
+    }
+}
+
+// Appending bus sources can't be done in a function generic over the samples of the decoder,
+// where the bound on them gets in the way of resolving the one on `f32` samples.
+fn append_to_sink(sink: &Sink, source: BusGain<BoxedSource>) {
+    sink.append(source);
+}
+
+fn append_to_spatial_sink(sink: &SpatialSink, source: BusGain<BoxedSource>) {
+    sink.append(source);
+}
+
 pub(crate) fn cleanup_finished_audio<T: Decodable + Asset>(
     mut commands: Commands,
     query_nonspatial_despawn: Query<
@@ -407,3 +442,123 @@ pub(crate) fn update_listener_positions(
         sink.set_ears_position(left_ear * scale, right_ear * scale);
     }
 }
+
+/// Marks audio sinks which were paused because their [`TimeDomain`](bevy_time::TimeDomain) is
+/// paused, so they resume along with it.
+#[derive(Component)]
+pub(crate) struct PausedByTimeDomain;
+
+/// The relative speed of the [`TimeDomain`](bevy_time::TimeDomain) last applied to an audio sink.
+///
+/// The speed of the sink is only written when the speed of its domain changes, so that changes
+/// made with [`AudioSinkPlayback::set_speed`] in the meantime are kept.
+#[derive(Component)]
+pub(crate) struct TimeDomainSpeed(f32);
+
+/// Follows the speed and pause state of the [`TimeDomain`](bevy_time::TimeDomain) of audio sinks.
+///
+/// The speed of a sink in a domain is its [`PlaybackSettings::speed`] scaled by the relative speed
+/// of the domain. Sinks which leave their domain are resumed and go back to their
+/// [`PlaybackSettings::speed`].
+pub(crate) fn update_time_domain_playback<S: AudioSinkPlayback + Component>(
+    mut commands: Commands,
+    sinks: Query<(
+        Entity,
+        &S,
+        &PlaybackSettings,
+        Has<PausedByTimeDomain>,
+        Option<&TimeDomainSpeed>,
+    )>,
+    domain_time: DomainTime,
+) {
+    for (entity, sink, settings, paused_by_domain, domain_speed) in &sinks {
+        let Some(domain) = domain_time.domain(entity) else {
+            // The sink left its domain, or the domain was despawned.
+            if paused_by_domain {
+                sink.play();
+            }
+            if domain_speed.is_some() {
+                sink.set_speed(settings.speed);
+            }
+            if paused_by_domain || domain_speed.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<(PausedByTimeDomain, TimeDomainSpeed)>();
+            }
+            continue;
+        };
+
+        if domain.is_paused() || domain.relative_speed() == 0.0 {
+            if !sink.is_paused() {
+                sink.pause();
+                commands.entity(entity).insert(PausedByTimeDomain);
+            }
+            continue;
+        }
+        if paused_by_domain {
+            sink.play();
+            commands.entity(entity).remove::<PausedByTimeDomain>();
+        }
+
+        let relative_speed = domain.relative_speed();
+        if domain_speed.is_none_or(|applied| applied.0 != relative_speed) {
+            sink.set_speed(settings.speed * relative_speed);
+            commands
+                .entity(entity)
+                .insert(TimeDomainSpeed(relative_speed));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_time::{InTimeDomain, Time, TimeDomain};
+    use rodio::Sink;
+
+    use super::*;
+
+    #[test]
+    fn sinks_follow_their_time_domain() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_systems(Update, update_time_domain_playback::<AudioSink>);
+        let domain = app
+            .world_mut()
+            .spawn(TimeDomain::with_relative_speed(0.5))
+            .id();
+        let entity = app
+            .world_mut()
+            .spawn((
+                AudioSink::new(Sink::new_idle().0),
+                PlaybackSettings::default(),
+                InTimeDomain(domain),
+            ))
+            .id();
+        fn sink(app: &App, entity: Entity) -> &AudioSink {
+            app.world().get::<AudioSink>(entity).unwrap()
+        }
+
+        app.update();
+        assert_eq!(sink(&app, entity).speed(), 0.5);
+
+        // Speed changes made while in the domain are kept until the domain's speed changes.
+        sink(&app, entity).set_speed(2.0);
+        app.update();
+        assert_eq!(sink(&app, entity).speed(), 2.0);
+
+        app.world_mut()
+            .get_mut::<TimeDomain>(domain)
+            .unwrap()
+            .pause();
+        app.update();
+        assert!(sink(&app, entity).is_paused());
+
+        app.world_mut().entity_mut(entity).remove::<InTimeDomain>();
+        app.update();
+        assert!(!sink(&app, entity).is_paused());
+        assert_eq!(sink(&app, entity).speed(), 1.0);
+        assert!(!app.world().entity(entity).contains::<PausedByTimeDomain>());
+        assert!(!app.world().entity(entity).contains::<TimeDomainSpeed>());
+    }
+}
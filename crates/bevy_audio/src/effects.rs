@@ -0,0 +1,520 @@
+use core::time::Duration;
+
+use bevy_reflect::prelude::*;
+use rodio::{source::SeekError, Source};
+
+/// A source of `f32` samples that effects can be chained onto.
+pub(crate) type BoxedSource = Box<dyn Source<Item = f32> + Send>;
+
+/// An effect applied to the sounds of an [`AudioBus`](crate::AudioBus).
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub enum AudioEffect {
+    /// Attenuates the frequencies above the cutoff, in hertz.
+    ///
+    /// Useful to muffle sounds, for example underwater or behind walls.
+    LowPass {
+        /// The frequency above which the sound is attenuated, in hertz.
+        cutoff: f32,
+    },
+    /// Attenuates the frequencies below the cutoff, in hertz.
+    ///
+    /// Useful to make sounds thinner, for example over a radio.
+    HighPass {
+        /// The frequency below which the sound is attenuated, in hertz.
+        cutoff: f32,
+    },
+    /// Simulates the reflections of a room.
+    Reverb {
+        /// The size of the room, from 0 to 1. Bigger rooms ring longer.
+        room_size: f32,
+        /// How quickly the high frequencies of the reflections fade out,
+        /// from 0 to 1.
+        damping: f32,
+        /// The proportion of reverberated sound in the output, from 0 to 1.
+        mix: f32,
+    },
+    /// Repeats the sound after a delay, with decaying echoes.
+    Delay {
+        /// The time between the sound and its first echo.
+        time: Duration,
+        /// The proportion of each echo that is fed into the next one, from 0
+        /// to 1.
+        feedback: f32,
+        /// The volume of the echoes relative to the sound.
+        mix: f32,
+    },
+}
+
+impl AudioEffect {
+    /// Wraps the source in this effect.
+    pub(crate) fn apply(self, source: BoxedSource) -> BoxedSource {
+        match self {
+            AudioEffect::LowPass { cutoff } => Box::new(source.low_pass(cutoff.max(1.0) as u32)),
+            AudioEffect::HighPass { cutoff } => Box::new(source.high_pass(cutoff.max(1.0) as u32)),
+            AudioEffect::Reverb {
+                room_size,
+                damping,
+                mix,
+            } => Box::new(Reverb::new(source, room_size, damping, mix)),
+            AudioEffect::Delay {
+                time,
+                feedback,
+                mix,
+            } => Box::new(Echo::new(source, time, feedback, mix)),
+        }
+    }
+}
+
+/// Below this amplitude, the tail of an effect is considered silent.
+const SILENCE: f32 = 1e-4;
+/// The longest tail an effect keeps playing after its input ended.
+const MAX_TAIL: Duration = Duration::from_secs(10);
+
+/// The sound an effect keeps producing after its input ended, like echoes or
+/// reverberation.
+///
+/// Once the input ended, silence is fed into the effect until its wet signal
+/// stays silent for long enough, or until a maximum length has passed.
+#[derive(Default)]
+struct Tail {
+    /// The number of samples of the tail left to play, once the input ended.
+    remaining: Option<usize>,
+    /// The number of consecutive silent samples of the wet signal.
+    quiet: usize,
+}
+
+impl Tail {
+    /// Returns the next sample of the input, silence during the tail, or
+    /// `None` once the tail ended.
+    fn next_input(&mut self, input: &mut impl Iterator<Item = f32>, max_len: usize) -> Option<f32> {
+        match &mut self.remaining {
+            None => input.next().or_else(|| {
+                self.remaining = Some(max_len);
+                self.next_input(input, max_len)
+            }),
+            Some(0) => None,
+            Some(remaining) => {
+                *remaining -= 1;
+                Some(0.0)
+            }
+        }
+    }
+
+    /// Ends the tail once the wet signal was silent for `quiet_len` samples.
+    fn observe(&mut self, wet: f32, quiet_len: usize) {
+        if wet.abs() < SILENCE {
+            self.quiet += 1;
+        } else {
+            self.quiet = 0;
+        }
+        if self.quiet >= quiet_len
+            && let Some(remaining) = &mut self.remaining
+        {
+            *remaining = 0;
+        }
+    }
+
+    /// Returns the number of samples left in the current frame of a source.
+    fn frame_len(&self, input_frame_len: Option<usize>) -> Option<usize> {
+        match self.remaining {
+            None => input_frame_len,
+            remaining => remaining,
+        }
+    }
+}
+
+/// Returns the number of samples of a tail that lasts until a signal decaying
+/// by `feedback` every `period` samples is silent, bounded by [`MAX_TAIL`].
+fn tail_len(feedback: f32, period: usize, sample_rate: u32, channels: u16) -> usize {
+    let max_len = (MAX_TAIL.as_secs_f32() * sample_rate as f32) as usize * channels as usize;
+    let periods = if feedback <= SILENCE {
+        1
+    } else {
+        (SILENCE.ln() / feedback.ln()).ceil() as usize + 1
+    };
+    periods.saturating_mul(period).min(max_len)
+}
+
+/// Returns the duration of `len` interleaved samples.
+fn samples_duration(len: usize, sample_rate: u32, channels: u16) -> Duration {
+    Duration::from_secs_f64(len as f64 / (sample_rate as f64 * channels.max(1) as f64))
+}
+
+/// A feedback delay line, applied to each channel of a source.
+///
+/// The echoes keep playing after the source ended, until they fade out.
+struct Echo<S> {
+    input: S,
+    tail: Tail,
+    tail_len: usize,
+    /// The interleaved samples waiting to be repeated.
+    buffer: Vec<f32>,
+    position: usize,
+    feedback: f32,
+    mix: f32,
+}
+
+impl<S: Source<Item = f32>> Echo<S> {
+    fn new(input: S, time: Duration, feedback: f32, mix: f32) -> Self {
+        let frames = (time.as_secs_f32() * input.sample_rate() as f32) as usize;
+        let len = frames.max(1) * input.channels().max(1) as usize;
+        let feedback = feedback.clamp(0.0, 0.99);
+        Self {
+            buffer: vec![0.0; len],
+            tail: Tail::default(),
+            tail_len: tail_len(feedback, len, input.sample_rate(), input.channels().max(1)),
+            input,
+            position: 0,
+            feedback,
+            mix,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Echo<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let dry = self.tail.next_input(&mut self.input, self.tail_len)?;
+        let delayed = self.buffer[self.position];
+        self.buffer[self.position] = dry + delayed * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        // Once a whole pass over the delay line was silent, so are all the
+        // following echoes.
+        self.tail.observe(delayed, self.buffer.len());
+        Some(dry + delayed * self.mix)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (lower, upper.map(|upper| upper + self.tail_len))
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Echo<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.tail.frame_len(self.input.current_frame_len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let tail = samples_duration(self.tail_len, self.sample_rate(), self.channels());
+        self.input.total_duration().map(|duration| duration + tail)
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.buffer.fill(0.0);
+        self.tail = Tail::default();
+        Ok(())
+    }
+}
+
+/// The delays of the comb filters of the reverb, in samples at
+/// [`TUNING_SAMPLE_RATE`], from the Freeverb algorithm.
+const COMB_TUNING: [usize; 4] = [1116, 1188, 1277, 1356];
+/// The delays of the all-pass filters of the reverb, in samples at
+/// [`TUNING_SAMPLE_RATE`].
+const ALLPASS_TUNING: [usize; 2] = [556, 441];
+/// The extra delay of every other channel, which decorrelates them.
+const STEREO_SPREAD: usize = 23;
+const TUNING_SAMPLE_RATE: f32 = 44_100.0;
+/// Scales the input of the filters so that they don't clip.
+const INPUT_GAIN: f32 = 0.015;
+/// Compensates for [`INPUT_GAIN`] in the output.
+const WET_GAIN: f32 = 3.0;
+
+/// A Freeverb-style reverb: parallel comb filters followed by all-pass
+/// filters, for each channel of a source.
+///
+/// The reverberation keeps playing after the source ended, until it fades out.
+struct Reverb<S> {
+    input: S,
+    tail: Tail,
+    tail_len: usize,
+    /// The number of samples it takes for a sound to pass through the filters
+    /// of every channel.
+    quiet_len: usize,
+    channels: Vec<ReverbChannel>,
+    /// The channel of the next sample.
+    channel: usize,
+    feedback: f32,
+    damping: f32,
+    mix: f32,
+}
+
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl<S: Source<Item = f32>> Reverb<S> {
+    fn new(input: S, room_size: f32, damping: f32, mix: f32) -> Self {
+        let scale = input.sample_rate() as f32 / TUNING_SAMPLE_RATE;
+        let delay = |tuning: usize, channel: usize| {
+            (((tuning + STEREO_SPREAD * (channel % 2)) as f32 * scale) as usize).max(1)
+        };
+        let channels = (0..input.channels().max(1) as usize)
+            .map(|channel| ReverbChannel {
+                combs: COMB_TUNING
+                    .iter()
+                    .map(|&tuning| Comb::new(delay(tuning, channel)))
+                    .collect(),
+                allpasses: ALLPASS_TUNING
+                    .iter()
+                    .map(|&tuning| Allpass::new(delay(tuning, channel)))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        let feedback = 0.7 + 0.28 * room_size.clamp(0.0, 1.0);
+        // The longest path through the filters: the longest comb, followed by
+        // all the all-passes.
+        let frames = delay(COMB_TUNING[3], 1)
+            + ALLPASS_TUNING
+                .iter()
+                .map(|&tuning| delay(tuning, 1))
+                .sum::<usize>();
+        let quiet_len = frames * channels.len();
+        Self {
+            tail: Tail::default(),
+            tail_len: tail_len(
+                feedback,
+                quiet_len,
+                input.sample_rate(),
+                channels.len() as u16,
+            ),
+            quiet_len,
+            input,
+            channels,
+            channel: 0,
+            feedback,
+            damping: 0.4 * damping.clamp(0.0, 1.0),
+            mix: mix.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Reverb<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let dry = self.tail.next_input(&mut self.input, self.tail_len)?;
+        let channel_count = self.channels.len();
+        let channel = &mut self.channels[self.channel];
+        self.channel = (self.channel + 1) % channel_count;
+
+        let input = dry * INPUT_GAIN;
+        let mut wet = channel
+            .combs
+            .iter_mut()
+            .map(|comb| comb.process(input, self.feedback, self.damping))
+            .sum();
+        for allpass in &mut channel.allpasses {
+            wet = allpass.process(wet);
+        }
+        self.tail.observe(wet * WET_GAIN, self.quiet_len);
+        Some(dry * (1.0 - self.mix) + wet * WET_GAIN * self.mix)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (lower, upper.map(|upper| upper + self.tail_len))
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Reverb<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.tail.frame_len(self.input.current_frame_len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let tail = samples_duration(self.tail_len, self.sample_rate(), self.channels());
+        self.input.total_duration().map(|duration| duration + tail)
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.tail = Tail::default();
+        for channel in &mut self.channels {
+            for comb in &mut channel.combs {
+                comb.buffer.fill(0.0);
+                comb.filter_store = 0.0;
+            }
+            for allpass in &mut channel.allpasses {
+                allpass.buffer.fill(0.0);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A feedback comb filter with a low-pass filter in its feedback loop.
+struct Comb {
+    buffer: Vec<f32>,
+    position: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len],
+            position: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.position];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.position] = input + self.filter_store * feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// An all-pass filter, which diffuses the echoes of the comb filters.
+struct Allpass {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len],
+            position: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.position];
+        self.buffer[self.position] = input + buffered * 0.5;
+        self.position = (self.position + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use rodio::buffer::SamplesBuffer;
+
+    use super::*;
+
+    /// A single non-zero sample followed by silence.
+    fn impulse(channels: u16, len: usize) -> BoxedSource {
+        let mut samples = vec![0.0; len];
+        samples[0] = 1.0;
+        Box::new(SamplesBuffer::new(channels, 1000, samples))
+    }
+
+    #[test]
+    fn delay_repeats_with_feedback() {
+        let effect = AudioEffect::Delay {
+            time: Duration::from_millis(10),
+            feedback: 0.5,
+            mix: 1.0,
+        };
+        let samples: Vec<f32> = effect.apply(impulse(1, 40)).collect();
+
+        assert_eq!(samples[0], 1.0);
+        assert_eq!(samples[10], 1.0);
+        assert_eq!(samples[20], 0.5);
+        assert_eq!(samples[30], 0.25);
+        assert!(samples[1..10].iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn delay_plays_echoes_after_the_input_ends() {
+        let effect = AudioEffect::Delay {
+            time: Duration::from_millis(10),
+            feedback: 0.5,
+            mix: 1.0,
+        };
+        let source = effect.apply(impulse(1, 15));
+        let total_duration = source.total_duration().unwrap();
+        let samples: Vec<f32> = source.collect();
+
+        // The echoes halve every 10 samples until they are silent, after the
+        // input ended at 15 samples.
+        let tail = &samples[15..];
+        assert_eq!(tail[5], 0.5);
+        assert_eq!(tail[15], 0.25);
+        let echoes = tail.iter().filter(|sample| **sample != 0.0).count();
+        assert_eq!(echoes, 14);
+        assert!(tail.len() < 15 * 10);
+        assert!(*tail.last().unwrap() < SILENCE);
+        assert!(total_duration >= Duration::from_millis(samples.len() as u64));
+    }
+
+    #[test]
+    fn delay_keeps_channels_apart() {
+        let effect = AudioEffect::Delay {
+            time: Duration::from_millis(2),
+            feedback: 0.0,
+            mix: 1.0,
+        };
+        let samples: Vec<f32> = effect.apply(impulse(2, 10)).collect();
+
+        // The echo of the left channel lands on the left channel, two frames
+        // later.
+        assert_eq!(samples[4], 1.0);
+        assert_eq!(samples[5], 0.0);
+    }
+
+    #[test]
+    fn reverb_rings_and_decays() {
+        let effect = AudioEffect::Reverb {
+            room_size: 0.5,
+            damping: 0.5,
+            mix: 1.0,
+        };
+        let samples: Vec<f32> = effect.apply(impulse(1, 20_000)).collect();
+
+        let energy = |samples: &[f32]| samples.iter().map(|sample| sample * sample).sum::<f32>();
+        assert!(samples.iter().all(|sample| sample.is_finite()));
+        assert!(energy(&samples[..5_000]) > 0.0);
+        assert!(energy(&samples[15_000..]) < energy(&samples[..5_000]));
+    }
+
+    #[test]
+    fn reverb_rings_after_the_input_ends() {
+        let effect = AudioEffect::Reverb {
+            room_size: 0.5,
+            damping: 0.5,
+            mix: 1.0,
+        };
+        let source = effect.apply(impulse(2, 100));
+        let total_duration = source.total_duration().unwrap();
+        let samples: Vec<f32> = source.collect();
+
+        let tail = &samples[100..];
+        assert!(tail.iter().any(|sample| sample.abs() > SILENCE));
+        assert!(tail
+            .iter()
+            .rev()
+            .take(10)
+            .all(|sample| sample.abs() < SILENCE));
+        // The tail fades out well before the longest tail, of 10 seconds.
+        assert!(tail.len() < 2 * 5_000);
+        assert!(total_duration >= Duration::from_millis(samples.len() as u64 / 2));
+    }
+}
@@ -32,6 +32,8 @@ extern crate alloc;
 mod audio;
 mod audio_output;
 mod audio_source;
+mod effects;
+mod mixer;
 mod pitch;
 mod sinks;
 mod volume;
@@ -42,13 +44,16 @@ mod volume;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        AudioPlayer, AudioSink, AudioSinkPlayback, AudioSource, Decodable, GlobalVolume, Pitch,
-        PlaybackSettings, SpatialAudioSink, SpatialListener,
+        AudioBus, AudioBusTarget, AudioEffect, AudioMixer, AudioPlayer, AudioSink,
+        AudioSinkPlayback, AudioSource, Decodable, GlobalVolume, Pitch, PlaybackSettings,
+        SpatialAudioSink, SpatialListener,
     };
 }
 
 pub use audio::*;
 pub use audio_source::*;
+pub use effects::*;
+pub use mixer::*;
 pub use pitch::*;
 pub use volume::*;
 
@@ -82,6 +87,8 @@ impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.global_volume)
             .insert_resource(DefaultSpatialScale(self.default_spatial_scale))
+            .init_resource::<AudioMixer>()
+            .init_resource::<AudioBusGains>()
             .configure_sets(
                 PostUpdate,
                 AudioPlaybackSystems
@@ -90,7 +97,14 @@ impl Plugin for AudioPlugin {
             )
             .add_systems(
                 PostUpdate,
-                (update_emitter_positions, update_listener_positions).in_set(AudioPlaybackSystems),
+                (
+                    update_audio_buses,
+                    update_emitter_positions,
+                    update_listener_positions,
+                    update_time_domain_playback::<AudioSink>,
+                    update_time_domain_playback::<SpatialAudioSink>,
+                )
+                    .in_set(AudioPlaybackSystems),
             )
             .init_resource::<AudioOutput>();
 
@@ -112,7 +126,10 @@ impl AddAudioSource for App {
     {
         self.init_asset::<T>().add_systems(
             PostUpdate,
-            (play_queued_audio_system::<T>, cleanup_finished_audio::<T>)
+            (
+                play_queued_audio_system::<T>.after(update_audio_buses),
+                cleanup_finished_audio::<T>,
+            )
                 .in_set(AudioPlaybackSystems),
         );
         self
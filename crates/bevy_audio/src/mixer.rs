@@ -0,0 +1,476 @@
+use alloc::{borrow::Cow, sync::Arc};
+use core::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+use bevy_reflect::prelude::*;
+use bevy_time::{Real, Time};
+use rodio::{source::SeekError, Source};
+use tracing::warn;
+
+use crate::{
+    effects::BoxedSource, AudioEffect, AudioSink, AudioSinkPlayback, SpatialAudioSink, Volume,
+};
+
+/// Use this [`Resource`] to route audio through named buses, such as "music",
+/// "sfx" or "voice", and mix them.
+///
+/// An [`AudioPlayer`](crate::AudioPlayer) with an [`AudioBusTarget`] plays
+/// through the [`AudioBus`] of that name. Unlike [`GlobalVolume`](crate::GlobalVolume),
+/// changes to the volume, mute and solo state of a bus, as well as ducking,
+/// affect audio that is already playing.
+///
+/// The [`AudioPlugin`](crate::AudioPlugin) starts with an empty mixer, unless one
+/// was inserted before it was added.
+#[derive(Resource, Clone, Debug, Default, Reflect)]
+#[reflect(Resource, Clone, Debug, Default)]
+pub struct AudioMixer {
+    /// The buses of the mixer, by name.
+    pub buses: HashMap<Cow<'static, str>, AudioBus>,
+    /// The rules that lower the volume of some buses while others play.
+    pub ducking: Vec<AudioDucking>,
+}
+
+impl AudioMixer {
+    /// Adds a bus with the given name, replacing any bus with the same name.
+    pub fn with_bus(mut self, name: impl Into<Cow<'static, str>>, bus: AudioBus) -> Self {
+        self.buses.insert(name.into(), bus);
+        self
+    }
+
+    /// Adds a ducking rule.
+    pub fn with_ducking(mut self, ducking: AudioDucking) -> Self {
+        self.ducking.push(ducking);
+        self
+    }
+
+    /// Returns the bus with the given name, if any.
+    pub fn bus(&self, name: &str) -> Option<&AudioBus> {
+        self.buses.get(name)
+    }
+
+    /// Returns the bus with the given name mutably, if any.
+    pub fn bus_mut(&mut self, name: &str) -> Option<&mut AudioBus> {
+        self.buses.get_mut(name)
+    }
+
+    /// Returns the linear gain of the bus with the given name, taking mute,
+    /// solo and the current level of each ducking rule into account.
+    fn bus_gain(&self, name: &str, duck_levels: &DuckLevels) -> Option<f32> {
+        let bus = self.buses.get(name)?;
+        let any_solo = self.buses.values().any(|bus| bus.solo);
+        if bus.muted || (any_solo && !bus.solo) {
+            return Some(0.0);
+        }
+
+        let ducked = duck_levels
+            .iter()
+            .filter(|((_, target), _)| target == name)
+            .map(|(_, level)| level)
+            .product::<f32>();
+        Some(bus.volume.to_linear() * ducked)
+    }
+}
+
+/// A mixer bus of the [`AudioMixer`].
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, Default, PartialEq)]
+pub struct AudioBus {
+    /// The volume of all the audio of this bus.
+    pub volume: Volume,
+    /// Whether the audio of this bus is silenced.
+    pub muted: bool,
+    /// Whether this bus is soloed. While any bus is soloed, all the buses that
+    /// aren't are silenced.
+    pub solo: bool,
+    /// The effects applied to the audio of this bus, in order.
+    ///
+    /// Effects are applied when audio starts playing, so changing them doesn't
+    /// affect audio that is already playing.
+    pub effects: Vec<AudioEffect>,
+}
+
+impl Default for AudioBus {
+    fn default() -> Self {
+        Self {
+            volume: Volume::Linear(1.0),
+            muted: false,
+            solo: false,
+            effects: Vec::new(),
+        }
+    }
+}
+
+impl AudioBus {
+    /// Helper to set the volume of the bus.
+    pub fn with_volume(mut self, volume: Volume) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Helper to add an effect at the end of the chain of the bus.
+    pub fn with_effect(mut self, effect: AudioEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+}
+
+/// Lowers the volume of a bus while audio plays on another one, for example
+/// to lower the music while a character speaks.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[reflect(Clone, Debug, PartialEq)]
+pub struct AudioDucking {
+    /// The bus whose audio triggers the ducking.
+    pub trigger: Cow<'static, str>,
+    /// The bus whose volume is lowered.
+    pub target: Cow<'static, str>,
+    /// The volume of the target bus while ducked, relative to its own volume.
+    pub volume: Volume,
+    /// How fast the target bus fades down once audio starts playing on the
+    /// trigger bus, as the time a fade from full volume to silence would take.
+    pub attack: Duration,
+    /// How fast the target bus fades back up once the trigger bus stops
+    /// playing, as the time a fade from silence to full volume would take.
+    pub release: Duration,
+}
+
+impl AudioDucking {
+    /// Creates a rule that lowers the `target` bus to the given volume while
+    /// the `trigger` bus plays.
+    pub fn new(
+        trigger: impl Into<Cow<'static, str>>,
+        target: impl Into<Cow<'static, str>>,
+        volume: Volume,
+    ) -> Self {
+        Self {
+            trigger: trigger.into(),
+            target: target.into(),
+            volume,
+            attack: Duration::from_millis(100),
+            release: Duration::from_millis(500),
+        }
+    }
+
+    /// Helper to set how long the target bus takes to fade down.
+    pub fn with_attack(mut self, attack: Duration) -> Self {
+        self.attack = attack;
+        self
+    }
+
+    /// Helper to set how long the target bus takes to fade back up.
+    pub fn with_release(mut self, release: Duration) -> Self {
+        self.release = release;
+        self
+    }
+}
+
+/// Routes the audio of an [`AudioPlayer`](crate::AudioPlayer) through the
+/// [`AudioBus`] of the [`AudioMixer`] with this name.
+///
+/// Like [`PlaybackSettings`](crate::PlaybackSettings), this is read when the
+/// audio starts playing.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, Clone, Debug, PartialEq)]
+pub struct AudioBusTarget(pub Cow<'static, str>);
+
+impl AudioBusTarget {
+    /// Creates a new [`AudioBusTarget`] for the bus with the given name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// The gains of the buses of the [`AudioMixer`], shared with the audio that
+/// plays through them.
+#[derive(Resource, Default)]
+pub(crate) struct AudioBusGains {
+    gains: HashMap<Cow<'static, str>, Arc<AtomicU32>>,
+    /// The current level of each ducking rule, from the ducked volume to 1.
+    duck_levels: DuckLevels,
+}
+
+/// The current level of the ducking rules, by their trigger and target buses.
+type DuckLevels = HashMap<(Cow<'static, str>, Cow<'static, str>), f32>;
+
+impl AudioBusGains {
+    /// Returns the route through the bus with the given name, or `None` if the
+    /// mixer has no such bus.
+    pub(crate) fn route(&self, mixer: &AudioMixer, name: &str) -> Option<BusRoute> {
+        let (Some(bus), Some(gain)) = (mixer.bus(name), self.gains.get(name)) else {
+            warn!("AudioBusTarget refers to missing audio bus {name:?}. Playing without it.");
+            return None;
+        };
+        Some(BusRoute {
+            gain: gain.clone(),
+            effects: bus.effects.clone(),
+        })
+    }
+}
+
+/// The effects and the gain of a bus, applied to a source.
+pub(crate) struct BusRoute {
+    gain: Arc<AtomicU32>,
+    effects: Vec<AudioEffect>,
+}
+
+impl BusRoute {
+    /// Wraps the source in the effects and the gain of the bus.
+    pub(crate) fn apply<S>(&self, source: S) -> BusGain<BoxedSource>
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample,
+        f32: rodio::cpal::FromSample<S::Item>,
+    {
+        let mut source: BoxedSource = Box::new(source.convert_samples::<f32>());
+        for effect in &self.effects {
+            source = effect.apply(source);
+        }
+        BusGain {
+            input: source,
+            gain: self.gain.clone(),
+        }
+    }
+}
+
+/// Scales a source by the gain of a bus, which can change while it plays.
+pub(crate) struct BusGain<S> {
+    input: S,
+    gain: Arc<AtomicU32>,
+}
+
+impl<S: Source<Item = f32>> Iterator for BusGain<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        Some(sample * f32::from_bits(self.gain.load(Ordering::Relaxed)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<S: Source<Item = f32>> Source for BusGain<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+/// Updates the ducking levels and the gains of the buses of the [`AudioMixer`].
+///
+/// Ducking levels only fade when the app has a [`Time<Real>`] resource.
+pub(crate) fn update_audio_buses(
+    mixer: Res<AudioMixer>,
+    mut bus_gains: ResMut<AudioBusGains>,
+    time: Option<Res<Time<Real>>>,
+    sinks: Query<(&AudioBusTarget, &AudioSink)>,
+    spatial_sinks: Query<(&AudioBusTarget, &SpatialAudioSink)>,
+) {
+    let bus_gains = &mut *bus_gains;
+
+    let is_playing = |name: &str| {
+        let playing = |sink: &dyn AudioSinkPlayback| !sink.is_paused() && !sink.empty();
+        sinks
+            .iter()
+            .any(|(target, sink)| target.0 == name && playing(sink))
+            || spatial_sinks
+                .iter()
+                .any(|(target, sink)| target.0 == name && playing(sink))
+    };
+
+    bus_gains.duck_levels.retain(|(trigger, target), _| {
+        mixer
+            .ducking
+            .iter()
+            .any(|ducking| ducking.trigger == *trigger && ducking.target == *target)
+    });
+    // Without a clock, ducking levels stay where they are.
+    if let Some(time) = time {
+        let delta = time.delta_secs();
+        for ducking in &mixer.ducking {
+            let (goal, fade) = if is_playing(&ducking.trigger) {
+                (ducking.volume.to_linear(), ducking.attack)
+            } else {
+                (1.0, ducking.release)
+            };
+            let level = bus_gains
+                .duck_levels
+                .entry((ducking.trigger.clone(), ducking.target.clone()))
+                .or_insert(1.0);
+            *level = fade_towards(*level, goal, delta, fade);
+        }
+    }
+
+    // Audio that still plays through a removed bus plays at full volume.
+    bus_gains.gains.retain(|name, gain| {
+        let retain = mixer.buses.contains_key(name);
+        if !retain {
+            gain.store(1.0f32.to_bits(), Ordering::Relaxed);
+        }
+        retain
+    });
+
+    for name in mixer.buses.keys() {
+        let gain = mixer.bus_gain(name, &bus_gains.duck_levels).unwrap_or(1.0);
+        bus_gains
+            .gains
+            .entry(name.clone())
+            .or_default()
+            .store(gain.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Moves `level` towards `goal`, at a speed that goes from 0 to 1 in `fade`.
+fn fade_towards(level: f32, goal: f32, delta: f32, fade: Duration) -> f32 {
+    let max_step = if fade.is_zero() {
+        f32::INFINITY
+    } else {
+        delta / fade.as_secs_f32()
+    };
+    if (goal - level).abs() <= max_step {
+        goal
+    } else {
+        level + max_step.copysign(goal - level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use bevy_app::{App, Update};
+    use rodio::{buffer::SamplesBuffer, Sink};
+
+    use super::*;
+
+    fn mixer() -> AudioMixer {
+        AudioMixer::default()
+            .with_bus(
+                "music",
+                AudioBus::default().with_volume(Volume::Linear(0.5)),
+            )
+            .with_bus("sfx", AudioBus::default())
+            .with_bus("voice", AudioBus::default())
+            .with_ducking(AudioDucking::new("voice", "music", Volume::Linear(0.2)))
+    }
+
+    fn duck_levels(level: f32) -> DuckLevels {
+        [(("voice".into(), "music".into()), level)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn bus_gains_follow_mute_and_solo() {
+        let mut mixer = mixer();
+        let levels = duck_levels(1.0);
+        assert_eq!(mixer.bus_gain("music", &levels), Some(0.5));
+        assert_eq!(mixer.bus_gain("missing", &levels), None);
+
+        mixer.bus_mut("sfx").unwrap().muted = true;
+        assert_eq!(mixer.bus_gain("sfx", &levels), Some(0.0));
+
+        mixer.bus_mut("voice").unwrap().solo = true;
+        assert_eq!(mixer.bus_gain("music", &levels), Some(0.0));
+        assert_eq!(mixer.bus_gain("voice", &levels), Some(1.0));
+    }
+
+    #[test]
+    fn ducking_lowers_target_bus() {
+        let mixer = mixer();
+        let levels = duck_levels(0.2);
+        assert_eq!(mixer.bus_gain("music", &levels), Some(0.1));
+        assert_eq!(mixer.bus_gain("voice", &levels), Some(1.0));
+
+        // Fades go from 0 to 1 in their duration.
+        let attack = Duration::from_millis(100);
+        assert_eq!(fade_towards(1.0, 0.2, 0.05, attack), 0.5);
+        assert_eq!(fade_towards(0.5, 0.2, 0.05, attack), 0.2);
+        assert_eq!(fade_towards(0.2, 1.0, 0.01, Duration::ZERO), 1.0);
+    }
+
+    #[test]
+    fn buses_update_without_time() {
+        let mut app = App::new();
+        app.insert_resource(mixer())
+            .init_resource::<AudioBusGains>()
+            .add_systems(Update, update_audio_buses);
+        app.world_mut().spawn((
+            AudioBusTarget::new("voice"),
+            AudioSink::new(Sink::new_idle().0),
+        ));
+        app.update();
+
+        let gains = &app.world().resource::<AudioBusGains>().gains;
+        assert_eq!(f32::from_bits(gains["music"].load(Ordering::Relaxed)), 0.5);
+    }
+
+    #[test]
+    fn ducking_follows_trigger_bus_playback() {
+        let mut app = App::new();
+        app.insert_resource(mixer())
+            .init_resource::<AudioBusGains>()
+            .init_resource::<Time<Real>>()
+            .add_systems(Update, update_audio_buses);
+        let step = |app: &mut App, millis: u64| {
+            app.world_mut()
+                .resource_mut::<Time<Real>>()
+                .update_with_duration(Duration::from_millis(millis));
+            app.update();
+        };
+        let gain = |app: &App, name: &str| {
+            let gains = &app.world().resource::<AudioBusGains>().gains;
+            f32::from_bits(gains[name].load(Ordering::Relaxed))
+        };
+        step(&mut app, 0);
+        assert_eq!(gain(&app, "music"), 0.5);
+
+        let sink = Sink::new_idle().0;
+        sink.append(SamplesBuffer::new(1, 1000, vec![0.0f32; 1000]));
+        let voice = app
+            .world_mut()
+            .spawn((AudioBusTarget::new("voice"), AudioSink::new(sink)))
+            .id();
+        step(&mut app, 50);
+        assert_eq!(gain(&app, "music"), 0.25);
+        step(&mut app, 50);
+        assert!((gain(&app, "music") - 0.1).abs() < 1e-6);
+
+        // Levels belong to their rule, not to its position in the list.
+        app.world_mut()
+            .resource_mut::<AudioMixer>()
+            .ducking
+            .insert(0, AudioDucking::new("voice", "sfx", Volume::Linear(0.5)));
+        step(&mut app, 0);
+        assert!((gain(&app, "music") - 0.1).abs() < 1e-6);
+        assert_eq!(gain(&app, "sfx"), 1.0);
+
+        // Releasing towards full volume doesn't depend on the ducked volume.
+        app.world_mut().resource_mut::<AudioMixer>().ducking[1].volume = Volume::Linear(1.0);
+        app.world_mut().entity_mut(voice).despawn();
+        step(&mut app, 250);
+        assert!((gain(&app, "music") - 0.35).abs() < 1e-6);
+        step(&mut app, 250);
+        assert_eq!(gain(&app, "music"), 0.5);
+    }
+}
@@ -0,0 +1,438 @@
+//! Records the input received by an app, and replays it deterministically.
+//!
+//! The [`InputRecordingPlugin`] captures every [`WindowEvent`], which includes the keyboard,
+//! mouse, touch and window events, and every [`RawGamepadEvent`], along with the duration of each
+//! frame. The resulting [`InputRecording`] can be saved to a [`ron`] file, and replayed into
+//! another app with the [`InputReplayPlugin`], which writes the events back on the same frames and
+//! advances time by the recorded durations through [`TimeUpdateStrategy::ManualDuration`].
+//!
+//! Replays don't need a window or a gamepad, which makes them suitable for regression tests and
+//! bug reproductions running in a headless app:
+//!
+//! ```no_run
+//! # use bevy_app::prelude::*;
+//! # use bevy_dev_tools::input_recording::*;
+//! let recording = InputRecording::load("bug_report.ron").unwrap();
+//!
+//! let mut app = App::new();
+//! app.add_plugins(InputReplayPlugin::new(recording));
+//! // Add the plugins and systems of the game under test.
+//!
+//! while !app.world().resource::<InputReplay>().is_finished() {
+//!     app.update();
+//! }
+//! ```
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy_app::{prelude::*, AppExit};
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_input::gamepad::RawGamepadEvent;
+use bevy_time::{Real, Time, TimeSystems, TimeUpdateStrategy};
+use bevy_window::{FileDragAndDrop, Ime, PrimaryWindow, WindowEvent};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{error, info};
+
+/// The input received by an app, frame by frame.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct InputRecording {
+    /// The recorded frames, in order.
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// The input received by an app during one frame.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct RecordedFrame {
+    /// How much real time passed since the previous frame.
+    pub delta: Duration,
+    /// The events received during the frame, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<RecordedEvent>,
+}
+
+/// An event stored in an [`InputRecording`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum RecordedEvent {
+    /// A window event, including keyboard, mouse and touch input.
+    Window(WindowEvent),
+    /// A gamepad event, before any filtering by the
+    /// [`GamepadSettings`](bevy_input::gamepad::GamepadSettings).
+    Gamepad(RawGamepadEvent),
+}
+
+/// An error which occurred while loading or saving an [`InputRecording`].
+#[derive(Error, Debug)]
+pub enum InputRecordingError {
+    /// The file couldn't be read or written.
+    #[error("failed to access the input recording file: {0}")]
+    Io(#[from] io::Error),
+    /// The file doesn't contain a valid recording.
+    #[error("failed to deserialize the input recording: {0}")]
+    Deserialize(#[from] ron::error::SpannedError),
+    /// The recording couldn't be serialized.
+    #[error("failed to serialize the input recording: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+impl InputRecording {
+    /// Reads a recording from a [`ron`] file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputRecordingError> {
+        let content = fs::read_to_string(path)?;
+        Ok(ron::from_str(&content)?)
+    }
+
+    /// Writes the recording to a [`ron`] file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputRecordingError> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// A plugin recording the input received by the app into the [`InputRecorder`] resource.
+///
+/// Recording starts with the first update. Events are captured in [`Last`], so gamepad events
+/// written during the update are included.
+#[derive(Default)]
+pub struct InputRecordingPlugin {
+    /// If set, the recording is saved to this file when the app exits.
+    pub path: Option<PathBuf>,
+}
+
+impl Plugin for InputRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WindowEvent>()
+            .add_event::<RawGamepadEvent>()
+            .insert_resource(InputRecorder {
+                recording: InputRecording::default(),
+                paused: false,
+                path: self.path.clone(),
+            })
+            .add_systems(Last, (record_input, save_recording_on_exit).chain());
+    }
+}
+
+/// Accumulates the input received by the app, added by the [`InputRecordingPlugin`].
+#[derive(Resource, Debug)]
+pub struct InputRecorder {
+    recording: InputRecording,
+    paused: bool,
+    path: Option<PathBuf>,
+}
+
+impl InputRecorder {
+    /// Returns the input recorded so far.
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    /// Returns the input recorded so far, and starts a new recording.
+    pub fn take(&mut self) -> InputRecording {
+        core::mem::take(&mut self.recording)
+    }
+
+    /// Stops recording frames until [`resume`](Self::resume) is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes recording frames.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if frames are not being recorded.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+fn record_input(
+    mut recorder: ResMut<InputRecorder>,
+    real_time: Res<Time<Real>>,
+    mut window_events: EventReader<WindowEvent>,
+    mut gamepad_events: EventReader<RawGamepadEvent>,
+) {
+    if recorder.paused {
+        window_events.clear();
+        gamepad_events.clear();
+        return;
+    }
+
+    let events = window_events
+        .read()
+        .cloned()
+        .map(RecordedEvent::Window)
+        .chain(gamepad_events.read().cloned().map(RecordedEvent::Gamepad))
+        .collect();
+    recorder.recording.frames.push(RecordedFrame {
+        delta: real_time.delta(),
+        events,
+    });
+}
+
+fn save_recording_on_exit(recorder: Res<InputRecorder>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    let Some(path) = &recorder.path else {
+        return;
+    };
+    match recorder.recording.save(path) {
+        Ok(()) => info!(
+            "Saved {} frames of input to {}.",
+            recorder.recording.frames.len(),
+            path.display()
+        ),
+        Err(err) => error!("Failed to save the input recording: {err}"),
+    }
+}
+
+/// A plugin replaying an [`InputRecording`] into the app, one recorded frame per update.
+///
+/// Each update writes the events of its recorded frame during [`First`], before the
+/// [`TimeSystems`], and advances time by the recorded duration of the frame. Once the recording is
+/// over, time keeps advancing by the duration of the last frame.
+///
+/// Window entities of the recording are replaced by the [`PrimaryWindow`] of the app, if there is
+/// one. Gamepads are spawned as new entities when they connect.
+pub struct InputReplayPlugin {
+    /// The recording to replay.
+    pub recording: InputRecording,
+    /// Whether to send [`AppExit::Success`] once every recorded frame was replayed.
+    pub exit_on_finish: bool,
+}
+
+impl InputReplayPlugin {
+    /// Creates a plugin replaying `recording`, without exiting once it is over.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            exit_on_finish: false,
+        }
+    }
+}
+
+impl Plugin for InputReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WindowEvent>()
+            .add_event::<RawGamepadEvent>()
+            .insert_resource(InputReplay {
+                recording: self.recording.clone(),
+                frame: 0,
+                exit_on_finish: self.exit_on_finish,
+            })
+            .add_systems(First, replay_input.before(TimeSystems));
+    }
+}
+
+/// The progress of the replay of an [`InputRecording`], added by the [`InputReplayPlugin`].
+#[derive(Resource, Debug)]
+pub struct InputReplay {
+    recording: InputRecording,
+    frame: usize,
+    exit_on_finish: bool,
+}
+
+impl InputReplay {
+    /// Returns the index of the next frame to replay.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Returns `true` once every recorded frame was replayed.
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.frames.len()
+    }
+}
+
+fn replay_input(
+    mut replay: ResMut<InputReplay>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut gamepads: Local<EntityHashMap<Entity>>,
+    mut commands: Commands,
+    mut window_events: EventWriter<WindowEvent>,
+    mut gamepad_events: EventWriter<RawGamepadEvent>,
+) {
+    let Some(frame) = replay.recording.frames.get(replay.frame) else {
+        return;
+    };
+    *time_update_strategy = TimeUpdateStrategy::ManualDuration(frame.delta);
+
+    let primary_window = primary_window.single().ok();
+    for event in frame.events.clone() {
+        match event {
+            RecordedEvent::Window(mut event) => {
+                if let Some(primary_window) = primary_window
+                    && let Some(window) = window_mut(&mut event)
+                {
+                    *window = primary_window;
+                }
+                window_events.write(event.clone());
+                write_window_event(&mut commands, event);
+            }
+            RecordedEvent::Gamepad(mut event) => {
+                let gamepad = match &mut event {
+                    RawGamepadEvent::Connection(event) => &mut event.gamepad,
+                    RawGamepadEvent::Button(event) => &mut event.gamepad,
+                    RawGamepadEvent::Axis(event) => &mut event.gamepad,
+                };
+                *gamepad = *gamepads
+                    .entry(*gamepad)
+                    .or_insert_with(|| commands.spawn_empty().id());
+                gamepad_events.write(event);
+            }
+        }
+    }
+
+    replay.frame += 1;
+    if replay.is_finished() && replay.exit_on_finish {
+        commands.write_event(AppExit::Success);
+    }
+}
+
+/// Returns the window entity of `event`, if it has one.
+fn window_mut(event: &mut WindowEvent) -> Option<&mut Entity> {
+    let window = match event {
+        WindowEvent::CursorEntered(e) => &mut e.window,
+        WindowEvent::CursorLeft(e) => &mut e.window,
+        WindowEvent::CursorMoved(e) => &mut e.window,
+        WindowEvent::FileDragAndDrop(
+            FileDragAndDrop::DroppedFile { window, .. }
+            | FileDragAndDrop::HoveredFile { window, .. }
+            | FileDragAndDrop::HoveredFileCanceled { window },
+        )
+        | WindowEvent::Ime(
+            Ime::Preedit { window, .. }
+            | Ime::Commit { window, .. }
+            | Ime::Enabled { window }
+            | Ime::Disabled { window },
+        ) => window,
+        WindowEvent::WindowBackendScaleFactorChanged(e) => &mut e.window,
+        WindowEvent::WindowCloseRequested(e) => &mut e.window,
+        WindowEvent::WindowCreated(e) => &mut e.window,
+        WindowEvent::WindowDestroyed(e) => &mut e.window,
+        WindowEvent::WindowFocused(e) => &mut e.window,
+        WindowEvent::WindowMoved(e) => &mut e.window,
+        WindowEvent::WindowOccluded(e) => &mut e.window,
+        WindowEvent::WindowResized(e) => &mut e.window,
+        WindowEvent::WindowScaleFactorChanged(e) => &mut e.window,
+        WindowEvent::WindowThemeChanged(e) => &mut e.window,
+        WindowEvent::MouseButtonInput(e) => &mut e.window,
+        WindowEvent::MouseWheel(e) => &mut e.window,
+        WindowEvent::TouchInput(e) => &mut e.window,
+        WindowEvent::KeyboardInput(e) => &mut e.window,
+        WindowEvent::AppLifecycle(_)
+        | WindowEvent::RequestRedraw(_)
+        | WindowEvent::MouseMotion(_)
+        | WindowEvent::PinchGesture(_)
+        | WindowEvent::RotationGesture(_)
+        | WindowEvent::DoubleTapGesture(_)
+        | WindowEvent::PanGesture(_)
+        | WindowEvent::KeyboardFocusLost(_) => return None,
+    };
+    Some(window)
+}
+
+/// Writes the event wrapped by `event`, like the windowing backend does.
+fn write_window_event(commands: &mut Commands, event: WindowEvent) {
+    match event {
+        WindowEvent::AppLifecycle(e) => commands.write_event(e),
+        WindowEvent::CursorEntered(e) => commands.write_event(e),
+        WindowEvent::CursorLeft(e) => commands.write_event(e),
+        WindowEvent::CursorMoved(e) => commands.write_event(e),
+        WindowEvent::FileDragAndDrop(e) => commands.write_event(e),
+        WindowEvent::Ime(e) => commands.write_event(e),
+        WindowEvent::RequestRedraw(e) => commands.write_event(e),
+        WindowEvent::WindowBackendScaleFactorChanged(e) => commands.write_event(e),
+        WindowEvent::WindowCloseRequested(e) => commands.write_event(e),
+        WindowEvent::WindowCreated(e) => commands.write_event(e),
+        WindowEvent::WindowDestroyed(e) => commands.write_event(e),
+        WindowEvent::WindowFocused(e) => commands.write_event(e),
+        WindowEvent::WindowMoved(e) => commands.write_event(e),
+        WindowEvent::WindowOccluded(e) => commands.write_event(e),
+        WindowEvent::WindowResized(e) => commands.write_event(e),
+        WindowEvent::WindowScaleFactorChanged(e) => commands.write_event(e),
+        WindowEvent::WindowThemeChanged(e) => commands.write_event(e),
+        WindowEvent::MouseButtonInput(e) => commands.write_event(e),
+        WindowEvent::MouseMotion(e) => commands.write_event(e),
+        WindowEvent::MouseWheel(e) => commands.write_event(e),
+        WindowEvent::PinchGesture(e) => commands.write_event(e),
+        WindowEvent::RotationGesture(e) => commands.write_event(e),
+        WindowEvent::DoubleTapGesture(e) => commands.write_event(e),
+        WindowEvent::PanGesture(e) => commands.write_event(e),
+        WindowEvent::TouchInput(e) => commands.write_event(e),
+        WindowEvent::KeyboardInput(e) => commands.write_event(e),
+        WindowEvent::KeyboardFocusLost(e) => commands.write_event(e),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_input::{
+        keyboard::{Key, KeyCode, KeyboardInput},
+        ButtonInput, ButtonState, InputPlugin,
+    };
+    use bevy_time::TimePlugin;
+
+    fn key_event(state: ButtonState) -> WindowEvent {
+        WindowEvent::KeyboardInput(KeyboardInput {
+            key_code: KeyCode::Space,
+            logical_key: Key::Space,
+            state,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        })
+    }
+
+    #[test]
+    fn replays_recorded_input() {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, InputRecordingPlugin::default()))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                10,
+            )));
+        app.update();
+        app.world_mut().write_event(key_event(ButtonState::Pressed));
+        app.update();
+        app.world_mut()
+            .write_event(key_event(ButtonState::Released));
+        app.update();
+
+        let recording = app.world_mut().resource_mut::<InputRecorder>().take();
+        assert_eq!(recording.frames.len(), 3);
+        assert_eq!(recording.frames[2].delta, Duration::from_millis(10));
+        assert_eq!(
+            recording.frames[1].events,
+            [RecordedEvent::Window(key_event(ButtonState::Pressed))]
+        );
+
+        let serialized = ron::to_string(&recording).unwrap();
+        let recording: InputRecording = ron::from_str(&serialized).unwrap();
+
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, InputPlugin, InputReplayPlugin::new(recording)));
+        let mut pressed = Vec::new();
+        while !app.world().resource::<InputReplay>().is_finished() {
+            app.update();
+            pressed.push(
+                app.world()
+                    .resource::<ButtonInput<KeyCode>>()
+                    .pressed(KeyCode::Space),
+            );
+        }
+        assert_eq!(pressed, [false, true, false]);
+        assert_eq!(
+            app.world().resource::<Time<Real>>().elapsed(),
+            Duration::from_millis(20)
+        );
+    }
+}
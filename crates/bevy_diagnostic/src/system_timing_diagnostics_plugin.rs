@@ -0,0 +1,171 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use bevy_app::prelude::*;
+use bevy_ecs::{prelude::*, schedule::SystemTimings};
+use bevy_platform::{collections::HashMap, hash::PassHash, time::Instant};
+
+use crate::{
+    Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore, DEFAULT_MAX_HISTORY_LENGTH,
+};
+
+/// Adds a run time diagnostic, in milliseconds, for every system and named system set to an App.
+///
+/// Systems are recorded under `systems/<schedule>/<system>`, for example `systems/Update/my_system`,
+/// and system sets under `system_sets/<schedule>/<set>`. A set's run time is the sum of the run times
+/// of the systems in it, so it can be larger than the wall-clock time spent in the set when its
+/// systems run in parallel. Systems with the same name in the same schedule share a diagnostic.
+///
+/// The measurements are taken by the schedule executors through the [`SystemTimings`] resource,
+/// so no external profiler is needed. System names are only available when the `debug` feature
+/// of `bevy_utils` is enabled.
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) to output diagnostics to the console.
+pub struct SystemTimingDiagnosticsPlugin {
+    /// The total number of values to keep for averaging.
+    pub max_history_length: usize,
+    /// The smoothing factor for the exponential moving average. Usually `2.0 / (history_length + 1.0)`.
+    pub smoothing_factor: f64,
+}
+
+impl Default for SystemTimingDiagnosticsPlugin {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_HISTORY_LENGTH)
+    }
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    /// Creates a new `SystemTimingDiagnosticsPlugin` with the specified `max_history_length` and a
+    /// reasonable `smoothing_factor`.
+    pub fn new(max_history_length: usize) -> Self {
+        Self {
+            max_history_length,
+            smoothing_factor: 2.0 / (max_history_length as f64 + 1.0),
+        }
+    }
+}
+
+impl Plugin for SystemTimingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DiagnosticsStore>()
+            .init_resource::<SystemTimings>()
+            .insert_resource(SystemTimingDiagnosticsSettings {
+                max_history_length: self.max_history_length,
+                smoothing_factor: self.smoothing_factor,
+            })
+            .add_systems(Last, Self::diagnostic_system);
+    }
+}
+
+/// The settings used for the diagnostics created by [`SystemTimingDiagnosticsPlugin`].
+#[derive(Resource)]
+struct SystemTimingDiagnosticsSettings {
+    max_history_length: usize,
+    smoothing_factor: f64,
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    /// Prefix of the run time diagnostics of systems.
+    pub const SYSTEMS: DiagnosticPath = DiagnosticPath::const_new("systems");
+
+    /// Prefix of the run time diagnostics of system sets.
+    pub const SYSTEM_SETS: DiagnosticPath = DiagnosticPath::const_new("system_sets");
+
+    /// Adds the run times recorded in [`SystemTimings`] since the last run as measurements.
+    fn diagnostic_system(
+        timings: Res<SystemTimings>,
+        settings: Res<SystemTimingDiagnosticsSettings>,
+        mut store: ResMut<DiagnosticsStore>,
+    ) {
+        let mut totals = HashMap::<DiagnosticPath, f64, PassHash>::default();
+        for timing in timings.drain() {
+            let info = &timing.system;
+            let milliseconds = timing.duration.as_secs_f64() * 1000.0;
+            let schedule = path_component(format!("{:?}", info.schedule));
+
+            let system = path_component(info.name.shortname().to_string());
+            *totals
+                .entry(DiagnosticPath::from_components([
+                    Self::SYSTEMS.as_str(),
+                    &schedule,
+                    &system,
+                ]))
+                .or_default() += milliseconds;
+
+            for set in &info.sets {
+                let set = path_component(format!("{set:?}"));
+                *totals
+                    .entry(DiagnosticPath::from_components([
+                        Self::SYSTEM_SETS.as_str(),
+                        &schedule,
+                        &set,
+                    ]))
+                    .or_default() += milliseconds;
+            }
+        }
+
+        let time = Instant::now();
+        for (path, value) in totals {
+            if store.get(&path).is_none() {
+                store.add(
+                    Diagnostic::new(path.clone())
+                        .with_suffix("ms")
+                        .with_max_history_length(settings.max_history_length)
+                        .with_smoothing_factor(settings.smoothing_factor),
+                );
+            }
+            if let Some(diagnostic) = store
+                .get_mut(&path)
+                .filter(|diagnostic| diagnostic.is_enabled)
+            {
+                diagnostic.add_measurement(DiagnosticMeasurement { time, value });
+            }
+        }
+    }
+}
+
+/// Makes a name usable as a single [`DiagnosticPath`] component.
+fn path_component(name: impl Into<String>) -> String {
+    let name = name.into();
+    if name.is_empty() {
+        "_".into()
+    } else {
+        name.replace('/', "|")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::schedule::IntoScheduleConfigs;
+
+    #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+    struct TimedSystems;
+
+    fn timed_system() {}
+
+    #[test]
+    fn records_systems_and_sets() {
+        let mut app = App::new();
+        app.add_plugins(SystemTimingDiagnosticsPlugin::default())
+            .add_systems(Update, timed_system.in_set(TimedSystems));
+
+        // Timings of systems running after the diagnostic system are added on the next update.
+        app.update();
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let set = store
+            .get(&DiagnosticPath::new("system_sets/Update/TimedSystems"))
+            .unwrap();
+        assert_eq!(set.suffix, "ms");
+        assert!(set.value().is_some());
+        assert!(store
+            .iter()
+            .any(|diagnostic| diagnostic.path().as_str().starts_with("systems/Update/")));
+    }
+}
@@ -10,7 +10,9 @@ use crate::{
     archetype::Archetype,
     bundle::{Bundle, BundleRemover, InsertMode},
     change_detection::MaybeLocation,
-    component::{Component, ComponentCloneBehavior, ComponentCloneFn, ComponentId, ComponentInfo},
+    component::{
+        Component, ComponentCloneBehavior, ComponentCloneFn, ComponentId, ComponentInfo, Components,
+    },
     entity::{hash_map::EntityHashMap, Entities, Entity, EntityMapper},
     query::DebugCheckedUnwrap,
     relationship::RelationshipHookMode,
@@ -150,11 +152,14 @@ impl<'a, 'b> ComponentCloneCtx<'a, 'b> {
     }
 
     /// Returns the [`ComponentId`] of the component being cloned.
+    ///
+    /// When cloning into [another world](EntityCloner::clone_entity_to_world), this is the id of
+    /// the component in the target world.
     pub fn component_id(&self) -> ComponentId {
         self.component_id
     }
 
-    /// Returns the [`ComponentInfo`] of the component being cloned.
+    /// Returns the [`ComponentInfo`] of the component being cloned, as registered in the source world.
     pub fn component_info(&self) -> &ComponentInfo {
         self.component_info
     }
@@ -506,7 +511,97 @@ impl EntityCloner {
         source: Entity,
         mapper: &mut dyn EntityMapper,
     ) -> Entity {
-        Self::clone_entity_mapped_internal(&mut self.state, &mut self.filter, world, source, mapper)
+        Self::clone_entity_mapped_internal(
+            &mut self.state,
+            &mut self.filter,
+            world,
+            None,
+            source,
+            mapper,
+        )
+    }
+
+    /// Clones and inserts components from the `source` entity in `source_world` into the `target` entity
+    /// in `target_world` using the stored configuration.
+    ///
+    /// Components are matched between the worlds by their [`TypeId`], so they must already be registered
+    /// in `target_world`. Components that are not registered there, or that have no [`TypeId`], are skipped.
+    /// Entities referenced by the cloned components are mapped like they are within a single world, so
+    /// references to entities that are not cloned along with `source` will not be valid in `target_world`.
+    ///
+    /// If this [`EntityCloner`] is configured to [move components](EntityClonerBuilder::move_components),
+    /// the components are removed from `source`. If it has [`EntityCloner::linked_cloning`], entities
+    /// linked through [`RelationshipTarget`](crate::relationship::RelationshipTarget) components are
+    /// cloned into `target_world` as well, which allows copying or moving whole hierarchies.
+    ///
+    /// The [`EntityCloner`] must have been built with `source_world`.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::entity::EntityCloner;
+    /// #[derive(Component, Clone, PartialEq, Debug)]
+    /// struct Health(u32);
+    ///
+    /// let mut simulation = World::new();
+    /// let mut render = World::new();
+    /// render.register_component::<Health>();
+    ///
+    /// let source = simulation.spawn(Health(10)).id();
+    /// let target = render.spawn_empty().id();
+    /// EntityCloner::build_opt_out(&mut simulation)
+    ///     .finish()
+    ///     .clone_entity_to_world(&mut simulation, source, &mut render, target);
+    ///
+    /// assert_eq!(render.get::<Health>(target), Some(&Health(10)));
+    /// ```
+    #[track_caller]
+    pub fn clone_entity_to_world(
+        &mut self,
+        source_world: &mut World,
+        source: Entity,
+        target_world: &mut World,
+        target: Entity,
+    ) {
+        let mut map = EntityHashMap::<Entity>::new();
+        map.set_mapped(source, target);
+        self.clone_entity_mapped_to_world(source_world, source, target_world, &mut map);
+    }
+
+    /// Clones and inserts components from the `source` entity in `source_world` into a newly spawned entity
+    /// in `target_world` using the stored configuration.
+    ///
+    /// See [`EntityCloner::clone_entity_to_world`] for more information.
+    #[track_caller]
+    pub fn spawn_clone_in_world(
+        &mut self,
+        source_world: &mut World,
+        source: Entity,
+        target_world: &mut World,
+    ) -> Entity {
+        let target = target_world.spawn_empty().id();
+        self.clone_entity_to_world(source_world, source, target_world, target);
+        target
+    }
+
+    /// Clones the entity in `source_world` into whatever entity in `target_world` `mapper` chooses for it.
+    ///
+    /// See [`EntityCloner::clone_entity_to_world`] for more information.
+    #[track_caller]
+    pub fn clone_entity_mapped_to_world(
+        &mut self,
+        source_world: &mut World,
+        source: Entity,
+        target_world: &mut World,
+        mapper: &mut dyn EntityMapper,
+    ) -> Entity {
+        Self::clone_entity_mapped_internal(
+            &mut self.state,
+            &mut self.filter,
+            source_world,
+            Some(target_world),
+            source,
+            mapper,
+        )
     }
 
     #[track_caller]
@@ -515,6 +610,7 @@ impl EntityCloner {
         state: &mut EntityClonerState,
         filter: &mut impl CloneByFilter,
         world: &mut World,
+        mut target_world: Option<&mut World>,
         source: Entity,
         mapper: &mut dyn EntityMapper,
     ) -> Entity {
@@ -523,6 +619,7 @@ impl EntityCloner {
             state,
             filter,
             world,
+            target_world.as_deref_mut(),
             source,
             mapper,
             RelationshipHookMode::Run,
@@ -543,6 +640,7 @@ impl EntityCloner {
                     state,
                     filter,
                     world,
+                    target_world.as_deref_mut(),
                     queued,
                     mapper,
                     child_hook_insert_mode,
@@ -555,11 +653,14 @@ impl EntityCloner {
     }
 
     /// Clones and inserts components from the `source` entity into the entity mapped by `mapper` from `source` using the stored configuration.
+    ///
+    /// If `target_world` is `Some`, the target entity lives in that world instead of `world`.
     #[track_caller]
     fn clone_entity_internal(
         state: &mut EntityClonerState,
         filter: &mut impl CloneByFilter,
         world: &mut World,
+        mut target_world: Option<&mut World>,
         source: Entity,
         mapper: &mut dyn EntityMapper,
         relationship_hook_insert_mode: RelationshipHookMode,
@@ -569,10 +670,19 @@ impl EntityCloner {
         let bundle_scratch_allocator = Bump::new();
         let mut bundle_scratch: BundleScratch;
         let mut moved_components: Vec<ComponentId> = Vec::new();
-        let mut deferred_cloned_component_ids: Vec<ComponentId> = Vec::new();
+        let mut cloned_components: Vec<ComponentId> = Vec::new();
         {
-            let world = world.as_unsafe_world_cell();
-            let source_entity = world.get_entity(source).expect("Source entity must exist");
+            let source_world = world.as_unsafe_world_cell();
+            let world = target_world
+                .as_deref_mut()
+                .map_or(source_world, World::as_unsafe_world_cell);
+            let source_entity = source_world
+                .get_entity(source)
+                .expect("Source entity must exist");
+            let target_components = (world.id() != source_world.id()).then(|| world.components());
+            let target_component_id = |component| {
+                map_component_id(source_world.components(), target_components, component)
+            };
 
             #[cfg(feature = "bevy_reflect")]
             // SAFETY: we have unique access to `world`, nothing else accesses the registry at this moment, and we clone
@@ -594,6 +704,9 @@ impl EntityCloner {
                     .expect("Target entity must exist")
                     .archetype()
             });
+            let target_contains = |component| {
+                target_component_id(component).is_some_and(|id| target_archetype.contains(id))
+            };
 
             if state.move_components {
                 moved_components.reserve(source_archetype.component_count());
@@ -602,9 +715,13 @@ impl EntityCloner {
                 state.default_clone_fn = |_, ctx| ctx.move_component();
             }
 
-            filter.clone_components(source_archetype, target_archetype, |component| {
+            filter.clone_components(source_archetype, target_contains, |component| {
+                let Some(target_component) = target_component_id(component) else {
+                    return;
+                };
+
                 let handler = match state.clone_behavior_overrides.get(&component).or_else(|| {
-                    world
+                    source_world
                         .components()
                         .get_info(component)
                         .map(ComponentInfo::clone_behavior)
@@ -618,7 +735,7 @@ impl EntityCloner {
                 };
 
                 // SAFETY: This component exists because it is present on the archetype.
-                let info = unsafe { world.components().get_info_unchecked(component) };
+                let info = unsafe { source_world.components().get_info_unchecked(component) };
 
                 // SAFETY:
                 // - There are no other mutable references to source entity.
@@ -632,11 +749,11 @@ impl EntityCloner {
                 };
 
                 // SAFETY:
-                // - `components` and `component` are from the same world
-                // - `source_component_ptr` is valid and points to the same type as represented by `component`
+                // - `target_component` is from the same world as `target` and has the same type as `info`
+                // - `source_component_ptr` is valid and points to the same type as represented by `info`
                 let mut ctx = unsafe {
                     ComponentCloneCtx::new(
-                        component,
+                        target_component,
                         source,
                         target,
                         &bundle_scratch_allocator,
@@ -655,39 +772,35 @@ impl EntityCloner {
                     if ctx.target_component_moved {
                         moved_components.push(component);
                     }
-                    // Component wasn't written by the clone handler, so assume it's going to be
+                    // Component was either written by the clone handler, or it's going to be
                     // cloned/processed using deferred_commands instead.
-                    // This means that it's ComponentId won't be present in BundleScratch's component_ids,
-                    // but it should still be removed when move_components is true.
-                    else if !ctx.target_component_written() {
-                        deferred_cloned_component_ids.push(component);
+                    // Either way it should be removed from the source entity when move_components is true.
+                    else {
+                        cloned_components.push(component);
                     }
                 }
             });
         }
 
-        world.flush();
+        {
+            let target_world = target_world.as_deref_mut().unwrap_or(&mut *world);
+            target_world.flush();
 
-        for deferred in state.deferred_commands.drain(..) {
-            (deferred)(world, mapper);
-        }
+            for deferred in state.deferred_commands.drain(..) {
+                (deferred)(target_world, mapper);
+            }
 
-        if !world.entities.contains(target) {
-            panic!("Target entity does not exist");
+            if !target_world.entities.contains(target) {
+                panic!("Target entity does not exist");
+            }
         }
 
         if state.move_components {
+            let target_components = target_world.as_deref().map(World::components);
             let mut source_entity = world.entity_mut(source);
 
-            let cloned_components = if deferred_cloned_component_ids.is_empty() {
-                &bundle_scratch.component_ids
-            } else {
-                // Remove all cloned components with drop by concatenating both vectors
-                deferred_cloned_component_ids.extend(&bundle_scratch.component_ids);
-                &deferred_cloned_component_ids
-            };
             source_entity.remove_by_ids_with_caller(
-                cloned_components,
+                &cloned_components,
                 MaybeLocation::caller(),
                 RelationshipHookMode::RunIfNotLinked,
                 BundleRemover::empty_pre_remove,
@@ -718,20 +831,25 @@ impl EntityCloner {
 
                         // SAFETY: component_id is valid because remove_by_ids_with_caller checked it before calling this closure
                         let info = unsafe { components.get_info_unchecked(component_id) };
+                        // SAFETY: moved components were mapped to the target world when they were cloned
+                        let target_component_id = unsafe {
+                            map_component_id(components, target_components, component_id)
+                                .debug_checked_unwrap()
+                        };
                         let layout = info.layout();
                         let target_ptr = bundle_scratch_allocator.alloc_layout(layout);
                         // SAFETY:
                         // - component_ptr points to data with component layout
                         // - target_ptr was just allocated with component layout
                         // - component_ptr and target_ptr don't overlap
-                        // - component_ptr matches component_id
+                        // - component_ptr matches target_component_id
                         unsafe {
                             core::ptr::copy_nonoverlapping(
                                 component_ptr.as_ptr(),
                                 target_ptr.as_ptr(),
                                 layout.size(),
                             );
-                            bundle_scratch.push_ptr(component_id, PtrMut::new(target_ptr));
+                            bundle_scratch.push_ptr(target_component_id, PtrMut::new(target_ptr));
                         }
                     }
 
@@ -743,11 +861,30 @@ impl EntityCloner {
         // SAFETY:
         // - All `component_ids` are from the same world as `target` entity
         // - All `component_data_ptrs` are valid types represented by `component_ids`
-        unsafe { bundle_scratch.write(world, target, relationship_hook_insert_mode) };
+        unsafe {
+            bundle_scratch.write(
+                target_world.unwrap_or(world),
+                target,
+                relationship_hook_insert_mode,
+            );
+        };
         target
     }
 }
 
+/// Maps a component of the source world to the same component in the target world, which is
+/// identified by its [`TypeId`]. If `target` is `None`, both worlds are the same.
+fn map_component_id(
+    source: &Components,
+    target: Option<&Components>,
+    component: ComponentId,
+) -> Option<ComponentId> {
+    match target {
+        None => Some(component),
+        Some(target) => target.get_valid_id(source.get_info(component)?.type_id()?),
+    }
+}
+
 /// Part of the [`EntityCloner`], see there for more information.
 struct EntityClonerState {
     clone_behavior_overrides: HashMap<ComponentId, ComponentCloneBehavior>,
@@ -787,6 +924,7 @@ impl<'w, Filter: CloneByFilter> EntityClonerBuilder<'w, Filter> {
             &mut self.state,
             &mut self.filter,
             self.world,
+            None,
             source,
             &mut mapper,
         );
@@ -1037,10 +1175,12 @@ impl<'w> EntityClonerBuilder<'w, OptIn> {
 #[doc(hidden)]
 pub trait CloneByFilter: Into<EntityClonerFilter> {
     /// The filter will call `clone_component` for every [`ComponentId`] that passes it.
-    fn clone_components<'a>(
+    ///
+    /// `target_contains` returns whether the target entity already contains a component of the source world.
+    fn clone_components(
         &mut self,
         source_archetype: &Archetype,
-        target_archetype: LazyCell<&'a Archetype, impl FnOnce() -> &'a Archetype>,
+        target_contains: impl Fn(ComponentId) -> bool,
         clone_component: impl FnMut(ComponentId),
     );
 }
@@ -1061,18 +1201,18 @@ impl Default for EntityClonerFilter {
 
 impl CloneByFilter for EntityClonerFilter {
     #[inline]
-    fn clone_components<'a>(
+    fn clone_components(
         &mut self,
         source_archetype: &Archetype,
-        target_archetype: LazyCell<&'a Archetype, impl FnOnce() -> &'a Archetype>,
+        target_contains: impl Fn(ComponentId) -> bool,
         clone_component: impl FnMut(ComponentId),
     ) {
         match self {
             Self::OptOut(filter) => {
-                filter.clone_components(source_archetype, target_archetype, clone_component);
+                filter.clone_components(source_archetype, target_contains, clone_component);
             }
             Self::OptIn(filter) => {
-                filter.clone_components(source_archetype, target_archetype, clone_component);
+                filter.clone_components(source_archetype, target_contains, clone_component);
             }
         }
     }
@@ -1108,10 +1248,10 @@ impl Default for OptOut {
 
 impl CloneByFilter for OptOut {
     #[inline]
-    fn clone_components<'a>(
+    fn clone_components(
         &mut self,
         source_archetype: &Archetype,
-        target_archetype: LazyCell<&'a Archetype, impl FnOnce() -> &'a Archetype>,
+        target_contains: impl Fn(ComponentId) -> bool,
         mut clone_component: impl FnMut(ComponentId),
     ) {
         match self.insert_mode {
@@ -1124,7 +1264,7 @@ impl CloneByFilter for OptOut {
             }
             InsertMode::Keep => {
                 for component in source_archetype.components() {
-                    if !target_archetype.contains(component) && !self.deny.contains(&component) {
+                    if !target_contains(component) && !self.deny.contains(&component) {
                         clone_component(component);
                     }
                 }
@@ -1182,10 +1322,10 @@ impl Default for OptIn {
 
 impl CloneByFilter for OptIn {
     #[inline]
-    fn clone_components<'a>(
+    fn clone_components(
         &mut self,
         source_archetype: &Archetype,
-        target_archetype: LazyCell<&'a Archetype, impl FnOnce() -> &'a Archetype>,
+        target_contains: impl Fn(ComponentId) -> bool,
         mut clone_component: impl FnMut(ComponentId),
     ) {
         // track the amount of components left not being cloned yet to exit this method early
@@ -1207,8 +1347,7 @@ impl CloneByFilter for OptIn {
             }
 
             let do_clone = source_archetype.contains(component)
-                && (explicit.insert_mode == InsertMode::Replace
-                    || !target_archetype.contains(component));
+                && (explicit.insert_mode == InsertMode::Replace || !target_contains(component));
             if do_clone {
                 clone_component(component);
                 uncloned_components -= 1;
@@ -1231,7 +1370,7 @@ impl CloneByFilter for OptIn {
             .filter_map(|(&component, required)| {
                 let do_clone = required.required_by_reduced > 0 // required by a cloned component
                     && source_archetype.contains(component) // must exist to clone, may miss if removed
-                    && !target_archetype.contains(component); // do not overwrite existing values
+                    && !target_contains(component); // do not overwrite existing values
 
                 // reset changed `Required::required_by_reduced` as this is done being checked here
                 required.reset();
@@ -2593,4 +2732,86 @@ mod tests {
         assert_eq!(moved_source.data, source_data);
         assert_eq!(moved_source.target, e_target_moved);
     }
+
+    #[test]
+    fn clone_hierarchy_to_another_world() {
+        #[derive(Component, Clone, PartialEq, Debug)]
+        struct A(u32);
+
+        #[derive(Component, Clone)]
+        struct NotInTarget;
+
+        let mut source_world = World::default();
+        let parent = source_world.spawn((A(0), NotInTarget)).id();
+        source_world.spawn((A(1), ChildOf(parent)));
+        source_world.spawn((A(2), ChildOf(parent)));
+
+        let mut target_world = World::default();
+        target_world.register_component::<A>();
+        target_world.register_component::<ChildOf>();
+        target_world.register_component::<Children>();
+
+        let mut builder = EntityCloner::build_opt_out(&mut source_world);
+        builder.linked_cloning(true);
+        let cloned_parent =
+            builder
+                .finish()
+                .spawn_clone_in_world(&mut source_world, parent, &mut target_world);
+
+        assert_eq!(target_world.get::<A>(cloned_parent), Some(&A(0)));
+        assert_eq!(target_world.entities().len(), 3);
+        let children = target_world.get::<Children>(cloned_parent).unwrap();
+        let mut values = children
+            .iter()
+            .map(|&child| {
+                assert_eq!(
+                    target_world.get::<ChildOf>(child),
+                    Some(&ChildOf(cloned_parent))
+                );
+                target_world.get::<A>(child).unwrap().0
+            })
+            .collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, [1, 2]);
+
+        assert_eq!(source_world.get::<A>(parent), Some(&A(0)));
+        assert_eq!(source_world.get::<Children>(parent).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn move_entity_to_another_world() {
+        #[derive(Component, Clone, PartialEq, Debug)]
+        struct A(u32);
+
+        #[derive(Component, PartialEq, Debug)]
+        #[component(clone_behavior = Default)]
+        struct B(alloc::string::String);
+
+        #[derive(Component, Clone, PartialEq, Debug)]
+        #[component(storage = "SparseSet")]
+        struct C(u32);
+
+        let mut source_world = World::default();
+        let source = source_world.spawn((A(1), B("b".into()), C(3))).id();
+
+        let mut target_world = World::default();
+        let target = target_world.spawn(A(0)).id();
+        target_world.register_component::<B>();
+        target_world.register_component::<C>();
+
+        let mut builder = EntityCloner::build_opt_out(&mut source_world);
+        builder.move_components(true);
+        builder.finish().clone_entity_to_world(
+            &mut source_world,
+            source,
+            &mut target_world,
+            target,
+        );
+
+        let source = source_world.entity(source);
+        assert!(!source.contains::<A>() && !source.contains::<B>() && !source.contains::<C>());
+        assert_eq!(target_world.get::<A>(target), Some(&A(1)));
+        assert_eq!(target_world.get::<B>(target), Some(&B("b".into())));
+        assert_eq!(target_world.get::<C>(target), Some(&C(3)));
+    }
 }
@@ -0,0 +1,704 @@
+//! Components that are defined at runtime and stored as reflected values.
+//!
+//! These components have no Rust type, so they have no [`TypeRegistration`] and no
+//! [`ReflectComponent`](super::ReflectComponent). Instead, they are looked up by name in the
+//! [`DynamicReflectComponents`] resource, which takes the place of the [`TypeRegistry`] for them:
+//! `bevy_scene` and `bevy_remote` use their name where they would use a type path, and
+//! (de)serialize their values with `DynamicReflectSerializer` and `DynamicReflectDeserializer`.
+//!
+//! [`TypeRegistration`]: bevy_reflect::TypeRegistration
+//! [`TypeRegistry`]: bevy_reflect::TypeRegistry
+
+#[cfg(feature = "serialize")]
+mod serde;
+
+#[cfg(feature = "serialize")]
+pub use serde::{DynamicReflectDeserializer, DynamicReflectSerializer};
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    vec::Vec,
+};
+use core::{
+    alloc::Layout,
+    fmt::{Debug, Formatter},
+    mem::ManuallyDrop,
+};
+
+use bevy_platform::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use bevy_ptr::{OwningPtr, Ptr};
+use bevy_reflect::{
+    ApplyError, DynamicEnum, DynamicStruct, DynamicTuple, DynamicTupleStruct, DynamicVariant,
+    PartialReflect, ReflectMut, ReflectRef, VariantType,
+};
+use thiserror::Error;
+
+use crate::{
+    change_detection::Mut,
+    component::{ComponentCloneBehavior, ComponentDescriptor, ComponentId, StorageType},
+    entity::{ComponentCloneCtx, Entity, EntityMapper, SourceComponent},
+    resource::Resource,
+    world::{EntityWorldMut, FromWorld, World, WorldId},
+};
+
+/// The value stored in the ECS for each dynamic reflect component.
+type DynamicReflectValue = Box<dyn PartialReflect>;
+
+/// Describes a component that is defined at runtime from a reflected template value,
+/// for example a [`DynamicStruct`](bevy_reflect::DynamicStruct) built from a schema.
+///
+/// Register it with [`World::register_dynamic_reflect_component`].
+pub struct DynamicReflectComponentDescriptor {
+    name: Cow<'static, str>,
+    template: DynamicReflectValue,
+    storage_type: StorageType,
+}
+
+impl DynamicReflectComponentDescriptor {
+    /// Creates a new descriptor for a component called `name`.
+    ///
+    /// `template` defines the shape of the component, and provides the values of all fields
+    /// that are not set when the component is inserted.
+    pub fn new(name: impl Into<Cow<'static, str>>, template: &dyn PartialReflect) -> Self {
+        Self {
+            name: name.into(),
+            template: template.to_dynamic(),
+            storage_type: StorageType::Table,
+        }
+    }
+
+    /// Sets the [`StorageType`] of the component. Defaults to [`StorageType::Table`].
+    pub fn with_storage_type(mut self, storage_type: StorageType) -> Self {
+        self.storage_type = storage_type;
+        self
+    }
+}
+
+/// Information about a component registered with [`World::register_dynamic_reflect_component`].
+pub struct DynamicReflectComponentInfo {
+    id: ComponentId,
+    name: Cow<'static, str>,
+    template: DynamicReflectValue,
+}
+
+impl DynamicReflectComponentInfo {
+    /// Returns the [`ComponentId`] of the component.
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    /// Returns the name the component was registered with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the template value the component was registered with.
+    pub fn template(&self) -> &dyn PartialReflect {
+        &*self.template
+    }
+
+    /// Creates a value of this component from `value`, using the template for missing fields.
+    ///
+    /// Lists, maps and sets are taken from `value` as they are, so they can be shorter or longer
+    /// than in the template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` does not match the shape of the template.
+    pub fn create(&self, value: &dyn PartialReflect) -> Result<DynamicReflectValue, ApplyError> {
+        fill_from_template(&*self.template, value)
+    }
+
+    /// Maps the entities referred to by `value`, a value of this component, with `mapper`.
+    ///
+    /// Dynamic reflect components have no [`ReflectMapEntities`](super::ReflectMapEntities), so
+    /// every [`Entity`] found anywhere in the value, including in the keys of maps and in sets,
+    /// is mapped.
+    pub fn map_entities(&self, value: &mut dyn PartialReflect, mapper: &mut dyn EntityMapper) {
+        map_reflect_entities(value, mapper);
+    }
+
+    /// Returns the reflected value of this component behind `ptr`.
+    ///
+    /// This allows reading dynamic reflect components from the [`Ptr`]s returned when
+    /// iterating queries built with [`QueryBuilder`](crate::query::QueryBuilder).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a value of the component described by `self`, in the [`World`]
+    /// this information was retrieved from.
+    pub unsafe fn reflect<'a>(&self, ptr: Ptr<'a>) -> &'a dyn PartialReflect {
+        // SAFETY: The caller ensures `ptr` points to this component, which is stored as a `DynamicReflectValue`.
+        unsafe { &**ptr.deref::<DynamicReflectValue>() }
+    }
+}
+
+/// A [`Resource`] tracking the components registered with [`World::register_dynamic_reflect_component`].
+///
+/// It is inserted automatically when the first such component is registered.
+/// Like [`AppTypeRegistry`](super::AppTypeRegistry), clones of this resource share their
+/// contents, so that code without access to the [`World`], such as asset loaders, can keep
+/// looking up components registered later on.
+#[derive(Resource, Clone)]
+pub struct DynamicReflectComponents {
+    world: WorldId,
+    components: Arc<RwLock<DynamicReflectComponentMap>>,
+}
+
+impl FromWorld for DynamicReflectComponents {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            world: world.id(),
+            components: Arc::default(),
+        }
+    }
+}
+
+impl Debug for DynamicReflectComponents {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let components = self.read();
+        f.debug_struct("DynamicReflectComponents")
+            .field("world", &self.world)
+            .field("components", &components.by_name)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct DynamicReflectComponentMap {
+    by_id: HashMap<ComponentId, Arc<DynamicReflectComponentInfo>>,
+    by_name: HashMap<Cow<'static, str>, ComponentId>,
+}
+
+impl DynamicReflectComponents {
+    fn read(&self) -> RwLockReadGuard<'_, DynamicReflectComponentMap> {
+        self.components
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, DynamicReflectComponentMap> {
+        self.components
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the information about the dynamic reflect component with the given `id`,
+    /// or [`None`] if `id` is not a dynamic reflect component.
+    pub fn get(&self, id: ComponentId) -> Option<Arc<DynamicReflectComponentInfo>> {
+        self.read().by_id.get(&id).cloned()
+    }
+
+    /// Returns the information about the dynamic reflect component registered as `name`,
+    /// or [`None`] if there is no such component.
+    ///
+    /// If several components were registered with the same name, the last one is returned.
+    pub fn get_by_name(&self, name: &str) -> Option<Arc<DynamicReflectComponentInfo>> {
+        let components = self.read();
+        let id = components.by_name.get(name)?;
+        components.by_id.get(id).cloned()
+    }
+
+    /// Returns the information about all registered dynamic reflect components.
+    pub fn iter(&self) -> impl Iterator<Item = Arc<DynamicReflectComponentInfo>> {
+        self.read()
+            .by_id
+            .values()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// An error returned when inserting a dynamic reflect component.
+#[derive(Error, Debug)]
+pub enum DynamicReflectComponentError {
+    /// The component was not registered with [`World::register_dynamic_reflect_component`].
+    #[error("{0:?} is not a dynamic reflect component")]
+    NotDynamicReflectComponent(ComponentId),
+    /// The value does not match the shape of the component.
+    #[error("the value does not match the shape of the component: {0}")]
+    Apply(#[from] ApplyError),
+}
+
+impl World {
+    /// Registers a component that is defined at runtime, and whose values are stored as
+    /// reflected data matching the template of `descriptor`.
+    ///
+    /// Values of the component can be inserted with [`EntityWorldMut::insert_dynamic_reflect`]
+    /// and accessed with [`World::get_dynamic_reflect`] and [`World::get_dynamic_reflect_mut`].
+    /// Like any component registered from a [`ComponentDescriptor`], it can be used in queries
+    /// through [`QueryBuilder`](crate::query::QueryBuilder).
+    ///
+    /// The component isn't part of the type registry, but it is extracted into scenes and visible
+    /// to `bevy_remote` under its name, which should therefore be unique.
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, reflect::DynamicReflectComponentDescriptor};
+    /// # use bevy_reflect::{DynamicStruct, GetField};
+    /// let mut world = World::new();
+    ///
+    /// let mut health = DynamicStruct::default();
+    /// health.insert("current", 100.0_f32);
+    /// health.insert("max", 100.0_f32);
+    /// let id = world.register_dynamic_reflect_component(DynamicReflectComponentDescriptor::new(
+    ///     "Health", &health,
+    /// ));
+    ///
+    /// // Fields that are not set are taken from the template.
+    /// let mut value = DynamicStruct::default();
+    /// value.insert("current", 25.0_f32);
+    /// let entity = world.spawn_empty().insert_dynamic_reflect(id, &value).unwrap().id();
+    ///
+    /// let health = world.get_dynamic_reflect(entity, id).unwrap();
+    /// let health = health.reflect_ref().as_struct().unwrap();
+    /// assert_eq!(health.get_field::<f32>("current"), Some(&25.0));
+    /// assert_eq!(health.get_field::<f32>("max"), Some(&100.0));
+    /// ```
+    pub fn register_dynamic_reflect_component(
+        &mut self,
+        descriptor: DynamicReflectComponentDescriptor,
+    ) -> ComponentId {
+        // SAFETY: `DynamicReflectValue` is `Send + Sync`, and `drop_value` matches the layout.
+        let component_descriptor = unsafe {
+            ComponentDescriptor::new_with_layout(
+                descriptor.name.clone(),
+                descriptor.storage_type,
+                Layout::new::<DynamicReflectValue>(),
+                Some(drop_value),
+                true,
+                ComponentCloneBehavior::Custom(clone_value),
+            )
+        };
+        let id = self.register_component_with_descriptor(component_descriptor);
+
+        if self.dynamic_reflect_components().is_none() {
+            let components = DynamicReflectComponents::from_world(self);
+            self.insert_resource(components);
+        }
+        let mut components = self.resource::<DynamicReflectComponents>().write();
+        components.by_name.insert(descriptor.name.clone(), id);
+        components.by_id.insert(
+            id,
+            Arc::new(DynamicReflectComponentInfo {
+                id,
+                name: descriptor.name,
+                template: descriptor.template,
+            }),
+        );
+        id
+    }
+
+    /// Returns the [`DynamicReflectComponents`] of this world, if any were registered.
+    pub fn dynamic_reflect_components(&self) -> Option<&DynamicReflectComponents> {
+        self.get_resource::<DynamicReflectComponents>()
+            .filter(|components| components.world == self.id())
+    }
+
+    /// Returns the value of the dynamic reflect component `id` on `entity`.
+    ///
+    /// Returns [`None`] if the entity does not exist, does not have the component, or if `id`
+    /// was not registered with [`World::register_dynamic_reflect_component`].
+    pub fn get_dynamic_reflect(
+        &self,
+        entity: Entity,
+        id: ComponentId,
+    ) -> Option<&dyn PartialReflect> {
+        let info = self.dynamic_reflect_components()?.get(id)?;
+        let ptr = self.get_by_id(entity, id)?;
+        // SAFETY: `info` describes `id` in this world, and `ptr` points to that component.
+        Some(unsafe { info.reflect(ptr) })
+    }
+
+    /// Returns a mutable reference to the value of the dynamic reflect component `id` on `entity`.
+    ///
+    /// Returns [`None`] if the entity does not exist, does not have the component, or if `id`
+    /// was not registered with [`World::register_dynamic_reflect_component`].
+    pub fn get_dynamic_reflect_mut(
+        &mut self,
+        entity: Entity,
+        id: ComponentId,
+    ) -> Option<Mut<'_, dyn PartialReflect>> {
+        self.dynamic_reflect_components()?.get(id)?;
+        let value = self.get_mut_by_id(entity, id)?;
+        // SAFETY: `id` is a dynamic reflect component of this world, so it stores a `DynamicReflectValue`.
+        Some(value.map_unchanged(|ptr| unsafe { &mut **ptr.deref_mut::<DynamicReflectValue>() }))
+    }
+}
+
+impl EntityWorldMut<'_> {
+    /// Inserts the dynamic reflect component `id` with the given `value`, replacing the existing
+    /// value if there is one.
+    ///
+    /// `value` is applied on top of the template the component was registered with, so it only
+    /// needs to contain the fields that differ from the template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `id` was not registered with [`World::register_dynamic_reflect_component`],
+    /// or if `value` does not match the shape of the component.
+    ///
+    /// # Panics
+    ///
+    /// If the entity has been despawned while this `EntityWorldMut` is still alive.
+    #[track_caller]
+    pub fn insert_dynamic_reflect(
+        &mut self,
+        id: ComponentId,
+        value: &dyn PartialReflect,
+    ) -> Result<&mut Self, DynamicReflectComponentError> {
+        let component = self
+            .world()
+            .dynamic_reflect_components()
+            .and_then(|components| components.get(id))
+            .ok_or(DynamicReflectComponentError::NotDynamicReflectComponent(id))?
+            .create(value)?;
+        OwningPtr::make(component, |ptr| {
+            // SAFETY: `id` is a dynamic reflect component of this world, so it stores a `DynamicReflectValue`.
+            unsafe { self.insert_by_id(id, ptr) }
+        });
+        Ok(self)
+    }
+}
+
+/// Builds a dynamic copy of `value`, taking the fields of structs, tuples and enum variants that
+/// are missing from `value` from `template`.
+fn fill_from_template(
+    template: &dyn PartialReflect,
+    value: &dyn PartialReflect,
+) -> Result<DynamicReflectValue, ApplyError> {
+    if template.reflect_kind() != value.reflect_kind() {
+        return Err(ApplyError::MismatchedKinds {
+            from_kind: value.reflect_kind(),
+            to_kind: template.reflect_kind(),
+        });
+    }
+
+    let represented_type = template.get_represented_type_info();
+    Ok(match (template.reflect_ref(), value.reflect_ref()) {
+        (ReflectRef::Struct(template), ReflectRef::Struct(value)) => {
+            let mut filled = DynamicStruct::default();
+            filled.set_represented_type(represented_type);
+            for (index, field) in template.iter_fields().enumerate() {
+                let name = template.name_at(index).unwrap_or_default();
+                filled.insert_boxed(name.to_owned(), fill_field(field, value.field(name))?);
+            }
+            Box::new(filled)
+        }
+        (ReflectRef::TupleStruct(template), ReflectRef::TupleStruct(value)) => {
+            let mut filled = DynamicTupleStruct::default();
+            filled.set_represented_type(represented_type);
+            for (index, field) in template.iter_fields().enumerate() {
+                filled.insert_boxed(fill_field(field, value.field(index))?);
+            }
+            Box::new(filled)
+        }
+        (ReflectRef::Tuple(template), ReflectRef::Tuple(value)) => {
+            let mut filled = DynamicTuple::default();
+            filled.set_represented_type(represented_type);
+            for (index, field) in template.iter_fields().enumerate() {
+                filled.insert_boxed(fill_field(field, value.field(index))?);
+            }
+            Box::new(filled)
+        }
+        (ReflectRef::Enum(template), ReflectRef::Enum(value))
+            if template.variant_name() == value.variant_name() =>
+        {
+            let variant = match value.variant_type() {
+                VariantType::Struct => {
+                    let mut variant = DynamicStruct::default();
+                    for field in template.iter_fields() {
+                        let name = field.name().unwrap_or_default();
+                        variant.insert_boxed(
+                            name.to_owned(),
+                            fill_field(field.value(), value.field(name))?,
+                        );
+                    }
+                    DynamicVariant::Struct(variant)
+                }
+                VariantType::Tuple => {
+                    let mut variant = DynamicTuple::default();
+                    for (index, field) in template.iter_fields().enumerate() {
+                        variant.insert_boxed(fill_field(field.value(), value.field_at(index))?);
+                    }
+                    DynamicVariant::Tuple(variant)
+                }
+                VariantType::Unit => DynamicVariant::Unit,
+            };
+            let mut filled = DynamicEnum::new(value.variant_name(), variant);
+            filled.set_represented_type(represented_type);
+            Box::new(filled)
+        }
+        (
+            ReflectRef::List(_) | ReflectRef::Map(_) | ReflectRef::Set(_) | ReflectRef::Enum(_),
+            _,
+        ) => value.to_dynamic(),
+        _ => {
+            let mut filled = template.to_dynamic();
+            filled.try_apply(value)?;
+            filled
+        }
+    })
+}
+
+/// Maps every [`Entity`] found in `value` with `mapper`.
+fn map_reflect_entities(value: &mut dyn PartialReflect, mapper: &mut dyn EntityMapper) {
+    if let Some(entity) = value.try_downcast_mut::<Entity>() {
+        *entity = mapper.get_mapped(*entity);
+        return;
+    }
+
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                map_reflect_entities(value.field_at_mut(index).unwrap(), mapper);
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                map_reflect_entities(value.field_mut(index).unwrap(), mapper);
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                map_reflect_entities(value.field_mut(index).unwrap(), mapper);
+            }
+        }
+        ReflectMut::List(value) => {
+            for index in 0..value.len() {
+                map_reflect_entities(value.get_mut(index).unwrap(), mapper);
+            }
+        }
+        ReflectMut::Array(value) => {
+            for index in 0..value.len() {
+                map_reflect_entities(value.get_mut(index).unwrap(), mapper);
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for index in 0..value.field_len() {
+                map_reflect_entities(value.field_at_mut(index).unwrap(), mapper);
+            }
+        }
+        // Keys can't be changed in place, so the entries are moved out and reinserted.
+        ReflectMut::Map(value) => {
+            for (mut key, mut entry) in value.drain() {
+                map_reflect_entities(&mut *key, mapper);
+                map_reflect_entities(&mut *entry, mapper);
+                value.insert_boxed(key, entry);
+            }
+        }
+        ReflectMut::Set(value) => {
+            for mut entry in value.drain() {
+                map_reflect_entities(&mut *entry, mapper);
+                value.insert_boxed(entry);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Fills a single field, which is taken from the template if `value` doesn't have it.
+fn fill_field(
+    template: &dyn PartialReflect,
+    value: Option<&dyn PartialReflect>,
+) -> Result<DynamicReflectValue, ApplyError> {
+    match value {
+        Some(value) => fill_from_template(template, value),
+        None => Ok(template.to_dynamic()),
+    }
+}
+
+/// Drops a [`DynamicReflectValue`] stored in the ECS.
+///
+/// # Safety
+///
+/// `ptr` must point to a valid `DynamicReflectValue`.
+unsafe fn drop_value(ptr: OwningPtr<'_>) {
+    // SAFETY: Guaranteed by the caller.
+    unsafe { ptr.drop_as::<DynamicReflectValue>() };
+}
+
+/// Clones a dynamic reflect component by converting its value to a new dynamic value.
+fn clone_value(source: &SourceComponent, ctx: &mut ComponentCloneCtx) {
+    // SAFETY: This is only used as the clone function of dynamic reflect components.
+    let value = unsafe { source.ptr().deref::<DynamicReflectValue>() };
+    let clone = ManuallyDrop::new(value.to_dynamic());
+    // SAFETY: `clone` is a `DynamicReflectValue` whose ownership is moved into the target component,
+    // since it is never dropped here.
+    unsafe { ctx.write_target_component_ptr(Ptr::from(&*clone)) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Component;
+    use crate::{entity::EntityCloner, query::QueryBuilder, world::FilteredEntityRef};
+    use alloc::string::String;
+    use bevy_reflect::{DynamicList, DynamicStruct, GetField};
+
+    #[derive(Component)]
+    struct A;
+
+    fn register_health(world: &mut World, storage_type: StorageType) -> ComponentId {
+        let mut template = DynamicStruct::default();
+        template.insert("current", 100_u32);
+        template.insert("max", 100_u32);
+        world.register_dynamic_reflect_component(
+            DynamicReflectComponentDescriptor::new("Health", &template)
+                .with_storage_type(storage_type),
+        )
+    }
+
+    fn current(value: &dyn PartialReflect) -> u32 {
+        *value
+            .reflect_ref()
+            .as_struct()
+            .unwrap()
+            .get_field::<u32>("current")
+            .unwrap()
+    }
+
+    fn with_current(current: u32) -> DynamicStruct {
+        let mut value = DynamicStruct::default();
+        value.insert("current", current);
+        value
+    }
+
+    #[test]
+    fn insert_and_mutate() {
+        for storage_type in [StorageType::Table, StorageType::SparseSet] {
+            let mut world = World::new();
+            let id = register_health(&mut world, storage_type);
+
+            let entity = world
+                .spawn_empty()
+                .insert_dynamic_reflect(id, &with_current(10))
+                .unwrap()
+                .id();
+            assert_eq!(current(world.get_dynamic_reflect(entity, id).unwrap()), 10);
+
+            world
+                .get_dynamic_reflect_mut(entity, id)
+                .unwrap()
+                .apply(&with_current(20));
+            assert_eq!(current(world.get_dynamic_reflect(entity, id).unwrap()), 20);
+
+            world.entity_mut(entity).remove_by_id(id);
+            assert!(world.get_dynamic_reflect(entity, id).is_none());
+        }
+    }
+
+    #[test]
+    fn insert_rejects_mismatched_values() {
+        let mut world = World::new();
+        let id = register_health(&mut world, StorageType::Table);
+        let entity = world.spawn_empty().id();
+
+        let mut entity = world.entity_mut(entity);
+        assert!(matches!(
+            entity.insert_dynamic_reflect(id, &5_u32),
+            Err(DynamicReflectComponentError::Apply(_))
+        ));
+
+        let other = entity.world_scope(World::register_component::<A>);
+        assert!(matches!(
+            entity.insert_dynamic_reflect(other, &with_current(5)),
+            Err(DynamicReflectComponentError::NotDynamicReflectComponent(_))
+        ));
+    }
+
+    #[test]
+    fn query_with_query_builder() {
+        let mut world = World::new();
+        let id = register_health(&mut world, StorageType::Table);
+        for value in [1, 2, 3] {
+            world
+                .spawn_empty()
+                .insert_dynamic_reflect(id, &with_current(value))
+                .unwrap();
+        }
+        world.spawn_empty();
+
+        let mut query = QueryBuilder::<FilteredEntityRef>::new(&mut world)
+            .ref_id(id)
+            .build();
+        let info = world.dynamic_reflect_components().unwrap().get(id).unwrap();
+        let mut values: Vec<_> = query
+            .iter(&world)
+            .map(|entity| {
+                // SAFETY: The query belongs to `world`, and `info` describes `id` in `world`.
+                current(unsafe { info.reflect(entity.get_by_id(id).unwrap()) })
+            })
+            .collect();
+        values.sort();
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    #[test]
+    fn round_trip_shorter_list() {
+        let mut template = DynamicStruct::default();
+        template.insert("name", String::from("chest"));
+        template.insert("items", DynamicList::from_iter([1_u32, 2, 3]));
+        let descriptor = || DynamicReflectComponentDescriptor::new("Inventory", &template);
+
+        let mut world = World::new();
+        let id = world.register_dynamic_reflect_component(descriptor());
+        let mut value = DynamicStruct::default();
+        value.insert("items", DynamicList::from_iter([7_u32]));
+        let entity = world
+            .spawn_empty()
+            .insert_dynamic_reflect(id, &value)
+            .unwrap()
+            .id();
+
+        // Load the saved value into another world, like a scene would.
+        let saved = world.get_dynamic_reflect(entity, id).unwrap();
+        let mut other_world = World::new();
+        let other_id = other_world.register_dynamic_reflect_component(descriptor());
+        let other_entity = other_world
+            .spawn_empty()
+            .insert_dynamic_reflect(other_id, saved)
+            .unwrap()
+            .id();
+
+        let loaded = other_world
+            .get_dynamic_reflect(other_entity, other_id)
+            .unwrap()
+            .reflect_ref()
+            .as_struct()
+            .unwrap();
+        assert_eq!(loaded.get_field::<String>("name").unwrap(), "chest");
+        let items = loaded
+            .field("items")
+            .unwrap()
+            .reflect_ref()
+            .as_list()
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items.get(0).unwrap().try_downcast_ref::<u32>(), Some(&7));
+    }
+
+    #[test]
+    fn clone_entity() {
+        let mut world = World::new();
+        let id = register_health(&mut world, StorageType::Table);
+        let source = world
+            .spawn_empty()
+            .insert_dynamic_reflect(id, &with_current(42))
+            .unwrap()
+            .id();
+        let target = world.spawn_empty().id();
+
+        EntityCloner::build_opt_out(&mut world).clone_entity(source, target);
+        world
+            .get_dynamic_reflect_mut(source, id)
+            .unwrap()
+            .apply(&with_current(0));
+
+        assert_eq!(current(world.get_dynamic_reflect(target, id).unwrap()), 42);
+    }
+}
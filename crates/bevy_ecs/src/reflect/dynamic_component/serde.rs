@@ -0,0 +1,264 @@
+//! `serde` support for the values of dynamic reflect components.
+//!
+//! The values of these components usually have no represented type, so the serializers of
+//! [`bevy_reflect::serde`] can't handle them on their own. The serializers of this module walk the
+//! structure of the value instead, and only use the reflection serializers for the parts that have
+//! a represented type. Deserialization is guided by the template of the component, which provides
+//! the shape of the value.
+//!
+//! Structs are written as maps of their fields, tuples, tuple structs, lists, arrays and sets as
+//! sequences, and maps as maps. Enums and opaque values must have a represented type.
+
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::fmt::Formatter;
+
+use bevy_reflect::{
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    DynamicArray, DynamicList, DynamicMap, DynamicSet, DynamicStruct, DynamicTuple,
+    DynamicTupleStruct, Map, PartialReflect, ReflectRef, TypeRegistry,
+};
+use serde::{
+    de::{DeserializeSeed, Error as _, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor},
+    ser::{Error as _, SerializeMap, SerializeSeq},
+    Deserializer, Serialize, Serializer,
+};
+
+use super::DynamicReflectComponentInfo;
+
+/// Serializes the value of a dynamic reflect component, or any part of it.
+///
+/// Parts of the value that have a represented type are serialized with a
+/// [`TypedReflectSerializer`], so they use the same format as they would in any other component.
+pub struct DynamicReflectSerializer<'a> {
+    value: &'a dyn PartialReflect,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> DynamicReflectSerializer<'a> {
+    /// Creates a serializer for `value`, using `registry` for the parts that have a represented type.
+    pub fn new(value: &'a dyn PartialReflect, registry: &'a TypeRegistry) -> Self {
+        Self { value, registry }
+    }
+
+    fn part(&self, value: &'a dyn PartialReflect) -> Self {
+        Self::new(value, self.registry)
+    }
+}
+
+impl Serialize for DynamicReflectSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.value.get_represented_type_info().is_some() {
+            return TypedReflectSerializer::new(self.value, self.registry).serialize(serializer);
+        }
+
+        match self.value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                let mut map = serializer.serialize_map(Some(value.field_len()))?;
+                for (index, field) in value.iter_fields().enumerate() {
+                    map.serialize_entry(value.name_at(index).unwrap(), &self.part(field))?;
+                }
+                map.end()
+            }
+            ReflectRef::TupleStruct(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.field_len()))?;
+                for field in value.iter_fields() {
+                    seq.serialize_element(&self.part(field))?;
+                }
+                seq.end()
+            }
+            ReflectRef::Tuple(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.field_len()))?;
+                for field in value.iter_fields() {
+                    seq.serialize_element(&self.part(field))?;
+                }
+                seq.end()
+            }
+            ReflectRef::List(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.len()))?;
+                for element in value.iter() {
+                    seq.serialize_element(&self.part(element))?;
+                }
+                seq.end()
+            }
+            ReflectRef::Array(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.len()))?;
+                for element in value.iter() {
+                    seq.serialize_element(&self.part(element))?;
+                }
+                seq.end()
+            }
+            ReflectRef::Set(value) => {
+                let mut seq = serializer.serialize_seq(Some(value.len()))?;
+                for element in value.iter() {
+                    seq.serialize_element(&self.part(element))?;
+                }
+                seq.end()
+            }
+            ReflectRef::Map(value) => {
+                let mut map = serializer.serialize_map(Some(value.len()))?;
+                for (key, value) in value.iter() {
+                    map.serialize_entry(&self.part(key), &self.part(value))?;
+                }
+                map.end()
+            }
+            _ => Err(S::Error::custom(format!(
+                "cannot serialize a {:?} without a represented type",
+                self.value.reflect_kind()
+            ))),
+        }
+    }
+}
+
+/// Deserializes the value of a dynamic reflect component, or any part of it, in the shape of
+/// a template.
+///
+/// Lists, arrays, sets and maps take the shape of their elements from the first element of the
+/// template, so they can only be deserialized empty from an empty template without a represented type.
+/// Fields missing from the input are left out of the result, to be filled in from the template
+/// when the component is inserted.
+pub struct DynamicReflectDeserializer<'a> {
+    template: &'a dyn PartialReflect,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> DynamicReflectDeserializer<'a> {
+    /// Creates a deserializer for values of the given dynamic reflect component.
+    pub fn new(info: &'a DynamicReflectComponentInfo, registry: &'a TypeRegistry) -> Self {
+        Self::with_template(info.template(), registry)
+    }
+
+    /// Creates a deserializer for values in the shape of `template`.
+    pub fn with_template(template: &'a dyn PartialReflect, registry: &'a TypeRegistry) -> Self {
+        Self { template, registry }
+    }
+
+    fn part(&self, template: &'a dyn PartialReflect) -> Self {
+        Self::with_template(template, self.registry)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DynamicReflectDeserializer<'_> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if let Some(info) = self.template.get_represented_type_info() {
+            let registration = self.registry.get(info.type_id()).ok_or_else(|| {
+                D::Error::custom(format!("`{}` is not registered", info.type_path()))
+            })?;
+            return TypedReflectDeserializer::new(registration, self.registry)
+                .deserialize(deserializer);
+        }
+
+        match self.template.reflect_ref() {
+            ReflectRef::TupleStruct(_)
+            | ReflectRef::Tuple(_)
+            | ReflectRef::List(_)
+            | ReflectRef::Array(_)
+            | ReflectRef::Set(_) => deserializer.deserialize_seq(DynamicReflectVisitor(self)),
+            ReflectRef::Struct(_) | ReflectRef::Map(_) => {
+                deserializer.deserialize_map(DynamicReflectVisitor(self))
+            }
+            _ => Err(D::Error::custom(format!(
+                "cannot deserialize a {:?} without a represented type",
+                self.template.reflect_kind()
+            ))),
+        }
+    }
+}
+
+struct DynamicReflectVisitor<'a>(DynamicReflectDeserializer<'a>);
+
+/// Returns the template of the element at `index` of a collection template.
+///
+/// Tuples and tuple structs have a template for each field, while the other collections use
+/// their first element as the template of all elements.
+fn element_template(template: &dyn PartialReflect, index: usize) -> Option<&dyn PartialReflect> {
+    match template.reflect_ref() {
+        ReflectRef::TupleStruct(template) => template.field(index),
+        ReflectRef::Tuple(template) => template.field(index),
+        ReflectRef::List(template) => template.get(0),
+        ReflectRef::Array(template) => template.get(0),
+        ReflectRef::Set(template) => template.iter().next(),
+        _ => None,
+    }
+}
+
+impl<'de> Visitor<'de> for DynamicReflectVisitor<'_> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        write!(formatter, "a {:?}", self.0.template.reflect_kind())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let template = self.0.template;
+        let mut elements = Vec::new();
+        loop {
+            let Some(element) = element_template(template, elements.len()) else {
+                // Check that there are no more elements than there are templates.
+                if seq.next_element::<IgnoredAny>()?.is_some() {
+                    return Err(A::Error::custom(
+                        "more elements than the template has, or an empty template without a represented type",
+                    ));
+                }
+                break;
+            };
+            match seq.next_element_seed(self.0.part(element))? {
+                Some(element) => elements.push(element),
+                None => break,
+            }
+        }
+
+        let elements = elements.into_iter();
+        Ok(match template.reflect_ref() {
+            ReflectRef::TupleStruct(_) => Box::new(elements.collect::<DynamicTupleStruct>()),
+            ReflectRef::Tuple(_) => Box::new(elements.collect::<DynamicTuple>()),
+            ReflectRef::List(_) => Box::new(elements.collect::<DynamicList>()),
+            ReflectRef::Array(_) => Box::new(elements.collect::<DynamicArray>()),
+            _ => Box::new(elements.collect::<DynamicSet>()),
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match self.0.template.reflect_ref() {
+            ReflectRef::Struct(template) => {
+                let mut value = DynamicStruct::default();
+                while let Some(name) = map.next_key::<String>()? {
+                    let field = template
+                        .field(&name)
+                        .ok_or_else(|| A::Error::custom(format!("unknown field `{name}`")))?;
+                    let field = map.next_value_seed(self.0.part(field))?;
+                    value.insert_boxed(name, field);
+                }
+                Ok(Box::new(value))
+            }
+            ReflectRef::Map(template) => {
+                let mut value = DynamicMap::default();
+                if let Some((key, entry)) = template.iter().next() {
+                    while let Some(entry_key) = map.next_key_seed(self.0.part(key))? {
+                        let entry_value = map.next_value_seed(self.0.part(entry))?;
+                        value.insert_boxed(entry_key, entry_value);
+                    }
+                } else if map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {
+                    return Err(A::Error::custom(
+                        "cannot deserialize the entries of an empty template without a represented type",
+                    ));
+                }
+                Ok(Box::new(value))
+            }
+            _ => Err(A::Error::invalid_type(Unexpected::Map, &self)),
+        }
+    }
+}
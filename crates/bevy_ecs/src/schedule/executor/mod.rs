@@ -2,6 +2,7 @@
 mod multi_threaded;
 mod simple;
 mod single_threaded;
+mod timing;
 
 use alloc::{vec, vec::Vec};
 use bevy_utils::prelude::DebugName;
@@ -12,6 +13,8 @@ pub use self::{simple::SimpleExecutor, single_threaded::SingleThreadedExecutor};
 
 #[cfg(feature = "std")]
 pub use self::multi_threaded::{MainThreadExecutor, MultiThreadedExecutor};
+pub(super) use self::timing::ScheduleTimings;
+pub use self::timing::{SystemTiming, SystemTimingInfo, SystemTimings};
 
 use fixedbitset::FixedBitSet;
 
@@ -107,6 +110,8 @@ pub struct SystemSchedule {
     ///
     /// If a set doesn't run because of its conditions, this is used to skip all systems in it.
     pub(super) systems_in_sets_with_conditions: Vec<FixedBitSet>,
+    /// Where to record how long each system took to run, if the [`SystemTimings`] resource exists.
+    pub(super) timings: Option<ScheduleTimings>,
 }
 
 impl SystemSchedule {
@@ -122,6 +127,7 @@ impl SystemSchedule {
             system_dependents: Vec::new(),
             sets_with_conditions_of_systems: Vec::new(),
             systems_in_sets_with_conditions: Vec::new(),
+            timings: None,
         }
     }
 }
@@ -315,9 +321,11 @@ mod __rust_begin_short_backtrace {
 
 #[cfg(test)]
 mod tests {
+    use alloc::{vec, vec::Vec};
+
     use crate::{
-        prelude::{Component, In, IntoSystem, Resource, Schedule},
-        schedule::ExecutorKind,
+        prelude::{Component, In, IntoScheduleConfigs, IntoSystem, Resource, Schedule, SystemSet},
+        schedule::{ExecutorKind, SystemTimings},
         system::{Populated, Res, ResMut, Single},
         world::World,
     };
@@ -379,6 +387,47 @@ mod tests {
         }
     }
 
+    #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+    struct TimedSystems;
+
+    #[test]
+    fn system_timings_are_recorded() {
+        for executor in EXECUTORS {
+            let mut world = World::new();
+            world.init_resource::<Counter>();
+
+            let mut schedule = Schedule::default();
+            schedule.set_executor_kind(executor);
+            schedule.add_systems(
+                (
+                    |mut counter: ResMut<Counter>| counter.0 += 1,
+                    |mut counter: ResMut<Counter>| counter.0 += 1,
+                )
+                    .chain()
+                    .in_set(TimedSystems),
+            );
+
+            // Nothing is recorded without the resource.
+            schedule.run(&mut world);
+
+            let timings = SystemTimings::new();
+            world.insert_resource(timings.clone());
+            schedule.run(&mut world);
+
+            let recorded: Vec<_> = timings.drain().collect();
+            assert_eq!(recorded.len(), 2, "{executor:?}");
+            for timing in recorded {
+                assert_eq!(timing.system.schedule, schedule.label());
+                assert_eq!(timing.system.sets, vec![TimedSystems.intern()]);
+            }
+
+            world.remove_resource::<SystemTimings>();
+            schedule.run(&mut world);
+            assert_eq!(timings.drain().count(), 0, "{executor:?}");
+            assert_eq!(world.resource::<Counter>().0, 6);
+        }
+    }
+
     fn look_for_missing_resource(_res: Res<TestState>) {}
 
     #[test]
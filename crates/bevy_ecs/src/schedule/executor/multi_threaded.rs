@@ -1,6 +1,6 @@
 use alloc::{boxed::Box, vec::Vec};
 use bevy_platform::cell::SyncUnsafeCell;
-use bevy_platform::sync::Arc;
+use bevy_platform::{sync::Arc, time::Instant};
 use bevy_tasks::{ComputeTaskPool, Scope, TaskPool, ThreadExecutor};
 use concurrent_queue::ConcurrentQueue;
 use core::{any::Any, panic::AssertUnwindSafe};
@@ -16,8 +16,8 @@ use crate::{
     error::{ErrorContext, ErrorHandler, Result},
     prelude::Resource,
     schedule::{
-        is_apply_deferred, ConditionWithAccess, ExecutorKind, SystemExecutor, SystemSchedule,
-        SystemWithAccess,
+        is_apply_deferred, ConditionWithAccess, ExecutorKind, ScheduleTimings, SystemExecutor,
+        SystemSchedule, SystemWithAccess,
     },
     system::{RunSystemError, ScheduleSystem},
     world::{unsafe_world_cell::UnsafeWorldCell, World},
@@ -32,6 +32,7 @@ struct Environment<'env, 'sys> {
     executor: &'env MultiThreadedExecutor,
     systems: &'sys [SyncUnsafeCell<SystemWithAccess>],
     conditions: SyncUnsafeCell<Conditions<'sys>>,
+    timings: Option<&'sys ScheduleTimings>,
     world_cell: UnsafeWorldCell<'env>,
 }
 
@@ -57,6 +58,7 @@ impl<'env, 'sys> Environment<'env, 'sys> {
                 sets_with_conditions_of_systems: &schedule.sets_with_conditions_of_systems,
                 systems_in_sets_with_conditions: &schedule.systems_in_sets_with_conditions,
             }),
+            timings: schedule.timings.as_ref(),
             world_cell: world.as_unsafe_world_cell(),
         }
     }
@@ -326,6 +328,13 @@ impl SystemExecutor for MultiThreadedExecutor {
 }
 
 impl<'scope, 'env: 'scope, 'sys> Context<'scope, 'env, 'sys> {
+    /// Records how long a system took to run, if timings are being recorded.
+    fn record_timing(&self, system_index: usize, start: Option<Instant>) {
+        if let (Some(timings), Some(start)) = (self.environment.timings, start) {
+            timings.record(system_index, start.elapsed());
+        }
+    }
+
     fn system_completed(
         &self,
         system_index: usize,
@@ -674,6 +683,7 @@ impl ExecutorState {
         let system_meta = &self.system_task_metadata[system_index];
 
         let task = async move {
+            let start = context.environment.timings.is_some().then(Instant::now);
             let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
                 // SAFETY:
                 // - The caller ensures that we have permission to
@@ -696,6 +706,7 @@ impl ExecutorState {
                     }
                 };
             }));
+            context.record_timing(system_index, start);
             context.system_completed(system_index, res, system);
         };
 
@@ -733,6 +744,7 @@ impl ExecutorState {
                 // SAFETY: `can_run` returned true for this system, which means
                 // that no other systems currently have access to the world.
                 let world = unsafe { context.environment.world_cell.world_mut() };
+                let start = context.environment.timings.is_some().then(Instant::now);
                 let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
                     if let Err(RunSystemError::Failed(err)) =
                         __rust_begin_short_backtrace::run(system, world)
@@ -746,6 +758,7 @@ impl ExecutorState {
                         );
                     }
                 }));
+                context.record_timing(system_index, start);
                 context.system_completed(system_index, res, system);
             };
 
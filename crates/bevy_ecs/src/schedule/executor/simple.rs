@@ -1,5 +1,6 @@
 #![expect(deprecated, reason = "Everything here is deprecated")]
 
+use bevy_platform::time::Instant;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -128,6 +129,8 @@ impl SystemExecutor for SimpleExecutor {
                 continue;
             }
 
+            let start = schedule.timings.is_some().then(Instant::now);
+
             let f = AssertUnwindSafe(|| {
                 if let Err(RunSystemError::Failed(err)) =
                     __rust_begin_short_backtrace::run(system, world)
@@ -155,6 +158,10 @@ impl SystemExecutor for SimpleExecutor {
             {
                 (f)();
             }
+
+            if let (Some(timings), Some(start)) = (&schedule.timings, start) {
+                timings.record(system_index, start.elapsed());
+            }
         }
 
         self.evaluated_sets.clear();
@@ -1,3 +1,4 @@
+use bevy_platform::time::Instant;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -131,6 +132,8 @@ impl SystemExecutor for SingleThreadedExecutor {
                 continue;
             }
 
+            let start = schedule.timings.is_some().then(Instant::now);
+
             let f = AssertUnwindSafe(|| {
                 if let Err(RunSystemError::Failed(err)) =
                     __rust_begin_short_backtrace::run_without_applying_deferred(system, world)
@@ -159,6 +162,10 @@ impl SystemExecutor for SingleThreadedExecutor {
                 (f)();
             }
 
+            if let (Some(timings), Some(start)) = (&schedule.timings, start) {
+                timings.record(system_index, start.elapsed());
+            }
+
             self.unapplied_systems.insert(system_index);
         }
 
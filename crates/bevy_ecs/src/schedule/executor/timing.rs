@@ -0,0 +1,108 @@
+use alloc::vec::Vec;
+use bevy_platform::sync::Arc;
+use bevy_utils::prelude::DebugName;
+use concurrent_queue::ConcurrentQueue;
+use core::{fmt, time::Duration};
+
+use crate::{
+    resource::Resource,
+    schedule::{InternedScheduleLabel, InternedSystemSet},
+};
+
+/// Collects how long each system took to run.
+///
+/// While this resource is present in the [`World`](crate::world::World), the schedule executors
+/// measure the run duration of every system they run and push a [`SystemTiming`] into it.
+/// The recorded timings are kept until they are taken out with [`SystemTimings::drain`].
+///
+/// This is meant for diagnostics tools that need to know which systems are slow without
+/// an external profiler. Measuring systems has a small cost, so only insert this resource
+/// if the timings are actually consumed.
+#[derive(Resource, Clone)]
+pub struct SystemTimings {
+    queue: Arc<ConcurrentQueue<SystemTiming>>,
+}
+
+impl SystemTimings {
+    /// Creates a new, empty [`SystemTimings`].
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(ConcurrentQueue::unbounded()),
+        }
+    }
+
+    /// Removes and returns all timings recorded since the last call.
+    pub fn drain(&self) -> impl Iterator<Item = SystemTiming> + '_ {
+        self.queue.try_iter()
+    }
+
+    fn push(&self, timing: SystemTiming) {
+        // The queue is unbounded and never closed, so pushing cannot fail.
+        let _ = self.queue.push(timing);
+    }
+}
+
+impl Default for SystemTimings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SystemTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SystemTimings")
+            .field("pending", &self.queue.len())
+            .finish()
+    }
+}
+
+/// How long a system took to run, recorded into [`SystemTimings`].
+#[derive(Clone, Debug)]
+pub struct SystemTiming {
+    /// The system that was run.
+    pub system: Arc<SystemTimingInfo>,
+    /// How long the system took to run.
+    pub duration: Duration,
+}
+
+/// Identifies the system a [`SystemTiming`] was recorded for.
+#[derive(Debug)]
+pub struct SystemTimingInfo {
+    /// The label of the schedule the system ran in.
+    pub schedule: InternedScheduleLabel,
+    /// The name of the system.
+    pub name: DebugName,
+    /// The named system sets the system is contained in, directly or through other sets.
+    ///
+    /// This excludes anonymous sets and the sets that are automatically created for each system type.
+    pub sets: Vec<InternedSystemSet>,
+}
+
+/// The [`SystemTimings`] of a single schedule, along with information about its systems.
+///
+/// Stored on the [`SystemSchedule`](super::SystemSchedule) while the resource is present,
+/// so the executors can record timings without looking anything up.
+pub(crate) struct ScheduleTimings {
+    timings: SystemTimings,
+    /// Indexed by system index.
+    systems: Vec<Arc<SystemTimingInfo>>,
+}
+
+impl ScheduleTimings {
+    pub(crate) fn new(timings: SystemTimings, systems: Vec<Arc<SystemTimingInfo>>) -> Self {
+        Self { timings, systems }
+    }
+
+    /// Returns `true` if this was created for the given [`SystemTimings`].
+    pub(crate) fn records_into(&self, timings: &SystemTimings) -> bool {
+        Arc::ptr_eq(&self.timings.queue, &timings.queue)
+    }
+
+    /// Records that the system at `system_index` took `duration` to run.
+    pub(crate) fn record(&self, system_index: usize, duration: Duration) {
+        self.timings.push(SystemTiming {
+            system: self.systems[system_index].clone(),
+            duration,
+        });
+    }
+}
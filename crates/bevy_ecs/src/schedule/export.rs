@@ -0,0 +1,432 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use bevy_platform::collections::HashMap;
+use core::fmt::Write;
+
+use crate::{
+    component::Components,
+    schedule::{ConditionWithAccess, Dag, NodeId, Schedule},
+};
+
+/// A snapshot of the systems, system sets and relationships of a [`Schedule`],
+/// which can be rendered as a graph for review or further tooling.
+///
+/// Created with [`Schedule::export`]. Ambiguities are only known once the schedule
+/// has been built, so export a schedule after it has been initialized or run.
+///
+/// With the `serialize` feature enabled, this type can be serialized to JSON or any other
+/// `serde` format. [`ScheduleExport::to_dot`] and [`ScheduleExport::to_mermaid`] render the
+/// export as Graphviz and Mermaid diagrams respectively.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # let mut world = World::new();
+/// fn a() {}
+/// fn b() {}
+///
+/// let mut schedule = Schedule::default();
+/// schedule.add_systems((a, b).chain());
+/// schedule.initialize(&mut world).unwrap();
+///
+/// let export = schedule.export(world.components());
+/// assert_eq!(export.dependencies.len(), 1);
+/// let dot = export.to_dot();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleExport {
+    /// The name of the schedule's label.
+    pub label: String,
+    /// The systems and system sets in the schedule.
+    ///
+    /// Edges and ambiguities refer to nodes by their index in this list.
+    pub nodes: Vec<ScheduleExportNode>,
+    /// Edges from a system set to its direct members.
+    pub hierarchy: Vec<(usize, usize)>,
+    /// Ordering edges, from the node that runs first to the node that runs after it.
+    pub dependencies: Vec<(usize, usize)>,
+    /// Pairs of systems with conflicting data access and no ordering between them.
+    pub ambiguities: Vec<ScheduleExportAmbiguity>,
+}
+
+/// A system or system set in a [`ScheduleExport`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleExportNode {
+    /// The name of the system or system set.
+    pub name: String,
+    /// Whether this node is a system or a system set.
+    pub kind: ScheduleExportNodeKind,
+    /// The names of the run conditions attached directly to this node.
+    pub conditions: Vec<String>,
+}
+
+/// The kind of a [`ScheduleExportNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScheduleExportNodeKind {
+    /// A system. Exclusive systems can't run in parallel with any other system.
+    System {
+        /// Whether this system requires exclusive [`World`](crate::world::World) access.
+        exclusive: bool,
+    },
+    /// A system set.
+    Set,
+}
+
+/// A pair of systems in a [`ScheduleExport`] whose order is ambiguous.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleExportAmbiguity {
+    /// The index of the first system.
+    pub first: usize,
+    /// The index of the second system.
+    pub second: usize,
+    /// The names of the components and resources both systems access, where at least one of them
+    /// accesses it mutably.
+    ///
+    /// If this is empty, the systems conflict on [`World`](crate::world::World) access.
+    pub conflicts: Vec<String>,
+}
+
+impl Schedule {
+    /// Exports the systems, system sets, ordering edges, run conditions and ambiguities
+    /// of this schedule. See [`ScheduleExport`] for more information.
+    pub fn export(&self, components: &Components) -> ScheduleExport {
+        let graph = self.graph();
+        let executable = self.executable();
+        let use_shortnames = self.get_build_settings().use_shortnames;
+        let mut export = ScheduleExport {
+            label: format!("{:?}", self.label()),
+            ..Default::default()
+        };
+        let mut indices = HashMap::<NodeId, usize>::default();
+        let mut anonymous_sets = Vec::new();
+
+        // Once the schedule has been built, its systems and conditions are moved from the
+        // graph into the executable schedule, so both need to be visited.
+        let built_systems = executable
+            .system_ids
+            .iter()
+            .zip(&executable.systems)
+            .zip(&executable.system_conditions)
+            .map(|((&key, system), conditions)| (key, &system.system, conditions.as_slice()));
+        for (key, system, conditions) in built_systems.chain(graph.systems.iter()) {
+            let name = system.name();
+            indices.insert(NodeId::System(key), export.nodes.len());
+            export.nodes.push(ScheduleExportNode {
+                name: if use_shortnames {
+                    name.shortname().to_string()
+                } else {
+                    name.to_string()
+                },
+                kind: ScheduleExportNodeKind::System {
+                    exclusive: system.is_exclusive(),
+                },
+                conditions: condition_names(conditions),
+            });
+        }
+
+        let built_set_conditions: HashMap<_, _> = executable
+            .set_ids
+            .iter()
+            .zip(&executable.set_conditions)
+            .collect();
+        for (key, set, conditions) in graph.system_sets.iter() {
+            // Anonymous sets are named after their members once the hierarchy is known.
+            let name = if set.is_anonymous() {
+                anonymous_sets.push(export.nodes.len());
+                String::new()
+            } else {
+                format!("{set:?}")
+            };
+            let built_conditions = built_set_conditions
+                .get(&key)
+                .map(|conditions| conditions.as_slice())
+                .unwrap_or_default();
+            indices.insert(NodeId::Set(key), export.nodes.len());
+            export.nodes.push(ScheduleExportNode {
+                name,
+                kind: ScheduleExportNodeKind::Set,
+                conditions: condition_names(built_conditions.iter().chain(conditions)),
+            });
+        }
+
+        let edges = |dag: &Dag<NodeId>| -> Vec<(usize, usize)> {
+            dag.graph()
+                .all_edges()
+                .filter_map(|(a, b)| Some((*indices.get(&a)?, *indices.get(&b)?)))
+                .collect()
+        };
+        export.hierarchy = edges(graph.hierarchy());
+        export.dependencies = edges(graph.dependency());
+
+        for &index in &anonymous_sets {
+            export.nodes[index].name = export.member_names(index);
+        }
+
+        export.ambiguities = graph
+            .conflicting_systems()
+            .iter()
+            .filter_map(|(a, b, conflicts)| {
+                Some(ScheduleExportAmbiguity {
+                    first: *indices.get(&NodeId::System(*a))?,
+                    second: *indices.get(&NodeId::System(*b))?,
+                    conflicts: conflicts
+                        .iter()
+                        .filter_map(|id| Some(components.get_name(*id)?.to_string()))
+                        .collect(),
+                })
+            })
+            .collect();
+
+        export
+    }
+}
+
+fn condition_names<'a>(
+    conditions: impl IntoIterator<Item = &'a ConditionWithAccess>,
+) -> Vec<String> {
+    conditions
+        .into_iter()
+        .map(|condition| condition.condition.name().to_string())
+        .collect()
+}
+
+impl ScheduleExport {
+    /// Returns a name describing the members of the set at `index`, like `(a, b)`.
+    ///
+    /// Members with an empty name are anonymous sets themselves, and are resolved recursively.
+    fn member_names(&self, index: usize) -> String {
+        let members: Vec<_> = self
+            .hierarchy
+            .iter()
+            .filter(|(set, _)| *set == index)
+            .map(|&(_, member)| match self.nodes[member].name.as_str() {
+                "" => self.member_names(member),
+                name => name.to_string(),
+            })
+            .collect();
+        format!("({})", members.join(", "))
+    }
+
+    /// Renders this export as a [Graphviz](https://graphviz.org/) DOT graph.
+    ///
+    /// Systems are drawn as boxes and system sets as dashed ellipses. Membership of a set is
+    /// drawn as a dashed edge, ordering as a solid edge and ambiguities as red, undirected edges
+    /// labeled with the conflicting components.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph \"{}\" {{", escape_dot(&self.label));
+        let _ = writeln!(dot, "    node [shape = box];");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut label = escape_dot(&node.name);
+            for condition in &node.conditions {
+                let _ = write!(label, "\\nrun_if: {}", escape_dot(condition));
+            }
+            let style = match node.kind {
+                ScheduleExportNodeKind::System { exclusive: false } => "",
+                ScheduleExportNodeKind::System { exclusive: true } => ", style = bold",
+                ScheduleExportNodeKind::Set => ", shape = ellipse, style = dashed",
+            };
+            let _ = writeln!(dot, "    n{index} [label = \"{label}\"{style}];");
+        }
+        for (set, member) in &self.hierarchy {
+            let _ = writeln!(dot, "    n{set} -> n{member} [style = dashed];");
+        }
+        for (before, after) in &self.dependencies {
+            let _ = writeln!(dot, "    n{before} -> n{after};");
+        }
+        for ambiguity in &self.ambiguities {
+            let _ = writeln!(
+                dot,
+                "    n{} -> n{} [dir = none, color = red, label = \"{}\"];",
+                ambiguity.first,
+                ambiguity.second,
+                escape_dot(&ambiguity.conflicts_label()),
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders this export as a [Mermaid](https://mermaid.js.org/) flowchart.
+    ///
+    /// Systems are drawn as rectangles and system sets as stadiums. Membership of a set is
+    /// drawn as a dotted link, ordering as an arrow and ambiguities as red, bidirectional links
+    /// labeled with the conflicting components.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        let _ = writeln!(
+            mermaid,
+            "---\ntitle: \"{}\"\n---",
+            escape_mermaid(&self.label)
+        );
+        let _ = writeln!(mermaid, "flowchart TB");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut label = escape_mermaid(&node.name);
+            for condition in &node.conditions {
+                let _ = write!(label, "<br/>run_if: {}", escape_mermaid(condition));
+            }
+            let (open, close) = match node.kind {
+                ScheduleExportNodeKind::System { exclusive: false } => ("[", "]"),
+                ScheduleExportNodeKind::System { exclusive: true } => ("[[", "]]"),
+                ScheduleExportNodeKind::Set => ("([", "])"),
+            };
+            let _ = writeln!(mermaid, "    n{index}{open}\"{label}\"{close}");
+        }
+        for (set, member) in &self.hierarchy {
+            let _ = writeln!(mermaid, "    n{set} -.- n{member}");
+        }
+        for (before, after) in &self.dependencies {
+            let _ = writeln!(mermaid, "    n{before} --> n{after}");
+        }
+        let first_ambiguity = self.hierarchy.len() + self.dependencies.len();
+        for (index, ambiguity) in self.ambiguities.iter().enumerate() {
+            let _ = writeln!(
+                mermaid,
+                "    n{} <-->|\"{}\"| n{}",
+                ambiguity.first,
+                escape_mermaid(&ambiguity.conflicts_label()),
+                ambiguity.second,
+            );
+            let _ = writeln!(
+                mermaid,
+                "    linkStyle {} stroke:red",
+                first_ambiguity + index
+            );
+        }
+        mermaid
+    }
+}
+
+impl ScheduleExportAmbiguity {
+    fn conflicts_label(&self) -> String {
+        if self.conflicts.is_empty() {
+            "World".to_string()
+        } else {
+            self.conflicts.join(", ")
+        }
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::{IntoScheduleConfigs, Resource, SystemSet, World},
+        schedule::{LogLevel, ScheduleBuildSettings},
+        system::ResMut,
+    };
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+    struct CountingSystems;
+
+    fn first(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn second(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn third(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn export_test_schedule() -> ScheduleExport {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::default();
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Ignore,
+            ..Default::default()
+        });
+        schedule.configure_sets(CountingSystems.run_if(|| true));
+        schedule.add_systems(((first, second).chain(), third).in_set(CountingSystems));
+        schedule.initialize(&mut world).unwrap();
+        schedule.export(world.components())
+    }
+
+    /// Returns the indices of the `first`, `second` and `third` systems.
+    fn systems(export: &ScheduleExport) -> [usize; 3] {
+        let [(first, second)] = export.dependencies[..] else {
+            panic!("expected a single ordering edge");
+        };
+        let third = (0..export.nodes.len())
+            .find(|&index| {
+                export.nodes[index].kind != ScheduleExportNodeKind::Set
+                    && index != first
+                    && index != second
+            })
+            .unwrap();
+        [first, second, third]
+    }
+
+    #[test]
+    fn export_contains_nodes_and_edges() {
+        let export = export_test_schedule();
+        let [first, second, _] = systems(&export);
+        let set = export
+            .nodes
+            .iter()
+            .position(|node| !node.conditions.is_empty())
+            .unwrap();
+
+        assert_eq!(
+            export.nodes[first].kind,
+            ScheduleExportNodeKind::System { exclusive: false }
+        );
+        assert_eq!(export.nodes[set].kind, ScheduleExportNodeKind::Set);
+        assert_eq!(export.nodes[set].conditions.len(), 1);
+        assert!(export.dependencies.contains(&(first, second)));
+        assert!(export
+            .hierarchy
+            .iter()
+            .any(|&(parent, child)| parent == set && child == first));
+    }
+
+    #[test]
+    fn export_reports_ambiguities() {
+        let export = export_test_schedule();
+        let [_, _, third] = systems(&export);
+
+        assert_eq!(export.ambiguities.len(), 2);
+        for ambiguity in &export.ambiguities {
+            assert!(ambiguity.first == third || ambiguity.second == third);
+            assert_eq!(ambiguity.conflicts.len(), 1);
+        }
+    }
+
+    #[test]
+    fn render_dot_and_mermaid() {
+        let export = export_test_schedule();
+        let [first, second, _] = systems(&export);
+
+        let dot = export.to_dot();
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains(&format!("n{first} -> n{second};")));
+        assert!(dot.contains("color = red"));
+
+        let mermaid = export.to_mermaid();
+        assert!(mermaid.contains("flowchart TB"));
+        assert!(mermaid.contains(&format!("n{first} --> n{second}")));
+        assert!(mermaid.contains("stroke:red"));
+    }
+}
@@ -5,6 +5,7 @@ mod condition;
 mod config;
 mod error;
 mod executor;
+mod export;
 mod node;
 mod pass;
 mod schedule;
@@ -13,7 +14,9 @@ mod stepping;
 
 pub use self::graph::GraphInfo;
 use self::graph::*;
-pub use self::{condition::*, config::*, error::*, executor::*, node::*, schedule::*, set::*};
+pub use self::{
+    condition::*, config::*, error::*, executor::*, export::*, node::*, schedule::*, set::*,
+};
 pub use pass::ScheduleBuildPass;
 
 /// An implementation of a graph data structure.
@@ -646,6 +646,11 @@ impl SystemSets {
         self.sets.get(key).map(|set| &**set)
     }
 
+    /// Returns the interned system set with the given key, if it exists.
+    pub(crate) fn get_interned(&self, key: SystemSetKey) -> Option<InternedSystemSet> {
+        self.sets.get(key).copied()
+    }
+
     /// Returns the key for the given system set, inserting it into this
     /// container if it does not already exist.
     pub fn get_key_or_insert(&mut self, set: InternedSystemSet) -> SystemSetKey {
@@ -10,7 +10,10 @@ use alloc::{
     vec,
     vec::Vec,
 };
-use bevy_platform::collections::{HashMap, HashSet};
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use bevy_utils::{default, prelude::DebugName, TypeIdMap};
 use core::{
     any::{Any, TypeId},
@@ -486,6 +489,8 @@ impl Schedule {
             )
         });
 
+        self.update_timings(world);
+
         let error_handler = world.default_error_handler();
 
         #[cfg(not(feature = "bevy_debug_stepping"))]
@@ -508,6 +513,37 @@ impl Schedule {
         }
     }
 
+    /// Prepares the executable schedule to record into the [`SystemTimings`] resource, if it exists.
+    fn update_timings(&mut self, world: &World) {
+        let Some(timings) = world.get_resource::<SystemTimings>() else {
+            self.executable.timings = None;
+            return;
+        };
+        if self
+            .executable
+            .timings
+            .as_ref()
+            .is_some_and(|current| current.records_into(timings))
+        {
+            return;
+        }
+
+        let systems = self
+            .executable
+            .system_ids
+            .iter()
+            .zip(&self.executable.systems)
+            .map(|(&key, system)| {
+                Arc::new(SystemTimingInfo {
+                    schedule: self.label,
+                    name: system.system.name(),
+                    sets: self.graph.named_sets_containing_node(NodeId::System(key)),
+                })
+            })
+            .collect();
+        self.executable.timings = Some(ScheduleTimings::new(timings.clone(), systems));
+    }
+
     /// Initializes any newly-added systems and conditions, rebuilds the executable schedule,
     /// and re-initializes the executor.
     ///
@@ -1325,6 +1361,7 @@ impl ScheduleGraph {
             system_dependents,
             sets_with_conditions_of_systems,
             systems_in_sets_with_conditions,
+            timings: None,
         }
     }
 
@@ -1679,6 +1716,18 @@ impl ScheduleGraph {
         }
     }
 
+    /// Returns the sets containing the node, excluding anonymous sets and system type sets.
+    fn named_sets_containing_node(&self, id: NodeId) -> Vec<InternedSystemSet> {
+        let mut sets = <HashSet<_>>::default();
+        self.traverse_sets_containing_node(id, &mut |key| {
+            self.system_sets[key].system_type().is_none() && sets.insert(key)
+        });
+        sets.into_iter()
+            .filter(|&key| !self.system_sets[key].is_anonymous())
+            .filter_map(|key| self.system_sets.get_interned(key))
+            .collect()
+    }
+
     fn names_of_sets_containing_node(&self, id: &NodeId) -> Vec<String> {
         let mut sets = <HashSet<_>>::default();
         self.traverse_sets_containing_node(*id, &mut |key| {
@@ -0,0 +1,310 @@
+//! Provides [`AsyncWorld`], a handle that lets asynchronous tasks access the [`World`].
+
+use crate::{resource::Resource, world::World};
+use alloc::boxed::Box;
+use bevy_platform::sync::{Arc, Mutex, PoisonError};
+use concurrent_queue::ConcurrentQueue;
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// A job queued by an [`AsyncWorld`].
+///
+/// Returns `true` once it has completed, or `false` if it should be run again at the next sync point.
+type AsyncWorldJob = Box<dyn FnMut(&mut World) -> bool + Send>;
+
+/// A cloneable handle that allows asynchronous code, such as tasks spawned on the
+/// [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool), to access the [`World`].
+///
+/// Work requested through this handle is queued and executed the next time
+/// [`AsyncWorld::apply`] is called, which usually happens once per frame through
+/// [`run_async_world_jobs`]. The returned [`AsyncWorldFuture`] completes once the
+/// work has been executed, which allows multi-frame sequences to be written as
+/// straight-line async code.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::world::AsyncWorld;
+/// # use bevy_tasks::{AsyncComputeTaskPool, TaskPool};
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// fn start_sequence(async_world: Res<AsyncWorld>) {
+///     let async_world = async_world.clone();
+///     AsyncComputeTaskPool::get_or_init(TaskPool::new)
+///         .spawn(async move {
+///             // Runs at the next sync point.
+///             let score = async_world.run(|world| world.resource::<Score>().0).await;
+///             // Waits one frame.
+///             async_world.next_frame().await;
+///             // Waits until a condition is met.
+///             async_world
+///                 .wait_until(move |world| world.resource::<Score>().0 > score)
+///                 .await;
+///         })
+///         .detach();
+/// }
+/// # bevy_ecs::system::assert_is_system(start_sequence);
+/// ```
+///
+/// If the queued work is never applied, for example because the [`World`] that was
+/// supposed to apply it has been dropped, the returned futures will never complete.
+#[derive(Resource, Clone)]
+pub struct AsyncWorld {
+    queue: Arc<ConcurrentQueue<AsyncWorldJob>>,
+}
+
+impl Default for AsyncWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AsyncWorld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncWorld")
+            .field("pending_jobs", &self.queue.len())
+            .finish()
+    }
+}
+
+impl AsyncWorld {
+    /// Creates a new [`AsyncWorld`] with an empty job queue.
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(ConcurrentQueue::unbounded()),
+        }
+    }
+
+    /// Queues `f` to be run against the [`World`] at the next sync point.
+    ///
+    /// The returned future resolves to the return value of `f`.
+    pub fn run<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut World) -> R + Send + 'static,
+    ) -> AsyncWorldFuture<R> {
+        let mut f = Some(f);
+        self.wait_for(move |world| f.take().map(|f| f(world)))
+    }
+
+    /// Returns a future that resolves after the next sync point has been reached.
+    pub fn next_frame(&self) -> AsyncWorldFuture<()> {
+        self.run(|_| {})
+    }
+
+    /// Returns a future that resolves at the first sync point where `condition` returns `true`.
+    ///
+    /// The condition is evaluated once per sync point, starting with the next one.
+    pub fn wait_until(
+        &self,
+        mut condition: impl FnMut(&mut World) -> bool + Send + 'static,
+    ) -> AsyncWorldFuture<()> {
+        self.wait_for(move |world| condition(world).then_some(()))
+    }
+
+    /// Returns a future that resolves to the first [`Some`] value returned by `f`.
+    ///
+    /// `f` is evaluated once per sync point, starting with the next one, until it returns [`Some`].
+    pub fn wait_for<R: Send + 'static>(
+        &self,
+        mut f: impl FnMut(&mut World) -> Option<R> + Send + 'static,
+    ) -> AsyncWorldFuture<R> {
+        let shared = Arc::new(Mutex::new(AsyncWorldFutureState {
+            value: None,
+            waker: None,
+        }));
+        let job_shared = shared.clone();
+        self.push(Box::new(move |world| {
+            // The future was dropped, so nothing is waiting for the job anymore.
+            if Arc::strong_count(&job_shared) == 1 {
+                return true;
+            }
+            let Some(value) = f(world) else {
+                return false;
+            };
+            let mut state = job_shared.lock().unwrap_or_else(PoisonError::into_inner);
+            state.value = Some(value);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            true
+        }));
+        AsyncWorldFuture { shared }
+    }
+
+    /// Returns the number of jobs waiting for the next sync point.
+    pub fn pending_jobs(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Runs all jobs that were queued before this call against `world`.
+    ///
+    /// Jobs queued while this method is running, including jobs queued by other jobs,
+    /// are deferred to the next call. Jobs that are still waiting for a condition are
+    /// re-queued and evaluated again on the next call.
+    pub fn apply(&self, world: &mut World) {
+        for _ in 0..self.queue.len() {
+            let Ok(mut job) = self.queue.pop() else {
+                break;
+            };
+            // Re-queue right away, so that the job isn't lost if a later one panics.
+            if !job(world) {
+                self.push(job);
+            }
+        }
+    }
+
+    fn push(&self, job: AsyncWorldJob) {
+        // The queue is unbounded and never closed, so pushing cannot fail.
+        let _ = self.queue.push(job);
+    }
+}
+
+/// Applies the jobs queued through the [`AsyncWorld`] resource, if it exists.
+///
+/// This is the sync point at which asynchronous code gets access to the [`World`].
+pub fn run_async_world_jobs(world: &mut World) {
+    if let Some(async_world) = world.get_resource::<AsyncWorld>().cloned() {
+        async_world.apply(world);
+    }
+}
+
+struct AsyncWorldFutureState<R> {
+    value: Option<R>,
+    waker: Option<Waker>,
+}
+
+/// A future returned by the methods of [`AsyncWorld`].
+///
+/// Resolves once the corresponding job has completed at a sync point.
+///
+/// Dropping this future cancels its job, which won't be run at later sync points.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AsyncWorldFuture<R> {
+    shared: Arc<Mutex<AsyncWorldFutureState<R>>>,
+}
+
+impl<R> Future for AsyncWorldFuture<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<R> fmt::Debug for AsyncWorldFuture<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncWorldFuture").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::panic::AssertUnwindSafe;
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    fn poll<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn run_resolves_after_apply() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let async_world = AsyncWorld::new();
+
+        let mut future = async_world.run(|world| {
+            world.resource_mut::<Counter>().0 += 1;
+            world.resource::<Counter>().0
+        });
+        assert_eq!(poll(&mut future), Poll::Pending);
+        assert_eq!(world.resource::<Counter>().0, 0);
+
+        async_world.apply(&mut world);
+        assert_eq!(poll(&mut future), Poll::Ready(1));
+        assert_eq!(async_world.pending_jobs(), 0);
+    }
+
+    #[test]
+    fn wait_until_reevaluates_each_apply() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(AsyncWorld::new());
+        let async_world = world.resource::<AsyncWorld>().clone();
+
+        let mut future = async_world.wait_until(|world| world.resource::<Counter>().0 >= 2);
+        for _ in 0..2 {
+            run_async_world_jobs(&mut world);
+            assert_eq!(poll(&mut future), Poll::Pending);
+            world.resource_mut::<Counter>().0 += 1;
+        }
+        run_async_world_jobs(&mut world);
+        assert_eq!(poll(&mut future), Poll::Ready(()));
+        assert_eq!(async_world.pending_jobs(), 0);
+    }
+
+    #[test]
+    fn jobs_queued_during_apply_are_deferred() {
+        let mut world = World::new();
+        let async_world = AsyncWorld::new();
+
+        let inner = async_world.clone();
+        let mut outer = async_world.run(move |_| inner.next_frame());
+        async_world.apply(&mut world);
+        let Poll::Ready(mut next_frame) = poll(&mut outer) else {
+            panic!("the outer job should have run");
+        };
+        assert_eq!(poll(&mut next_frame), Poll::Pending);
+
+        async_world.apply(&mut world);
+        assert_eq!(poll(&mut next_frame), Poll::Ready(()));
+    }
+
+    #[test]
+    fn dropped_futures_cancel_their_jobs() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let async_world = AsyncWorld::new();
+
+        let future = async_world.wait_until(|world| {
+            world.resource_mut::<Counter>().0 += 1;
+            false
+        });
+        async_world.apply(&mut world);
+        assert_eq!(async_world.pending_jobs(), 1);
+
+        drop(future);
+        async_world.apply(&mut world);
+        assert_eq!(async_world.pending_jobs(), 0);
+        assert_eq!(world.resource::<Counter>().0, 1);
+    }
+
+    #[test]
+    fn waiting_jobs_survive_a_panicking_job() {
+        let mut world = World::new();
+        let async_world = AsyncWorld::new();
+
+        let mut waiting = async_world.wait_until(|world| world.contains_resource::<Counter>());
+        let _panicking = async_world.run(|_| panic!("job failed"));
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| async_world.apply(&mut world)));
+        assert!(result.is_err());
+        assert_eq!(async_world.pending_jobs(), 1);
+
+        world.init_resource::<Counter>();
+        async_world.apply(&mut world);
+        assert_eq!(poll(&mut waiting), Poll::Ready(()));
+    }
+}
@@ -1,5 +1,6 @@
 //! Defines the [`World`] and APIs for accessing it directly.
 
+mod async_world;
 pub(crate) mod command_queue;
 mod deferred_world;
 mod entity_fetch;
@@ -23,6 +24,7 @@ use crate::{
     lifecycle::{ComponentHooks, ADD, DESPAWN, INSERT, REMOVE, REPLACE},
     prelude::{Add, Despawn, Insert, Remove, Replace},
 };
+pub use async_world::{run_async_world_jobs, AsyncWorld, AsyncWorldFuture};
 pub use bevy_ecs_macros::FromWorld;
 use bevy_utils::prelude::DebugName;
 pub use deferred_world::DeferredWorld;
@@ -0,0 +1,1096 @@
+use core::{any::TypeId, ops::RangeInclusive};
+
+use bevy_app::{Plugin, PostUpdate};
+use bevy_color::{Alpha, Color, Hsla, Srgba};
+use bevy_core_widgets::{
+    Activate, Callback, CoreRadioGroup, SliderPrecision, SliderStep, SliderValue, ValueChange,
+};
+use bevy_ecs::{
+    archetype::ArchetypeId,
+    bundle::Bundle,
+    component::{Component, ComponentInfo},
+    entity::Entity,
+    hierarchy::{ChildOf, Children},
+    reflect::{AppTypeRegistry, ReflectComponent},
+    resource::Resource,
+    schedule::IntoScheduleConfigs,
+    spawn::Spawn,
+    system::{In, SystemId},
+    world::World,
+};
+use bevy_reflect::{
+    std_traits::ReflectDefault, validation::Range, Access, DynamicEnum, DynamicStruct,
+    DynamicTuple, DynamicVariant, NamedField, OffsetAccess, ParsedPath, PartialReflect,
+    ReflectFromReflect, ReflectPath, ReflectRef, TypeInfo, TypeRegistry, UnnamedField, VariantInfo,
+    VariantType,
+};
+use bevy_ui::{
+    widget::Text, AlignItems, BackgroundColor, Checked, Display, FlexDirection, Node, UiRect,
+    UiSystems, Val,
+};
+
+use crate::{
+    constants::fonts,
+    controls::{
+        button, checkbox, color_slider, color_swatch, radio, slider, ButtonProps, CheckboxProps,
+        ColorChannel, ColorSlider, ColorSliderProps, ColorSwatchFg, SliderBaseColor, SliderProps,
+    },
+    font_styles::InheritableFont,
+    handle_or_path::HandleOrPath,
+    theme::{ThemeFontColor, ThemedText},
+    tokens,
+};
+
+/// Half-width of the slider range used for numeric fields that have no [`Range`] attribute.
+const DEFAULT_RANGE: f32 = 100.0;
+
+/// Parameters for the inspector template, passed to [`inspector`] function.
+pub struct InspectorProps {
+    /// The entity whose components are displayed.
+    pub target: Entity,
+}
+
+/// An inspector widget, which displays the reflected components of an entity and lets the user
+/// edit them.
+///
+/// Changing the target causes the inspector to be rebuilt, as does adding or removing reflected
+/// components on the target.
+#[derive(Component, Clone, Debug)]
+pub struct Inspector {
+    /// The entity whose components are displayed.
+    pub target: Entity,
+}
+
+/// The state the inspector was last built for.
+#[derive(Component, Default)]
+struct InspectorContents {
+    target: Option<Entity>,
+    /// The archetype of the target, whose components only need to be listed again when it
+    /// changes.
+    archetype: Option<ArchetypeId>,
+    components: Vec<TypeId>,
+    stale: bool,
+}
+
+/// The location of the value edited by an inspector widget.
+#[derive(Component, Clone)]
+struct InspectorField {
+    target: Entity,
+    component: TypeId,
+    path: ParsedPath,
+}
+
+/// How an inspector widget displays its field.
+#[derive(Component, Clone)]
+enum FieldWidget {
+    /// A slider.
+    Number,
+    /// A checkbox.
+    Bool,
+    /// A color swatch or color slider.
+    Color,
+    /// One of the radio buttons used to pick a variant of an enum.
+    VariantOption(&'static str),
+    /// The header of an enum whose variants have fields. The inspector needs to be rebuilt
+    /// whenever the variant changes, since the fields change with it.
+    Variant { inspector: Entity, name: String },
+    /// A read-only text display.
+    Text,
+}
+
+/// Header of a collapsible section, which toggles the visibility of the section body.
+#[derive(Component)]
+struct SectionHeader {
+    body: Entity,
+}
+
+/// One-shot systems which the inspector widgets call when they are edited.
+#[derive(Resource, Clone, Copy)]
+struct InspectorCallbacks {
+    number: SystemId<In<ValueChange<f32>>>,
+    bool: SystemId<In<ValueChange<bool>>>,
+    color: SystemId<In<ValueChange<f32>>>,
+    variant: SystemId<In<Activate>>,
+    toggle: SystemId<In<Activate>>,
+}
+
+/// Template function to spawn an inspector.
+///
+/// Each reflected component of the target gets a collapsible section, in which the fields are
+/// displayed according to their type: numbers as sliders, `bool`s as checkboxes, [`Color`]s as a
+/// swatch with RGBA sliders, enums without fields as radio buttons, and nested structs, tuples,
+/// lists and enums as collapsible sections. Any other value is displayed as read-only text.
+///
+/// The section of an enum whose variants have fields starts with radio buttons to pick its
+/// variant. The fields of the new variant get their default values, so switching to a variant is
+/// ignored unless the types of all its fields are registered with [`ReflectDefault`].
+///
+/// Numeric sliders use the bounds of the field's [`Range`] attribute if it has one.
+///
+/// # Arguments
+/// * `props` - construction properties for the inspector.
+/// * `overrides` - a bundle of components that are merged in with the normal inspector components.
+pub fn inspector<B: Bundle>(props: InspectorProps, overrides: B) -> impl Bundle {
+    (
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..Default::default()
+        },
+        Inspector {
+            target: props.target,
+        },
+        InspectorContents::default(),
+        ThemeFontColor(tokens::TEXT_MAIN),
+        InheritableFont {
+            font: HandleOrPath::Path(fonts::REGULAR.to_owned()),
+            font_size: 14.0,
+        },
+        overrides,
+    )
+}
+
+/// Description of the widgets for a value, gathered before they are spawned.
+enum FieldTree {
+    Number {
+        label: String,
+        path: Vec<Access<'static>>,
+        value: f32,
+        range: (f32, f32),
+        integer: bool,
+    },
+    Bool {
+        label: String,
+        path: Vec<Access<'static>>,
+        value: bool,
+    },
+    Color {
+        label: String,
+        path: Vec<Access<'static>>,
+        value: Color,
+    },
+    Variants {
+        label: String,
+        path: Vec<Access<'static>>,
+        variants: &'static [&'static str],
+        selected: String,
+    },
+    Section {
+        label: String,
+        path: Vec<Access<'static>>,
+        variant: Option<String>,
+        children: Vec<FieldTree>,
+    },
+    Text {
+        label: String,
+        path: Vec<Access<'static>>,
+        text: String,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumberKind {
+    Float,
+    Signed,
+    Unsigned,
+}
+
+/// Reads a number as [`f64`], which holds every integer of the sliders' range exactly.
+fn read_number(value: &dyn PartialReflect) -> Option<(f64, NumberKind)> {
+    macro_rules! read {
+        ($($ty:ty => $kind:ident),*) => {
+            $(
+                if let Some(number) = value.try_downcast_ref::<$ty>() {
+                    return Some((*number as f64, NumberKind::$kind));
+                }
+            )*
+        };
+    }
+    read!(
+        f32 => Float, f64 => Float,
+        i8 => Signed, i16 => Signed, i32 => Signed, i64 => Signed, isize => Signed,
+        u8 => Unsigned, u16 => Unsigned, u32 => Unsigned, u64 => Unsigned, usize => Unsigned
+    );
+    None
+}
+
+fn write_number(value: &mut dyn PartialReflect, number: f64) {
+    macro_rules! write {
+        ($number:expr => $($ty:ty),*) => {
+            $(
+                if let Some(field) = value.try_downcast_mut::<$ty>() {
+                    *field = $number as $ty;
+                    return;
+                }
+            )*
+        };
+    }
+    write!(number => f32, f64);
+    write!(number.round() => i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+}
+
+fn display_text(value: &dyn PartialReflect) -> String {
+    match value.try_downcast_ref::<String>() {
+        Some(string) => string.clone(),
+        None => format!("{value:?}"),
+    }
+}
+
+fn channel_value(channel: &ColorChannel, color: Color) -> f32 {
+    let srgba = color.to_srgba();
+    let hsla = Hsla::from(color);
+    match channel {
+        ColorChannel::Red => srgba.red,
+        ColorChannel::Green => srgba.green,
+        ColorChannel::Blue => srgba.blue,
+        ColorChannel::HslHue => hsla.hue,
+        ColorChannel::HslSaturation => hsla.saturation,
+        ColorChannel::HslLightness => hsla.lightness,
+        ColorChannel::Alpha => srgba.alpha,
+    }
+}
+
+fn with_channel(channel: &ColorChannel, color: Color, value: f32) -> Color {
+    let srgba = color.to_srgba();
+    let hsla = Hsla::from(color);
+    match channel {
+        ColorChannel::Red => Srgba {
+            red: value,
+            ..srgba
+        }
+        .into(),
+        ColorChannel::Green => Srgba {
+            green: value,
+            ..srgba
+        }
+        .into(),
+        ColorChannel::Blue => Srgba {
+            blue: value,
+            ..srgba
+        }
+        .into(),
+        ColorChannel::HslHue => Hsla { hue: value, ..hsla }.into(),
+        ColorChannel::HslSaturation => Hsla {
+            saturation: value,
+            ..hsla
+        }
+        .into(),
+        ColorChannel::HslLightness => Hsla {
+            lightness: value,
+            ..hsla
+        }
+        .into(),
+        ColorChannel::Alpha => color.with_alpha(value),
+    }
+}
+
+/// Describes the widgets for `value`, which is found at `path` within its component.
+fn describe(
+    label: String,
+    path: Vec<Access<'static>>,
+    value: &dyn PartialReflect,
+    range: Option<&RangeInclusive<f64>>,
+) -> FieldTree {
+    if let Some((number, kind)) = read_number(value) {
+        let number = number as f32;
+        let range = match range {
+            Some(range) => (*range.start() as f32, *range.end() as f32),
+            None => {
+                let min = match kind {
+                    NumberKind::Unsigned => 0.0,
+                    NumberKind::Float | NumberKind::Signed => -DEFAULT_RANGE,
+                };
+                (min.min(number), DEFAULT_RANGE.max(number))
+            }
+        };
+        return FieldTree::Number {
+            label,
+            path,
+            value: number,
+            range,
+            integer: kind != NumberKind::Float,
+        };
+    }
+    if let Some(&value) = value.try_downcast_ref::<bool>() {
+        return FieldTree::Bool { label, path, value };
+    }
+    if let Some(&value) = value.try_downcast_ref::<Color>() {
+        return FieldTree::Color { label, path, value };
+    }
+
+    let child = |access: Access<'static>| {
+        let mut path = path.clone();
+        path.push(access);
+        path
+    };
+    let mut variant = None;
+    let children = match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            let info = value.get_represented_struct_info();
+            value
+                .iter_fields()
+                .enumerate()
+                .filter_map(|(index, field)| {
+                    let name = value.name_at(index)?;
+                    let range = info
+                        .and_then(|info| info.field(name))
+                        .map(NamedField::custom_attributes)
+                        .and_then(|attributes| attributes.get::<Range>());
+                    let path = child(Access::Field(name.to_owned().into()));
+                    Some(describe(name.to_owned(), path, field, range.map(|r| &r.0)))
+                })
+                .collect()
+        }
+        ReflectRef::TupleStruct(value) => {
+            let info = value.get_represented_tuple_struct_info();
+            value
+                .iter_fields()
+                .enumerate()
+                .map(|(index, field)| {
+                    let range = info
+                        .and_then(|info| info.field_at(index))
+                        .map(UnnamedField::custom_attributes)
+                        .and_then(|attributes| attributes.get::<Range>());
+                    let path = child(Access::TupleIndex(index));
+                    describe(index.to_string(), path, field, range.map(|r| &r.0))
+                })
+                .collect()
+        }
+        ReflectRef::Tuple(value) => value
+            .iter_fields()
+            .enumerate()
+            .map(|(index, field)| {
+                describe(
+                    index.to_string(),
+                    child(Access::TupleIndex(index)),
+                    field,
+                    None,
+                )
+            })
+            .collect(),
+        ReflectRef::List(value) => value
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                describe(
+                    format!("[{index}]"),
+                    child(Access::ListIndex(index)),
+                    item,
+                    None,
+                )
+            })
+            .collect(),
+        ReflectRef::Array(value) => value
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                describe(
+                    format!("[{index}]"),
+                    child(Access::ListIndex(index)),
+                    item,
+                    None,
+                )
+            })
+            .collect(),
+        ReflectRef::Enum(value) => {
+            let info = value.get_represented_enum_info();
+            if let Some(info) = info.filter(|info| {
+                info.iter()
+                    .all(|variant| matches!(variant, VariantInfo::Unit(_)))
+            }) {
+                return FieldTree::Variants {
+                    label,
+                    path,
+                    variants: info.variant_names(),
+                    selected: value.variant_name().to_owned(),
+                };
+            }
+            variant = Some(value.variant_name().to_owned());
+            let picker = info.map(|info| FieldTree::Variants {
+                label: "variant".to_owned(),
+                path: path.clone(),
+                variants: info.variant_names(),
+                selected: value.variant_name().to_owned(),
+            });
+            picker
+                .into_iter()
+                .chain(value.iter_fields().enumerate().map(|(index, field)| {
+                    match (value.variant_type(), field.name()) {
+                        (VariantType::Struct, Some(name)) => describe(
+                            name.to_owned(),
+                            child(Access::Field(name.to_owned().into())),
+                            field.value(),
+                            None,
+                        ),
+                        _ => describe(
+                            index.to_string(),
+                            child(Access::TupleIndex(index)),
+                            field.value(),
+                            None,
+                        ),
+                    }
+                }))
+                .collect()
+        }
+        _ => {
+            return FieldTree::Text {
+                label,
+                path,
+                text: display_text(value),
+            };
+        }
+    };
+    FieldTree::Section {
+        label,
+        path,
+        variant,
+        children,
+    }
+}
+
+/// Returns the reflected components of `target`, sorted by name.
+fn inspected_components(world: &World, registry: &TypeRegistry, target: Entity) -> Vec<TypeId> {
+    let Ok(entity) = world.get_entity(target) else {
+        return Vec::new();
+    };
+    let mut components = entity
+        .archetype()
+        .components()
+        .filter_map(|id| world.components().get_info(id)?.type_id())
+        .filter_map(|type_id| {
+            let registration = registry.get(type_id)?;
+            registration.data::<ReflectComponent>()?;
+            Some((
+                registration.type_info().type_path_table().short_path(),
+                type_id,
+            ))
+        })
+        .collect::<Vec<_>>();
+    components.sort_unstable();
+    components.into_iter().map(|(_, type_id)| type_id).collect()
+}
+
+/// Returns the value a field of `target` points to.
+fn read_field<'w>(
+    world: &'w World,
+    registry: &TypeRegistry,
+    field: &InspectorField,
+) -> Option<&'w dyn PartialReflect> {
+    let reflect_component = registry.get_type_data::<ReflectComponent>(field.component)?;
+    let component = reflect_component.reflect(world.get_entity(field.target).ok()?)?;
+    (&field.path)
+        .reflect_element(component.as_partial_reflect())
+        .ok()
+}
+
+/// Applies `edit` to the field that `field_ent` is a widget for, writing it back to the
+/// component through [`ReflectComponent`].
+///
+/// Immutable components are cloned, edited and reinserted, so that their hooks run.
+fn edit_field(world: &mut World, field_ent: Entity, edit: impl FnOnce(&mut dyn PartialReflect)) {
+    let Some(field) = world.get::<InspectorField>(field_ent).cloned() else {
+        return;
+    };
+    let Some(registry) = world.get_resource::<AppTypeRegistry>().cloned() else {
+        return;
+    };
+    // Don't hold the registry lock while editing: the hooks and observers of the component
+    // run during the insertion, and may lock the registry themselves.
+    let (reflect_component, reflect_from_reflect) = {
+        let registry = registry.read();
+        let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(field.component)
+        else {
+            return;
+        };
+        (
+            reflect_component.clone(),
+            registry
+                .get_type_data::<ReflectFromReflect>(field.component)
+                .cloned(),
+        )
+    };
+    let mutable = world
+        .components()
+        .get_id(field.component)
+        .and_then(|id| world.components().get_info(id))
+        .is_some_and(ComponentInfo::mutable);
+    let Ok(mut entity) = world.get_entity_mut(field.target) else {
+        return;
+    };
+
+    if mutable {
+        let Some(mut component) = reflect_component.reflect_mut(&mut entity) else {
+            return;
+        };
+        if let Ok(value) = (&field.path).reflect_element_mut(component.as_partial_reflect_mut()) {
+            edit(value);
+        }
+    } else {
+        let Some(component) = reflect_component.reflect(&entity) else {
+            return;
+        };
+        let Some(mut component) = component
+            .reflect_clone()
+            .ok()
+            .or_else(|| reflect_from_reflect?.from_reflect(component.as_partial_reflect()))
+        else {
+            return;
+        };
+        if let Ok(value) = (&field.path).reflect_element_mut(component.as_partial_reflect_mut()) {
+            edit(value);
+            // The component is concrete, so inserting it doesn't need any registration.
+            reflect_component.insert(
+                &mut entity,
+                component.as_partial_reflect(),
+                &TypeRegistry::empty(),
+            );
+        }
+    }
+}
+
+fn on_number_change(In(change): In<ValueChange<f32>>, world: &mut World) {
+    edit_field(world, change.source, |value| {
+        // Sliders only have `f32` precision, so leave large integers alone unless the slider
+        // actually moved.
+        if read_number(value).is_some_and(|(number, _)| number as f32 != change.value) {
+            write_number(value, change.value.into());
+        }
+    });
+}
+
+fn on_bool_change(In(change): In<ValueChange<bool>>, world: &mut World) {
+    edit_field(world, change.source, |value| {
+        if let Some(field) = value.try_downcast_mut::<bool>() {
+            *field = change.value;
+        }
+    });
+}
+
+fn on_color_change(In(change): In<ValueChange<f32>>, world: &mut World) {
+    let Some(ColorSlider { channel }) = world.get::<ColorSlider>(change.source).cloned() else {
+        return;
+    };
+    edit_field(world, change.source, |value| {
+        if let Some(color) = value.try_downcast_mut::<Color>() {
+            *color = with_channel(&channel, *color, change.value);
+        }
+    });
+}
+
+/// Returns the variant `name` of the enum `value`, with default values for its fields.
+///
+/// Returns `None` if the enum doesn't have this variant, or if the type of one of its fields isn't
+/// registered with [`ReflectDefault`].
+fn default_variant(
+    registry: &TypeRegistry,
+    value: &dyn PartialReflect,
+    name: &str,
+) -> Option<DynamicVariant> {
+    let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() else {
+        return None;
+    };
+    let default = |type_id: TypeId| {
+        let reflect_default = registry.get_type_data::<ReflectDefault>(type_id)?;
+        Some(reflect_default.default().into_partial_reflect())
+    };
+    Some(match info.variant(name)? {
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+        VariantInfo::Tuple(info) => {
+            let mut fields = DynamicTuple::default();
+            for field in info.iter() {
+                fields.insert_boxed(default(field.type_id())?);
+            }
+            DynamicVariant::Tuple(fields)
+        }
+        VariantInfo::Struct(info) => {
+            let mut fields = DynamicStruct::default();
+            for field in info.iter() {
+                fields.insert_boxed(field.name(), default(field.type_id())?);
+            }
+            DynamicVariant::Struct(fields)
+        }
+    })
+}
+
+fn on_variant_change(In(Activate(radio)): In<Activate>, world: &mut World) {
+    let Some(&FieldWidget::VariantOption(name)) = world.get::<FieldWidget>(radio) else {
+        return;
+    };
+    let Some(field) = world.get::<InspectorField>(radio).cloned() else {
+        return;
+    };
+    let Some(registry) = world.get_resource::<AppTypeRegistry>().cloned() else {
+        return;
+    };
+    let variant = {
+        let registry = registry.read();
+        read_field(world, &registry, &field)
+            .filter(|value| {
+                !matches!(value.reflect_ref(), ReflectRef::Enum(value) if value.variant_name() == name)
+            })
+            .and_then(|value| default_variant(&registry, value, name))
+    };
+    let Some(variant) = variant else {
+        return;
+    };
+    edit_field(world, radio, |value| {
+        // Ignore failures: the enum can only have changed type if the inspector is stale.
+        let _ = value.try_apply(&DynamicEnum::new(name, variant));
+    });
+}
+
+fn on_section_toggle(In(Activate(header)): In<Activate>, world: &mut World) {
+    let Some(&SectionHeader { body }) = world.get::<SectionHeader>(header) else {
+        return;
+    };
+    if let Some(mut node) = world.get_mut::<Node>(body) {
+        node.display = match node.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
+
+/// Spawns a row with a label on the left, returning the row entity.
+fn spawn_row(world: &mut World, parent: Entity, label: &str) -> Entity {
+    let row = world
+        .spawn((
+            Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            ChildOf(parent),
+        ))
+        .id();
+    world.spawn((
+        Node {
+            width: Val::Percent(35.0),
+            flex_shrink: 0.0,
+            ..Default::default()
+        },
+        Text::new(label),
+        ThemedText,
+        ChildOf(row),
+    ));
+    row
+}
+
+fn spawn_field(
+    world: &mut World,
+    parent: Entity,
+    inspector: Entity,
+    field: &InspectorField,
+    tree: FieldTree,
+    callbacks: InspectorCallbacks,
+) {
+    let field_at = |path: Vec<Access<'static>>| InspectorField {
+        path: ParsedPath(path.into_iter().map(OffsetAccess::from).collect()),
+        ..field.clone()
+    };
+    match tree {
+        FieldTree::Number {
+            label,
+            path,
+            value,
+            range: (min, max),
+            integer,
+        } => {
+            let row = spawn_row(world, parent, &label);
+            let (step, precision) = match integer {
+                true => (1.0, 0),
+                false => ((max - min) / 100.0, 2),
+            };
+            world.spawn((
+                slider(
+                    SliderProps {
+                        value,
+                        min,
+                        max,
+                        on_change: Callback::System(callbacks.number),
+                    },
+                    (
+                        field_at(path),
+                        FieldWidget::Number,
+                        SliderStep(step),
+                        SliderPrecision(precision),
+                    ),
+                ),
+                ChildOf(row),
+            ));
+        }
+        FieldTree::Bool { label, path, value } => {
+            let mut entity = world.spawn((
+                checkbox(
+                    CheckboxProps {
+                        on_change: Callback::System(callbacks.bool),
+                    },
+                    (field_at(path), FieldWidget::Bool),
+                    Spawn((Text::new(label), ThemedText)),
+                ),
+                ChildOf(parent),
+            ));
+            if value {
+                entity.insert(Checked);
+            }
+        }
+        FieldTree::Color { label, path, value } => {
+            let row = spawn_row(world, parent, &label);
+            let column = world
+                .spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        flex_grow: 1.0,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    ChildOf(row),
+                ))
+                .id();
+            let field = field_at(path);
+            world.spawn((
+                color_swatch((field.clone(), FieldWidget::Color)),
+                ChildOf(column),
+            ));
+            for channel in [
+                ColorChannel::Red,
+                ColorChannel::Green,
+                ColorChannel::Blue,
+                ColorChannel::Alpha,
+            ] {
+                world.spawn((
+                    color_slider(
+                        ColorSliderProps {
+                            value: channel_value(&channel, value),
+                            on_change: Callback::System(callbacks.color),
+                            channel,
+                        },
+                        (field.clone(), FieldWidget::Color, SliderBaseColor(value)),
+                    ),
+                    ChildOf(column),
+                ));
+            }
+        }
+        FieldTree::Variants {
+            label,
+            path,
+            variants,
+            selected,
+        } => {
+            let row = spawn_row(world, parent, &label);
+            let group = world
+                .spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    CoreRadioGroup {
+                        on_change: Callback::System(callbacks.variant),
+                    },
+                    ChildOf(row),
+                ))
+                .id();
+            let field = field_at(path);
+            for &name in variants {
+                let mut entity = world.spawn((
+                    radio(
+                        (field.clone(), FieldWidget::VariantOption(name)),
+                        Spawn((Text::new(name), ThemedText)),
+                    ),
+                    ChildOf(group),
+                ));
+                if name == selected {
+                    entity.insert(Checked);
+                }
+            }
+        }
+        FieldTree::Section {
+            label,
+            path,
+            variant,
+            children,
+        } => {
+            let title = match &variant {
+                Some(variant) => format!("{label}: {variant}"),
+                None => label,
+            };
+            let mut header = world.spawn((
+                button(
+                    ButtonProps {
+                        on_click: Callback::System(callbacks.toggle),
+                        ..Default::default()
+                    },
+                    (),
+                    Spawn((Text::new(title), ThemedText)),
+                ),
+                ChildOf(parent),
+            ));
+            if let Some(name) = variant {
+                header.insert((field_at(path), FieldWidget::Variant { inspector, name }));
+            }
+            let header = header.id();
+            let body = world
+                .spawn((
+                    Node {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        padding: UiRect::left(Val::Px(12.0)),
+                        ..Default::default()
+                    },
+                    ChildOf(parent),
+                ))
+                .id();
+            world.entity_mut(header).insert(SectionHeader { body });
+            for child in children {
+                spawn_field(world, body, inspector, field, child, callbacks);
+            }
+        }
+        FieldTree::Text { label, path, text } => {
+            let row = spawn_row(world, parent, &label);
+            world.spawn((
+                Text::new(text),
+                ThemedText,
+                field_at(path),
+                FieldWidget::Text,
+                ChildOf(row),
+            ));
+        }
+    }
+}
+
+/// Rebuilds the widgets of every inspector whose target or set of components changed.
+///
+/// The components of a target are only listed again when its archetype changes.
+fn build_inspectors(world: &mut World) {
+    let Some(registry) = world.get_resource::<AppTypeRegistry>().cloned() else {
+        return;
+    };
+    let registry = registry.read();
+    let callbacks = *world.resource::<InspectorCallbacks>();
+
+    let mut archetypes = Vec::new();
+    let mut rebuilds = Vec::new();
+    let mut q_inspectors = world.query::<(Entity, &Inspector, &InspectorContents)>();
+    for (inspector_ent, inspector, contents) in q_inspectors.iter(world) {
+        let target = inspector.target;
+        let archetype = world
+            .get_entity(target)
+            .ok()
+            .map(|entity| entity.archetype().id());
+        let same_target = contents.target == Some(target);
+        if !contents.stale && same_target && contents.archetype == archetype {
+            continue;
+        }
+        let components = inspected_components(world, &registry, target);
+        if !contents.stale && same_target && contents.components == components {
+            archetypes.push((inspector_ent, archetype));
+            continue;
+        }
+        let sections = components
+            .iter()
+            .filter_map(|&type_id| {
+                let registration = registry.get(type_id)?;
+                let component = registration
+                    .data::<ReflectComponent>()?
+                    .reflect(world.entity(target))?;
+                let label = registration.type_info().type_path_table().short_path();
+                Some((
+                    type_id,
+                    describe(
+                        label.to_owned(),
+                        Vec::new(),
+                        component.as_partial_reflect(),
+                        None,
+                    ),
+                ))
+            })
+            .collect::<Vec<_>>();
+        rebuilds.push((inspector_ent, target, archetype, components, sections));
+    }
+    drop(registry);
+
+    for (inspector_ent, archetype) in archetypes {
+        world
+            .entity_mut(inspector_ent)
+            .get_mut::<InspectorContents>()
+            .unwrap()
+            .archetype = archetype;
+    }
+    for (inspector_ent, target, archetype, components, sections) in rebuilds {
+        world
+            .entity_mut(inspector_ent)
+            .despawn_related::<Children>()
+            .insert(InspectorContents {
+                target: Some(target),
+                archetype,
+                components,
+                stale: false,
+            });
+        for (component, tree) in sections {
+            let field = InspectorField {
+                target,
+                component,
+                path: ParsedPath(Vec::new()),
+            };
+            spawn_field(world, inspector_ent, inspector_ent, &field, tree, callbacks);
+        }
+    }
+}
+
+/// Change to apply to a widget so that it shows the current value of its field.
+enum FieldUpdate {
+    Number(f32),
+    Checked(bool),
+    Color(Color),
+    Text(String),
+    Rebuild(Entity),
+}
+
+/// Updates the inspector widgets to match the values of the fields they display, since those
+/// can be changed by other systems as well as by the widgets themselves.
+///
+/// Only the widgets of components that changed since the last update are synced.
+fn sync_inspector_fields(world: &mut World) {
+    let Some(registry) = world.get_resource::<AppTypeRegistry>().cloned() else {
+        return;
+    };
+    let registry = registry.read();
+    let mut q_fields = world.query::<(Entity, &InspectorField, &FieldWidget)>();
+    let (last_run, this_run) = (world.last_change_tick(), world.read_change_tick());
+    let changed = |field: &InspectorField| {
+        let Some(component_id) = world.components().get_id(field.component) else {
+            return false;
+        };
+        world
+            .get_entity(field.target)
+            .ok()
+            .and_then(|entity| entity.get_change_ticks_by_id(component_id))
+            .is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+    };
+
+    let mut updates = Vec::new();
+    for (field_ent, field, widget) in q_fields.iter(world) {
+        if !changed(field) {
+            continue;
+        }
+        let Some(value) = read_field(world, &registry, field) else {
+            continue;
+        };
+        let update = match widget {
+            FieldWidget::Number => {
+                read_number(value).map(|(number, _)| FieldUpdate::Number(number as f32))
+            }
+            FieldWidget::Bool => value
+                .try_downcast_ref::<bool>()
+                .map(|&checked| FieldUpdate::Checked(checked)),
+            FieldWidget::Color => value
+                .try_downcast_ref::<Color>()
+                .map(|&color| FieldUpdate::Color(color)),
+            FieldWidget::VariantOption(name) => match value.reflect_ref() {
+                ReflectRef::Enum(value) => {
+                    Some(FieldUpdate::Checked(value.variant_name() == *name))
+                }
+                _ => None,
+            },
+            FieldWidget::Variant { inspector, name } => match value.reflect_ref() {
+                ReflectRef::Enum(value) if value.variant_name() != name => {
+                    Some(FieldUpdate::Rebuild(*inspector))
+                }
+                _ => None,
+            },
+            FieldWidget::Text => Some(FieldUpdate::Text(display_text(value))),
+        };
+        updates.extend(update.map(|update| (field_ent, update)));
+    }
+    drop(registry);
+
+    for (field_ent, update) in updates {
+        match update {
+            FieldUpdate::Number(number) => set_slider_value(world, field_ent, number),
+            FieldUpdate::Checked(checked) => {
+                let mut entity = world.entity_mut(field_ent);
+                if entity.contains::<Checked>() != checked {
+                    match checked {
+                        true => entity.insert(Checked),
+                        false => entity.remove::<Checked>(),
+                    };
+                }
+            }
+            FieldUpdate::Color(color) => {
+                if let Some(ColorSlider { channel }) = world.get::<ColorSlider>(field_ent).cloned()
+                {
+                    set_slider_value(world, field_ent, channel_value(&channel, color));
+                    if let Some(mut base_color) = world.get_mut::<SliderBaseColor>(field_ent)
+                        && base_color.0 != color
+                    {
+                        base_color.0 = color;
+                    }
+                    continue;
+                }
+                let Some(children) = world.get::<Children>(field_ent) else {
+                    continue;
+                };
+                let swatch_fg = children
+                    .iter()
+                    .copied()
+                    .find(|&child| world.entity(child).contains::<ColorSwatchFg>());
+                if let Some(mut background) =
+                    swatch_fg.and_then(|fg| world.get_mut::<BackgroundColor>(fg))
+                    && background.0 != color
+                {
+                    background.0 = color;
+                }
+            }
+            FieldUpdate::Text(text) => {
+                if let Some(mut current) = world.get_mut::<Text>(field_ent)
+                    && current.0 != text
+                {
+                    current.0 = text;
+                }
+            }
+            FieldUpdate::Rebuild(inspector) => {
+                if let Some(mut contents) = world.get_mut::<InspectorContents>(inspector) {
+                    contents.stale = true;
+                }
+            }
+        }
+    }
+}
+
+fn set_slider_value(world: &mut World, slider_ent: Entity, value: f32) {
+    if world
+        .get::<SliderValue>(slider_ent)
+        .is_some_and(|current| current.0 != value)
+    {
+        world.entity_mut(slider_ent).insert(SliderValue(value));
+    }
+}
+
+/// Plugin which registers the systems for building and updating inspectors.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        let world = app.world_mut();
+        let callbacks = InspectorCallbacks {
+            number: world.register_system(on_number_change),
+            bool: world.register_system(on_bool_change),
+            color: world.register_system(on_color_change),
+            variant: world.register_system(on_variant_change),
+            toggle: world.register_system(on_section_toggle),
+        };
+        app.insert_resource(callbacks).add_systems(
+            PostUpdate,
+            (build_inspectors, sync_inspector_fields)
+                .chain()
+                .before(UiSystems::Prepare),
+        );
+    }
+}
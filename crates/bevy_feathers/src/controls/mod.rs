@@ -5,6 +5,7 @@ mod button;
 mod checkbox;
 mod color_slider;
 mod color_swatch;
+mod inspector;
 mod radio;
 mod slider;
 mod toggle_switch;
@@ -16,6 +17,7 @@ pub use color_slider::{
     color_slider, ColorChannel, ColorSlider, ColorSliderPlugin, ColorSliderProps, SliderBaseColor,
 };
 pub use color_swatch::{color_swatch, ColorSwatch, ColorSwatchFg};
+pub use inspector::{inspector, Inspector, InspectorPlugin, InspectorProps};
 pub use radio::{radio, RadioPlugin};
 pub use slider::{slider, SliderPlugin, SliderProps};
 pub use toggle_switch::{toggle_switch, ToggleSwitchPlugin, ToggleSwitchProps};
@@ -33,6 +35,7 @@ impl Plugin for ControlsPlugin {
             ButtonPlugin,
             CheckboxPlugin,
             ColorSliderPlugin,
+            InspectorPlugin,
             RadioPlugin,
             SliderPlugin,
             ToggleSwitchPlugin,
@@ -0,0 +1,694 @@
+//! Maps raw input to user-defined actions.
+//!
+//! Rather than checking for specific keys or buttons, gameplay code can check for actions such as
+//! "jump" or "move left", defined as an enum. An [`InputMap`] binds each action to any number of
+//! [`InputBinding`]s, and the [`InputActionPlugin`] updates the matching [`ActionState`] every
+//! frame.
+//!
+//! Both components live on a context entity, usually one per local player. Each context can be
+//! restricted to one gamepad with [`ActionGamepad`], while the keyboard and the mouse are shared
+//! by all contexts. Bindings can be changed at runtime, and serialized with the `serialize`
+//! feature.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_input::{action::*, gamepad::{GamepadAxis, GamepadButton}, keyboard::KeyCode};
+//! #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+//! enum Action {
+//!     Jump,
+//!     Move,
+//!     Save,
+//! }
+//!
+//! fn spawn_player(mut commands: Commands) {
+//!     commands.spawn(
+//!         InputMap::new()
+//!             .with(Action::Jump, KeyCode::Space)
+//!             .with(Action::Jump, GamepadButton::South)
+//!             .with(Action::Move, GamepadAxis::LeftStickX)
+//!             .with(
+//!                 Action::Save,
+//!                 InputBinding::chord([KeyCode::ControlLeft, KeyCode::KeyS]),
+//!             ),
+//!     );
+//! }
+//!
+//! fn jump(players: Query<&ActionState<Action>>) {
+//!     for actions in &players {
+//!         if actions.just_pressed(&Action::Jump) {
+//!             // Jump!
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{
+    gamepad::{Gamepad, GamepadAxis, GamepadButton},
+    keyboard::KeyCode,
+    mouse::MouseButton,
+    ButtonInput, InputSystems,
+};
+use alloc::{vec, vec::Vec};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    schedule::IntoScheduleConfigs,
+    system::{Query, Res},
+};
+use bevy_platform::collections::HashMap;
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use derive_more::derive::From;
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::prelude::ReflectComponent;
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+#[cfg(all(feature = "serialize", feature = "bevy_reflect"))]
+use bevy_reflect::{ReflectDeserialize, ReflectSerialize};
+
+/// A user-defined action, such as "jump" or "move left".
+///
+/// This is implemented for every type fulfilling its bounds, which is usually a fieldless enum.
+pub trait InputAction: Clone + Eq + Hash + Debug + Send + Sync + 'static {}
+
+impl<T: Clone + Eq + Hash + Debug + Send + Sync + 'static> InputAction for T {}
+
+/// The direction of a [`GamepadAxis`] which an [`InputBinding`] responds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Hash, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum AxisDirection {
+    /// Values above zero.
+    Positive,
+    /// Values below zero.
+    Negative,
+}
+
+/// An input which can trigger an action of an [`InputMap`].
+///
+/// Every binding has a value, which is `1.0` or `0.0` for digital inputs and ranges from `-1.0`
+/// to `1.0` for gamepad axes. The action is pressed while the value isn't zero.
+///
+/// Gamepad inputs are read from the [`Gamepad`] component, so the dead zones and thresholds of its
+/// [`GamepadSettings`](crate::gamepad::GamepadSettings) apply.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, From)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Hash, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum InputBinding {
+    /// A key of the keyboard, identified by its physical position.
+    Key(KeyCode),
+    /// A button of the mouse.
+    Mouse(MouseButton),
+    /// A button of a gamepad.
+    ///
+    /// Its value is the analog value of the button while it is pressed, such as how far a
+    /// trigger is pulled.
+    GamepadButton(GamepadButton),
+    /// An axis of a gamepad, such as a stick moving along one direction.
+    GamepadAxis(GamepadAxis),
+    /// One direction of an axis of a gamepad, with values from `0.0` to `1.0`.
+    GamepadAxisDirection(GamepadAxis, AxisDirection),
+    /// Several bindings which must all be pressed together, such as `Ctrl + S`.
+    ///
+    /// The value of the chord is the value of its last binding, once all the others are pressed.
+    ///
+    /// A pressed chord consumes its bindings: within the same [`InputMap`], bindings made of only
+    /// some of its inputs don't trigger their actions. For example, an action bound to `S` isn't
+    /// pressed while `Ctrl + S` is, so the longest matching binding wins.
+    #[from(ignore)]
+    Chord(Vec<InputBinding>),
+}
+
+impl InputBinding {
+    /// Creates a [`Chord`](InputBinding::Chord) from the given bindings.
+    pub fn chord(bindings: impl IntoIterator<Item = impl Into<InputBinding>>) -> Self {
+        Self::Chord(bindings.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the inputs of this binding, which are the bindings of a chord and itself otherwise.
+    fn inputs(&self) -> Vec<&InputBinding> {
+        match self {
+            InputBinding::Chord(bindings) => bindings.iter().flat_map(Self::inputs).collect(),
+            binding => vec![binding],
+        }
+    }
+
+    /// Returns a binding for an input that was just pressed, if any.
+    ///
+    /// This is meant for rebinding actions at runtime, by waiting for the player to press the
+    /// input they want to use. Gamepad axes are not considered.
+    pub fn just_pressed(
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: Option<&Gamepad>,
+    ) -> Option<Self> {
+        keys.get_just_pressed()
+            .next()
+            .map(|key| Self::Key(*key))
+            .or_else(|| {
+                mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Self::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepad
+                    .and_then(|gamepad| gamepad.get_just_pressed().next())
+                    .map(|button| Self::GamepadButton(*button))
+            })
+    }
+}
+
+/// The bindings of the actions of type `A`, for one input context.
+///
+/// Adding this component to an entity makes it an input context, whose [`ActionState`] is updated
+/// by the [`InputActionPlugin`]. An action can have any number of bindings, and is pressed while
+/// any of them is.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(ActionState<A>)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serialize",
+    serde(bound(
+        serialize = "A: serde::Serialize",
+        deserialize = "A: serde::Deserialize<'de>"
+    ))
+)]
+pub struct InputMap<A: InputAction> {
+    bindings: HashMap<A, Vec<InputBinding>>,
+}
+
+impl<A: InputAction> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::default(),
+        }
+    }
+}
+
+impl<A: InputAction> InputMap<A> {
+    /// Creates an input map without any bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this input map with `binding` added to the bindings of `action`.
+    pub fn with(mut self, action: A, binding: impl Into<InputBinding>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Adds `binding` to the bindings of `action`, unless it is already bound to it.
+    pub fn bind(&mut self, action: A, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Removes `binding` from the bindings of `action`, returning `false` if it wasn't bound.
+    pub fn unbind(&mut self, action: &A, binding: &InputBinding) -> bool {
+        let Some(bindings) = self.bindings.get_mut(action) else {
+            return false;
+        };
+        let len = bindings.len();
+        bindings.retain(|bound| bound != binding);
+        bindings.len() != len
+    }
+
+    /// Replaces `old` with `new` in the bindings of `action`, keeping its position.
+    ///
+    /// If `old` isn't bound to `action`, `new` is added to its bindings instead.
+    pub fn rebind(&mut self, action: A, old: &InputBinding, new: impl Into<InputBinding>) {
+        let new = new.into();
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|bound| *bound != new);
+        match bindings.iter_mut().find(|bound| *bound == old) {
+            Some(bound) => *bound = new,
+            None => bindings.push(new),
+        }
+    }
+
+    /// Removes every binding of `action`.
+    pub fn clear_action(&mut self, action: &A) {
+        self.bindings.remove(action);
+    }
+
+    /// Returns the bindings of `action`.
+    pub fn bindings(&self, action: &A) -> &[InputBinding] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over every action and its bindings.
+    pub fn iter(&self) -> impl Iterator<Item = (&A, &[InputBinding])> {
+        self.bindings
+            .iter()
+            .map(|(action, bindings)| (action, bindings.as_slice()))
+    }
+}
+
+/// Restricts the gamepad inputs of an input context to a single gamepad.
+///
+/// Without this component, an [`InputMap`] responds to every connected gamepad.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Debug, PartialEq, Clone)
+)]
+pub struct ActionGamepad(pub Entity);
+
+/// The state of the actions of type `A` for one input context, updated from its [`InputMap`].
+///
+/// Actions can be checked like buttons through [`pressed`](Self::pressed),
+/// [`just_pressed`](Self::just_pressed) and [`just_released`](Self::just_released), or read as
+/// analog values through [`value`](Self::value).
+#[derive(Component, Debug, Clone)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, Debug, Clone)
+)]
+pub struct ActionState<A: InputAction> {
+    buttons: ButtonInput<A>,
+    values: HashMap<A, f32>,
+}
+
+impl<A: InputAction> Default for ActionState<A> {
+    fn default() -> Self {
+        Self {
+            buttons: ButtonInput::default(),
+            values: HashMap::default(),
+        }
+    }
+}
+
+impl<A: InputAction> ActionState<A> {
+    /// Returns `true` if `action` is pressed.
+    pub fn pressed(&self, action: &A) -> bool {
+        self.buttons.pressed(action.clone())
+    }
+
+    /// Returns `true` if `action` was pressed during the last update.
+    pub fn just_pressed(&self, action: &A) -> bool {
+        self.buttons.just_pressed(action.clone())
+    }
+
+    /// Returns `true` if `action` was released during the last update.
+    pub fn just_released(&self, action: &A) -> bool {
+        self.buttons.just_released(action.clone())
+    }
+
+    /// Returns the value of `action`, from the binding with the largest magnitude.
+    ///
+    /// This is `0.0` if the action isn't pressed.
+    pub fn value(&self, action: &A) -> f32 {
+        self.values.get(action).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the actions as a [`ButtonInput`], to access its other methods.
+    pub fn buttons(&self) -> &ButtonInput<A> {
+        &self.buttons
+    }
+
+    /// Sets the value of `action`, pressing it if the value isn't zero.
+    ///
+    /// This is done by the [`InputActionPlugin`], but can also be used to simulate input.
+    pub fn set(&mut self, action: A, value: f32) {
+        if value != 0.0 {
+            self.buttons.press(action.clone());
+            self.values.insert(action, value);
+        } else {
+            self.buttons.release(action.clone());
+            self.values.remove(&action);
+        }
+    }
+
+    /// Releases every action.
+    pub fn release_all(&mut self) {
+        self.buttons.release_all();
+        self.values.clear();
+    }
+}
+
+/// Adds support for the actions of type `A` to an app, updating every [`ActionState<A>`] from
+/// its [`InputMap<A>`] in [`PreUpdate`], after [`InputSystems`].
+pub struct InputActionPlugin<A: InputAction>(PhantomData<A>);
+
+impl<A: InputAction> Default for InputActionPlugin<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: InputAction> Plugin for InputActionPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, update_action_states::<A>.after(InputSystems));
+    }
+}
+
+/// The inputs an [`InputBinding`] is evaluated against.
+struct BindingInputs<'a> {
+    keys: Option<&'a ButtonInput<KeyCode>>,
+    mouse: Option<&'a ButtonInput<MouseButton>>,
+    gamepads: &'a [&'a Gamepad],
+}
+
+impl BindingInputs<'_> {
+    fn value(&self, binding: &InputBinding) -> f32 {
+        let button = |pressed: bool| if pressed { 1.0 } else { 0.0 };
+        match binding {
+            InputBinding::Key(key) => button(self.keys.is_some_and(|keys| keys.pressed(*key))),
+            InputBinding::Mouse(mouse_button) => {
+                button(self.mouse.is_some_and(|mouse| mouse.pressed(*mouse_button)))
+            }
+            InputBinding::GamepadButton(gamepad_button) => self.gamepad_value(|gamepad| {
+                if gamepad.pressed(*gamepad_button) {
+                    gamepad
+                        .get(*gamepad_button)
+                        .filter(|value| *value > 0.0)
+                        .unwrap_or(1.0)
+                } else {
+                    0.0
+                }
+            }),
+            InputBinding::GamepadAxis(axis) => {
+                self.gamepad_value(|gamepad| gamepad.get(*axis).unwrap_or(0.0))
+            }
+            InputBinding::GamepadAxisDirection(axis, direction) => self.gamepad_value(|gamepad| {
+                let value = gamepad.get(*axis).unwrap_or(0.0);
+                match direction {
+                    AxisDirection::Positive => value.max(0.0),
+                    AxisDirection::Negative => (-value).max(0.0),
+                }
+            }),
+            InputBinding::Chord(bindings) => match bindings.split_last() {
+                Some((last, modifiers))
+                    if modifiers.iter().all(|binding| self.value(binding) != 0.0) =>
+                {
+                    self.value(last)
+                }
+                _ => 0.0,
+            },
+        }
+    }
+
+    /// Returns the value with the largest magnitude among the gamepads.
+    fn gamepad_value(&self, value: impl Fn(&Gamepad) -> f32) -> f32 {
+        self.gamepads
+            .iter()
+            .map(|gamepad| value(gamepad))
+            .fold(
+                0.0,
+                |max, value| {
+                    if value.abs() > max.abs() {
+                        value
+                    } else {
+                        max
+                    }
+                },
+            )
+    }
+}
+
+/// Updates every [`ActionState<A>`] from its [`InputMap<A>`].
+pub fn update_action_states<A: InputAction>(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mouse: Option<Res<ButtonInput<MouseButton>>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut contexts: Query<(&InputMap<A>, &mut ActionState<A>, Option<&ActionGamepad>)>,
+) {
+    let all_gamepads: Vec<&Gamepad> = gamepads.iter().map(|(_, gamepad)| gamepad).collect();
+
+    for (input_map, mut state, action_gamepad) in &mut contexts {
+        let context_gamepads: Vec<&Gamepad>;
+        let inputs = BindingInputs {
+            keys: keys.as_deref(),
+            mouse: mouse.as_deref(),
+            gamepads: match action_gamepad {
+                Some(ActionGamepad(entity)) => {
+                    context_gamepads = gamepads
+                        .get(*entity)
+                        .map(|(_, gamepad)| gamepad)
+                        .into_iter()
+                        .collect();
+                    &context_gamepads
+                }
+                None => &all_gamepads,
+            },
+        };
+
+        state.buttons.clear();
+        // Release actions which lost all their bindings.
+        let unbound: Vec<A> = state
+            .buttons
+            .get_pressed()
+            .filter(|action| input_map.bindings(action).is_empty())
+            .cloned()
+            .collect();
+        for action in unbound {
+            state.set(action, 0.0);
+        }
+
+        // Pressed chords consume their inputs, so that bindings made of some of them don't also
+        // trigger their actions.
+        let pressed_chords: Vec<Vec<&InputBinding>> = input_map
+            .iter()
+            .flat_map(|(_, bindings)| bindings)
+            .filter(|binding| {
+                matches!(binding, InputBinding::Chord(_)) && inputs.value(binding) != 0.0
+            })
+            .map(InputBinding::inputs)
+            .collect();
+        let consumed = |binding: &InputBinding| {
+            let binding_inputs = binding.inputs();
+            pressed_chords.iter().any(|chord| {
+                chord.len() > binding_inputs.len()
+                    && binding_inputs.iter().all(|input| chord.contains(input))
+            })
+        };
+
+        for (action, bindings) in input_map.iter() {
+            let value = bindings
+                .iter()
+                .filter(|binding| !consumed(binding))
+                .map(|binding| inputs.value(binding))
+                .fold(
+                    0.0,
+                    |max: f32, value| {
+                        if value.abs() > max.abs() {
+                            value
+                        } else {
+                            max
+                        }
+                    },
+                );
+            if value != state.value(action) || (value != 0.0) != state.pressed(action) {
+                state.set(action.clone(), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    #[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+    enum Action {
+        Jump,
+        Move,
+        Save,
+        Down,
+    }
+
+    fn world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_action_states::<Action>);
+        (world, schedule)
+    }
+
+    #[test]
+    fn buttons_and_chords() {
+        let (mut world, mut schedule) = world();
+        let player = world
+            .spawn(
+                InputMap::new()
+                    .with(Action::Jump, KeyCode::Space)
+                    .with(Action::Jump, MouseButton::Left)
+                    .with(
+                        Action::Save,
+                        InputBinding::chord([KeyCode::ControlLeft, KeyCode::KeyS]),
+                    ),
+            )
+            .id();
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyS);
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        schedule.run(&mut world);
+        let state = world.get::<ActionState<Action>>(player).unwrap();
+        assert!(state.just_pressed(&Action::Jump));
+        assert_eq!(state.value(&Action::Jump), 1.0);
+        assert!(!state.pressed(&Action::Save));
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ControlLeft);
+        schedule.run(&mut world);
+        let state = world.get::<ActionState<Action>>(player).unwrap();
+        assert!(state.pressed(&Action::Jump));
+        assert!(!state.just_pressed(&Action::Jump));
+        assert!(state.just_pressed(&Action::Save));
+
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .release(MouseButton::Left);
+        schedule.run(&mut world);
+        let state = world.get::<ActionState<Action>>(player).unwrap();
+        assert!(state.just_released(&Action::Jump));
+    }
+
+    #[test]
+    fn chords_consume_their_inputs() {
+        let (mut world, mut schedule) = world();
+        let player = world
+            .spawn(InputMap::new().with(Action::Down, KeyCode::KeyS).with(
+                Action::Save,
+                InputBinding::chord([KeyCode::ControlLeft, KeyCode::KeyS]),
+            ))
+            .id();
+        let mut update = |world: &mut World, keys: fn(&mut ButtonInput<KeyCode>)| {
+            let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+            input.clear();
+            keys(&mut input);
+            schedule.run(world);
+            world.get::<ActionState<Action>>(player).unwrap().clone()
+        };
+
+        let state = update(&mut world, |keys| keys.press(KeyCode::KeyS));
+        assert!(state.just_pressed(&Action::Down));
+        assert!(!state.pressed(&Action::Save));
+
+        let state = update(&mut world, |keys| keys.press(KeyCode::ControlLeft));
+        assert!(state.just_pressed(&Action::Save));
+        assert!(state.just_released(&Action::Down));
+
+        let state = update(&mut world, |keys| keys.release(KeyCode::ControlLeft));
+        assert!(state.just_released(&Action::Save));
+        assert!(state.just_pressed(&Action::Down));
+    }
+
+    #[cfg(feature = "bevy_reflect")]
+    #[test]
+    fn components_are_reflected() {
+        use bevy_reflect::TypeRegistry;
+        use core::any::TypeId;
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<InputMap<Action>>();
+        registry.register::<ActionState<Action>>();
+        for type_id in [
+            TypeId::of::<InputMap<Action>>(),
+            TypeId::of::<ActionState<Action>>(),
+        ] {
+            assert!(registry
+                .get_type_data::<ReflectComponent>(type_id)
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn gamepads_per_context() {
+        let (mut world, mut schedule) = world();
+        let mut first_gamepad = Gamepad::default();
+        first_gamepad
+            .analog_mut()
+            .set(GamepadAxis::LeftStickX, -0.5);
+        let first_gamepad = world.spawn(first_gamepad).id();
+        let mut second_gamepad = Gamepad::default();
+        second_gamepad.digital_mut().press(GamepadButton::South);
+        let second_gamepad = world.spawn(second_gamepad).id();
+
+        let input_map = InputMap::new()
+            .with(Action::Jump, GamepadButton::South)
+            .with(Action::Move, GamepadAxis::LeftStickX);
+        let first = world
+            .spawn((input_map.clone(), ActionGamepad(first_gamepad)))
+            .id();
+        let second = world
+            .spawn((input_map.clone(), ActionGamepad(second_gamepad)))
+            .id();
+        let any = world.spawn(input_map).id();
+        schedule.run(&mut world);
+
+        let state = |entity| world.get::<ActionState<Action>>(entity).unwrap();
+        assert_eq!(state(first).value(&Action::Move), -0.5);
+        assert!(!state(first).pressed(&Action::Jump));
+        assert_eq!(state(second).value(&Action::Move), 0.0);
+        assert!(state(second).pressed(&Action::Jump));
+        assert_eq!(state(any).value(&Action::Move), -0.5);
+        assert!(state(any).pressed(&Action::Jump));
+    }
+
+    #[test]
+    fn rebinding() {
+        let (mut world, mut schedule) = world();
+        let player = world
+            .spawn(InputMap::new().with(Action::Jump, KeyCode::Space))
+            .id();
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        schedule.run(&mut world);
+        assert!(world
+            .get::<ActionState<Action>>(player)
+            .unwrap()
+            .pressed(&Action::Jump));
+
+        let mut input_map = world.get_mut::<InputMap<Action>>(player).unwrap();
+        input_map.rebind(Action::Jump, &KeyCode::Space.into(), GamepadButton::South);
+        assert_eq!(
+            input_map.bindings(&Action::Jump),
+            &[InputBinding::GamepadButton(GamepadButton::South)]
+        );
+        input_map.clear_action(&Action::Jump);
+        schedule.run(&mut world);
+        assert!(world
+            .get::<ActionState<Action>>(player)
+            .unwrap()
+            .just_released(&Action::Jump));
+    }
+}
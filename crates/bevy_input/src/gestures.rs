@@ -1,9 +1,35 @@
 //! Gestures functionality, from touchscreens and touchpads.
+//!
+//! Gesture events are sent by the platform where it supports them. On other platforms, and for
+//! touchscreens in general, the [`TouchGesturePlugin`] recognizes gestures from the [`Touches`]
+//! and sends the same events. It is only available with the `touch_gestures` feature, which is
+//! not a default feature of `bevy`: the plugin isn't part of the default plugins, and has to be
+//! added by apps that want it.
 
 use bevy_ecs::event::BufferedEvent;
 use bevy_math::Vec2;
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
+#[cfg(feature = "touch_gestures")]
+use {
+    crate::{
+        touch::{touch_screen_input_system, Touches},
+        InputSystems,
+    },
+    alloc::vec::Vec,
+    bevy_app::{App, Plugin, PreUpdate},
+    bevy_ecs::{
+        event::EventWriter,
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
+        system::{Local, Res},
+    },
+    bevy_platform::collections::HashMap,
+    bevy_time::{Real, Time},
+    core::time::Duration,
+};
+#[cfg(all(feature = "touch_gestures", feature = "bevy_reflect"))]
+use {bevy_ecs::reflect::ReflectResource, bevy_reflect::prelude::ReflectDefault};
 
 #[cfg(all(feature = "serialize", feature = "bevy_reflect"))]
 use bevy_reflect::{ReflectDeserialize, ReflectSerialize};
@@ -15,7 +41,8 @@ use bevy_reflect::{ReflectDeserialize, ReflectSerialize};
 ///
 /// ## Platform-specific
 ///
-/// - Only available on **`macOS`** and **`iOS`**.
+/// - Only sent by the platform on **`macOS`** and **`iOS`**. Elsewhere, add the
+///   [`TouchGesturePlugin`] to recognize it from touch input.
 /// - On **`iOS`**, must be enabled first
 #[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(
@@ -37,7 +64,8 @@ pub struct PinchGesture(pub f32);
 ///
 /// ## Platform-specific
 ///
-/// - Only available on **`macOS`** and **`iOS`**.
+/// - Only sent by the platform on **`macOS`** and **`iOS`**. Elsewhere, add the
+///   [`TouchGesturePlugin`] to recognize it from touch input.
 /// - On **`iOS`**, must be enabled first
 #[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(
@@ -56,7 +84,8 @@ pub struct RotationGesture(pub f32);
 ///
 /// ## Platform-specific
 ///
-/// - Only available on **`macOS`** and **`iOS`**.
+/// - Only sent by the platform on **`macOS`** and **`iOS`**. Elsewhere, add the
+///   [`TouchGesturePlugin`] to recognize it from touch input.
 /// - On **`iOS`**, must be enabled first
 #[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(
@@ -75,6 +104,7 @@ pub struct DoubleTapGesture;
 ///
 /// ## Platform-specific
 ///
+/// - On touchscreens, add the [`TouchGesturePlugin`] to recognize it from touch input.
 /// - On **`iOS`**, must be enabled first
 #[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(
@@ -88,3 +118,386 @@ pub struct DoubleTapGesture;
     reflect(Serialize, Deserialize)
 )]
 pub struct PanGesture(pub Vec2);
+
+/// Single tap gesture, at the given position of the window.
+///
+/// ## Platform-specific
+///
+/// - Only recognized from touch input by the [`TouchGesturePlugin`].
+#[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct TapGesture(pub Vec2);
+
+/// Long press gesture, at the given position of the window.
+///
+/// This is sent once the touch has been held long enough, before it is released.
+///
+/// ## Platform-specific
+///
+/// - Only recognized from touch input by the [`TouchGesturePlugin`].
+#[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct LongPressGesture(pub Vec2);
+
+/// Swipe gesture, with its velocity in logical pixels per second.
+///
+/// Like the positions of touches, the velocity has its y axis pointing down.
+///
+/// ## Platform-specific
+///
+/// - Only recognized from touch input by the [`TouchGesturePlugin`].
+#[derive(BufferedEvent, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serialize", feature = "bevy_reflect"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct SwipeGesture(pub Vec2);
+
+/// Recognizes gestures from the [`Touches`], independently of the platform.
+///
+/// The recognized gestures are sent as the same events as the gestures of the platform:
+///
+/// - [`TapGesture`] and [`DoubleTapGesture`], when one finger touches the screen briefly.
+/// - [`LongPressGesture`], when one finger is held without moving.
+/// - [`SwipeGesture`], when one finger is released while moving quickly.
+/// - [`PanGesture`], every frame the fingers move, with the delta of their center.
+/// - [`PinchGesture`] and [`RotationGesture`], every frame two fingers move relative to each
+///   other. The rotation is in degrees, like the one sent by the platform.
+///
+/// Gestures are recognized in [`PreUpdate`], in [`InputSystems`], using the thresholds of the
+/// [`TouchGestureSettings`] resource. This plugin requires the [`Time<Real>`] resource, which is
+/// added by `TimePlugin`.
+///
+/// This plugin is only available with the `touch_gestures` feature.
+#[cfg(feature = "touch_gestures")]
+#[derive(Default)]
+pub struct TouchGesturePlugin;
+
+#[cfg(feature = "touch_gestures")]
+impl Plugin for TouchGesturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchGestureSettings>().add_systems(
+            PreUpdate,
+            touch_gesture_system
+                .after(touch_screen_input_system)
+                .in_set(InputSystems),
+        );
+    }
+}
+
+/// The thresholds used by the [`TouchGesturePlugin`] to recognize gestures.
+///
+/// Distances are in logical pixels.
+#[cfg(feature = "touch_gestures")]
+#[derive(Resource, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Resource, Debug, Default, PartialEq, Clone)
+)]
+pub struct TouchGestureSettings {
+    /// How far a finger can move before its touch stops being a tap or a long press, and starts
+    /// panning.
+    pub tap_max_distance: f32,
+    /// How long a finger can touch the screen for its touch to be a tap.
+    pub tap_max_duration: Duration,
+    /// The longest time between the end of two taps for them to be a double tap.
+    pub double_tap_max_interval: Duration,
+    /// How far apart two taps can be for them to be a double tap.
+    pub double_tap_max_distance: f32,
+    /// How long a finger must be held without moving for its touch to be a long press.
+    pub long_press_duration: Duration,
+    /// How far a finger must move for its touch to be a swipe.
+    pub swipe_min_distance: f32,
+    /// The lowest average speed, in logical pixels per second, for a touch to be a swipe.
+    pub swipe_min_velocity: f32,
+}
+
+#[cfg(feature = "touch_gestures")]
+impl Default for TouchGestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_max_distance: 10.0,
+            tap_max_duration: Duration::from_millis(300),
+            double_tap_max_interval: Duration::from_millis(300),
+            double_tap_max_distance: 40.0,
+            long_press_duration: Duration::from_millis(500),
+            swipe_min_distance: 50.0,
+            swipe_min_velocity: 300.0,
+        }
+    }
+}
+
+/// The progress of the gesture recognized by the [`touch_gesture_system`].
+///
+/// A gesture starts when a finger touches the screen, and ends when no finger does.
+#[cfg(feature = "touch_gestures")]
+#[derive(Default)]
+pub struct TouchGestureState {
+    /// The last known position of every pressed touch.
+    positions: HashMap<u64, Vec2>,
+    started: Duration,
+    start_position: Vec2,
+    max_touches: usize,
+    moved: bool,
+    long_pressed: bool,
+    canceled: bool,
+    /// When and where the last single tap ended.
+    last_tap: Option<(Duration, Vec2)>,
+}
+
+/// Sends gesture events recognized from the [`Touches`].
+#[cfg(feature = "touch_gestures")]
+pub fn touch_gesture_system(
+    settings: Res<TouchGestureSettings>,
+    touches: Res<Touches>,
+    time: Res<Time<Real>>,
+    mut state: Local<TouchGestureState>,
+    mut taps: EventWriter<TapGesture>,
+    mut double_taps: EventWriter<DoubleTapGesture>,
+    mut long_presses: EventWriter<LongPressGesture>,
+    mut swipes: EventWriter<SwipeGesture>,
+    mut pans: EventWriter<PanGesture>,
+    mut pinches: EventWriter<PinchGesture>,
+    mut rotations: EventWriter<RotationGesture>,
+) {
+    let now = time.elapsed();
+
+    for touch in touches.iter_just_pressed() {
+        if state.positions.is_empty() {
+            let last_tap = state.last_tap;
+            *state = TouchGestureState {
+                started: now,
+                start_position: touch.position(),
+                last_tap,
+                ..Default::default()
+            };
+        }
+        state.positions.insert(touch.id(), touch.position());
+    }
+    state.max_touches = state.max_touches.max(state.positions.len());
+
+    // Compare the pressed touches to their last known positions.
+    let pressed: Vec<(Vec2, Vec2)> = touches
+        .iter()
+        .filter_map(|touch| {
+            let previous = *state.positions.get(&touch.id())?;
+            Some((previous, touch.position()))
+        })
+        .collect();
+    if touches
+        .iter()
+        .any(|touch| touch.distance().length() > settings.tap_max_distance)
+    {
+        state.moved = true;
+    }
+    if state.moved && !pressed.is_empty() {
+        let count = pressed.len() as f32;
+        let previous_center = pressed.iter().map(|(previous, _)| *previous).sum::<Vec2>() / count;
+        let center = pressed.iter().map(|(_, current)| *current).sum::<Vec2>() / count;
+        if center != previous_center {
+            pans.write(PanGesture(center - previous_center));
+        }
+
+        if let [(previous_a, a), (previous_b, b)] = pressed[..] {
+            let previous_span = previous_b - previous_a;
+            let span = b - a;
+            if previous_span.length() > 0.0 && span.length() != previous_span.length() {
+                pinches.write(PinchGesture(span.length() / previous_span.length() - 1.0));
+            }
+            // Flip the y axis, which points down, so counterclockwise rotations are positive.
+            let angle =
+                Vec2::new(previous_span.x, -previous_span.y).angle_to(Vec2::new(span.x, -span.y));
+            if angle != 0.0 && angle.is_finite() {
+                rotations.write(RotationGesture(angle.to_degrees()));
+            }
+        }
+    }
+    for touch in touches.iter() {
+        state.positions.insert(touch.id(), touch.position());
+    }
+
+    if !state.moved
+        && !state.long_pressed
+        && state.max_touches == 1
+        && now - state.started >= settings.long_press_duration
+        && let Some(touch) = touches.iter().next()
+    {
+        state.long_pressed = true;
+        long_presses.write(LongPressGesture(touch.position()));
+    }
+
+    for touch in touches.iter_just_canceled() {
+        state.canceled = true;
+        state.positions.remove(&touch.id());
+    }
+    for touch in touches.iter_just_released() {
+        state.positions.remove(&touch.id());
+        if !state.positions.is_empty() || state.canceled || state.max_touches != 1 {
+            continue;
+        }
+
+        let duration = now - state.started;
+        let position = touch.position();
+        if !state.moved && !state.long_pressed && duration <= settings.tap_max_duration {
+            taps.write(TapGesture(position));
+            match state.last_tap {
+                Some((time, last_position))
+                    if now - time <= settings.double_tap_max_interval
+                        && last_position.distance(position) <= settings.double_tap_max_distance =>
+                {
+                    double_taps.write(DoubleTapGesture);
+                    state.last_tap = None;
+                }
+                _ => state.last_tap = Some((now, position)),
+            }
+        } else if state.moved && duration > Duration::ZERO {
+            let distance = position - state.start_position;
+            let velocity = distance / duration.as_secs_f32();
+            if distance.length() >= settings.swipe_min_distance
+                && velocity.length() >= settings.swipe_min_velocity
+            {
+                swipes.write(SwipeGesture(velocity));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "touch_gestures"))]
+mod tests {
+    use super::*;
+    use crate::{
+        touch::{TouchInput, TouchPhase},
+        InputPlugin,
+    };
+    use bevy_ecs::{entity::Entity, event::Events};
+    use bevy_time::{TimePlugin, TimeUpdateStrategy};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, InputPlugin, TouchGesturePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app.update();
+        app
+    }
+
+    fn touch(app: &mut App, id: u64, phase: TouchPhase, position: Vec2) {
+        app.world_mut().write_event(TouchInput {
+            phase,
+            position,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id,
+        });
+    }
+
+    fn count<E: BufferedEvent>(app: &App) -> usize {
+        app.world()
+            .resource::<Events<E>>()
+            .iter_current_update_events()
+            .count()
+    }
+
+    #[test]
+    fn taps_and_long_presses() {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::ZERO);
+        app.update();
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::ZERO);
+        app.update();
+        assert_eq!(count::<TapGesture>(&app), 1);
+        assert_eq!(count::<DoubleTapGesture>(&app), 0);
+
+        touch(&mut app, 1, TouchPhase::Started, Vec2::ONE);
+        touch(&mut app, 1, TouchPhase::Ended, Vec2::ONE);
+        app.update();
+        assert_eq!(count::<TapGesture>(&app), 1);
+        assert_eq!(count::<DoubleTapGesture>(&app), 1);
+
+        touch(&mut app, 2, TouchPhase::Started, Vec2::ZERO);
+        for _ in 0..6 {
+            app.update();
+        }
+        assert_eq!(count::<LongPressGesture>(&app), 1);
+        app.update();
+        assert_eq!(count::<LongPressGesture>(&app), 0);
+        touch(&mut app, 2, TouchPhase::Ended, Vec2::ZERO);
+        app.update();
+        assert_eq!(count::<TapGesture>(&app), 0);
+    }
+
+    #[test]
+    fn swipes_and_pans() {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::ZERO);
+        app.update();
+        touch(&mut app, 0, TouchPhase::Moved, Vec2::new(100.0, 0.0));
+        app.update();
+        let pans = app.world().resource::<Events<PanGesture>>();
+        assert_eq!(
+            pans.iter_current_update_events().next(),
+            Some(&PanGesture(Vec2::new(100.0, 0.0)))
+        );
+
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::new(100.0, 0.0));
+        app.update();
+        let swipes = app.world().resource::<Events<SwipeGesture>>();
+        assert_eq!(
+            swipes.iter_current_update_events().next(),
+            Some(&SwipeGesture(Vec2::new(500.0, 0.0)))
+        );
+        assert_eq!(count::<TapGesture>(&app), 0);
+    }
+
+    #[test]
+    fn pinches_and_rotations() {
+        let mut app = app();
+        touch(&mut app, 0, TouchPhase::Started, Vec2::new(-10.0, 0.0));
+        touch(&mut app, 1, TouchPhase::Started, Vec2::new(10.0, 0.0));
+        app.update();
+        // Spread the fingers apart, and turn them counterclockwise on screen.
+        touch(&mut app, 0, TouchPhase::Moved, Vec2::new(0.0, 20.0));
+        touch(&mut app, 1, TouchPhase::Moved, Vec2::new(0.0, -20.0));
+        app.update();
+
+        let pinch = app.world().resource::<Events<PinchGesture>>();
+        let pinch = pinch.iter_current_update_events().next().unwrap().0;
+        assert!((pinch - 1.0).abs() < 1e-5);
+        let rotation = app.world().resource::<Events<RotationGesture>>();
+        let rotation = rotation.iter_current_update_events().next().unwrap().0;
+        assert!((rotation - 90.0).abs() < 1e-3);
+        assert_eq!(count::<PanGesture>(&app), 0);
+
+        touch(&mut app, 0, TouchPhase::Ended, Vec2::new(0.0, 20.0));
+        touch(&mut app, 1, TouchPhase::Ended, Vec2::new(0.0, -20.0));
+        app.update();
+        assert_eq!(count::<TapGesture>(&app), 0);
+        assert_eq!(count::<SwipeGesture>(&app), 0);
+    }
+}
@@ -17,6 +17,7 @@ extern crate std;
 
 extern crate alloc;
 
+pub mod action;
 mod axis;
 mod button_input;
 /// Common run conditions
@@ -36,6 +37,7 @@ pub use button_input::*;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
+        action::{ActionState, InputActionPlugin, InputBinding, InputMap},
         gamepad::{Gamepad, GamepadAxis, GamepadButton, GamepadSettings},
         keyboard::KeyCode,
         mouse::MouseButton,
@@ -106,6 +108,9 @@ impl Plugin for InputPlugin {
             .add_event::<RotationGesture>()
             .add_event::<DoubleTapGesture>()
             .add_event::<PanGesture>()
+            .add_event::<TapGesture>()
+            .add_event::<LongPressGesture>()
+            .add_event::<SwipeGesture>()
             // gamepad
             .add_event::<GamepadEvent>()
             .add_event::<GamepadConnectionEvent>()
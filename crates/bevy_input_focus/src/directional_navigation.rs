@@ -13,7 +13,13 @@
 //!
 //! Under the hood, the [`DirectionalNavigationMap`] stores a directed graph of focusable entities.
 //! Each entity can have up to 8 neighbors, one for each [`CompassOctant`], balancing flexibility and required precision.
-//! For now, this graph must be built manually, but in the future, it could be generated automatically.
+//!
+//! Edges can be authored by hand with methods like [`add_edge`](DirectionalNavigationMap::add_edge),
+//! or generated from the layout of entities marked with [`AutoDirectionalNavigation`].
+//! Layout crates such as `bevy_ui` describe the position and size of each marked entity as a
+//! [`FocusableArea`], and the neighbor in each direction is picked with a spatial heuristic
+//! controlled by [`AutoNavigationConfig`]. Hand-authored edges always take precedence over generated ones,
+//! and generated edges can be suppressed with [`block_direction`](DirectionalNavigationMap::block_direction).
 
 use bevy_app::prelude::*;
 use bevy_ecs::{
@@ -21,7 +27,8 @@ use bevy_ecs::{
     prelude::*,
     system::SystemParam,
 };
-use bevy_math::CompassOctant;
+use bevy_math::{ops, CompassOctant, Dir2, Vec2};
+use core::f32::consts::FRAC_PI_4;
 use thiserror::Error;
 
 use crate::InputFocus;
@@ -35,10 +42,78 @@ pub struct DirectionalNavigationPlugin;
 
 impl Plugin for DirectionalNavigationPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<DirectionalNavigationMap>();
+        app.init_resource::<DirectionalNavigationMap>()
+            .init_resource::<AutoNavigationConfig>();
     }
 }
 
+/// Marks a focusable entity whose [`DirectionalNavigationMap`] neighbors should be computed
+/// automatically from its layout.
+///
+/// Only entities in the same focus group are connected to each other, where the group of an entity
+/// is its nearest [`TabGroup`](crate::tab_navigation::TabGroup) ancestor.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, Debug, PartialEq, Clone)
+)]
+pub struct AutoDirectionalNavigation;
+
+/// Settings for the heuristic used to generate navigation edges from layout.
+///
+/// Distances are measured between the centers of [`FocusableArea`]s.
+#[derive(Resource, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Resource, Debug, Default, PartialEq, Clone)
+)]
+pub struct AutoNavigationConfig {
+    /// The maximum angle, in radians, between a navigation direction and the direction to a
+    /// candidate neighbor.
+    ///
+    /// Diagonal directions additionally only consider candidates that are offset along both axes.
+    ///
+    /// Defaults to 45 degrees.
+    pub max_angle: f32,
+    /// How much the offset perpendicular to the navigation direction counts against a candidate,
+    /// relative to the distance along it.
+    ///
+    /// Larger values favor neighbors that are lined up with the current focus over closer ones.
+    /// For the four cardinal directions, the offset is measured between the edges of the areas,
+    /// so entities in the same row or column are never penalized.
+    ///
+    /// Defaults to 2.
+    pub alignment_weight: f32,
+    /// Candidates further away than this are never connected.
+    pub max_distance: Option<f32>,
+}
+
+impl Default for AutoNavigationConfig {
+    fn default() -> Self {
+        Self {
+            max_angle: FRAC_PI_4,
+            alignment_weight: 2.,
+            max_distance: None,
+        }
+    }
+}
+
+/// The bounds of a focusable entity, used to generate navigation edges.
+///
+/// Positions use a y-up coordinate system, so that [`CompassOctant::North`] points towards
+/// positive y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusableArea {
+    /// The focusable entity.
+    pub entity: Entity,
+    /// The center of the entity.
+    pub position: Vec2,
+    /// The size of the entity.
+    pub size: Vec2,
+}
+
 /// The up-to-eight neighbors of a focusable entity, one for each [`CompassOctant`].
 #[derive(Default, Debug, Clone, PartialEq)]
 #[cfg_attr(
@@ -85,7 +160,10 @@ impl NavNeighbors {
 ///   although looping around the edges of the screen is also acceptable.
 /// - **Not self-connected**: An entity should not be a neighbor of itself; use [`None`] instead.
 ///
-/// For now, this graph must be built manually, and the developer is responsible for ensuring that it meets the above criteria.
+/// Hand-authored edges are the developer's responsibility. Edges generated from layout with
+/// [`add_automatic_edges`](Self::add_automatic_edges) are stored separately, see
+/// [`get_automatic_neighbors`](Self::get_automatic_neighbors), and are only used in directions
+/// where no edge was authored and that were not [blocked](Self::block_direction).
 #[derive(Resource, Debug, Default, Clone, PartialEq)]
 #[cfg_attr(
     feature = "bevy_reflect",
@@ -98,6 +176,10 @@ pub struct DirectionalNavigationMap {
     /// Pass in the current focus as a key, and get back a collection of up to 8 neighbors,
     /// each keyed by a [`CompassOctant`].
     pub neighbors: EntityHashMap<NavNeighbors>,
+    /// Edges generated from the layout of entities, used as a fallback for [`neighbors`](Self::neighbors).
+    automatic_neighbors: EntityHashMap<NavNeighbors>,
+    /// Directions in which no automatic edge is followed, indexed by [`CompassOctant::to_index`].
+    blocked: EntityHashMap<[bool; 8]>,
 }
 
 impl DirectionalNavigationMap {
@@ -111,8 +193,14 @@ impl DirectionalNavigationMap {
     /// If you are removing multiple entities, consider using [`remove_multiple`](Self::remove_multiple) instead.
     pub fn remove(&mut self, entity: Entity) {
         self.neighbors.remove(&entity);
-
-        for node in self.neighbors.values_mut() {
+        self.automatic_neighbors.remove(&entity);
+        self.blocked.remove(&entity);
+
+        for node in self
+            .neighbors
+            .values_mut()
+            .chain(self.automatic_neighbors.values_mut())
+        {
             for neighbor in node.neighbors.iter_mut() {
                 if *neighbor == Some(entity) {
                     *neighbor = None;
@@ -131,9 +219,15 @@ impl DirectionalNavigationMap {
     pub fn remove_multiple(&mut self, entities: EntityHashSet) {
         for entity in &entities {
             self.neighbors.remove(entity);
+            self.automatic_neighbors.remove(entity);
+            self.blocked.remove(entity);
         }
 
-        for node in self.neighbors.values_mut() {
+        for node in self
+            .neighbors
+            .values_mut()
+            .chain(self.automatic_neighbors.values_mut())
+        {
             for neighbor in node.neighbors.iter_mut() {
                 if let Some(entity) = *neighbor {
                     if entities.contains(&entity) {
@@ -147,6 +241,33 @@ impl DirectionalNavigationMap {
     /// Completely clears the navigation map, removing all entities and connections.
     pub fn clear(&mut self) {
         self.neighbors.clear();
+        self.automatic_neighbors.clear();
+        self.blocked.clear();
+    }
+
+    /// Removes all edges generated from layout, keeping the hand-authored ones.
+    pub fn clear_automatic_edges(&mut self) {
+        self.automatic_neighbors.clear();
+    }
+
+    /// Generates edges between the given areas based on their positions, as configured by `config`.
+    ///
+    /// For each area and each [`CompassOctant`], the neighbor is the area that is within
+    /// [`max_angle`](AutoNavigationConfig::max_angle) of that direction and has the lowest
+    /// score, where the score is the distance along the direction plus the perpendicular offset
+    /// scaled by [`alignment_weight`](AutoNavigationConfig::alignment_weight).
+    ///
+    /// Only the given areas are considered as neighbors of each other, so call this once per
+    /// focus group. Existing automatic edges of these areas are overwritten.
+    pub fn add_automatic_edges(&mut self, areas: &[FocusableArea], config: &AutoNavigationConfig) {
+        for area in areas {
+            let mut neighbors = NavNeighbors::EMPTY;
+            for index in 0..8 {
+                let octant = CompassOctant::from_index(index).unwrap();
+                neighbors.neighbors[index] = best_neighbor(area, areas, octant, config);
+            }
+            self.automatic_neighbors.insert(area.entity, neighbors);
+        }
     }
 
     /// Adds an edge between two entities in the navigation map.
@@ -191,20 +312,112 @@ impl DirectionalNavigationMap {
         }
     }
 
+    /// Prevents navigation from an entity in the given direction through automatic edges.
+    ///
+    /// Hand-authored edges in that direction are still followed.
+    pub fn block_direction(&mut self, entity: Entity, direction: CompassOctant) {
+        self.blocked.entry(entity).or_default()[direction.to_index()] = true;
+    }
+
+    /// Allows navigation through automatic edges again after [`block_direction`](Self::block_direction).
+    pub fn unblock_direction(&mut self, entity: Entity, direction: CompassOctant) {
+        if let Some(blocked) = self.blocked.get_mut(&entity) {
+            blocked[direction.to_index()] = false;
+            if !blocked.contains(&true) {
+                self.blocked.remove(&entity);
+            }
+        }
+    }
+
+    /// Returns `true` if automatic edges are not followed from an entity in the given direction.
+    ///
+    /// See [`block_direction`](Self::block_direction).
+    pub fn is_direction_blocked(&self, entity: Entity, direction: CompassOctant) -> bool {
+        self.blocked
+            .get(&entity)
+            .is_some_and(|blocked| blocked[direction.to_index()])
+    }
+
     /// Gets the entity in a given direction from the current focus, if any.
+    ///
+    /// Hand-authored edges take precedence over automatic ones,
+    /// which are skipped in [blocked](Self::block_direction) directions.
     pub fn get_neighbor(&self, focus: Entity, octant: CompassOctant) -> Option<Entity> {
         self.neighbors
             .get(&focus)
             .and_then(|neighbors| neighbors.get(octant))
+            .or_else(|| {
+                if self.is_direction_blocked(focus, octant) {
+                    return None;
+                }
+                self.automatic_neighbors
+                    .get(&focus)
+                    .and_then(|neighbors| neighbors.get(octant))
+            })
     }
 
-    /// Looks up the neighbors of a given entity.
+    /// Looks up the hand-authored neighbors of a given entity.
     ///
     /// If the entity is not in the map, [`None`] will be returned.
     /// Note that the set of neighbors is not guaranteed to be non-empty though!
+    /// Automatically generated edges are not included; use [`get_neighbor`](Self::get_neighbor)
+    /// to take them into account.
     pub fn get_neighbors(&self, entity: Entity) -> Option<&NavNeighbors> {
         self.neighbors.get(&entity)
     }
+
+    /// Looks up the neighbors of a given entity that were generated from layout with
+    /// [`add_automatic_edges`](Self::add_automatic_edges).
+    ///
+    /// If the entity has no automatic edges, [`None`] will be returned.
+    pub fn get_automatic_neighbors(&self, entity: Entity) -> Option<&NavNeighbors> {
+        self.automatic_neighbors.get(&entity)
+    }
+}
+
+/// Picks the best neighbor of `from` in the given direction, see [`DirectionalNavigationMap::add_automatic_edges`].
+fn best_neighbor(
+    from: &FocusableArea,
+    candidates: &[FocusableArea],
+    octant: CompassOctant,
+    config: &AutoNavigationConfig,
+) -> Option<Entity> {
+    let direction = Vec2::from(Dir2::from(octant));
+    let min_cos = ops::cos(config.max_angle);
+    let cardinal = matches!(
+        octant,
+        CompassOctant::North | CompassOctant::East | CompassOctant::South | CompassOctant::West
+    );
+
+    candidates
+        .iter()
+        .filter(|candidate| candidate.entity != from.entity)
+        .filter_map(|candidate| {
+            let delta = candidate.position - from.position;
+            let along = delta.dot(direction);
+            let distance = delta.length();
+            // Diagonal neighbors must be strictly offset along both axes,
+            // so that entities in the same row or column are left to the cardinal directions.
+            let off_axis =
+                !cardinal && (delta.x * direction.x <= 0. || delta.y * direction.y <= 0.);
+            if along <= 0.
+                || off_axis
+                || along < distance * min_cos
+                || config.max_distance.is_some_and(|max| distance > max)
+            {
+                return None;
+            }
+
+            let mut across = ops::abs(delta.perp_dot(direction));
+            if cardinal {
+                let extent = 0.5 * (from.size + candidate.size).dot(direction.perp().abs());
+                across = (across - extent).max(0.);
+            }
+
+            Some((candidate.entity, along + across * config.alignment_weight))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
 }
 
 /// A system parameter for navigating between focusable entities in a directional way.
@@ -263,6 +476,8 @@ pub enum DirectionalNavigationError {
 mod tests {
     use bevy_ecs::system::RunSystemOnce;
 
+    use alloc::vec::Vec;
+
     use super::*;
 
     #[test]
@@ -426,4 +641,161 @@ mod tests {
         world.run_system_once(navigate_east).unwrap();
         assert_eq!(world.resource::<InputFocus>().get(), Some(a));
     }
+
+    fn grid(world: &mut World) -> [[Entity; 3]; 3] {
+        core::array::from_fn(|_| core::array::from_fn(|_| world.spawn_empty().id()))
+    }
+
+    fn grid_areas(grid: &[[Entity; 3]; 3]) -> Vec<FocusableArea> {
+        let mut areas = Vec::new();
+        for (row, entities) in grid.iter().enumerate() {
+            for (column, entity) in entities.iter().enumerate() {
+                areas.push(FocusableArea {
+                    entity: *entity,
+                    // Rows go downwards, towards negative y.
+                    position: Vec2::new(column as f32 * 100., row as f32 * -50.),
+                    size: Vec2::new(80., 30.),
+                });
+            }
+        }
+        areas
+    }
+
+    #[test]
+    fn automatic_edges_follow_layout() {
+        let mut world = World::new();
+        let g = grid(&mut world);
+
+        let mut map = DirectionalNavigationMap::default();
+        map.add_automatic_edges(&grid_areas(&g), &AutoNavigationConfig::default());
+
+        let center = g[1][1];
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::North),
+            Some(g[0][1])
+        );
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::NorthEast),
+            Some(g[0][2])
+        );
+        assert_eq!(map.get_neighbor(center, CompassOctant::East), Some(g[1][2]));
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::SouthEast),
+            Some(g[2][2])
+        );
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::South),
+            Some(g[2][1])
+        );
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::SouthWest),
+            Some(g[2][0])
+        );
+        assert_eq!(map.get_neighbor(center, CompassOctant::West), Some(g[1][0]));
+        assert_eq!(
+            map.get_neighbor(center, CompassOctant::NorthWest),
+            Some(g[0][0])
+        );
+
+        // Edges of the grid have no neighbors beyond them.
+        assert_eq!(map.get_neighbor(g[0][0], CompassOctant::North), None);
+        assert_eq!(map.get_neighbor(g[0][0], CompassOctant::West), None);
+        // The closest entity in the same row wins over further ones.
+        assert_eq!(
+            map.get_neighbor(g[0][0], CompassOctant::East),
+            Some(g[0][1])
+        );
+        // Hand-authored edges are left untouched.
+        assert_eq!(map.get_neighbors(center), None);
+    }
+
+    #[test]
+    fn automatic_edges_respect_max_distance() {
+        let mut world = World::new();
+        let g = grid(&mut world);
+
+        let config = AutoNavigationConfig {
+            max_distance: Some(75.),
+            ..Default::default()
+        };
+        let mut map = DirectionalNavigationMap::default();
+        map.add_automatic_edges(&grid_areas(&g), &config);
+
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::North),
+            Some(g[0][1])
+        );
+        assert_eq!(map.get_neighbor(g[1][1], CompassOctant::East), None);
+    }
+
+    #[test]
+    fn manual_edges_override_automatic_edges() {
+        let mut world = World::new();
+        let g = grid(&mut world);
+
+        let mut map = DirectionalNavigationMap::default();
+        map.add_automatic_edges(&grid_areas(&g), &AutoNavigationConfig::default());
+        map.add_edge(g[1][1], g[2][2], CompassOctant::East);
+
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::East),
+            Some(g[2][2])
+        );
+        // Directions without a manual edge still fall back to the automatic ones.
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::West),
+            Some(g[1][0])
+        );
+
+        map.clear_automatic_edges();
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::East),
+            Some(g[2][2])
+        );
+        assert_eq!(map.get_neighbor(g[1][1], CompassOctant::West), None);
+    }
+
+    #[test]
+    fn blocked_directions_skip_automatic_edges() {
+        let mut world = World::new();
+        let g = grid(&mut world);
+
+        let mut map = DirectionalNavigationMap::default();
+        map.add_automatic_edges(&grid_areas(&g), &AutoNavigationConfig::default());
+        map.block_direction(g[1][1], CompassOctant::East);
+
+        assert_eq!(map.get_neighbor(g[1][1], CompassOctant::East), None);
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::West),
+            Some(g[1][0])
+        );
+
+        // Hand-authored edges are still followed in blocked directions.
+        map.add_edge(g[1][1], g[2][2], CompassOctant::East);
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::East),
+            Some(g[2][2])
+        );
+
+        map.neighbors.clear();
+        map.unblock_direction(g[1][1], CompassOctant::East);
+        assert_eq!(
+            map.get_neighbor(g[1][1], CompassOctant::East),
+            Some(g[1][2])
+        );
+        assert!(map.blocked.is_empty());
+    }
+
+    #[test]
+    fn removing_entity_clears_automatic_edges() {
+        let mut world = World::new();
+        let g = grid(&mut world);
+
+        let mut map = DirectionalNavigationMap::default();
+        map.add_automatic_edges(&grid_areas(&g), &AutoNavigationConfig::default());
+        map.remove(g[1][2]);
+
+        assert_eq!(map.get_neighbor(g[1][1], CompassOctant::East), None);
+        assert_eq!(map.get_neighbor(g[1][2], CompassOctant::West), None);
+    }
 }
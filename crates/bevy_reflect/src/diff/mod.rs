@@ -0,0 +1,563 @@
+//! Structural diffing of reflected values.
+//!
+//! [`diff`] compares two values and returns a [`Patch`] containing only what changed:
+//! modified fields, list insertions and removals, map and set entries, and enum variant switches.
+//! Applying the patch to the old value with [`Patch::apply`] turns it into the new one.
+//!
+//! ```
+//! # use bevy_reflect::{diff::diff, Reflect};
+//! #[derive(Reflect, Debug, PartialEq)]
+//! struct Player {
+//!     name: String,
+//!     health: u32,
+//!     inventory: Vec<String>,
+//! }
+//!
+//! let old = Player {
+//!     name: "Alice".to_string(),
+//!     health: 100,
+//!     inventory: vec!["sword".to_string()],
+//! };
+//! let new = Player {
+//!     name: "Alice".to_string(),
+//!     health: 80,
+//!     inventory: vec!["sword".to_string(), "shield".to_string()],
+//! };
+//!
+//! // Only `health` and the new inventory item are part of the patch.
+//! let patch = diff(&old, &new).unwrap();
+//!
+//! let mut value = old;
+//! patch.apply(&mut value).unwrap();
+//! assert_eq!(value, new);
+//! ```
+//!
+//! Diffing the values the other way around creates a patch that reverts the change, which can
+//! be used to implement undo and redo. Patches can be serialized with [`PatchSerializer`] and
+//! deserialized with [`PatchDeserializer`], for example to send only the changed fields of a
+//! value over the network.
+
+mod patch;
+mod serde;
+
+pub use self::serde::*;
+pub use patch::*;
+
+use alloc::{borrow::Cow, boxed::Box, vec, vec::Vec};
+
+use crate::{Access, Enum, List, Map, PartialReflect, ReflectRef, Set, VariantType};
+
+/// Computes the [`Patch`] that turns `old` into `new`.
+///
+/// Returns [`None`] if the values are equal.
+///
+/// Values are compared structurally, so `old` and `new` may be of a concrete or a dynamic type.
+/// Values that can't be compared field by field, such as [opaque](crate::ReflectKind::Opaque)
+/// values or values of different types, are replaced as a whole if they are not equal according
+/// to [`PartialReflect::reflect_partial_eq`].
+///
+/// Lists are compared by finding the longest common subsequence of the elements between their
+/// common prefix and suffix, which takes time and memory proportional to the product of the
+/// lengths of these ranges. Lists whose changed ranges are too long for this are replaced as a
+/// whole.
+///
+/// # Panics
+///
+/// Panics if a value that has to be stored in the patch can't be converted to a dynamic value,
+/// see [`PartialReflect::to_dynamic`].
+pub fn diff(old: &dyn PartialReflect, new: &dyn PartialReflect) -> Option<Patch> {
+    let same_type = match (
+        old.get_represented_type_info(),
+        new.get_represented_type_info(),
+    ) {
+        (Some(old_info), Some(new_info)) => old_info.type_id() == new_info.type_id(),
+        _ => old.reflect_kind() == new.reflect_kind(),
+    };
+    if !same_type {
+        return replace(old, new);
+    }
+
+    match (old.reflect_ref(), new.reflect_ref()) {
+        (ReflectRef::Struct(old_struct), ReflectRef::Struct(new_struct)) => {
+            if old_struct.field_len() != new_struct.field_len() {
+                return replace(old, new);
+            }
+            let mut fields = Vec::new();
+            for (index, new_field) in new_struct.iter_fields().enumerate() {
+                let Some(name) = new_struct.name_at(index) else {
+                    return replace(old, new);
+                };
+                let Some(old_field) = old_struct.field(name) else {
+                    return replace(old, new);
+                };
+                if let Some(patch) = diff(old_field, new_field) {
+                    fields.push((Access::Field(Cow::Owned(name.into())), patch));
+                }
+            }
+            fields_patch(fields)
+        }
+        (ReflectRef::TupleStruct(old_tuple), ReflectRef::TupleStruct(new_tuple)) => {
+            if old_tuple.field_len() != new_tuple.field_len() {
+                return replace(old, new);
+            }
+            diff_fields(
+                old_tuple.iter_fields().zip(new_tuple.iter_fields()),
+                Access::TupleIndex,
+            )
+        }
+        (ReflectRef::Tuple(old_tuple), ReflectRef::Tuple(new_tuple)) => {
+            if old_tuple.field_len() != new_tuple.field_len() {
+                return replace(old, new);
+            }
+            diff_fields(
+                old_tuple.iter_fields().zip(new_tuple.iter_fields()),
+                Access::TupleIndex,
+            )
+        }
+        (ReflectRef::Array(old_array), ReflectRef::Array(new_array)) => {
+            if old_array.len() != new_array.len() {
+                return replace(old, new);
+            }
+            diff_fields(old_array.iter().zip(new_array.iter()), Access::ListIndex)
+        }
+        (ReflectRef::List(old_list), ReflectRef::List(new_list)) => diff_list(old_list, new_list),
+        (ReflectRef::Map(old_map), ReflectRef::Map(new_map)) => diff_map(old_map, new_map),
+        (ReflectRef::Set(old_set), ReflectRef::Set(new_set)) => diff_set(old_set, new_set),
+        (ReflectRef::Enum(old_enum), ReflectRef::Enum(new_enum)) => diff_enum(old_enum, new_enum),
+        _ => replace(old, new),
+    }
+}
+
+/// Returns a [`Patch::Replace`] with `new`, unless `old` is known to be equal to it.
+fn replace(old: &dyn PartialReflect, new: &dyn PartialReflect) -> Option<Patch> {
+    match old.reflect_partial_eq(new) {
+        Some(true) => None,
+        _ => Some(Patch::Replace(new.to_dynamic())),
+    }
+}
+
+/// Returns `true` if `a` and `b` are known to be equal.
+fn is_equal(a: &dyn PartialReflect, b: &dyn PartialReflect) -> bool {
+    a.reflect_partial_eq(b) == Some(true)
+}
+
+fn fields_patch(fields: Vec<(Access<'static>, Patch)>) -> Option<Patch> {
+    (!fields.is_empty()).then_some(Patch::Fields(fields))
+}
+
+/// Diffs fields that are accessed by index.
+fn diff_fields<'a>(
+    fields: impl Iterator<Item = (&'a dyn PartialReflect, &'a dyn PartialReflect)>,
+    access: fn(usize) -> Access<'static>,
+) -> Option<Patch> {
+    fields_patch(
+        fields
+            .enumerate()
+            .filter_map(|(index, (old, new))| Some((access(index), diff(old, new)?)))
+            .collect(),
+    )
+}
+
+fn diff_enum(old: &dyn Enum, new: &dyn Enum) -> Option<Patch> {
+    if old.variant_name() != new.variant_name() || old.field_len() != new.field_len() {
+        return Some(Patch::Variant(Box::new(new.to_dynamic_enum())));
+    }
+    match new.variant_type() {
+        VariantType::Struct => {
+            let mut fields = Vec::new();
+            for index in 0..new.field_len() {
+                let (Some(name), Some(new_field)) = (new.name_at(index), new.field_at(index))
+                else {
+                    continue;
+                };
+                let Some(old_field) = old.field(name) else {
+                    return Some(Patch::Variant(Box::new(new.to_dynamic_enum())));
+                };
+                if let Some(patch) = diff(old_field, new_field) {
+                    fields.push((Access::Field(Cow::Owned(name.into())), patch));
+                }
+            }
+            fields_patch(fields)
+        }
+        VariantType::Tuple => diff_fields(
+            old.iter_fields()
+                .map(|field| field.value())
+                .zip(new.iter_fields().map(|field| field.value())),
+            Access::TupleIndex,
+        ),
+        VariantType::Unit => None,
+    }
+}
+
+/// The largest table of longest common subsequence lengths that [`diff_list`] computes, in
+/// entries. Lists that would need a larger one are replaced as a whole.
+const MAX_LCS_TABLE_SIZE: usize = 1 << 20;
+
+fn diff_list(old_list: &dyn List, new_list: &dyn List) -> Option<Patch> {
+    let old: Vec<_> = old_list.iter().collect();
+    let new: Vec<_> = new_list.iter().collect();
+
+    // Only the elements between the common prefix and suffix need to be compared.
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|&(&old, &new)| is_equal(old, new))
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(&old, &new)| is_equal(old, new))
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_LCS_TABLE_SIZE {
+        return Some(Patch::Replace(new_list.to_dynamic()));
+    }
+
+    // `lcs[i][j]` is the length of the longest common subsequence of `old[i..]` and `new[j..]`.
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if is_equal(old[i], new[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    // The index in the list after the edits so far have been applied.
+    let mut index = prefix;
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && is_equal(old[i], new[j]) {
+            i += 1;
+            j += 1;
+            index += 1;
+            continue;
+        }
+
+        // Collect the elements up to the next common element.
+        let (start_i, start_j) = (i, j);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && is_equal(old[i], new[j]) {
+                break;
+            }
+            if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                j += 1;
+            } else {
+                i += 1;
+            }
+        }
+
+        // Elements that were replaced are patched, the rest is removed or inserted.
+        let removed = &old[start_i..i];
+        let inserted = &new[start_j..j];
+        for (&old, &new) in removed.iter().zip(inserted) {
+            if let Some(patch) = diff(old, new) {
+                edits.push(ListEdit::Patch { index, patch });
+            }
+            index += 1;
+        }
+        for _ in inserted.len()..removed.len() {
+            edits.push(ListEdit::Remove { index });
+        }
+        for &value in inserted.iter().skip(removed.len()) {
+            edits.push(ListEdit::Insert {
+                index,
+                value: value.to_dynamic(),
+            });
+            index += 1;
+        }
+    }
+
+    (!edits.is_empty()).then_some(Patch::List(edits))
+}
+
+fn diff_map(old: &dyn Map, new: &dyn Map) -> Option<Patch> {
+    let mut edits = Vec::new();
+    for (key, old_value) in old.iter() {
+        match new.get(key) {
+            None => edits.push(MapEdit::Remove {
+                key: key.to_dynamic(),
+            }),
+            Some(new_value) => {
+                if let Some(patch) = diff(old_value, new_value) {
+                    edits.push(MapEdit::Patch {
+                        key: key.to_dynamic(),
+                        patch,
+                    });
+                }
+            }
+        }
+    }
+    for (key, value) in new.iter() {
+        if old.get(key).is_none() {
+            edits.push(MapEdit::Insert {
+                key: key.to_dynamic(),
+                value: value.to_dynamic(),
+            });
+        }
+    }
+    (!edits.is_empty()).then_some(Patch::Map(edits))
+}
+
+fn diff_set(old: &dyn Set, new: &dyn Set) -> Option<Patch> {
+    let removed = old
+        .iter()
+        .filter(|value| !new.contains(*value))
+        .map(|value| SetEdit::Remove(value.to_dynamic()));
+    let inserted = new
+        .iter()
+        .filter(|value| !old.contains(*value))
+        .map(|value| SetEdit::Insert(value.to_dynamic()));
+    let edits: Vec<_> = removed.chain(inserted).collect();
+    (!edits.is_empty()).then_some(Patch::Set(edits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Reflect, TypeRegistry};
+    use alloc::{
+        string::{String, ToString},
+        vec,
+    };
+    use bevy_platform::collections::{HashMap, HashSet};
+
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    enum Shape {
+        Circle { radius: f32 },
+        Rect(f32, f32),
+        Empty,
+    }
+
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    struct Scene {
+        name: String,
+        shapes: Vec<Shape>,
+        tags: HashSet<String>,
+        scores: HashMap<String, u32>,
+        position: (f32, f32),
+        grid: [u8; 3],
+    }
+
+    fn scene() -> Scene {
+        Scene {
+            name: "scene".to_string(),
+            shapes: vec![
+                Shape::Circle { radius: 1.0 },
+                Shape::Rect(1.0, 2.0),
+                Shape::Empty,
+            ],
+            tags: ["a".to_string(), "b".to_string()].into_iter().collect(),
+            scores: [("alice".to_string(), 1), ("bob".to_string(), 2)]
+                .into_iter()
+                .collect(),
+            position: (0.0, 0.0),
+            grid: [0, 1, 2],
+        }
+    }
+
+    fn assert_roundtrip(old: &Scene, new: &Scene) {
+        let patch = diff(old, new).unwrap();
+        let mut value = old.clone();
+        patch.apply(&mut value).unwrap();
+        assert_eq!(&value, new);
+    }
+
+    #[test]
+    fn equal_values_have_no_patch() {
+        assert!(diff(&scene(), &scene()).is_none());
+    }
+
+    #[test]
+    fn only_changed_fields_are_patched() {
+        let old = scene();
+        let mut new = scene();
+        new.position.1 = 5.0;
+        new.grid[2] = 7;
+
+        let Some(Patch::Fields(fields)) = diff(&old, &new) else {
+            panic!("expected a field patch");
+        };
+        let accessed: Vec<_> = fields.iter().map(|(access, _)| access.clone()).collect();
+        assert_eq!(
+            accessed,
+            [
+                Access::Field("position".into()),
+                Access::Field("grid".into())
+            ]
+        );
+        assert_roundtrip(&old, &new);
+    }
+
+    #[test]
+    fn list_insertions_and_removals() {
+        let old = scene();
+        let mut new = scene();
+        new.shapes.remove(0);
+        new.shapes.insert(1, Shape::Rect(3.0, 4.0));
+        new.shapes.push(Shape::Circle { radius: 2.0 });
+
+        let Some(Patch::Fields(fields)) = diff(&old, &new) else {
+            panic!("expected a field patch");
+        };
+        let Patch::List(edits) = &fields[0].1 else {
+            panic!("expected a list patch");
+        };
+        assert!(matches!(edits[0], ListEdit::Remove { index: 0 }));
+        assert_eq!(edits.len(), 3);
+        assert_roundtrip(&old, &new);
+
+        assert_roundtrip(&new, &old);
+    }
+
+    #[test]
+    fn enum_variants_and_fields() {
+        let old = scene();
+        let mut new = scene();
+        new.shapes[0] = Shape::Circle { radius: 5.0 };
+        new.shapes[2] = Shape::Rect(1.0, 1.0);
+
+        let Some(Patch::Fields(fields)) = diff(&old, &new) else {
+            panic!("expected a field patch");
+        };
+        let Patch::List(edits) = &fields[0].1 else {
+            panic!("expected a list patch");
+        };
+        assert!(matches!(
+            edits[0],
+            ListEdit::Patch {
+                index: 0,
+                patch: Patch::Fields(_)
+            }
+        ));
+        assert!(matches!(
+            edits[1],
+            ListEdit::Patch {
+                index: 2,
+                patch: Patch::Variant(_)
+            }
+        ));
+        assert_roundtrip(&old, &new);
+    }
+
+    #[test]
+    fn long_lists_only_compare_changed_ranges() {
+        let old: Vec<u32> = (0..10_000).collect();
+        let mut new = old.clone();
+        new[5_000] = 0;
+        new.insert(5_100, 1);
+
+        let Some(Patch::List(edits)) = diff(&old, &new) else {
+            panic!("expected a list patch");
+        };
+        assert!(matches!(edits[0], ListEdit::Patch { index: 5_000, .. }));
+        assert!(matches!(edits[1], ListEdit::Insert { index: 5_100, .. }));
+        assert_eq!(edits.len(), 2);
+
+        let mut value = old.clone();
+        Patch::List(edits).apply(&mut value).unwrap();
+        assert_eq!(value, new);
+
+        // Lists that changed too much to be compared are replaced.
+        let reversed: Vec<u32> = (0..10_000).rev().collect();
+        let patch = diff(&old, &reversed).unwrap();
+        assert!(matches!(patch, Patch::Replace(_)));
+        let mut value = old;
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value, reversed);
+    }
+
+    #[test]
+    fn replacing_a_longer_list_shortens_it() {
+        let patch = diff(&(0..2_000).collect::<Vec<u32>>(), &vec![7_u32; 2_000]).unwrap();
+        assert!(matches!(patch, Patch::Replace(_)));
+        let mut value: Vec<u32> = (0..3_000).collect();
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value, vec![7_u32; 2_000]);
+
+        #[derive(Reflect, Clone, Debug, PartialEq)]
+        enum Slot {
+            Empty,
+            Items(Vec<u32>),
+        }
+
+        let patch = diff(&Slot::Empty, &Slot::Items(vec![1])).unwrap();
+        assert!(matches!(patch, Patch::Variant(_)));
+        let mut value = Slot::Items(vec![5, 6, 7]);
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value, Slot::Items(vec![1]));
+    }
+
+    #[test]
+    fn maps_and_sets() {
+        let old = scene();
+        let mut new = scene();
+        new.tags.remove("a");
+        new.tags.insert("c".to_string());
+        new.scores.remove("alice");
+        new.scores.insert("bob".to_string(), 3);
+        new.scores.insert("carol".to_string(), 4);
+        assert_roundtrip(&old, &new);
+    }
+
+    #[test]
+    fn apply_to_dynamic_value() {
+        let old = scene();
+        let mut new = scene();
+        new.name = "renamed".to_string();
+
+        let patch = diff(&old, &new).unwrap();
+        let mut value = old.to_dynamic();
+        patch.apply(value.as_mut()).unwrap();
+        assert!(value.reflect_partial_eq(&new).unwrap());
+    }
+
+    #[test]
+    fn apply_to_mismatched_value() {
+        let patch = diff(&vec![1_u32], &vec![1_u32, 2]).unwrap();
+        assert!(matches!(
+            patch.apply(&mut 1_u32),
+            Err(PatchError::MismatchedKinds { .. })
+        ));
+
+        let mut scene = scene();
+        let patch = diff(&(1_u32, 2_u32), &(1_u32, 3_u32)).unwrap();
+        assert!(matches!(
+            patch.apply(&mut scene),
+            Err(PatchError::Access(_))
+        ));
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let old = scene();
+        let mut new = scene();
+        new.name = "renamed".to_string();
+        new.shapes.insert(0, Shape::Empty);
+        new.shapes[2] = Shape::Rect(1.0, 3.0);
+        new.tags.insert("c".to_string());
+        new.scores.insert("bob".to_string(), 3);
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Scene>();
+        let patch = diff(&old, &new).unwrap();
+
+        let serializer = PatchSerializer::new(&patch, &registry);
+        let json = serde_json::to_string(&serializer).unwrap();
+
+        let deserializer = PatchDeserializer::new(&registry);
+        let mut json_deserializer = serde_json::Deserializer::from_str(&json);
+        let deserialized =
+            ::serde::de::DeserializeSeed::deserialize(deserializer, &mut json_deserializer)
+                .unwrap();
+
+        let mut value = old.clone();
+        deserialized.apply(&mut value).unwrap();
+        assert_eq!(value, new);
+    }
+}
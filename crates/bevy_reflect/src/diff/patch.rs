@@ -0,0 +1,289 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+
+use thiserror::Error;
+
+use crate::{Access, AccessError, ApplyError, PartialReflect, ReflectKind, ReflectMut, ReflectRef};
+
+/// A set of changes that turns one reflected value into another.
+///
+/// Patches are created with [`diff`](super::diff) and applied with [`Patch::apply`].
+/// They only contain the parts of a value that changed, and can be serialized with
+/// [`PatchSerializer`](super::PatchSerializer).
+#[derive(Debug)]
+pub enum Patch {
+    /// Replaces the whole value.
+    ///
+    /// Used for [opaque](ReflectKind::Opaque) values, when the values have different types or shapes,
+    /// and for lists that changed too much to be compared element by element.
+    Replace(Box<dyn PartialReflect>),
+    /// Patches individual fields of a struct, tuple struct, tuple or array,
+    /// or of the fields of an enum whose variant did not change.
+    Fields(Vec<(Access<'static>, Patch)>),
+    /// Edits the elements of a list, in order.
+    List(Vec<ListEdit>),
+    /// Edits the entries of a map.
+    Map(Vec<MapEdit>),
+    /// Edits the values of a set.
+    Set(Vec<SetEdit>),
+    /// Switches an enum to another variant, given as a value of the enum.
+    Variant(Box<dyn PartialReflect>),
+}
+
+/// A single edit of a [`Patch::List`].
+///
+/// Indices refer to the list after all previous edits of the patch have been applied.
+#[derive(Debug)]
+pub enum ListEdit {
+    /// Inserts `value` at `index`.
+    Insert {
+        /// The index to insert at.
+        index: usize,
+        /// The inserted value.
+        value: Box<dyn PartialReflect>,
+    },
+    /// Removes the element at `index`.
+    Remove {
+        /// The index of the removed element.
+        index: usize,
+    },
+    /// Patches the element at `index`.
+    Patch {
+        /// The index of the patched element.
+        index: usize,
+        /// The patch applied to the element.
+        patch: Patch,
+    },
+}
+
+/// A single edit of a [`Patch::Map`].
+#[derive(Debug)]
+pub enum MapEdit {
+    /// Inserts `value` at `key`, replacing the previous value if there is one.
+    Insert {
+        /// The key to insert at.
+        key: Box<dyn PartialReflect>,
+        /// The inserted value.
+        value: Box<dyn PartialReflect>,
+    },
+    /// Removes the entry at `key`.
+    Remove {
+        /// The key of the removed entry.
+        key: Box<dyn PartialReflect>,
+    },
+    /// Patches the value at `key`.
+    Patch {
+        /// The key of the patched entry.
+        key: Box<dyn PartialReflect>,
+        /// The patch applied to the value.
+        patch: Patch,
+    },
+}
+
+/// A single edit of a [`Patch::Set`].
+#[derive(Debug)]
+pub enum SetEdit {
+    /// Inserts a value.
+    Insert(Box<dyn PartialReflect>),
+    /// Removes a value.
+    Remove(Box<dyn PartialReflect>),
+}
+
+/// An error returned when applying a [`Patch`] fails.
+#[derive(Error, Debug)]
+pub enum PatchError {
+    /// A field of the patch does not exist on the value.
+    #[error(transparent)]
+    Access(#[from] AccessError<'static>),
+    /// A value of the patch could not be applied.
+    #[error(transparent)]
+    Apply(#[from] ApplyError),
+    /// The patch was created for a different [kind](ReflectKind) of value.
+    #[error("cannot apply a patch for a `{expected}` to a `{received}`")]
+    MismatchedKinds {
+        /// The kind the patch applies to.
+        expected: ReflectKind,
+        /// The kind of the value the patch was applied to.
+        received: ReflectKind,
+    },
+    /// A list edit refers to an index that is out of bounds.
+    #[error("index {index} is out of bounds for a list of length {len}")]
+    IndexOutOfBounds {
+        /// The index of the edit.
+        index: usize,
+        /// The length of the list.
+        len: usize,
+    },
+    /// A map edit refers to a key that does not exist.
+    #[error("the map does not contain the key `{key}`")]
+    MissingKey {
+        /// The debug representation of the key.
+        key: String,
+    },
+}
+
+impl Patch {
+    /// Applies this patch to `value`.
+    ///
+    /// The patch is not consumed, so it can be applied to multiple values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` does not have the shape the patch was created for.
+    /// `value` may be partially patched when this happens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an inserted value cannot be converted to the element type of a list,
+    /// see [`List::insert`](crate::List::insert).
+    pub fn apply(&self, value: &mut dyn PartialReflect) -> Result<(), PatchError> {
+        match self {
+            Patch::Replace(new) | Patch::Variant(new) => {
+                value.try_apply(new.as_ref())?;
+                truncate_lists(value, new.as_ref());
+            }
+            Patch::Fields(fields) => {
+                for (access, patch) in fields {
+                    patch.apply(access.element_mut(value, None)?)?;
+                }
+            }
+            Patch::List(edits) => {
+                let received = value.reflect_kind();
+                let ReflectMut::List(list) = value.reflect_mut() else {
+                    return Err(mismatched_kinds(ReflectKind::List, received));
+                };
+                for edit in edits {
+                    let (ListEdit::Insert { index, .. }
+                    | ListEdit::Remove { index }
+                    | ListEdit::Patch { index, .. }) = *edit;
+                    let len = list.len();
+                    let in_bounds = match edit {
+                        ListEdit::Insert { .. } => index <= len,
+                        _ => index < len,
+                    };
+                    if !in_bounds {
+                        return Err(PatchError::IndexOutOfBounds { index, len });
+                    }
+                    match edit {
+                        ListEdit::Insert { value, .. } => list.insert(index, value.to_dynamic()),
+                        ListEdit::Remove { .. } => drop(list.remove(index)),
+                        ListEdit::Patch { patch, .. } => {
+                            // The index was checked above.
+                            patch.apply(list.get_mut(index).unwrap())?;
+                        }
+                    }
+                }
+            }
+            Patch::Map(edits) => {
+                let received = value.reflect_kind();
+                let ReflectMut::Map(map) = value.reflect_mut() else {
+                    return Err(mismatched_kinds(ReflectKind::Map, received));
+                };
+                for edit in edits {
+                    match edit {
+                        MapEdit::Insert { key, value } => {
+                            map.insert_boxed(key.to_dynamic(), value.to_dynamic());
+                        }
+                        MapEdit::Remove { key } => {
+                            map.remove(key.as_ref());
+                        }
+                        MapEdit::Patch { key, patch } => {
+                            let value = map.get_mut(key.as_ref()).ok_or_else(|| {
+                                PatchError::MissingKey {
+                                    key: format!("{key:?}"),
+                                }
+                            })?;
+                            patch.apply(value)?;
+                        }
+                    }
+                }
+            }
+            Patch::Set(edits) => {
+                let received = value.reflect_kind();
+                let ReflectMut::Set(set) = value.reflect_mut() else {
+                    return Err(mismatched_kinds(ReflectKind::Set, received));
+                };
+                for edit in edits {
+                    match edit {
+                        SetEdit::Insert(value) => {
+                            set.insert_boxed(value.to_dynamic());
+                        }
+                        SetEdit::Remove(value) => {
+                            set.remove(value.as_ref());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removes the trailing elements of the lists in `value` that are longer than the matching
+/// lists in `new`.
+///
+/// [`PartialReflect::try_apply`] only overwrites and appends list elements, so this is needed
+/// after applying a whole value for `value` to become equal to `new`.
+fn truncate_lists(value: &mut dyn PartialReflect, new: &dyn PartialReflect) {
+    match (value.reflect_mut(), new.reflect_ref()) {
+        (ReflectMut::List(list), ReflectRef::List(new_list)) => {
+            while list.len() > new_list.len() {
+                list.pop();
+            }
+            for (index, new_element) in new_list.iter().enumerate() {
+                if let Some(element) = list.get_mut(index) {
+                    truncate_lists(element, new_element);
+                }
+            }
+        }
+        (ReflectMut::Array(array), ReflectRef::Array(new_array)) => {
+            for (index, new_element) in new_array.iter().enumerate() {
+                if let Some(element) = array.get_mut(index) {
+                    truncate_lists(element, new_element);
+                }
+            }
+        }
+        (ReflectMut::Struct(value), ReflectRef::Struct(new_struct)) => {
+            for (index, new_field) in new_struct.iter_fields().enumerate() {
+                if let Some(field) = new_struct
+                    .name_at(index)
+                    .and_then(|name| value.field_mut(name))
+                {
+                    truncate_lists(field, new_field);
+                }
+            }
+        }
+        (ReflectMut::TupleStruct(value), ReflectRef::TupleStruct(new_tuple)) => {
+            for (index, new_field) in new_tuple.iter_fields().enumerate() {
+                if let Some(field) = value.field_mut(index) {
+                    truncate_lists(field, new_field);
+                }
+            }
+        }
+        (ReflectMut::Tuple(value), ReflectRef::Tuple(new_tuple)) => {
+            for (index, new_field) in new_tuple.iter_fields().enumerate() {
+                if let Some(field) = value.field_mut(index) {
+                    truncate_lists(field, new_field);
+                }
+            }
+        }
+        (ReflectMut::Enum(value), ReflectRef::Enum(new_enum)) => {
+            for (index, new_field) in new_enum.iter_fields().enumerate() {
+                if let Some(field) = value.field_at_mut(index) {
+                    truncate_lists(field, new_field.value());
+                }
+            }
+        }
+        (ReflectMut::Map(map), ReflectRef::Map(new_map)) => {
+            for (key, new_value) in new_map.iter() {
+                if let Some(value) = map.get_mut(key) {
+                    truncate_lists(value, new_value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn mismatched_kinds(expected: ReflectKind, received: ReflectKind) -> PatchError {
+    PatchError::MismatchedKinds { expected, received }
+}
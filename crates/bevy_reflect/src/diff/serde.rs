@@ -0,0 +1,532 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use serde::{
+    de::{self, DeserializeSeed, EnumAccess, SeqAccess, VariantAccess, Visitor},
+    ser::{SerializeSeq, SerializeTupleVariant},
+    Deserializer, Serialize, Serializer,
+};
+
+use super::{ListEdit, MapEdit, Patch, SetEdit};
+use crate::{
+    serde::{ReflectDeserializer, ReflectSerializer},
+    Access, ParsedPath, PartialReflect, TypeRegistry,
+};
+
+const PATCH_VARIANTS: &[&str] = &["Replace", "Fields", "List", "Map", "Set", "Variant"];
+const LIST_EDIT_VARIANTS: &[&str] = &["Insert", "Remove", "Patch"];
+const MAP_EDIT_VARIANTS: &[&str] = &["Insert", "Remove", "Patch"];
+const SET_EDIT_VARIANTS: &[&str] = &["Insert", "Remove"];
+
+/// A serializer for [`Patch`] values.
+///
+/// The values contained in the patch are serialized with [`ReflectSerializer`],
+/// so their types must be registered in the [`TypeRegistry`].
+pub struct PatchSerializer<'a> {
+    patch: &'a Patch,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> PatchSerializer<'a> {
+    /// Creates a serializer for `patch`.
+    pub fn new(patch: &'a Patch, registry: &'a TypeRegistry) -> Self {
+        Self { patch, registry }
+    }
+
+    fn value(&self, value: &'a dyn PartialReflect) -> ReflectSerializer<'a> {
+        ReflectSerializer::new(value, self.registry)
+    }
+
+    fn patch(&self, patch: &'a Patch) -> Self {
+        Self::new(patch, self.registry)
+    }
+}
+
+impl Serialize for PatchSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.patch {
+            Patch::Replace(value) => serializer.serialize_newtype_variant(
+                "Patch",
+                0,
+                PATCH_VARIANTS[0],
+                &self.value(value.as_ref()),
+            ),
+            Patch::Fields(fields) => serializer.serialize_newtype_variant(
+                "Patch",
+                1,
+                PATCH_VARIANTS[1],
+                &SeqSerializer(
+                    fields
+                        .iter()
+                        .map(|(access, patch)| (access.to_string(), self.patch(patch))),
+                ),
+            ),
+            Patch::List(edits) => serializer.serialize_newtype_variant(
+                "Patch",
+                2,
+                PATCH_VARIANTS[2],
+                &SeqSerializer(edits.iter().map(|edit| EditSerializer {
+                    edit: Edit::List(edit),
+                    registry: self.registry,
+                })),
+            ),
+            Patch::Map(edits) => serializer.serialize_newtype_variant(
+                "Patch",
+                3,
+                PATCH_VARIANTS[3],
+                &SeqSerializer(edits.iter().map(|edit| EditSerializer {
+                    edit: Edit::Map(edit),
+                    registry: self.registry,
+                })),
+            ),
+            Patch::Set(edits) => serializer.serialize_newtype_variant(
+                "Patch",
+                4,
+                PATCH_VARIANTS[4],
+                &SeqSerializer(edits.iter().map(|edit| EditSerializer {
+                    edit: Edit::Set(edit),
+                    registry: self.registry,
+                })),
+            ),
+            Patch::Variant(value) => serializer.serialize_newtype_variant(
+                "Patch",
+                5,
+                PATCH_VARIANTS[5],
+                &self.value(value.as_ref()),
+            ),
+        }
+    }
+}
+
+/// Serializes the items of an iterator as a sequence.
+struct SeqSerializer<I>(I);
+
+impl<T: Serialize, I: Iterator<Item = T> + Clone> Serialize for SeqSerializer<I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(self.0.size_hint().1)?;
+        for item in self.0.clone() {
+            seq.serialize_element(&item)?;
+        }
+        seq.end()
+    }
+}
+
+enum Edit<'a> {
+    List(&'a ListEdit),
+    Map(&'a MapEdit),
+    Set(&'a SetEdit),
+}
+
+struct EditSerializer<'a> {
+    edit: Edit<'a>,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> EditSerializer<'a> {
+    fn value(&self, value: &'a dyn PartialReflect) -> ReflectSerializer<'a> {
+        ReflectSerializer::new(value, self.registry)
+    }
+
+    fn patch(&self, patch: &'a Patch) -> PatchSerializer<'a> {
+        PatchSerializer::new(patch, self.registry)
+    }
+}
+
+impl Serialize for EditSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.edit {
+            Edit::List(ListEdit::Insert { index, value }) => {
+                let mut variant =
+                    serializer.serialize_tuple_variant("ListEdit", 0, LIST_EDIT_VARIANTS[0], 2)?;
+                variant.serialize_field(index)?;
+                variant.serialize_field(&self.value(value.as_ref()))?;
+                variant.end()
+            }
+            Edit::List(ListEdit::Remove { index }) => {
+                serializer.serialize_newtype_variant("ListEdit", 1, LIST_EDIT_VARIANTS[1], index)
+            }
+            Edit::List(ListEdit::Patch { index, patch }) => {
+                let mut variant =
+                    serializer.serialize_tuple_variant("ListEdit", 2, LIST_EDIT_VARIANTS[2], 2)?;
+                variant.serialize_field(index)?;
+                variant.serialize_field(&self.patch(patch))?;
+                variant.end()
+            }
+            Edit::Map(MapEdit::Insert { key, value }) => {
+                let mut variant =
+                    serializer.serialize_tuple_variant("MapEdit", 0, MAP_EDIT_VARIANTS[0], 2)?;
+                variant.serialize_field(&self.value(key.as_ref()))?;
+                variant.serialize_field(&self.value(value.as_ref()))?;
+                variant.end()
+            }
+            Edit::Map(MapEdit::Remove { key }) => serializer.serialize_newtype_variant(
+                "MapEdit",
+                1,
+                MAP_EDIT_VARIANTS[1],
+                &self.value(key.as_ref()),
+            ),
+            Edit::Map(MapEdit::Patch { key, patch }) => {
+                let mut variant =
+                    serializer.serialize_tuple_variant("MapEdit", 2, MAP_EDIT_VARIANTS[2], 2)?;
+                variant.serialize_field(&self.value(key.as_ref()))?;
+                variant.serialize_field(&self.patch(patch))?;
+                variant.end()
+            }
+            Edit::Set(SetEdit::Insert(value)) => serializer.serialize_newtype_variant(
+                "SetEdit",
+                0,
+                SET_EDIT_VARIANTS[0],
+                &self.value(value.as_ref()),
+            ),
+            Edit::Set(SetEdit::Remove(value)) => serializer.serialize_newtype_variant(
+                "SetEdit",
+                1,
+                SET_EDIT_VARIANTS[1],
+                &self.value(value.as_ref()),
+            ),
+        }
+    }
+}
+
+/// A deserializer for [`Patch`] values serialized with [`PatchSerializer`].
+///
+/// The values contained in the patch are deserialized with [`ReflectDeserializer`],
+/// so they are returned as dynamic values unless their types are opaque.
+#[derive(Clone, Copy)]
+pub struct PatchDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> PatchDeserializer<'a> {
+    /// Creates a deserializer that uses `registry` to deserialize the values in the patch.
+    pub fn new(registry: &'a TypeRegistry) -> Self {
+        Self { registry }
+    }
+
+    fn value(self) -> ReflectDeserializer<'a> {
+        ReflectDeserializer::new(self.registry)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for PatchDeserializer<'_> {
+    type Value = Patch;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_enum("Patch", PATCH_VARIANTS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for PatchDeserializer<'_> {
+    type Value = Patch;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a reflect patch")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (variant, access) = data.variant_seed(VariantIndex(PATCH_VARIANTS))?;
+        Ok(match variant {
+            0 => Patch::Replace(access.newtype_variant_seed(self.value())?),
+            1 => Patch::Fields(
+                access.newtype_variant_seed(SeqDeserializer(FieldDeserializer(self)))?,
+            ),
+            2 => Patch::List(
+                access
+                    .newtype_variant_seed(SeqDeserializer(EditDeserializer {
+                        kind: EditKind::List,
+                        patch: self,
+                    }))?
+                    .into_iter()
+                    .map(|edit| match edit {
+                        DeserializedEdit::List(edit) => edit,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            3 => Patch::Map(
+                access
+                    .newtype_variant_seed(SeqDeserializer(EditDeserializer {
+                        kind: EditKind::Map,
+                        patch: self,
+                    }))?
+                    .into_iter()
+                    .map(|edit| match edit {
+                        DeserializedEdit::Map(edit) => edit,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            4 => Patch::Set(
+                access
+                    .newtype_variant_seed(SeqDeserializer(EditDeserializer {
+                        kind: EditKind::Set,
+                        patch: self,
+                    }))?
+                    .into_iter()
+                    .map(|edit| match edit {
+                        DeserializedEdit::Set(edit) => edit,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            _ => Patch::Variant(access.newtype_variant_seed(self.value())?),
+        })
+    }
+}
+
+/// Deserializes the index of an enum variant from its name or index.
+struct VariantIndex(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for VariantIndex {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for VariantIndex {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a variant identifier")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        usize::try_from(value)
+            .ok()
+            .filter(|&index| index < self.0.len())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        self.0
+            .iter()
+            .position(|&variant| variant == value)
+            .ok_or_else(|| E::unknown_variant(value, self.0))
+    }
+}
+
+/// Deserializes a sequence of values with the same seed.
+struct SeqDeserializer<T>(T);
+
+impl<'de, T: DeserializeSeed<'de> + Copy> DeserializeSeed<'de> for SeqDeserializer<T> {
+    type Value = Vec<T::Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: DeserializeSeed<'de> + Copy> Visitor<'de> for SeqDeserializer<T> {
+    type Value = Vec<T::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element_seed(self.0)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+/// Deserializes an entry of [`Patch::Fields`].
+#[derive(Clone, Copy)]
+struct FieldDeserializer<'a>(PatchDeserializer<'a>);
+
+impl<'de> DeserializeSeed<'de> for FieldDeserializer<'_> {
+    type Value = (Access<'static>, Patch);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldDeserializer<'_> {
+    type Value = (Access<'static>, Patch);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a field access and a patch")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let access: String = next_element(&mut seq, 0, &self)?;
+        let access = parse_access(&access).map_err(de::Error::custom)?;
+        let patch = next_element_seed(&mut seq, self.0, 1, &self)?;
+        Ok((access, patch))
+    }
+}
+
+/// Parses an [`Access`] serialized with its [`Display`](fmt::Display) implementation.
+fn parse_access(access: &str) -> Result<Access<'static>, String> {
+    let mut path = ParsedPath::parse(access).map_err(|err| format!("{err}"))?.0;
+    match (path.pop(), path.is_empty()) {
+        (Some(access), true) => Ok(access.access),
+        _ => Err(format!("`{access}` is not a single field access")),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum EditKind {
+    List,
+    Map,
+    Set,
+}
+
+enum DeserializedEdit {
+    List(ListEdit),
+    Map(MapEdit),
+    Set(SetEdit),
+}
+
+/// Deserializes a [`ListEdit`], [`MapEdit`] or [`SetEdit`].
+#[derive(Clone, Copy)]
+struct EditDeserializer<'a> {
+    kind: EditKind,
+    patch: PatchDeserializer<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for EditDeserializer<'_> {
+    type Value = DeserializedEdit;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        match self.kind {
+            EditKind::List => deserializer.deserialize_enum("ListEdit", LIST_EDIT_VARIANTS, self),
+            EditKind::Map => deserializer.deserialize_enum("MapEdit", MAP_EDIT_VARIANTS, self),
+            EditKind::Set => deserializer.deserialize_enum("SetEdit", SET_EDIT_VARIANTS, self),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for EditDeserializer<'_> {
+    type Value = DeserializedEdit;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a reflect patch edit")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let variants = match self.kind {
+            EditKind::List => LIST_EDIT_VARIANTS,
+            EditKind::Map => MAP_EDIT_VARIANTS,
+            EditKind::Set => SET_EDIT_VARIANTS,
+        };
+        let (variant, access) = data.variant_seed(VariantIndex(variants))?;
+        let value = self.patch.value();
+        Ok(match (self.kind, variant) {
+            (EditKind::List, 0) => {
+                let (index, value) =
+                    access.tuple_variant(2, PairDeserializer::<usize, _>::new(value))?;
+                DeserializedEdit::List(ListEdit::Insert { index, value })
+            }
+            (EditKind::List, 1) => DeserializedEdit::List(ListEdit::Remove {
+                index: access.newtype_variant()?,
+            }),
+            (EditKind::List, _) => {
+                let (index, patch) =
+                    access.tuple_variant(2, PairDeserializer::<usize, _>::new(self.patch))?;
+                DeserializedEdit::List(ListEdit::Patch { index, patch })
+            }
+            (EditKind::Map, 0) => {
+                let (key, value) =
+                    access.tuple_variant(2, ReflectPairDeserializer(value, self.patch.value()))?;
+                DeserializedEdit::Map(MapEdit::Insert { key, value })
+            }
+            (EditKind::Map, 1) => DeserializedEdit::Map(MapEdit::Remove {
+                key: access.newtype_variant_seed(value)?,
+            }),
+            (EditKind::Map, _) => {
+                let (key, patch) =
+                    access.tuple_variant(2, ReflectPairDeserializer(value, self.patch))?;
+                DeserializedEdit::Map(MapEdit::Patch { key, patch })
+            }
+            (EditKind::Set, 0) => {
+                DeserializedEdit::Set(SetEdit::Insert(access.newtype_variant_seed(value)?))
+            }
+            (EditKind::Set, _) => {
+                DeserializedEdit::Set(SetEdit::Remove(access.newtype_variant_seed(value)?))
+            }
+        })
+    }
+}
+
+/// Deserializes a pair of a deserializable value and a value deserialized with a seed.
+struct PairDeserializer<T, S> {
+    seed: S,
+    marker: core::marker::PhantomData<T>,
+}
+
+impl<T, S> PairDeserializer<T, S> {
+    fn new(seed: S) -> Self {
+        Self {
+            seed,
+            marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'de, T: de::Deserialize<'de>, S: DeserializeSeed<'de>> Visitor<'de>
+    for PairDeserializer<T, S>
+{
+    type Value = (T, S::Value);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a pair")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let first = next_element(&mut seq, 0, &"a pair")?;
+        let second = next_element_seed(&mut seq, self.seed, 1, &"a pair")?;
+        Ok((first, second))
+    }
+}
+
+/// Deserializes a pair of values, each with its own seed.
+struct ReflectPairDeserializer<S1, S2>(S1, S2);
+
+impl<'de, S1: DeserializeSeed<'de>, S2: DeserializeSeed<'de>> Visitor<'de>
+    for ReflectPairDeserializer<S1, S2>
+{
+    type Value = (S1::Value, S2::Value);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a pair")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let first = seq
+            .next_element_seed(self.0)?
+            .ok_or_else(|| de::Error::invalid_length(0, &"a pair"))?;
+        let second = seq
+            .next_element_seed(self.1)?
+            .ok_or_else(|| de::Error::invalid_length(1, &"a pair"))?;
+        Ok((first, second))
+    }
+}
+
+fn next_element<'de, A: SeqAccess<'de>, T: de::Deserialize<'de>>(
+    seq: &mut A,
+    index: usize,
+    expected: &dyn de::Expected,
+) -> Result<T, A::Error> {
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(index, expected))
+}
+
+fn next_element_seed<'de, A: SeqAccess<'de>, S: DeserializeSeed<'de>>(
+    seq: &mut A,
+    seed: S,
+    index: usize,
+    expected: &dyn de::Expected,
+) -> Result<S::Value, A::Error> {
+    seq.next_element_seed(seed)?
+        .ok_or_else(|| de::Error::invalid_length(index, expected))
+}
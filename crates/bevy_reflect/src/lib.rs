@@ -624,6 +624,7 @@ mod impls {
 }
 
 pub mod attributes;
+pub mod diff;
 mod enums;
 mod generics;
 pub mod serde;
@@ -631,6 +632,7 @@ pub mod std_traits;
 #[cfg(feature = "debug_stack")]
 mod type_info_stack;
 pub mod utility;
+pub mod validation;
 
 /// The reflect prelude.
 ///
@@ -5,7 +5,7 @@ use core::{
 };
 use thiserror::Error;
 
-use super::{Access, ReflectPathError};
+use super::{Access, QuerySegment, ReflectPathError};
 
 /// An error that occurs when parsing reflect path strings.
 #[derive(Debug, PartialEq, Eq, Error)]
@@ -89,6 +89,7 @@ impl<'a> PathParser<'a> {
             Token::Pound => self.next_ident()?.field_index(),
             Token::Ident(ident) => Ok(ident.field()),
             Token::CloseBracket => Err(Error::CloseBeforeOpen),
+            Token::Star => Err(Error::ExpectedIdent(Token::Star)),
             Token::OpenBracket => {
                 let index_ident = self.next_ident()?.list_index()?;
                 match self.next_token() {
@@ -100,6 +101,53 @@ impl<'a> PathParser<'a> {
         }
     }
 
+    fn segment_following(&mut self, token: Token<'a>) -> Result<QuerySegment<'a>, Error<'a>> {
+        match token {
+            Token::Star => Ok(QuerySegment::AnyField),
+            Token::Dot => match self.next_token() {
+                Some(Token::Star) => Ok(QuerySegment::AnyField),
+                Some(Token::Ident(ident)) => Ok(QuerySegment::Access(ident.field())),
+                Some(other) => Err(Error::ExpectedIdent(other)),
+                None => Err(Error::NoIdent),
+            },
+            Token::OpenBracket => {
+                let segment = match self.next_token() {
+                    Some(Token::Star) => QuerySegment::AnyElement,
+                    Some(Token::Ident(ident)) => QuerySegment::Access(ident.list_index()?),
+                    Some(other) => return Err(Error::ExpectedIdent(other)),
+                    None => return Err(Error::NoIdent),
+                };
+                match self.next_token() {
+                    Some(Token::CloseBracket) => Ok(segment),
+                    Some(other) => Err(Error::BadClose(other)),
+                    None => Err(Error::Unclosed),
+                }
+            }
+            token => self.access_following(token).map(QuerySegment::Access),
+        }
+    }
+
+    /// Parses the next segment of a [`PathQuery`](super::PathQuery), which may be a wildcard.
+    pub(super) fn next_segment(
+        &mut self,
+    ) -> Option<(Result<QuerySegment<'a>, ReflectPathError<'a>>, usize)> {
+        let token = self.next_token()?;
+        let offset = self.offset();
+        Some((
+            self.segment_following(token)
+                .map_err(|error| self.error(offset, error)),
+            offset,
+        ))
+    }
+
+    fn error(&self, offset: usize, error: Error<'a>) -> ReflectPathError<'a> {
+        ReflectPathError::ParseError {
+            offset,
+            path: self.path,
+            error: ParseError(error),
+        }
+    }
+
     fn offset(&self) -> usize {
         self.path.len() - self.remaining.len()
     }
@@ -113,11 +161,7 @@ impl<'a> Iterator for PathParser<'a> {
         let offset = self.offset();
         Some((
             self.access_following(token)
-                .map_err(|error| ReflectPathError::ParseError {
-                    offset,
-                    path: self.path,
-                    error: ParseError(error),
-                }),
+                .map_err(|error| self.error(offset, error)),
             offset,
         ))
     }
@@ -149,6 +193,7 @@ enum Token<'a> {
     Pound = b'#',
     OpenBracket = b'[',
     CloseBracket = b']',
+    Star = b'*',
     Ident(Ident<'a>),
 }
 
@@ -159,19 +204,21 @@ impl fmt::Display for Token<'_> {
             Token::Pound => f.write_char('#'),
             Token::OpenBracket => f.write_char('['),
             Token::CloseBracket => f.write_char(']'),
+            Token::Star => f.write_char('*'),
             Token::Ident(ident) => f.write_str(ident.0),
         }
     }
 }
 
 impl<'a> Token<'a> {
-    const SYMBOLS: &'static [u8] = b".#[]";
+    const SYMBOLS: &'static [u8] = b".#[]*";
     fn symbol_from_byte(byte: u8) -> Option<Self> {
         match byte {
             b'.' => Some(Self::Dot),
             b'#' => Some(Self::Pound),
             b'[' => Some(Self::OpenBracket),
             b']' => Some(Self::CloseBracket),
+            b'*' => Some(Self::Star),
             _ => None,
         }
     }
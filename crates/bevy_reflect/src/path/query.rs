@@ -0,0 +1,471 @@
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use super::{parse::PathParser, Access, PathResult};
+use crate::{PartialReflect, Reflect, ReflectMut, ReflectRef};
+
+/// A segment of a [`PathQuery`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QuerySegment<'a> {
+    /// Matches a single element, like a segment of a [`ParsedPath`](super::ParsedPath).
+    Access(Access<'a>),
+    /// Matches every field of a struct, tuple struct, tuple or enum variant (`.*`).
+    AnyField,
+    /// Matches every element of a list or array, and every value of a map (`[*]`).
+    AnyElement,
+}
+
+impl QuerySegment<'_> {
+    /// Converts this into an "owned" value.
+    pub fn into_owned(self) -> QuerySegment<'static> {
+        match self {
+            QuerySegment::Access(access) => QuerySegment::Access(access.into_owned()),
+            QuerySegment::AnyField => QuerySegment::AnyField,
+            QuerySegment::AnyElement => QuerySegment::AnyElement,
+        }
+    }
+}
+
+impl fmt::Display for QuerySegment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuerySegment::Access(access) => write!(f, "{access}"),
+            QuerySegment::AnyField => f.write_str(".*"),
+            QuerySegment::AnyElement => f.write_str("[*]"),
+        }
+    }
+}
+
+/// A path that can match multiple elements within a type, using wildcards.
+///
+/// Path queries use the same syntax as [`GetPath`](super::GetPath), with two additions:
+/// - `.*` matches every field of a struct, tuple struct, tuple or of the current variant of an enum.
+/// - `[*]` matches every element of a list or array, and every value of a map.
+///
+/// Elements that do not have a segment of the query, such as a list element of an
+/// enum variant without the queried field, are skipped rather than causing an error.
+///
+/// Each match is returned together with its concrete path, in which the wildcards are replaced
+/// by the field or index that was matched, such as `.items[2].count` for `.items[*].count`.
+/// These paths can be used with [`GetPath`](super::GetPath), except for map values,
+/// which are written with the [`Debug`](fmt::Debug) representation of their key: `.scores["alice"]`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_reflect::{PathQuery, Reflect};
+/// #[derive(Reflect)]
+/// struct Inventory {
+///     items: Vec<Item>,
+/// }
+///
+/// #[derive(Reflect)]
+/// struct Item {
+///     count: u32,
+/// }
+///
+/// let mut inventory = Inventory {
+///     items: vec![Item { count: 1 }, Item { count: 5 }],
+/// };
+///
+/// let query = PathQuery::parse(".items[*].count").unwrap();
+///
+/// let mut matches = query.iter_mut(&mut inventory);
+/// while let Some((path, count)) = matches.next_match() {
+///     println!("doubling {path}");
+///     *count.try_downcast_mut::<u32>().unwrap() *= 2;
+/// }
+///
+/// let paths = query
+///     .iter(&inventory)
+///     .map(|(path, _)| path)
+///     .collect::<Vec<_>>();
+/// assert_eq!(paths, [".items[0].count", ".items[1].count"]);
+/// assert_eq!(inventory.items[1].count, 10);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PathQuery(
+    /// The segments of the query.
+    pub Vec<QuerySegment<'static>>,
+);
+
+impl PathQuery {
+    /// Parses a [`PathQuery`] from a string.
+    ///
+    /// Returns an error if the string is not a valid path query.
+    pub fn parse(string: &str) -> PathResult<'_, Self> {
+        let mut parser = PathParser::new(string);
+        let mut segments = Vec::new();
+        while let Some((segment, _)) = parser.next_segment() {
+            segments.push(segment?.into_owned());
+        }
+        Ok(Self(segments))
+    }
+
+    /// Returns every element of `root` matching this query, along with its concrete path.
+    pub fn iter<'r>(
+        &self,
+        root: &'r dyn PartialReflect,
+    ) -> impl Iterator<Item = (String, &'r dyn PartialReflect)> {
+        let mut matches = Vec::new();
+        let mut path = String::new();
+        visit(
+            &self.0,
+            root,
+            &mut Vec::new(),
+            &mut path,
+            &mut |_, path, value| {
+                matches.push((path.to_string(), value));
+            },
+        );
+        matches.into_iter()
+    }
+
+    /// Returns every element of `root` matching this query mutably, along with its concrete path.
+    ///
+    /// The matches are returned one at a time with [`QueryMatchesMut::next_match`],
+    /// since each match mutably borrows `root`.
+    pub fn iter_mut<'r>(&self, root: &'r mut dyn PartialReflect) -> QueryMatchesMut<'r> {
+        let mut matches = Vec::new();
+        let mut path = String::new();
+        visit(
+            &self.0,
+            root,
+            &mut Vec::new(),
+            &mut path,
+            &mut |steps, path, _| {
+                matches.push((
+                    path.to_string(),
+                    steps.iter().map(Step::clone_step).collect(),
+                ));
+            },
+        );
+        matches.reverse();
+        QueryMatchesMut {
+            root,
+            matches,
+            current: String::new(),
+        }
+    }
+
+    /// Calls `f` with every element of `root` matching this query, along with its concrete path.
+    pub fn for_each_mut(
+        &self,
+        root: &mut dyn PartialReflect,
+        mut f: impl FnMut(&str, &mut dyn PartialReflect),
+    ) {
+        let mut matches = self.iter_mut(root);
+        while let Some((path, value)) = matches.next_match() {
+            f(path, value);
+        }
+    }
+}
+
+impl fmt::Display for PathQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.0 {
+            write!(f, "{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for PathQuery {
+    type Error = super::ReflectPathError<'a>;
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        PathQuery::parse(value)
+    }
+}
+
+/// The mutable matches of a [`PathQuery`], returned by [`PathQuery::iter_mut`].
+///
+/// This is a lending iterator: each match borrows the iterator, so it must be
+/// released before the next one is requested with [`next_match`](Self::next_match).
+pub struct QueryMatchesMut<'r> {
+    root: &'r mut dyn PartialReflect,
+    /// The remaining matches, in reverse order.
+    matches: Vec<(String, Vec<Step>)>,
+    current: String,
+}
+
+impl QueryMatchesMut<'_> {
+    /// Returns the next match and its concrete path, or `None` once all matches have been returned.
+    pub fn next_match(&mut self) -> Option<(&str, &mut dyn PartialReflect)> {
+        // All matches are at the same depth, so changing one cannot remove another.
+        let (path, steps) = self.matches.pop()?;
+        let value = steps
+            .iter()
+            .try_fold(&mut *self.root, |value, step| step.element_mut(value))?;
+        self.current = path;
+        Some((&self.current, value))
+    }
+
+    /// Returns the number of remaining matches.
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Returns `true` if there are no remaining matches.
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+}
+
+/// A concrete step from a value to one of its elements.
+enum Step {
+    Access(Access<'static>),
+    MapValue(Box<dyn PartialReflect>),
+}
+
+impl Step {
+    fn clone_step(&self) -> Self {
+        match self {
+            Step::Access(access) => Step::Access(access.clone()),
+            Step::MapValue(key) => Step::MapValue(clone_key(key.as_ref())),
+        }
+    }
+
+    fn element_mut<'r>(
+        &self,
+        value: &'r mut dyn PartialReflect,
+    ) -> Option<&'r mut dyn PartialReflect> {
+        match self {
+            Step::Access(access) => access.element_mut(value, None).ok(),
+            Step::MapValue(key) => match value.reflect_mut() {
+                ReflectMut::Map(map) => map.get_mut(key.as_ref()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Clones a map key, preferring its concrete type so that it can be hashed.
+fn clone_key(key: &dyn PartialReflect) -> Box<dyn PartialReflect> {
+    key.reflect_clone()
+        .map(<dyn Reflect>::into_partial_reflect)
+        .unwrap_or_else(|_| key.to_dynamic())
+}
+
+type OnMatch<'f, 'r> = dyn FnMut(&[Step], &str, &'r dyn PartialReflect) + 'f;
+
+/// Calls `on_match` for every element of `value` matching `segments`.
+fn visit<'r>(
+    segments: &[QuerySegment<'static>],
+    value: &'r dyn PartialReflect,
+    steps: &mut Vec<Step>,
+    path: &mut String,
+    on_match: &mut OnMatch<'_, 'r>,
+) {
+    let Some((segment, rest)) = segments.split_first() else {
+        on_match(steps, path, value);
+        return;
+    };
+
+    let mut visit_access = |access: Access<'static>| {
+        if let Ok(element) = access.element(value, None) {
+            let segment = access.to_string();
+            visit_step(
+                rest,
+                element,
+                Step::Access(access),
+                &segment,
+                steps,
+                path,
+                on_match,
+            );
+        }
+    };
+
+    match segment {
+        QuerySegment::Access(access) => visit_access(access.clone()),
+        QuerySegment::AnyField => match value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                for index in 0..value.field_len() {
+                    if let Some(name) = value.name_at(index) {
+                        visit_access(Access::Field(Cow::Owned(name.into())));
+                    }
+                }
+            }
+            ReflectRef::TupleStruct(value) => {
+                for index in 0..value.field_len() {
+                    visit_access(Access::TupleIndex(index));
+                }
+            }
+            ReflectRef::Tuple(value) => {
+                for index in 0..value.field_len() {
+                    visit_access(Access::TupleIndex(index));
+                }
+            }
+            ReflectRef::Enum(value) => {
+                for index in 0..value.field_len() {
+                    match value.name_at(index) {
+                        Some(name) => visit_access(Access::Field(Cow::Owned(name.into()))),
+                        None => visit_access(Access::TupleIndex(index)),
+                    }
+                }
+            }
+            _ => {}
+        },
+        QuerySegment::AnyElement => match value.reflect_ref() {
+            ReflectRef::List(value) => {
+                for index in 0..value.len() {
+                    visit_access(Access::ListIndex(index));
+                }
+            }
+            ReflectRef::Array(value) => {
+                for index in 0..value.len() {
+                    visit_access(Access::ListIndex(index));
+                }
+            }
+            ReflectRef::Map(map) => {
+                for (key, element) in map.iter() {
+                    let segment = format!("[{key:?}]");
+                    let step = Step::MapValue(clone_key(key));
+                    visit_step(rest, element, step, &segment, steps, path, on_match);
+                }
+            }
+            _ => {}
+        },
+    }
+}
+
+/// Visits `element` after pushing its step and path segment.
+fn visit_step<'r>(
+    segments: &[QuerySegment<'static>],
+    element: &'r dyn PartialReflect,
+    step: Step,
+    segment: &str,
+    steps: &mut Vec<Step>,
+    path: &mut String,
+    on_match: &mut OnMatch<'_, 'r>,
+) {
+    let len = path.len();
+    path.push_str(segment);
+    steps.push(step);
+    visit(segments, element, steps, path, on_match);
+    steps.pop();
+    path.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use bevy_platform::collections::HashMap;
+
+    #[derive(Reflect)]
+    struct World {
+        players: Vec<Player>,
+        teams: HashMap<String, Team>,
+        spawn: (f32, f32),
+    }
+
+    #[derive(Reflect)]
+    struct Player {
+        health: u32,
+        state: State,
+    }
+
+    #[derive(Reflect)]
+    struct Team {
+        score: u32,
+    }
+
+    #[derive(Reflect)]
+    enum State {
+        Idle,
+        Moving { speed: f32 },
+    }
+
+    fn world() -> World {
+        World {
+            players: vec![
+                Player {
+                    health: 10,
+                    state: State::Idle,
+                },
+                Player {
+                    health: 20,
+                    state: State::Moving { speed: 2.0 },
+                },
+            ],
+            teams: [(String::from("red"), Team { score: 3 })]
+                .into_iter()
+                .collect(),
+            spawn: (1.0, 2.0),
+        }
+    }
+
+    fn paths(query: &str, world: &World) -> Vec<String> {
+        PathQuery::parse(query)
+            .unwrap()
+            .iter(world)
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let query = PathQuery::parse(".players[*].*[0]#1").unwrap();
+        assert_eq!(
+            query.0,
+            [
+                QuerySegment::Access(Access::Field("players".into())),
+                QuerySegment::AnyElement,
+                QuerySegment::AnyField,
+                QuerySegment::Access(Access::ListIndex(0)),
+                QuerySegment::Access(Access::FieldIndex(1)),
+            ]
+        );
+        assert_eq!(query.to_string(), ".players[*].*[0]#1");
+        assert!(PathQuery::parse("players[*").is_err());
+        assert!(PathQuery::parse("players[*x]").is_err());
+    }
+
+    #[test]
+    fn wildcards() {
+        let world = world();
+        assert_eq!(
+            paths(".players[*].health", &world),
+            [".players[0].health", ".players[1].health"]
+        );
+        assert_eq!(paths(".spawn.*", &world), [".spawn.0", ".spawn.1"]);
+        assert_eq!(paths(".teams[*].score", &world), [r#".teams["red"].score"#]);
+        // Only the second player is in a variant with a field.
+        assert_eq!(
+            paths(".players[*].state.*", &world),
+            [".players[1].state.speed"]
+        );
+        assert_eq!(paths(".players[*].missing", &world), Vec::<String>::new());
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut world = world();
+
+        let query = PathQuery::parse(".players[*].health").unwrap();
+        let mut matches = query.iter_mut(&mut world);
+        assert_eq!(matches.len(), 2);
+        while let Some((_, health)) = matches.next_match() {
+            *health.try_downcast_mut::<u32>().unwrap() += 1;
+        }
+        assert_eq!(world.players[0].health, 11);
+        assert_eq!(world.players[1].health, 21);
+
+        let mut visited = Vec::new();
+        PathQuery::parse(".teams[*].*")
+            .unwrap()
+            .for_each_mut(&mut world, |path, score| {
+                visited.push(path.to_string());
+                score.apply(&100u32);
+            });
+        assert_eq!(visited, [r#".teams["red"].score"#]);
+        assert_eq!(world.teams["red"].score, 100);
+    }
+}
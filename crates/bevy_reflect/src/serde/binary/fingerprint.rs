@@ -0,0 +1,258 @@
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{any::TypeId, fmt};
+
+use bevy_platform::collections::HashSet;
+
+use super::{is_skipped, Encoding};
+use crate::{TypeInfo, TypeRegistration, TypeRegistry, VariantInfo};
+
+/// A hash of the shape of reflected types, as used by the binary encoding.
+///
+/// Two fingerprints are equal when the types they were computed from have the same
+/// type paths, field names, field types, variants and serialization settings,
+/// so data encoded with [`BinaryReflectSerializer`] for one can be decoded with the other.
+///
+/// The fingerprint does not cover the serde implementations of types that are encoded
+/// with [`ReflectSerialize`], since those are not visible to reflection.
+///
+/// The hash is stable across platforms and program runs, so it can be stored
+/// in save files or sent over the network.
+///
+/// [`BinaryReflectSerializer`]: super::BinaryReflectSerializer
+/// [`ReflectSerialize`]: crate::ReflectSerialize
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SchemaFingerprint(pub u64);
+
+impl SchemaFingerprint {
+    /// Computes the fingerprint of every type in `registry`.
+    ///
+    /// This changes whenever a type is added to or removed from the registry,
+    /// even when it is never encoded. Use [`of_type`](Self::of_type) to only
+    /// cover the types that make up a specific value.
+    pub fn of_registry(registry: &TypeRegistry) -> Self {
+        let mut registrations = registry.iter().collect::<Vec<_>>();
+        registrations.sort_unstable_by_key(|registration| registration.type_info().type_path());
+
+        let mut hasher = FingerprintHasher::default();
+        for registration in registrations {
+            hasher.write_type(registration, |_| {});
+        }
+        Self(hasher.finish())
+    }
+
+    /// Computes the fingerprint of `T` and of all the types it contains.
+    pub fn of_type<T: 'static>(registry: &TypeRegistry) -> Self {
+        Self::of_type_id(registry, TypeId::of::<T>())
+    }
+
+    /// Computes the fingerprint of the type with the given [`TypeId`] and of all the types it contains.
+    ///
+    /// Types that are not registered are included as such in the fingerprint.
+    pub fn of_type_id(registry: &TypeRegistry, type_id: TypeId) -> Self {
+        let mut hasher = FingerprintHasher::default();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([type_id]);
+        while let Some(type_id) = queue.pop_front() {
+            if !visited.insert(type_id) {
+                continue;
+            }
+            match registry.get(type_id) {
+                Some(registration) => hasher.write_type(registration, |type_id| {
+                    queue.push_back(type_id);
+                }),
+                None => hasher.write_u8(u8::MAX),
+            }
+        }
+        Self(hasher.finish())
+    }
+}
+
+impl fmt::Display for SchemaFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// A 64-bit FNV-1a hasher, which unlike the hashers of the standard library
+/// is guaranteed to give the same results on every platform and version.
+struct FingerprintHasher(u64);
+
+impl Default for FingerprintHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl FingerprintHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write(&(value as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_usize(value.len());
+        self.write(value.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    /// Hashes the shape of a type, calling `visit` with the types it refers to.
+    fn write_type(&mut self, registration: &TypeRegistration, mut visit: impl FnMut(TypeId)) {
+        let info = registration.type_info();
+        self.write_str(info.type_path());
+
+        let encoding = Encoding::of(registration);
+        self.write_u8(encoding as u8);
+        if encoding != Encoding::Reflect {
+            return;
+        }
+
+        self.write_u8(info.kind() as u8);
+        match info {
+            TypeInfo::Struct(info) => {
+                self.write_usize(info.field_len());
+                for (index, field) in info.iter().enumerate() {
+                    self.write_str(field.name());
+                    self.write_str(field.type_path());
+                    self.write_u8(is_skipped(registration, index).into());
+                    visit(field.type_id());
+                }
+            }
+            TypeInfo::TupleStruct(info) => {
+                self.write_usize(info.field_len());
+                for (index, field) in info.iter().enumerate() {
+                    self.write_str(field.type_path());
+                    self.write_u8(is_skipped(registration, index).into());
+                    visit(field.type_id());
+                }
+            }
+            TypeInfo::Tuple(info) => {
+                self.write_usize(info.field_len());
+                for field in info.iter() {
+                    self.write_str(field.type_path());
+                    visit(field.type_id());
+                }
+            }
+            TypeInfo::List(info) => {
+                self.write_str(info.item_ty().path());
+                visit(info.item_ty().id());
+            }
+            TypeInfo::Array(info) => {
+                self.write_str(info.item_ty().path());
+                self.write_usize(info.capacity());
+                visit(info.item_ty().id());
+            }
+            TypeInfo::Map(info) => {
+                self.write_str(info.key_ty().path());
+                self.write_str(info.value_ty().path());
+                visit(info.key_ty().id());
+                visit(info.value_ty().id());
+            }
+            TypeInfo::Set(info) => {
+                self.write_str(info.value_ty().path());
+                visit(info.value_ty().id());
+            }
+            TypeInfo::Enum(info) => {
+                self.write_usize(info.variant_len());
+                for variant in info.iter() {
+                    self.write_str(variant.name());
+                    match variant {
+                        VariantInfo::Struct(variant) => {
+                            self.write_u8(0);
+                            self.write_usize(variant.field_len());
+                            for field in variant.iter() {
+                                self.write_str(field.name());
+                                self.write_str(field.type_path());
+                                visit(field.type_id());
+                            }
+                        }
+                        VariantInfo::Tuple(variant) => {
+                            self.write_u8(1);
+                            self.write_usize(variant.field_len());
+                            for field in variant.iter() {
+                                self.write_str(field.type_path());
+                                visit(field.type_id());
+                            }
+                        }
+                        VariantInfo::Unit(_) => self.write_u8(2),
+                    }
+                }
+            }
+            TypeInfo::Opaque(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Reflect;
+
+    mod v1 {
+        use crate::Reflect;
+
+        #[derive(Reflect)]
+        #[type_path = "game"]
+        pub struct Save {
+            pub level: u32,
+            pub score: u64,
+        }
+    }
+
+    mod v2 {
+        use crate::Reflect;
+
+        #[derive(Reflect)]
+        #[type_path = "game"]
+        pub struct Save {
+            pub level: u32,
+            pub score: u32,
+        }
+    }
+
+    #[derive(Reflect)]
+    struct Unrelated;
+
+    #[test]
+    fn fingerprint_of_type() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<v1::Save>();
+        let fingerprint = SchemaFingerprint::of_type::<v1::Save>(&registry);
+
+        registry.register::<Unrelated>();
+        assert_eq!(
+            fingerprint,
+            SchemaFingerprint::of_type::<v1::Save>(&registry)
+        );
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<v2::Save>();
+        assert_ne!(
+            fingerprint,
+            SchemaFingerprint::of_type::<v2::Save>(&registry)
+        );
+    }
+
+    #[test]
+    fn fingerprint_of_registry() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<v1::Save>();
+        let fingerprint = SchemaFingerprint::of_registry(&registry);
+        assert_eq!(fingerprint, SchemaFingerprint::of_registry(&registry));
+
+        registry.register::<Unrelated>();
+        assert_ne!(fingerprint, SchemaFingerprint::of_registry(&registry));
+    }
+}
@@ -0,0 +1,743 @@
+//! A compact binary encoding for reflected values.
+//!
+//! [`ReflectSerializer`] and [`TypedReflectSerializer`] are built around self-describing
+//! formats, where field names, map keys and enum variants are written out alongside the data.
+//! This module instead uses the [`TypeInfo`] of each value to write only its data:
+//! struct fields are written in declaration order without their names,
+//! enum variants are written as their index, and collections are prefixed with their length.
+//! Integers are written as variable-length integers, so small values take a single byte.
+//!
+//! Because the encoding relies on both sides agreeing on the shape of every type,
+//! a [`SchemaFingerprint`] can be computed from the [`TypeRegistry`] and stored or sent
+//! alongside the data to detect mismatches, for example in save files or network messages.
+//!
+//! Types that register [`ReflectSerialize`] and [`ReflectDeserialize`]
+//! (or [`ReflectSerializeWithRegistry`] and [`ReflectDeserializeWithRegistry`])
+//! are written with their serde implementations, using an equally compact,
+//! non-self-describing format. Opaque types must register one of these pairs.
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy_reflect::{FromReflect, GetTypeRegistration, Reflect, TypeRegistry};
+//! # use bevy_reflect::serde::binary::{BinaryReflectDeserializer, BinaryReflectSerializer, SchemaFingerprint};
+//! #[derive(Reflect, PartialEq, Debug)]
+//! struct Player {
+//!     name: String,
+//!     health: u32,
+//!     position: (f32, f32),
+//! }
+//!
+//! let mut registry = TypeRegistry::default();
+//! registry.register::<Player>();
+//!
+//! let player = Player {
+//!     name: String::from("Ferris"),
+//!     health: 100,
+//!     position: (1.0, 2.0),
+//! };
+//!
+//! let fingerprint = SchemaFingerprint::of_type::<Player>(&registry);
+//! let bytes = BinaryReflectSerializer::new(&registry).to_bytes(&player).unwrap();
+//! // 7 bytes for the name, 1 for the health and 8 for the position.
+//! assert_eq!(bytes.len(), 16);
+//!
+//! // On load, compare the stored fingerprint before decoding the data.
+//! assert_eq!(fingerprint, SchemaFingerprint::of_type::<Player>(&registry));
+//! let registration = registry.get(core::any::TypeId::of::<Player>()).unwrap();
+//! let value = BinaryReflectDeserializer::new(&registry)
+//!     .from_bytes(registration, &bytes)
+//!     .unwrap();
+//! assert_eq!(Player::from_reflect(value.as_ref()), Some(player));
+//! ```
+//!
+//! [`ReflectSerializer`]: crate::serde::ReflectSerializer
+//! [`TypedReflectSerializer`]: crate::serde::TypedReflectSerializer
+
+mod fingerprint;
+mod format;
+
+pub use fingerprint::*;
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use thiserror::Error;
+
+use self::format::{Reader, Writer};
+use crate::{
+    serde::{ReflectDeserializeWithRegistry, ReflectSerializeWithRegistry, SerializationData},
+    DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicSet, DynamicStruct, DynamicTuple,
+    DynamicTupleStruct, DynamicVariant, Map, PartialReflect, Reflect, ReflectDeserialize,
+    ReflectFromReflect, ReflectKind, ReflectRef, ReflectSerialize, Set, Type, TypeInfo,
+    TypeRegistration, TypeRegistry, VariantInfo,
+};
+
+/// An error that occurs when writing or reading the binary encoding of a reflected value.
+#[derive(Error, Debug)]
+pub enum BinaryReflectError {
+    /// The type of a value is not registered in the [`TypeRegistry`].
+    #[error("type `{type_path}` is not registered in the type registry")]
+    NotRegistered {
+        /// The type path of the unregistered type.
+        type_path: String,
+    },
+    /// A value does not represent a type, so its shape is unknown.
+    #[error("value of type `{type_path}` does not have type info")]
+    MissingTypeInfo {
+        /// The type path of the value.
+        type_path: String,
+    },
+    /// The type is opaque and does not register serde type data.
+    #[error(
+        "type `{type_path}` is opaque and must register `ReflectSerialize` and `ReflectDeserialize` to be encoded"
+    )]
+    Unsupported {
+        /// The type path of the unsupported type.
+        type_path: String,
+    },
+    /// The value does not have the kind of the type it represents.
+    #[error("expected a `{expected}` value for `{type_path}`, found a `{received}` value")]
+    MismatchedKinds {
+        /// The type path of the represented type.
+        type_path: String,
+        /// The kind of the represented type.
+        expected: ReflectKind,
+        /// The kind of the value.
+        received: ReflectKind,
+    },
+    /// The value is missing a field of the type it represents.
+    #[error("value of type `{type_path}` is missing field `{field}`")]
+    MissingField {
+        /// The type path of the represented type.
+        type_path: String,
+        /// The name or index of the missing field.
+        field: String,
+    },
+    /// The value has a variant that its type does not have, or the input contains an invalid variant index.
+    #[error("type `{type_path}` does not have variant `{variant}`")]
+    InvalidVariant {
+        /// The type path of the enum.
+        type_path: String,
+        /// The name or index of the variant.
+        variant: String,
+    },
+    /// The input ended before the value was fully read.
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+    /// The input contains bytes after the value.
+    #[error("{0} bytes remain after the value")]
+    TrailingBytes(usize),
+    /// The input or value is malformed.
+    #[error("invalid data: {0}")]
+    InvalidData(&'static str),
+    /// A custom error from a serde implementation.
+    #[error("{0}")]
+    Custom(String),
+}
+
+/// How the values of a type are encoded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// With [`ReflectSerialize`] and [`ReflectDeserialize`].
+    Serde,
+    /// With [`ReflectSerializeWithRegistry`] and [`ReflectDeserializeWithRegistry`].
+    SerdeWithRegistry,
+    /// Field by field, based on the [`TypeInfo`] of the type.
+    Reflect,
+}
+
+impl Encoding {
+    fn of(registration: &TypeRegistration) -> Self {
+        if registration.contains::<ReflectSerialize>()
+            && registration.contains::<ReflectDeserialize>()
+        {
+            Encoding::Serde
+        } else if registration.contains::<ReflectSerializeWithRegistry>()
+            && registration.contains::<ReflectDeserializeWithRegistry>()
+        {
+            Encoding::SerdeWithRegistry
+        } else {
+            Encoding::Reflect
+        }
+    }
+}
+
+fn get_registration<'a>(
+    registry: &'a TypeRegistry,
+    info: &TypeInfo,
+) -> Result<&'a TypeRegistration, BinaryReflectError> {
+    registry
+        .get(info.type_id())
+        .ok_or_else(|| BinaryReflectError::NotRegistered {
+            type_path: info.type_path().to_string(),
+        })
+}
+
+fn is_skipped(registration: &TypeRegistration, index: usize) -> bool {
+    registration
+        .data::<SerializationData>()
+        .is_some_and(|data| data.is_field_skipped(index))
+}
+
+/// Writes reflected values in the compact binary encoding.
+///
+/// See the [module-level documentation](self) for details on the encoding.
+pub struct BinaryReflectSerializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> BinaryReflectSerializer<'a> {
+    /// Creates a serializer that looks up the types of values in `registry`.
+    pub fn new(registry: &'a TypeRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Encodes `value` and returns the encoded bytes.
+    pub fn to_bytes(&self, value: &dyn PartialReflect) -> Result<Vec<u8>, BinaryReflectError> {
+        let mut bytes = Vec::new();
+        self.serialize(value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Encodes `value`, appending the encoded bytes to `bytes`.
+    ///
+    /// Only the data of the value is written, not its type.
+    /// On error, `bytes` may contain part of the encoded value.
+    pub fn serialize(
+        &self,
+        value: &dyn PartialReflect,
+        bytes: &mut Vec<u8>,
+    ) -> Result<(), BinaryReflectError> {
+        self.write_value(value, &mut Writer { bytes })
+    }
+
+    fn write_value(
+        &self,
+        value: &dyn PartialReflect,
+        writer: &mut Writer,
+    ) -> Result<(), BinaryReflectError> {
+        let info = value.get_represented_type_info().ok_or_else(|| {
+            BinaryReflectError::MissingTypeInfo {
+                type_path: value.reflect_type_path().to_string(),
+            }
+        })?;
+        let registration = get_registration(self.registry, info)?;
+
+        match Encoding::of(registration) {
+            Encoding::Serde => {
+                let concrete = Self::concrete(value, registration)?;
+                let value = concrete.as_deref().or(value.try_as_reflect()).unwrap();
+                return erased_serde::serialize(
+                    &*registration
+                        .data::<ReflectSerialize>()
+                        .unwrap()
+                        .get_serializable(value),
+                    writer,
+                );
+            }
+            Encoding::SerdeWithRegistry => {
+                let concrete = Self::concrete(value, registration)?;
+                let value = concrete.as_deref().or(value.try_as_reflect()).unwrap();
+                return registration
+                    .data::<ReflectSerializeWithRegistry>()
+                    .unwrap()
+                    .serialize(value, writer, self.registry);
+            }
+            Encoding::Reflect => {}
+        }
+
+        let mismatched_kinds = || BinaryReflectError::MismatchedKinds {
+            type_path: info.type_path().to_string(),
+            expected: info.kind(),
+            received: value.reflect_kind(),
+        };
+        let missing_field = |field: String| BinaryReflectError::MissingField {
+            type_path: info.type_path().to_string(),
+            field,
+        };
+
+        match (info, value.reflect_ref()) {
+            (TypeInfo::Struct(info), ReflectRef::Struct(value)) => {
+                for (index, field) in info.iter().enumerate() {
+                    if is_skipped(registration, index) {
+                        continue;
+                    }
+                    let field = value
+                        .field(field.name())
+                        .ok_or_else(|| missing_field(field.name().to_string()))?;
+                    self.write_value(field, writer)?;
+                }
+            }
+            (TypeInfo::TupleStruct(info), ReflectRef::TupleStruct(value)) => {
+                for index in 0..info.field_len() {
+                    if is_skipped(registration, index) {
+                        continue;
+                    }
+                    let field = value
+                        .field(index)
+                        .ok_or_else(|| missing_field(index.to_string()))?;
+                    self.write_value(field, writer)?;
+                }
+            }
+            (TypeInfo::Tuple(info), ReflectRef::Tuple(value)) => {
+                for index in 0..info.field_len() {
+                    let field = value
+                        .field(index)
+                        .ok_or_else(|| missing_field(index.to_string()))?;
+                    self.write_value(field, writer)?;
+                }
+            }
+            (TypeInfo::List(_), ReflectRef::List(value)) => {
+                writer.write_len(value.len());
+                for item in value.iter() {
+                    self.write_value(item, writer)?;
+                }
+            }
+            (TypeInfo::Array(info), ReflectRef::Array(value)) => {
+                if value.len() != info.capacity() {
+                    return Err(BinaryReflectError::InvalidData(
+                        "array length does not match its type",
+                    ));
+                }
+                for item in value.iter() {
+                    self.write_value(item, writer)?;
+                }
+            }
+            (TypeInfo::Map(_), ReflectRef::Map(value)) => {
+                writer.write_len(value.len());
+                for (key, value) in value.iter() {
+                    self.write_value(key, writer)?;
+                    self.write_value(value, writer)?;
+                }
+            }
+            (TypeInfo::Set(_), ReflectRef::Set(value)) => {
+                writer.write_len(value.len());
+                for item in value.iter() {
+                    self.write_value(item, writer)?;
+                }
+            }
+            (TypeInfo::Enum(info), ReflectRef::Enum(value)) => {
+                let variant_name = value.variant_name();
+                let (index, variant) = info
+                    .index_of(variant_name)
+                    .zip(info.variant(variant_name))
+                    .ok_or_else(|| BinaryReflectError::InvalidVariant {
+                        type_path: info.type_path().to_string(),
+                        variant: variant_name.to_string(),
+                    })?;
+                writer.write_len(index);
+                match variant {
+                    VariantInfo::Struct(variant) => {
+                        for field in variant.iter() {
+                            let field = value
+                                .field(field.name())
+                                .ok_or_else(|| missing_field(field.name().to_string()))?;
+                            self.write_value(field, writer)?;
+                        }
+                    }
+                    VariantInfo::Tuple(variant) => {
+                        for index in 0..variant.field_len() {
+                            let field = value
+                                .field_at(index)
+                                .ok_or_else(|| missing_field(index.to_string()))?;
+                            self.write_value(field, writer)?;
+                        }
+                    }
+                    VariantInfo::Unit(_) => {}
+                }
+            }
+            (TypeInfo::Opaque(info), _) => {
+                return Err(BinaryReflectError::Unsupported {
+                    type_path: info.type_path().to_string(),
+                });
+            }
+            _ => return Err(mismatched_kinds()),
+        }
+        Ok(())
+    }
+
+    /// Converts a dynamic value to its concrete type so that its serde implementation can be used.
+    ///
+    /// Returns `None` if `value` already is of the concrete type.
+    fn concrete(
+        value: &dyn PartialReflect,
+        registration: &TypeRegistration,
+    ) -> Result<Option<Box<dyn Reflect>>, BinaryReflectError> {
+        if value
+            .try_as_reflect()
+            .is_some_and(|value| value.type_id() == registration.type_id())
+        {
+            return Ok(None);
+        }
+        registration
+            .data::<ReflectFromReflect>()
+            .and_then(|from_reflect| from_reflect.from_reflect(value))
+            .map(Some)
+            .ok_or_else(|| {
+                BinaryReflectError::Custom(alloc::format!(
+                    "dynamic value of type `{}` could not be converted with `ReflectFromReflect`",
+                    registration.type_info().type_path()
+                ))
+            })
+    }
+}
+
+/// Reads reflected values from the compact binary encoding.
+///
+/// Values are returned as dynamic types, except for those encoded with their serde implementations.
+/// Use [`FromReflect`](crate::FromReflect) or [`ReflectFromReflect`] to convert them to their concrete type.
+///
+/// See the [module-level documentation](self) for details on the encoding.
+pub struct BinaryReflectDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> BinaryReflectDeserializer<'a> {
+    /// Creates a deserializer that looks up the types of fields in `registry`.
+    pub fn new(registry: &'a TypeRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Decodes a value of the type of `registration` from `bytes`.
+    ///
+    /// Returns an error if `bytes` contains more data than the value.
+    pub fn from_bytes(
+        &self,
+        registration: &TypeRegistration,
+        mut bytes: &[u8],
+    ) -> Result<Box<dyn PartialReflect>, BinaryReflectError> {
+        let value = self.deserialize(registration, &mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(BinaryReflectError::TrailingBytes(bytes.len()));
+        }
+        Ok(value)
+    }
+
+    /// Decodes a value of the type of `registration` from the start of `bytes`,
+    /// advancing `bytes` past the decoded value.
+    pub fn deserialize(
+        &self,
+        registration: &TypeRegistration,
+        bytes: &mut &[u8],
+    ) -> Result<Box<dyn PartialReflect>, BinaryReflectError> {
+        let mut reader = Reader { bytes };
+        let value = self.read_value(registration, &mut reader);
+        *bytes = reader.bytes;
+        value
+    }
+
+    fn read_field(
+        &self,
+        ty: &Type,
+        reader: &mut Reader,
+    ) -> Result<Box<dyn PartialReflect>, BinaryReflectError> {
+        let registration =
+            self.registry
+                .get(ty.id())
+                .ok_or_else(|| BinaryReflectError::NotRegistered {
+                    type_path: ty.path().to_string(),
+                })?;
+        self.read_value(registration, reader)
+    }
+
+    fn read_value(
+        &self,
+        registration: &TypeRegistration,
+        reader: &mut Reader,
+    ) -> Result<Box<dyn PartialReflect>, BinaryReflectError> {
+        match Encoding::of(registration) {
+            Encoding::Serde => {
+                return registration
+                    .data::<ReflectDeserialize>()
+                    .unwrap()
+                    .deserialize(reader)
+                    .map(<dyn Reflect>::into_partial_reflect);
+            }
+            Encoding::SerdeWithRegistry => {
+                return registration
+                    .data::<ReflectDeserializeWithRegistry>()
+                    .unwrap()
+                    .deserialize(reader, self.registry);
+            }
+            Encoding::Reflect => {}
+        }
+
+        let type_info = registration.type_info();
+
+        Ok(match type_info {
+            TypeInfo::Struct(info) => {
+                let mut value = DynamicStruct::default();
+                for (index, field) in info.iter().enumerate() {
+                    if is_skipped(registration, index) {
+                        if let Some(default) = registration
+                            .data::<SerializationData>()
+                            .and_then(|data| data.generate_default(index))
+                        {
+                            value.insert_boxed(field.name(), default.into_partial_reflect());
+                        }
+                        continue;
+                    }
+                    value.insert_boxed(field.name(), self.read_field(field.ty(), reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::TupleStruct(info) => {
+                let mut value = DynamicTupleStruct::default();
+                for (index, field) in info.iter().enumerate() {
+                    if is_skipped(registration, index) {
+                        if let Some(default) = registration
+                            .data::<SerializationData>()
+                            .and_then(|data| data.generate_default(index))
+                        {
+                            value.insert_boxed(default.into_partial_reflect());
+                        }
+                        continue;
+                    }
+                    value.insert_boxed(self.read_field(field.ty(), reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Tuple(info) => {
+                let mut value = DynamicTuple::default();
+                for field in info.iter() {
+                    value.insert_boxed(self.read_field(field.ty(), reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::List(info) => {
+                let item_ty = info.item_ty();
+                let mut value = DynamicList::default();
+                for _ in 0..reader.read_len()? {
+                    value.push_box(self.read_field(&item_ty, reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Array(info) => {
+                let item_ty = info.item_ty();
+                let items = (0..info.capacity())
+                    .map(|_| self.read_field(&item_ty, reader))
+                    .collect::<Result<Box<[_]>, _>>()?;
+                let mut value = DynamicArray::new(items);
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Map(info) => {
+                let key_ty = info.key_ty();
+                let value_ty = info.value_ty();
+                let mut value = DynamicMap::default();
+                for _ in 0..reader.read_len()? {
+                    let key = self.read_field(&key_ty, reader)?;
+                    value.insert_boxed(key, self.read_field(&value_ty, reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Set(info) => {
+                let value_ty = info.value_ty();
+                let mut value = DynamicSet::default();
+                for _ in 0..reader.read_len()? {
+                    value.insert_boxed(self.read_field(&value_ty, reader)?);
+                }
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Enum(info) => {
+                let index = reader.read_index()?;
+                let variant =
+                    info.variant_at(index)
+                        .ok_or_else(|| BinaryReflectError::InvalidVariant {
+                            type_path: info.type_path().to_string(),
+                            variant: index.to_string(),
+                        })?;
+                let dynamic_variant = match variant {
+                    VariantInfo::Struct(variant) => {
+                        let mut fields = DynamicStruct::default();
+                        for field in variant.iter() {
+                            fields.insert_boxed(field.name(), self.read_field(field.ty(), reader)?);
+                        }
+                        DynamicVariant::Struct(fields)
+                    }
+                    VariantInfo::Tuple(variant) => {
+                        let mut fields = DynamicTuple::default();
+                        for field in variant.iter() {
+                            fields.insert_boxed(self.read_field(field.ty(), reader)?);
+                        }
+                        DynamicVariant::Tuple(fields)
+                    }
+                    VariantInfo::Unit(_) => DynamicVariant::Unit,
+                };
+                let mut value = DynamicEnum::new_with_index(index, variant.name(), dynamic_variant);
+                value.set_represented_type(Some(type_info));
+                Box::new(value)
+            }
+            TypeInfo::Opaque(info) => {
+                return Err(BinaryReflectError::Unsupported {
+                    type_path: info.type_path().to_string(),
+                });
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromReflect;
+    use alloc::{string::String, vec, vec::Vec};
+    use bevy_platform::collections::HashMap;
+    use core::any::TypeId;
+
+    #[derive(Reflect, Debug, PartialEq)]
+    struct Inventory {
+        owner: String,
+        slots: Vec<Option<Item>>,
+        counts: HashMap<u32, i64>,
+        grid: [u8; 3],
+        #[reflect(skip_serializing)]
+        cached_weight: f32,
+    }
+
+    #[derive(Reflect, Debug, PartialEq)]
+    enum Item {
+        Empty,
+        Potion(u8),
+        Weapon { damage: f32, name: String },
+    }
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Inventory>();
+        registry
+    }
+
+    fn inventory() -> Inventory {
+        Inventory {
+            owner: String::from("Ferris"),
+            slots: vec![
+                None,
+                Some(Item::Empty),
+                Some(Item::Potion(3)),
+                Some(Item::Weapon {
+                    damage: 1.5,
+                    name: String::from("Claw"),
+                }),
+            ],
+            counts: [(1, -5), (300, 70_000)].into_iter().collect(),
+            grid: [1, 2, 3],
+            cached_weight: 12.0,
+        }
+    }
+
+    fn roundtrip<T: Reflect + FromReflect>(registry: &TypeRegistry, value: &T) -> T {
+        let bytes = BinaryReflectSerializer::new(registry)
+            .to_bytes(value)
+            .unwrap();
+        let registration = registry.get(TypeId::of::<T>()).unwrap();
+        let output = BinaryReflectDeserializer::new(registry)
+            .from_bytes(registration, &bytes)
+            .unwrap();
+        T::from_reflect(output.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn roundtrip_concrete_value() {
+        let registry = registry();
+        let output = roundtrip(&registry, &inventory());
+        assert_eq!(
+            output,
+            Inventory {
+                cached_weight: 0.0,
+                ..inventory()
+            }
+        );
+    }
+
+    #[test]
+    fn roundtrip_dynamic_value() {
+        let registry = registry();
+        let dynamic = inventory().to_dynamic();
+        let serializer = BinaryReflectSerializer::new(&registry);
+        assert_eq!(
+            serializer.to_bytes(dynamic.as_ref()).unwrap(),
+            serializer.to_bytes(&inventory()).unwrap()
+        );
+    }
+
+    #[test]
+    fn encoding_is_compact() {
+        #[derive(Reflect)]
+        struct Small {
+            a: u32,
+            b: i64,
+            c: bool,
+            d: Option<u16>,
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Small>();
+
+        let bytes = BinaryReflectSerializer::new(&registry)
+            .to_bytes(&Small {
+                a: 5,
+                b: -1,
+                c: true,
+                d: None,
+            })
+            .unwrap();
+        assert_eq!(bytes, [5, 1, 1, 0]);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let registry = registry();
+        let bytes = BinaryReflectSerializer::new(&registry)
+            .to_bytes(&inventory())
+            .unwrap();
+        let registration = registry.get(TypeId::of::<Inventory>()).unwrap();
+        let deserializer = BinaryReflectDeserializer::new(&registry);
+
+        // Errors of values read with their serde implementation are converted to custom errors.
+        assert!(deserializer
+            .from_bytes(registration, &bytes[..bytes.len() - 1])
+            .is_err());
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(matches!(
+            deserializer.from_bytes(registration, &extended),
+            Err(BinaryReflectError::TrailingBytes(1))
+        ));
+
+        let mut bytes = bytes.as_slice();
+        assert!(deserializer.deserialize(registration, &mut bytes).is_ok());
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn unregistered_opaque_type() {
+        #[derive(Reflect, Clone)]
+        #[reflect(opaque)]
+        struct Handle(u32);
+
+        #[derive(Reflect)]
+        struct Holder {
+            handle: Handle,
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Holder>();
+
+        assert!(matches!(
+            BinaryReflectSerializer::new(&registry).to_bytes(&Holder { handle: Handle(1) }),
+            Err(BinaryReflectError::Unsupported { .. })
+        ));
+    }
+}
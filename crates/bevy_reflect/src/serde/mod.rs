@@ -1,5 +1,6 @@
 //! Serde integration for reflected types.
 
+pub mod binary;
 mod de;
 mod ser;
 mod type_data;
@@ -0,0 +1,695 @@
+//! Constraint attributes for reflected fields, and functions for checking values against them.
+//!
+//! Fields can be annotated with the constraint attributes of this module using the
+//! [custom attribute](crate::attributes) syntax of the [`Reflect` derive macro]:
+//!
+//! - [`Range`] for numbers that must be within an inclusive range,
+//! - [`MinLen`] and [`MaxLen`] for strings and collections with a bounded length,
+//! - [`NonEmpty`] for strings and collections that must not be empty,
+//! - [`Matches`] for strings that must match a pattern, such as a regular expression.
+//!
+//! [`validate`] walks a value and reports every [`Violation`] along with the
+//! [path](crate::GetPath) of the offending field, and [`try_apply_validated`] only
+//! applies a change to a value if the result is valid.
+//! This lets scene loading, remote mutations and inspectors reject invalid data consistently.
+//!
+//! Types can add their own rules by implementing [`Validate`] and registering [`ReflectValidate`].
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy_reflect::{Reflect, TypeRegistry};
+//! # use bevy_reflect::validation::{validate, MaxLen, NonEmpty, Range, ViolationKind};
+//! #[derive(Reflect)]
+//! struct Player {
+//!     #[reflect(@NonEmpty, @MaxLen(16))]
+//!     name: String,
+//!     #[reflect(@Range(0.0..=100.0))]
+//!     health: u32,
+//! }
+//!
+//! let registry = TypeRegistry::default();
+//! let player = Player {
+//!     name: String::from("Ferris"),
+//!     health: 150,
+//! };
+//!
+//! let violations = validate(&player, &registry);
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].path, ".health");
+//! assert!(matches!(violations[0].kind, ViolationKind::OutOfRange { .. }));
+//! ```
+//!
+//! [`Reflect` derive macro]: derive@crate::Reflect
+
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, ops::RangeInclusive};
+
+use thiserror::Error;
+
+use crate::{
+    attributes::CustomAttributes, reflect_trait, ApplyError, NamedField, PartialReflect, Reflect,
+    ReflectFromReflect, ReflectRef, TypeRegistry, UnnamedField, VariantInfo,
+};
+
+/// Requires a number to be within an inclusive range.
+///
+/// The value of any primitive number type is converted to `f64` before being compared,
+/// so the bounds are always given as floats, even for integer fields.
+///
+/// ```
+/// # use bevy_reflect::{Reflect, validation::Range};
+/// #[derive(Reflect)]
+/// struct Volume {
+///     #[reflect(@Range(0.0..=1.0))]
+///     level: f32,
+/// }
+/// ```
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct Range(pub RangeInclusive<f64>);
+
+/// Requires a string or collection to have at least the given length.
+///
+/// The length of a string is its number of characters.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinLen(pub usize);
+
+/// Requires a string or collection to have at most the given length.
+///
+/// The length of a string is its number of characters.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxLen(pub usize);
+
+/// Requires a string or collection to not be empty.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonEmpty;
+
+/// Requires a string to match a pattern.
+///
+/// The pattern is given as a function, so that any matcher can be used.
+/// For example, with the `regex` crate:
+///
+/// ```ignore
+/// static IDENTIFIER: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[a-z_]+$").unwrap());
+///
+/// #[derive(Reflect)]
+/// struct Action {
+///     #[reflect(@Matches::new("^[a-z_]+$", |name| IDENTIFIER.is_match(name)))]
+///     name: String,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy)]
+#[reflect(opaque)]
+pub struct Matches {
+    /// A description of the pattern, used in violation messages.
+    pub description: &'static str,
+    /// Returns `true` if a string matches the pattern.
+    pub predicate: fn(&str) -> bool,
+}
+
+impl Matches {
+    /// Creates a new `Matches` attribute.
+    pub const fn new(description: &'static str, predicate: fn(&str) -> bool) -> Self {
+        Self {
+            description,
+            predicate,
+        }
+    }
+}
+
+impl fmt::Debug for Matches {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Matches").field(&self.description).finish()
+    }
+}
+
+/// Custom validation rules for a type.
+///
+/// [`validate`] calls this for every value whose type registers [`ReflectValidate`],
+/// in addition to checking the constraint attributes of its fields.
+///
+/// ```
+/// # use bevy_reflect::{Reflect, TypeRegistry};
+/// # use bevy_reflect::validation::{validate, Validate, ReflectValidate};
+/// #[derive(Reflect)]
+/// #[reflect(Validate)]
+/// struct Bounds {
+///     min: f32,
+///     max: f32,
+/// }
+///
+/// impl Validate for Bounds {
+///     fn validate(&self) -> Result<(), String> {
+///         if self.min <= self.max {
+///             Ok(())
+///         } else {
+///             Err(String::from("`min` must not be larger than `max`"))
+///         }
+///     }
+/// }
+///
+/// let mut registry = TypeRegistry::default();
+/// registry.register::<Bounds>();
+///
+/// assert_eq!(validate(&Bounds { min: 2.0, max: 1.0 }, &registry).len(), 1);
+/// ```
+#[reflect_trait]
+pub trait Validate {
+    /// Returns an error describing why this value is invalid, if it is.
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// A constraint that a reflected value does not satisfy.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("`{path}`: {kind}")]
+pub struct Violation {
+    /// The [path](crate::GetPath) of the invalid value, relative to the validated value.
+    ///
+    /// The path is empty for the validated value itself.
+    /// Entries of maps and sets are identified by the debug representation of their key.
+    pub path: String,
+    /// The violated constraint.
+    pub kind: ViolationKind,
+}
+
+/// The kind of a [`Violation`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// The number is outside of its [`Range`].
+    #[error("{value} is outside of the range {}..={}", range.start(), range.end())]
+    OutOfRange {
+        /// The number.
+        value: f64,
+        /// The allowed range.
+        range: RangeInclusive<f64>,
+    },
+    /// The length is lower than its [`MinLen`].
+    #[error("length {len} is lower than the minimum of {min}")]
+    TooShort {
+        /// The length of the value.
+        len: usize,
+        /// The minimum length.
+        min: usize,
+    },
+    /// The length is greater than its [`MaxLen`].
+    #[error("length {len} is greater than the maximum of {max}")]
+    TooLong {
+        /// The length of the value.
+        len: usize,
+        /// The maximum length.
+        max: usize,
+    },
+    /// The value is empty despite being [`NonEmpty`].
+    #[error("value must not be empty")]
+    Empty,
+    /// The string does not match its [`Matches`] pattern.
+    #[error("value does not match `{pattern}`")]
+    Mismatch {
+        /// The description of the pattern.
+        pattern: &'static str,
+    },
+    /// The [`Validate`] implementation of the type returned an error.
+    #[error("{0}")]
+    Custom(String),
+    /// A constraint attribute was used on a field of a type it does not support,
+    /// such as [`Range`] on a string.
+    #[error("`{attribute}` cannot be applied to a value of type `{type_path}`")]
+    Unsupported {
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The type path of the value.
+        type_path: String,
+    },
+}
+
+/// An error returned by [`try_apply_validated`].
+#[derive(Error, Debug)]
+pub enum ValidatedApplyError {
+    /// The value could not be applied.
+    #[error(transparent)]
+    Apply(#[from] ApplyError),
+    /// The result of applying the value would not be valid.
+    #[error("the result would be invalid: {}", format_violations(.0))]
+    Invalid(Vec<Violation>),
+}
+
+fn format_violations(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Checks `value` and all of its fields against their constraint attributes
+/// and [`Validate`] implementations, returning every violation found.
+///
+/// Constraint attributes are read from the [type info](crate::TypeInfo) of the structs,
+/// tuple structs and enums containing the fields, so dynamic values must
+/// [represent](PartialReflect::get_represented_type_info) a type for their fields to be checked.
+/// The registry is used to find [`ReflectValidate`] type data.
+pub fn validate(value: &dyn PartialReflect, registry: &TypeRegistry) -> Vec<Violation> {
+    let mut validator = Validator {
+        registry,
+        path: String::new(),
+        violations: Vec::new(),
+    };
+    validator.visit(value, None);
+    validator.violations
+}
+
+/// Applies `value` to `target` if the result passes [`validate`], leaving `target` unchanged otherwise.
+///
+/// # Panics
+///
+/// Panics if `target` contains an [opaque](crate::ReflectKind::Opaque) value
+/// that cannot be cloned with [`PartialReflect::reflect_clone`].
+pub fn try_apply_validated(
+    target: &mut dyn PartialReflect,
+    value: &dyn PartialReflect,
+    registry: &TypeRegistry,
+) -> Result<(), ValidatedApplyError> {
+    let mut candidate = target.to_dynamic();
+    candidate.try_apply(value)?;
+    let violations = validate(candidate.as_ref(), registry);
+    if !violations.is_empty() {
+        return Err(ValidatedApplyError::Invalid(violations));
+    }
+    target.try_apply(candidate.as_ref())?;
+    Ok(())
+}
+
+struct Validator<'a> {
+    registry: &'a TypeRegistry,
+    path: String,
+    violations: Vec<Violation>,
+}
+
+impl Validator<'_> {
+    fn violation(&mut self, kind: ViolationKind) {
+        self.violations.push(Violation {
+            path: self.path.clone(),
+            kind,
+        });
+    }
+
+    /// Validates `value`, which is at the current path, along with its fields.
+    fn visit(&mut self, value: &dyn PartialReflect, attributes: Option<&CustomAttributes>) {
+        if let Some(attributes) = attributes {
+            self.check_attributes(value, attributes);
+        }
+        self.check_type(value);
+
+        match value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                let info = value.get_represented_struct_info();
+                for (index, field) in value.iter_fields().enumerate() {
+                    let Some(name) = value.name_at(index) else {
+                        continue;
+                    };
+                    let attributes = info
+                        .and_then(|info| info.field(name))
+                        .map(NamedField::custom_attributes);
+                    self.visit_at(format_args!(".{name}"), field, attributes);
+                }
+            }
+            ReflectRef::TupleStruct(value) => {
+                let info = value.get_represented_tuple_struct_info();
+                for (index, field) in value.iter_fields().enumerate() {
+                    let attributes = info
+                        .and_then(|info| info.field_at(index))
+                        .map(UnnamedField::custom_attributes);
+                    self.visit_at(format_args!(".{index}"), field, attributes);
+                }
+            }
+            ReflectRef::Tuple(value) => {
+                for (index, field) in value.iter_fields().enumerate() {
+                    self.visit_at(format_args!(".{index}"), field, None);
+                }
+            }
+            ReflectRef::List(value) => {
+                for (index, item) in value.iter().enumerate() {
+                    self.visit_at(format_args!("[{index}]"), item, None);
+                }
+            }
+            ReflectRef::Array(value) => {
+                for (index, item) in value.iter().enumerate() {
+                    self.visit_at(format_args!("[{index}]"), item, None);
+                }
+            }
+            ReflectRef::Map(value) => {
+                for (key, value) in value.iter() {
+                    self.visit_at(format_args!("[{key:?}]"), value, None);
+                }
+            }
+            ReflectRef::Set(value) => {
+                for item in value.iter() {
+                    self.visit_at(format_args!("[{item:?}]"), item, None);
+                }
+            }
+            ReflectRef::Enum(value) => {
+                let variant = value
+                    .get_represented_enum_info()
+                    .and_then(|info| info.variant(value.variant_name()));
+                for (index, field) in value.iter_fields().enumerate() {
+                    match field.name() {
+                        Some(name) => {
+                            let attributes = match variant {
+                                Some(VariantInfo::Struct(variant)) => {
+                                    variant.field(name).map(NamedField::custom_attributes)
+                                }
+                                _ => None,
+                            };
+                            self.visit_at(format_args!(".{name}"), field.value(), attributes);
+                        }
+                        None => {
+                            let attributes = match variant {
+                                Some(VariantInfo::Tuple(variant)) => {
+                                    variant.field_at(index).map(UnnamedField::custom_attributes)
+                                }
+                                _ => None,
+                            };
+                            self.visit_at(format_args!(".{index}"), field.value(), attributes);
+                        }
+                    }
+                }
+            }
+            ReflectRef::Opaque(_) => {}
+            #[cfg(feature = "functions")]
+            ReflectRef::Function(_) => {}
+        }
+    }
+
+    fn visit_at(
+        &mut self,
+        segment: fmt::Arguments,
+        value: &dyn PartialReflect,
+        attributes: Option<&CustomAttributes>,
+    ) {
+        let len = self.path.len();
+        fmt::Write::write_fmt(&mut self.path, segment).unwrap();
+        self.visit(value, attributes);
+        self.path.truncate(len);
+    }
+
+    fn check_attributes(&mut self, value: &dyn PartialReflect, attributes: &CustomAttributes) {
+        let unsupported = |attribute| ViolationKind::Unsupported {
+            attribute,
+            type_path: value.reflect_type_path().to_string(),
+        };
+
+        if let Some(Range(range)) = attributes.get::<Range>() {
+            match as_f64(value) {
+                Some(number) if !range.contains(&number) => {
+                    self.violation(ViolationKind::OutOfRange {
+                        value: number,
+                        range: range.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => self.violation(unsupported("Range")),
+            }
+        }
+
+        let len = len(value);
+        if let Some(&MinLen(min)) = attributes.get::<MinLen>() {
+            match len {
+                Some(len) if len < min => self.violation(ViolationKind::TooShort { len, min }),
+                Some(_) => {}
+                None => self.violation(unsupported("MinLen")),
+            }
+        }
+        if let Some(&MaxLen(max)) = attributes.get::<MaxLen>() {
+            match len {
+                Some(len) if len > max => self.violation(ViolationKind::TooLong { len, max }),
+                Some(_) => {}
+                None => self.violation(unsupported("MaxLen")),
+            }
+        }
+        if attributes.contains::<NonEmpty>() {
+            match len {
+                Some(0) => self.violation(ViolationKind::Empty),
+                Some(_) => {}
+                None => self.violation(unsupported("NonEmpty")),
+            }
+        }
+
+        if let Some(matches) = attributes.get::<Matches>() {
+            match as_str(value) {
+                Some(text) if !(matches.predicate)(text) => {
+                    self.violation(ViolationKind::Mismatch {
+                        pattern: matches.description,
+                    });
+                }
+                Some(_) => {}
+                None => self.violation(unsupported("Matches")),
+            }
+        }
+    }
+
+    /// Runs the [`Validate`] implementation of the type of `value`, if it registers one.
+    fn check_type(&mut self, value: &dyn PartialReflect) {
+        let Some(registration) = value
+            .get_represented_type_info()
+            .and_then(|info| self.registry.get(info.type_id()))
+        else {
+            return;
+        };
+        let Some(reflect_validate) = registration.data::<ReflectValidate>() else {
+            return;
+        };
+
+        // Dynamic values need to be converted to their concrete type first.
+        let concrete: Box<dyn Reflect>;
+        let value = match value.try_as_reflect() {
+            Some(value) if value.type_id() == registration.type_id() => value,
+            _ => {
+                let Some(value) = registration
+                    .data::<ReflectFromReflect>()
+                    .and_then(|from_reflect| from_reflect.from_reflect(value))
+                else {
+                    self.violation(ViolationKind::Custom(format!(
+                        "value could not be converted to `{}` to be validated",
+                        registration.type_info().type_path()
+                    )));
+                    return;
+                };
+                concrete = value;
+                concrete.as_ref()
+            }
+        };
+
+        if let Some(Err(error)) = reflect_validate.get(value).map(Validate::validate) {
+            self.violation(ViolationKind::Custom(error));
+        }
+    }
+}
+
+/// Converts a value of a primitive number type to `f64`.
+fn as_f64(value: &dyn PartialReflect) -> Option<f64> {
+    macro_rules! convert {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(&number) = value.try_downcast_ref::<$ty>() {
+                    return Some(number as f64);
+                }
+            )*
+        };
+    }
+
+    convert!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+    None
+}
+
+fn as_str(value: &dyn PartialReflect) -> Option<&str> {
+    if let Some(text) = value.try_downcast_ref::<String>() {
+        Some(text)
+    } else if let Some(text) = value.try_downcast_ref::<Cow<'static, str>>() {
+        Some(text)
+    } else {
+        value.try_downcast_ref::<&'static str>().copied()
+    }
+}
+
+/// Returns the number of characters of a string, or the number of elements of a collection.
+fn len(value: &dyn PartialReflect) -> Option<usize> {
+    match value.reflect_ref() {
+        ReflectRef::List(list) => Some(list.len()),
+        ReflectRef::Array(array) => Some(array.len()),
+        ReflectRef::Map(map) => Some(map.len()),
+        ReflectRef::Set(set) => Some(set.len()),
+        _ => as_str(value).map(|text| text.chars().count()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    #[derive(Reflect, Debug)]
+    struct Settings {
+        #[reflect(@Range(0.0..=1.0))]
+        volume: f32,
+        #[reflect(@NonEmpty, @MaxLen(8))]
+        profile: String,
+        #[reflect(@MinLen(1))]
+        keys: Vec<Key>,
+        mode: Mode,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Key(#[reflect(@Range(0.0..=255.0))] u32);
+
+    #[derive(Reflect, Debug)]
+    enum Mode {
+        Windowed {
+            #[reflect(@Range(1.0..=8192.0))]
+            width: u32,
+        },
+        Fullscreen,
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            volume: 0.5,
+            profile: String::from("default"),
+            keys: vec![Key(32)],
+            mode: Mode::Windowed { width: 1280 },
+        }
+    }
+
+    fn paths(violations: &[Violation]) -> Vec<&str> {
+        violations
+            .iter()
+            .map(|violation| violation.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn valid_value() {
+        assert!(validate(&settings(), &TypeRegistry::default()).is_empty());
+    }
+
+    #[test]
+    fn reports_paths_of_violations() {
+        let settings = Settings {
+            volume: 1.5,
+            profile: String::new(),
+            keys: vec![Key(1), Key(300)],
+            mode: Mode::Windowed { width: 0 },
+        };
+
+        let violations = validate(&settings, &TypeRegistry::default());
+        assert_eq!(
+            paths(&violations),
+            [".volume", ".profile", ".keys[1].0", ".mode.width"]
+        );
+        assert_eq!(violations[1].kind, ViolationKind::Empty);
+        assert_eq!(
+            violations[0].to_string(),
+            "`.volume`: 1.5 is outside of the range 0..=1"
+        );
+    }
+
+    #[test]
+    fn lengths_and_patterns() {
+        #[derive(Reflect)]
+        struct Names {
+            #[reflect(@MaxLen(3))]
+            short: String,
+            #[reflect(@MinLen(2))]
+            list: Vec<u8>,
+            #[reflect(@Matches::new("lowercase", |text| text.chars().all(char::is_lowercase)))]
+            lowercase: String,
+            #[reflect(@Range(0.0..=1.0))]
+            not_a_number: String,
+        }
+
+        let names = Names {
+            short: String::from("abcd"),
+            list: vec![1],
+            lowercase: String::from("Abc"),
+            not_a_number: String::new(),
+        };
+
+        let kinds = validate(&names, &TypeRegistry::default())
+            .into_iter()
+            .map(|violation| violation.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ViolationKind::TooLong { len: 4, max: 3 },
+                ViolationKind::TooShort { len: 1, min: 2 },
+                ViolationKind::Mismatch {
+                    pattern: "lowercase"
+                },
+                ViolationKind::Unsupported {
+                    attribute: "Range",
+                    type_path: String::from("alloc::string::String"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_type_data() {
+        #[derive(Reflect)]
+        #[reflect(Validate)]
+        struct Even(u32);
+
+        impl Validate for Even {
+            fn validate(&self) -> Result<(), String> {
+                if self.0 % 2 == 0 {
+                    Ok(())
+                } else {
+                    Err(String::from("must be even"))
+                }
+            }
+        }
+
+        #[derive(Reflect)]
+        struct Container {
+            values: Vec<Even>,
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Container>();
+
+        let container = Container {
+            values: vec![Even(2), Even(3)],
+        };
+        let violations = validate(&container, &registry);
+        assert_eq!(paths(&violations), [".values[1]"]);
+
+        // Dynamic values are converted to their concrete type.
+        let violations = validate(container.to_dynamic().as_ref(), &registry);
+        assert_eq!(paths(&violations), [".values[1]"]);
+    }
+
+    #[test]
+    fn checked_apply() {
+        let registry = TypeRegistry::default();
+        let mut settings = settings();
+
+        let mut patch = crate::DynamicStruct::default();
+        patch.insert("volume", 2.0f32);
+        assert!(matches!(
+            try_apply_validated(&mut settings, &patch, &registry),
+            Err(ValidatedApplyError::Invalid(violations)) if paths(&violations) == [".volume"]
+        ));
+        assert_eq!(settings.volume, 0.5);
+
+        let mut patch = crate::DynamicStruct::default();
+        patch.insert("volume", 0.25f32);
+        try_apply_validated(&mut settings, &patch, &registry).unwrap();
+        assert_eq!(settings.volume, 0.25);
+    }
+}
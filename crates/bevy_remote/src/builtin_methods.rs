@@ -10,7 +10,10 @@ use bevy_ecs::{
     hierarchy::ChildOf,
     lifecycle::RemovedComponentEntity,
     query::QueryBuilder,
-    reflect::{AppTypeRegistry, ReflectComponent, ReflectResource},
+    reflect::{
+        AppTypeRegistry, DynamicReflectDeserializer, DynamicReflectSerializer, ReflectComponent,
+        ReflectResource,
+    },
     system::{In, Local},
     world::{EntityRef, EntityWorldMut, FilteredEntityRef, World},
 };
@@ -498,8 +501,14 @@ pub fn process_remote_get_components_request(
     let type_registry = app_type_registry.read();
     let entity_ref = get_entity(world, entity)?;
 
-    let response =
-        reflect_components_to_response(components, strict, entity, entity_ref, &type_registry)?;
+    let response = reflect_components_to_response(
+        components,
+        strict,
+        entity,
+        entity_ref,
+        world,
+        &type_registry,
+    )?;
     serde_json::to_value(response).map_err(BrpError::internal)
 }
 
@@ -616,7 +625,7 @@ pub fn process_remote_get_components_watching_request(
     }
 
     let response =
-        reflect_components_to_response(changed, strict, entity, entity_ref, &type_registry)?;
+        reflect_components_to_response(changed, strict, entity, entity_ref, world, &type_registry)?;
 
     let response = match response {
         BrpGetComponentsResponse::Lenient {
@@ -647,6 +656,7 @@ fn reflect_components_to_response(
     strict: bool,
     entity: Entity,
     entity_ref: EntityRef,
+    world: &World,
     type_registry: &TypeRegistry,
 ) -> BrpResult<BrpGetComponentsResponse> {
     let mut response = if strict {
@@ -659,7 +669,7 @@ fn reflect_components_to_response(
     };
 
     for component_path in components {
-        match reflect_component(&component_path, entity, entity_ref, type_registry) {
+        match reflect_component(&component_path, entity, entity_ref, world, type_registry) {
             Ok(serialized_object) => match response {
                 BrpGetComponentsResponse::Strict(ref mut components)
                 | BrpGetComponentsResponse::Lenient {
@@ -682,12 +692,28 @@ fn reflect_components_to_response(
 }
 
 /// Reflect a single component on an entity with the given component path.
+///
+/// Paths that aren't registered types are looked up as the names of dynamic reflect components.
 fn reflect_component(
     component_path: &str,
     entity: Entity,
     entity_ref: EntityRef,
+    world: &World,
     type_registry: &TypeRegistry,
 ) -> BrpResult<Map<String, Value>> {
+    if type_registry.get_with_type_path(component_path).is_none()
+        && let Some(info) = world
+            .dynamic_reflect_components()
+            .and_then(|components| components.get_by_name(component_path))
+    {
+        let Some(reflected) = world.get_dynamic_reflect(entity, info.id()) else {
+            return Err(BrpError::component_not_present(component_path, entity));
+        };
+        let value = serde_json::to_value(DynamicReflectSerializer::new(reflected, type_registry))
+            .map_err(BrpError::component_error)?;
+        return Ok(Map::from_iter([(component_path.to_owned(), value)]));
+    }
+
     let reflect_component =
         get_reflect_component(type_registry, component_path).map_err(BrpError::component_error)?;
 
@@ -947,8 +973,8 @@ pub fn process_remote_spawn_entity_request(
     let app_type_registry = world.resource::<AppTypeRegistry>().clone();
     let type_registry = app_type_registry.read();
 
-    let reflect_components =
-        deserialize_components(&type_registry, components).map_err(BrpError::component_error)?;
+    let reflect_components = deserialize_components(&type_registry, world, components)
+        .map_err(BrpError::component_error)?;
 
     let entity = world.spawn_empty();
     let entity_id = entity.id();
@@ -1007,8 +1033,8 @@ pub fn process_remote_insert_components_request(
     let app_type_registry = world.resource::<AppTypeRegistry>().clone();
     let type_registry = app_type_registry.read();
 
-    let reflect_components =
-        deserialize_components(&type_registry, components).map_err(BrpError::component_error)?;
+    let reflect_components = deserialize_components(&type_registry, world, components)
+        .map_err(BrpError::component_error)?;
 
     insert_reflected_components(
         &type_registry,
@@ -1276,6 +1302,9 @@ pub fn process_remote_list_components_request(
                 response.push(registered_type.type_info().type_path().to_owned());
             }
         }
+        if let Some(dynamic_components) = world.dynamic_reflect_components() {
+            response.extend(dynamic_components.iter().map(|info| info.name().to_owned()));
+        }
     }
 
     // Sort both for cleanliness and to reduce the risk that clients start
@@ -1512,26 +1541,48 @@ fn reflect_component_from_id(
 
 /// Given a collection of component paths and their associated serialized values (`components`),
 /// return the associated collection of deserialized reflected values.
+///
+/// Paths that aren't registered types are looked up as the names of dynamic reflect components
+/// in `world`.
 fn deserialize_components(
     type_registry: &TypeRegistry,
+    world: &World,
     components: HashMap<String, Value>,
-) -> AnyhowResult<Vec<Box<dyn PartialReflect>>> {
-    let mut reflect_components = vec![];
+) -> AnyhowResult<DeserializedComponents> {
+    let mut reflect_components = DeserializedComponents::default();
 
     for (component_path, component) in components {
-        let Some(component_type) = type_registry.get_with_type_path(&component_path) else {
-            return Err(anyhow!("Unknown component type: `{}`", component_path));
-        };
-        let reflected: Box<dyn PartialReflect> =
-            TypedReflectDeserializer::new(component_type, type_registry)
+        if let Some(component_type) = type_registry.get_with_type_path(&component_path) {
+            let reflected: Box<dyn PartialReflect> =
+                TypedReflectDeserializer::new(component_type, type_registry)
+                    .deserialize(&component)
+                    .map_err(|err| anyhow!("{component_path} is invalid: {err}"))?;
+            reflect_components.typed.push(reflected);
+        } else if let Some(info) = world
+            .dynamic_reflect_components()
+            .and_then(|components| components.get_by_name(&component_path))
+        {
+            let reflected = DynamicReflectDeserializer::new(&info, type_registry)
                 .deserialize(&component)
                 .map_err(|err| anyhow!("{component_path} is invalid: {err}"))?;
-        reflect_components.push(reflected);
+            reflect_components.dynamic.push((info.id(), reflected));
+        } else {
+            return Err(anyhow!("Unknown component type: `{}`", component_path));
+        }
     }
 
     Ok(reflect_components)
 }
 
+/// Components deserialized by [`deserialize_components`].
+#[derive(Default)]
+struct DeserializedComponents {
+    /// Components of registered types.
+    typed: Vec<Box<dyn PartialReflect>>,
+    /// Dynamic reflect components, with their id.
+    dynamic: Vec<(ComponentId, Box<dyn PartialReflect>)>,
+}
+
 /// Given a resource path and an associated serialized value (`value`), return the
 /// deserialized value.
 fn deserialize_resource(
@@ -1554,13 +1605,16 @@ fn deserialize_resource(
 fn insert_reflected_components(
     type_registry: &TypeRegistry,
     mut entity_world_mut: EntityWorldMut,
-    reflect_components: Vec<Box<dyn PartialReflect>>,
+    reflect_components: DeserializedComponents,
 ) -> AnyhowResult<()> {
-    for reflected in reflect_components {
+    for reflected in reflect_components.typed {
         let reflect_component =
             get_reflect_component(type_registry, reflected.reflect_type_path())?;
         reflect_component.insert(&mut entity_world_mut, &*reflected, type_registry);
     }
+    for (id, reflected) in reflect_components.dynamic {
+        entity_world_mut.insert_dynamic_reflect(id, &*reflected)?;
+    }
 
     Ok(())
 }
@@ -1657,4 +1711,60 @@ mod tests {
             entity: Entity::from_raw_u32(0).unwrap(),
         });
     }
+
+    #[test]
+    fn dynamic_reflect_components() {
+        use bevy_ecs::reflect::DynamicReflectComponentDescriptor;
+        use bevy_reflect::DynamicStruct;
+        use serde_json::json;
+
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        type_registry.write().register::<u32>();
+        world.insert_resource(type_registry);
+
+        let mut template = DynamicStruct::default();
+        template.insert("current", 100_u32);
+        template.insert("max", 100_u32);
+        world.register_dynamic_reflect_component(DynamicReflectComponentDescriptor::new(
+            "Health", &template,
+        ));
+
+        let response = process_remote_spawn_entity_request(
+            In(Some(
+                json!({ "components": { "Health": { "current": 25 } } }),
+            )),
+            &mut world,
+        )
+        .unwrap();
+        let BrpSpawnEntityResponse { entity } = parse(response).unwrap();
+
+        let response = process_remote_get_components_request(
+            In(Some(
+                json!({ "entity": entity, "components": ["Health"], "strict": true }),
+            )),
+            &world,
+        )
+        .unwrap();
+        assert_eq!(response, json!({ "Health": { "current": 25, "max": 100 } }));
+
+        process_remote_insert_components_request(
+            In(Some(
+                json!({ "entity": entity, "components": { "Health": { "max": 50 } } }),
+            )),
+            &mut world,
+        )
+        .unwrap();
+        let response = process_remote_get_components_request(
+            In(Some(
+                json!({ "entity": entity, "components": ["Health"], "strict": true }),
+            )),
+            &world,
+        )
+        .unwrap();
+        assert_eq!(response, json!({ "Health": { "current": 100, "max": 50 } }));
+
+        let response = process_remote_list_components_request(In(None), &world).unwrap();
+        assert_eq!(response, json!(["Health"]));
+    }
 }
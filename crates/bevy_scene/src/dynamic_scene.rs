@@ -6,7 +6,7 @@ use bevy_ecs::{
     reflect::{AppTypeRegistry, ReflectComponent},
     world::World,
 };
-use bevy_reflect::{PartialReflect, TypePath};
+use bevy_reflect::{ApplyError, PartialReflect, TypePath};
 
 use crate::reflect_utils::clone_reflect_value;
 use bevy_ecs::component::ComponentCloneBehavior;
@@ -43,6 +43,13 @@ pub struct DynamicEntity {
     /// A vector of boxed components that belong to the given entity and
     /// implement the [`PartialReflect`] trait.
     pub components: Vec<Box<dyn PartialReflect>>,
+    /// The values of components registered with [`World::register_dynamic_reflect_component`],
+    /// along with the name they were registered with.
+    ///
+    /// Unlike [`components`](Self::components), these are matched to the components of the target
+    /// world by name. Every [`Entity`] found in their values is mapped when the scene is written
+    /// to a world.
+    pub dynamic_components: Vec<(String, Box<dyn PartialReflect>)>,
 }
 
 impl DynamicScene {
@@ -71,7 +78,8 @@ impl DynamicScene {
     ///
     /// This method will return a [`SceneSpawnError`] if a type either is not registered
     /// in the provided [`AppTypeRegistry`] resource, or doesn't reflect the
-    /// [`Component`](bevy_ecs::component::Component) or [`Resource`](bevy_ecs::prelude::Resource) trait.
+    /// [`Component`](bevy_ecs::component::Component) or [`Resource`](bevy_ecs::prelude::Resource) trait,
+    /// or if a dynamic component is not registered in `world` or doesn't match its template.
     pub fn write_to_world_with(
         &self,
         world: &mut World,
@@ -140,6 +148,34 @@ impl DynamicScene {
                     );
                 });
             }
+
+            for (name, component) in &scene_entity.dynamic_components {
+                let info = world
+                    .dynamic_reflect_components()
+                    .and_then(|components| components.get_by_name(name))
+                    .ok_or_else(|| SceneSpawnError::UnregisteredDynamicComponent {
+                        name: name.clone(),
+                    })?;
+                let invalid = |error: ApplyError| SceneSpawnError::InvalidDynamicComponent {
+                    name: name.clone(),
+                    error: error.into(),
+                };
+
+                // Map the entities before inserting the component, like other components.
+                let mut component = info
+                    .create(component.as_partial_reflect())
+                    .map_err(invalid)?;
+                SceneEntityMapper::world_scope(entity_map, world, |_, mapper| {
+                    info.map_entities(&mut *component, mapper);
+                });
+                world
+                    .entity_mut(entity)
+                    .insert_dynamic_reflect(info.id(), &*component)
+                    .map_err(|error| SceneSpawnError::InvalidDynamicComponent {
+                        name: name.clone(),
+                        error,
+                    })?;
+            }
         }
 
         // Insert resources after all entities have been added to the world.
@@ -230,12 +266,15 @@ mod tests {
         component::Component,
         entity::{Entity, EntityHashMap, EntityMapper, MapEntities},
         hierarchy::ChildOf,
-        reflect::{AppTypeRegistry, ReflectComponent, ReflectMapEntities, ReflectResource},
+        reflect::{
+            AppTypeRegistry, DynamicReflectComponentDescriptor, ReflectComponent,
+            ReflectMapEntities, ReflectResource,
+        },
         resource::Resource,
         world::World,
     };
 
-    use bevy_reflect::Reflect;
+    use bevy_reflect::{DynamicList, DynamicStruct, GetField, Reflect};
 
     use crate::dynamic_scene::DynamicScene;
     use crate::dynamic_scene_builder::DynamicSceneBuilder;
@@ -288,6 +327,62 @@ mod tests {
         assert_eq!(from_entity_b, test_resource.entity_b);
     }
 
+    #[test]
+    fn dynamic_component_entity_map_maps_entities() {
+        let mut template = DynamicStruct::default();
+        template.insert("target", Entity::PLACEHOLDER);
+        template.insert("followers", DynamicList::default());
+        let descriptor = || DynamicReflectComponentDescriptor::new("Leader", &template);
+
+        let mut source_world = World::new();
+        source_world.init_resource::<AppTypeRegistry>();
+        let leader = source_world.register_dynamic_reflect_component(descriptor());
+        let original_target = source_world.spawn_empty().id();
+        let original_follower = source_world.spawn_empty().id();
+        let mut value = DynamicStruct::default();
+        value.insert("target", original_target);
+        value.insert("followers", DynamicList::from_iter([original_follower]));
+        let original_leader = source_world
+            .spawn_empty()
+            .insert_dynamic_reflect(leader, &value)
+            .unwrap()
+            .id();
+
+        let scene = DynamicScene::from_world(&source_world);
+
+        let mut entity_map = EntityHashMap::default();
+        let mut destination_world = World::new();
+        destination_world.init_resource::<AppTypeRegistry>();
+        let leader = destination_world.register_dynamic_reflect_component(descriptor());
+        // Offset the entities so that the mapped ones differ from the original ones.
+        destination_world.spawn_batch((0..10).map(|_| ()));
+        scene
+            .write_to_world(&mut destination_world, &mut entity_map)
+            .unwrap();
+
+        let value = destination_world
+            .get_dynamic_reflect(entity_map[&original_leader], leader)
+            .unwrap()
+            .reflect_ref()
+            .as_struct()
+            .unwrap();
+        assert_eq!(
+            value.get_field::<Entity>("target"),
+            Some(&entity_map[&original_target])
+        );
+        let followers = value
+            .field("followers")
+            .unwrap()
+            .reflect_ref()
+            .as_list()
+            .unwrap();
+        assert_eq!(
+            followers.get(0).unwrap().try_downcast_ref::<Entity>(),
+            Some(&entity_map[&original_follower])
+        );
+        assert_ne!(entity_map[&original_follower], original_follower);
+    }
+
     #[test]
     fn components_not_defined_in_scene_should_not_be_affected_by_scene_entity_map() {
         // Testing that scene reloading applies EntityMap correctly to MapEntities components.
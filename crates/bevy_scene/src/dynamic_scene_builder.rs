@@ -23,6 +23,11 @@ use bevy_utils::default;
 /// This can be changed by [specifying a filter](DynamicSceneBuilder::with_component_filter) or by explicitly
 /// [allowing](DynamicSceneBuilder::allow_component)/[denying](DynamicSceneBuilder::deny_component) certain components.
 ///
+/// Components registered with [`World::register_dynamic_reflect_component`] are extracted as well.
+/// They have no type that a [`SceneFilter`] could refer to, so they can't be allowed or denied
+/// individually: a filter that only allows specific components excludes all of them, and any other
+/// filter includes all of them.
+///
 /// Extraction happens immediately and uses the filter as it exists during the time of extraction.
 ///
 /// # Resource Extraction
@@ -229,8 +234,9 @@ impl<'w> DynamicSceneBuilder<'w> {
     /// These were likely created because none of their components were present in the provided type registry upon extraction.
     #[must_use]
     pub fn remove_empty_entities(mut self) -> Self {
-        self.extracted_scene
-            .retain(|_, entity| !entity.components.is_empty());
+        self.extracted_scene.retain(|_, entity| {
+            !entity.components.is_empty() || !entity.dynamic_components.is_empty()
+        });
 
         self
     }
@@ -265,6 +271,8 @@ impl<'w> DynamicSceneBuilder<'w> {
     /// ```
     ///
     /// Note that components extracted from queried entities must still pass through the filter if one is set.
+    /// See the [type-level documentation](Self#component-extraction) for how the filter applies to
+    /// components registered with [`World::register_dynamic_reflect_component`].
     ///
     /// [`allow`]: Self::allow_component
     /// [`deny`]: Self::deny_component
@@ -280,10 +288,26 @@ impl<'w> DynamicSceneBuilder<'w> {
             let mut entry = DynamicEntity {
                 entity,
                 components: Vec::new(),
+                dynamic_components: Vec::new(),
             };
+            let dynamic_components = self.original_world.dynamic_reflect_components();
 
             let original_entity = self.original_world.entity(entity);
             for component_id in original_entity.archetype().components() {
+                if let Some(info) = dynamic_components.and_then(|c| c.get(component_id)) {
+                    // Dynamic components have no type to allow, so they're only denied by allowlists.
+                    if !matches!(self.component_filter, SceneFilter::Allowlist(_)) {
+                        let component = self
+                            .original_world
+                            .get_dynamic_reflect(entity, component_id)
+                            .unwrap();
+                        entry
+                            .dynamic_components
+                            .push((info.name().to_owned(), component.to_dynamic()));
+                    }
+                    continue;
+                }
+
                 let mut extract_and_push = || {
                     let type_id = self
                         .original_world
@@ -49,7 +49,10 @@ pub mod prelude {
 use bevy_app::prelude::*;
 
 #[cfg(feature = "serialize")]
-use {bevy_asset::AssetApp, bevy_ecs::schedule::IntoScheduleConfigs};
+use {
+    bevy_asset::AssetApp,
+    bevy_ecs::{reflect::DynamicReflectComponents, schedule::IntoScheduleConfigs},
+};
 
 /// Plugin that provides scene functionality to an [`App`].
 #[derive(Default)]
@@ -60,6 +63,7 @@ impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<DynamicScene>()
             .init_asset::<Scene>()
+            .init_resource::<DynamicReflectComponents>()
             .init_asset_loader::<SceneLoader>()
             .init_resource::<SceneSpawner>()
             .add_systems(SpawnScene, (scene_spawner, scene_spawner_system).chain());
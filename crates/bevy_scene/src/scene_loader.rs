@@ -1,6 +1,6 @@
 use crate::ron;
 use bevy_ecs::{
-    reflect::AppTypeRegistry,
+    reflect::{AppTypeRegistry, DynamicReflectComponents},
     world::{FromWorld, World},
 };
 use bevy_reflect::TypeRegistryArc;
@@ -23,13 +23,22 @@ pub struct SceneLoader {
         expect(dead_code, reason = "only used with `serialize` feature")
     )]
     type_registry: TypeRegistryArc,
+    #[cfg_attr(
+        not(feature = "serialize"),
+        expect(dead_code, reason = "only used with `serialize` feature")
+    )]
+    dynamic_components: Option<DynamicReflectComponents>,
 }
 
 impl FromWorld for SceneLoader {
     fn from_world(world: &mut World) -> Self {
-        let type_registry = world.resource::<AppTypeRegistry>();
+        let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+        // Shares the components registered with `World::register_dynamic_reflect_component`,
+        // including the ones registered after the loader was created.
+        let dynamic_components = world.get_resource::<DynamicReflectComponents>().cloned();
         SceneLoader {
-            type_registry: type_registry.0.clone(),
+            type_registry,
+            dynamic_components,
         }
     }
 }
@@ -63,6 +72,7 @@ impl AssetLoader for SceneLoader {
         let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
         let scene_deserializer = SceneDeserializer {
             type_registry: &self.type_registry.read(),
+            dynamic_components: self.dynamic_components.as_ref(),
         };
         Ok(scene_deserializer
             .deserialize(&mut deserializer)
@@ -4,7 +4,7 @@ use bevy_ecs::{
     entity::{Entity, EntityHashMap},
     event::{EntityEvent, EventCursor, Events},
     hierarchy::ChildOf,
-    reflect::AppTypeRegistry,
+    reflect::{AppTypeRegistry, DynamicReflectComponentError},
     resource::Resource,
     world::{Mut, World},
 };
@@ -136,6 +136,20 @@ pub enum SceneSpawnError {
         /// The unregistered type.
         type_path: String,
     },
+    /// Scene contains a dynamic component that is not registered in the world.
+    #[error("scene contains the dynamic component `{name}`, which is not registered in the world. consider registering it using `World::register_dynamic_reflect_component`")]
+    UnregisteredDynamicComponent {
+        /// Name of the unregistered dynamic component.
+        name: String,
+    },
+    /// Scene contains a dynamic component whose value does not match its template.
+    #[error("scene contains an invalid value for the dynamic component `{name}`: {error}")]
+    InvalidDynamicComponent {
+        /// Name of the dynamic component.
+        name: String,
+        /// The error returned when inserting the component.
+        error: DynamicReflectComponentError,
+    },
     /// Scene contains a proxy without a represented type.
     #[error("scene contains dynamic type `{type_path}` without a represented type. consider changing this using `set_represented_type`.")]
     NoRepresentedType {
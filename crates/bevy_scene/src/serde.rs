@@ -1,7 +1,10 @@
 //! `serde` serialization and deserialization implementation for Bevy scenes.
 
 use crate::{DynamicEntity, DynamicScene};
-use bevy_ecs::entity::Entity;
+use bevy_ecs::{
+    entity::Entity,
+    reflect::{DynamicReflectComponents, DynamicReflectDeserializer, DynamicReflectSerializer},
+};
 use bevy_platform::collections::HashSet;
 use bevy_reflect::{
     serde::{
@@ -125,6 +128,9 @@ impl<'a> Serialize for EntitiesSerializer<'a> {
 }
 
 /// Handles entity serialization as a map of component type to component value.
+///
+/// [Dynamic components](DynamicEntity::dynamic_components) are part of the same map,
+/// with their name in place of a type path.
 pub struct EntitySerializer<'a> {
     /// The entity to serialize.
     pub entity: &'a DynamicEntity,
@@ -140,8 +146,8 @@ impl<'a> Serialize for EntitySerializer<'a> {
         let mut state = serializer.serialize_struct(ENTITY_STRUCT, 1)?;
         state.serialize_field(
             ENTITY_FIELD_COMPONENTS,
-            &SceneMapSerializer {
-                entries: &self.entity.components,
+            &EntityComponentsSerializer {
+                entity: self.entity,
                 registry: self.registry,
             },
         )?;
@@ -149,6 +155,51 @@ impl<'a> Serialize for EntitySerializer<'a> {
     }
 }
 
+/// Serializes the components of an entity like a [`SceneMapSerializer`], followed by its dynamic components.
+struct EntityComponentsSerializer<'a> {
+    entity: &'a DynamicEntity,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for EntityComponentsSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(
+            self.entity.components.len() + self.entity.dynamic_components.len(),
+        ))?;
+        let mut entries = self
+            .entity
+            .components
+            .iter()
+            .map(|entry| {
+                (
+                    entry.get_represented_type_info().unwrap().type_path(),
+                    entry.as_partial_reflect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(type_path, _)| *type_path);
+        for (type_path, partial_reflect) in entries {
+            state.serialize_entry(
+                type_path,
+                &TypedReflectSerializer::new(partial_reflect, self.registry),
+            )?;
+        }
+
+        let mut dynamic_entries = self.entity.dynamic_components.iter().collect::<Vec<_>>();
+        dynamic_entries.sort_by_key(|(name, _)| name);
+        for (name, value) in dynamic_entries {
+            state.serialize_entry(
+                name,
+                &DynamicReflectSerializer::new(value.as_ref(), self.registry),
+            )?;
+        }
+        state.end()
+    }
+}
+
 /// Handles serializing a list of values with a unique type as a map of type to value.
 ///
 /// Used to serialize scene resources in [`SceneSerializer`] and entity components in [`EntitySerializer`].
@@ -211,6 +262,8 @@ enum EntityField {
 pub struct SceneDeserializer<'a> {
     /// Type registry in which the components and resources types used in the scene to deserialize are registered.
     pub type_registry: &'a TypeRegistry,
+    /// The dynamic reflect components that may be used in the scene to deserialize.
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
@@ -225,6 +278,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
             &[SCENE_RESOURCES, SCENE_ENTITIES],
             SceneVisitor {
                 type_registry: self.type_registry,
+                dynamic_components: self.dynamic_components,
             },
         )
     }
@@ -232,6 +286,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
 
 struct SceneVisitor<'a> {
     pub type_registry: &'a TypeRegistry,
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
@@ -254,6 +309,7 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
         let entities = seq
             .next_element_seed(SceneEntitiesDeserializer {
                 type_registry: self.type_registry,
+                dynamic_components: self.dynamic_components,
             })?
             .ok_or_else(|| Error::missing_field(SCENE_ENTITIES))?;
 
@@ -285,6 +341,7 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
                     }
                     entities = Some(map.next_value_seed(SceneEntitiesDeserializer {
                         type_registry: self.type_registry,
+                        dynamic_components: self.dynamic_components,
                     })?);
                 }
             }
@@ -304,6 +361,8 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
 pub struct SceneEntitiesDeserializer<'a> {
     /// Type registry in which the component types used by the entities to deserialize are registered.
     pub type_registry: &'a TypeRegistry,
+    /// The dynamic reflect components that may be used by the entities to deserialize.
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
@@ -315,12 +374,14 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
     {
         deserializer.deserialize_map(SceneEntitiesVisitor {
             type_registry: self.type_registry,
+            dynamic_components: self.dynamic_components,
         })
     }
 }
 
 struct SceneEntitiesVisitor<'a> {
     pub type_registry: &'a TypeRegistry,
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
@@ -339,6 +400,7 @@ impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
             let entity = map.next_value_seed(SceneEntityDeserializer {
                 entity,
                 type_registry: self.type_registry,
+                dynamic_components: self.dynamic_components,
             })?;
             entities.push(entity);
         }
@@ -353,6 +415,8 @@ pub struct SceneEntityDeserializer<'a> {
     pub entity: Entity,
     /// Type registry in which the component types used by the entity to deserialize are registered.
     pub type_registry: &'a TypeRegistry,
+    /// The dynamic reflect components that may be used by the entity to deserialize.
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
@@ -368,6 +432,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
             SceneEntityVisitor {
                 entity: self.entity,
                 registry: self.type_registry,
+                dynamic_components: self.dynamic_components,
             },
         )
     }
@@ -376,6 +441,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
 struct SceneEntityVisitor<'a> {
     pub entity: Entity,
     pub registry: &'a TypeRegistry,
+    pub dynamic_components: Option<&'a DynamicReflectComponents>,
 }
 
 impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
@@ -389,15 +455,17 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
     where
         A: SeqAccess<'de>,
     {
-        let components = seq
-            .next_element_seed(SceneMapDeserializer {
+        let (components, dynamic_components) = seq
+            .next_element_seed(EntityComponentsDeserializer {
                 registry: self.registry,
+                dynamic_components: self.dynamic_components,
             })?
             .ok_or_else(|| Error::missing_field(ENTITY_FIELD_COMPONENTS))?;
 
         Ok(DynamicEntity {
             entity: self.entity,
             components,
+            dynamic_components,
         })
     }
 
@@ -413,23 +481,108 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
                         return Err(Error::duplicate_field(ENTITY_FIELD_COMPONENTS));
                     }
 
-                    components = Some(map.next_value_seed(SceneMapDeserializer {
+                    components = Some(map.next_value_seed(EntityComponentsDeserializer {
                         registry: self.registry,
+                        dynamic_components: self.dynamic_components,
                     })?);
                 }
             }
         }
 
-        let components = components
+        let (components, dynamic_components) = components
             .take()
             .ok_or_else(|| Error::missing_field(ENTITY_FIELD_COMPONENTS))?;
         Ok(DynamicEntity {
             entity: self.entity,
             components,
+            dynamic_components,
         })
     }
 }
 
+/// Deserializes the components of an entity like a [`SceneMapDeserializer`], except that keys
+/// which aren't registered type paths are looked up as the names of dynamic reflect components.
+struct EntityComponentsDeserializer<'a> {
+    registry: &'a TypeRegistry,
+    dynamic_components: Option<&'a DynamicReflectComponents>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for EntityComponentsDeserializer<'a> {
+    type Value = (
+        Vec<Box<dyn PartialReflect>>,
+        Vec<(String, Box<dyn PartialReflect>)>,
+    );
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for EntityComponentsDeserializer<'a> {
+    type Value = (
+        Vec<Box<dyn PartialReflect>>,
+        Vec<(String, Box<dyn PartialReflect>)>,
+    );
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("map of reflect types")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let components = SceneMapVisitor {
+            registry: self.registry,
+        }
+        .visit_seq(seq)?;
+        Ok((components, Vec::new()))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut added = <HashSet<String>>::default();
+        let mut components = Vec::new();
+        let mut dynamic_components = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if !added.insert(key.clone()) {
+                return Err(Error::custom(format_args!(
+                    "duplicate reflect type: `{key}`"
+                )));
+            }
+
+            if let Some(registration) = self.registry.get_with_type_path(&key) {
+                let value = map
+                    .next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?;
+                let value = registration
+                    .data::<ReflectFromReflect>()
+                    .and_then(|fr| fr.from_reflect(value.as_partial_reflect()))
+                    .map(PartialReflect::into_partial_reflect)
+                    .unwrap_or(value);
+                components.push(value);
+            } else if let Some(info) = self
+                .dynamic_components
+                .and_then(|dynamic_components| dynamic_components.get_by_name(&key))
+            {
+                let value =
+                    map.next_value_seed(DynamicReflectDeserializer::new(&info, self.registry))?;
+                dynamic_components.push((key, value));
+            } else {
+                return Err(Error::custom(format_args!(
+                    "no registration found for `{key}`"
+                )));
+            }
+        }
+
+        Ok((components, dynamic_components))
+    }
+}
+
 /// Handles deserialization of a sequence of values with unique types.
 pub struct SceneMapDeserializer<'a> {
     /// Type registry in which the types of the values to deserialize are registered.
@@ -512,16 +665,17 @@ mod tests {
     use crate::{
         ron,
         serde::{SceneDeserializer, SceneSerializer},
-        DynamicScene, DynamicSceneBuilder,
+        DynamicScene, DynamicSceneBuilder, SceneSpawnError,
     };
     use bevy_ecs::{
+        component::ComponentId,
         entity::{Entity, EntityHashMap},
         prelude::{Component, ReflectComponent, ReflectResource, Resource, World},
         query::{With, Without},
-        reflect::AppTypeRegistry,
+        reflect::{AppTypeRegistry, DynamicReflectComponentDescriptor},
         world::FromWorld,
     };
-    use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
+    use bevy_reflect::{DynamicStruct, GetField, Reflect, ReflectDeserialize, ReflectSerialize};
     use serde::{de::DeserializeSeed, Deserialize, Serialize};
     use std::io::BufReader;
 
@@ -698,6 +852,7 @@ mod tests {
         let mut deserializer = ron::de::Deserializer::from_str(input).unwrap();
         let scene_deserializer = SceneDeserializer {
             type_registry: &world.resource::<AppTypeRegistry>().read(),
+            dynamic_components: None,
         };
         let scene = scene_deserializer.deserialize(&mut deserializer).unwrap();
 
@@ -733,6 +888,7 @@ mod tests {
         let mut deserializer = ron::de::Deserializer::from_str(&serialized).unwrap();
         let scene_deserializer = SceneDeserializer {
             type_registry: &registry,
+            dynamic_components: world.dynamic_reflect_components(),
         };
         let deserialized_scene = scene_deserializer.deserialize(&mut deserializer).unwrap();
         (scene, deserialized_scene)
@@ -795,6 +951,66 @@ mod tests {
         assert_eq!(&qux, world.query::<&Qux>().single(&world).unwrap());
     }
 
+    fn register_health(world: &mut World) -> ComponentId {
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<u32>();
+        let mut template = DynamicStruct::default();
+        template.insert("current", 100_u32);
+        template.insert("max", 100_u32);
+        world.register_dynamic_reflect_component(DynamicReflectComponentDescriptor::new(
+            "Health", &template,
+        ))
+    }
+
+    #[test]
+    fn should_roundtrip_dynamic_components() {
+        let mut world = create_world();
+        let health = register_health(&mut world);
+        let mut value = DynamicStruct::default();
+        value.insert("current", 42_u32);
+        world
+            .spawn(Foo(123))
+            .insert_dynamic_reflect(health, &value)
+            .unwrap();
+
+        let scene = DynamicScene::from_world(&world);
+        let serialized = scene
+            .serialize(&world.resource::<AppTypeRegistry>().read())
+            .unwrap();
+        assert!(serialized.contains(r#""Health": {"#));
+        assert!(serialized.contains(r#""current": 42,"#));
+
+        let (_, deserialized_scene) = roundtrip_ron(&world);
+        assert_eq!(1, deserialized_scene.entities.len());
+        assert_eq!(1, deserialized_scene.entities[0].dynamic_components.len());
+
+        // The component ids of the target world don't have to match the original ones.
+        let mut dst_world = create_world();
+        dst_world.register_component::<Bar>();
+        let dst_health = register_health(&mut dst_world);
+        assert_ne!(health, dst_health);
+        deserialized_scene
+            .write_to_world(&mut dst_world, &mut EntityHashMap::default())
+            .unwrap();
+
+        let entity = dst_world
+            .query_filtered::<Entity, With<Foo>>()
+            .single(&dst_world)
+            .unwrap();
+        let health = dst_world.get_dynamic_reflect(entity, dst_health).unwrap();
+        let health = health.reflect_ref().as_struct().unwrap();
+        assert_eq!(health.get_field::<u32>("current"), Some(&42));
+        assert_eq!(health.get_field::<u32>("max"), Some(&100));
+
+        // Writing to a world without the component fails instead of dropping it.
+        assert!(matches!(
+            deserialized_scene.write_to_world(&mut create_world(), &mut EntityHashMap::default()),
+            Err(SceneSpawnError::UnregisteredDynamicComponent { .. })
+        ));
+    }
+
     #[test]
     fn should_roundtrip_postcard() {
         let mut world = create_world();
@@ -825,6 +1041,7 @@ mod tests {
 
         let scene_deserializer = SceneDeserializer {
             type_registry: registry,
+            dynamic_components: None,
         };
         let deserialized_scene = scene_deserializer
             .deserialize(&mut postcard::Deserializer::from_bytes(&serialized_scene))
@@ -867,6 +1084,7 @@ mod tests {
 
         let scene_deserializer = SceneDeserializer {
             type_registry: registry,
+            dynamic_components: None,
         };
         let mut reader = BufReader::new(buf.as_slice());
 
@@ -912,6 +1130,7 @@ mod tests {
 
         let scene_deserializer = SceneDeserializer {
             type_registry: registry,
+            dynamic_components: None,
         };
 
         let (deserialized_scene, _read_bytes) =
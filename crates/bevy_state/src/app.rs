@@ -5,8 +5,10 @@ use log::warn;
 
 use crate::{
     state::{
-        setup_state_transitions_in_world, ComputedStates, FreelyMutableState, NextState, State,
-        StateTransition, StateTransitionEvent, StateTransitionSystems, States, SubStates,
+        register_stacked_state, register_transition_gate, setup_state_transitions_in_world,
+        ComputedStates, FreelyMutableState, NextState, NextStateStack, State, StateStack,
+        StateTransition, StateTransitionEvent, StateTransitionStarted, StateTransitionSystems,
+        States, SubStates, TransitionGate,
     },
     state_scoped::{despawn_entities_on_enter_state, despawn_entities_on_exit_state},
 };
@@ -47,6 +49,33 @@ pub trait AppExtStates {
     /// by triggering the [`StateTransition`](struct@StateTransition) schedule manually.
     fn insert_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self;
 
+    /// Initializes a [`State`] with standard starting values, which can be pushed and popped
+    /// through a [`StateStack`].
+    ///
+    /// This method is idempotent: it has no effect when called again using the same generic type.
+    ///
+    /// Adds the resources and schedules of [`init_state`](Self::init_state), along with the
+    /// [`StateStack<S>`] and [`NextStateStack<S>`] resources and the [`OnPause`](crate::state::OnPause)
+    /// and [`OnResume`](crate::state::OnResume) schedules. A state must not be installed both
+    /// with this method and with [`init_state`](Self::init_state).
+    fn init_stacked_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self;
+
+    /// Inserts a specific [`State`] which can be pushed and popped through a [`StateStack`],
+    /// overriding any [`State`] previously added of the same type and clearing its stack.
+    ///
+    /// See [`init_stacked_state`](Self::init_stacked_state) for details.
+    fn insert_stacked_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self;
+
+    /// Enables gated transitions for the [`State`] `S`, which must already be installed.
+    ///
+    /// This method is idempotent: it has no effect when called again using the same generic type.
+    ///
+    /// Adds the [`TransitionGate<S>`] resource, the [`StateTransitionStarted<S>`] event and enables use
+    /// of the [`OnTransitionStart`](crate::state::OnTransitionStart) schedule. Transitions requested
+    /// through [`NextState<S>`] then stay in flight while any hold is placed on the gate.
+    /// Transitions of a [`StateStack<S>`] are not gated.
+    fn enable_gated_transitions<S: FreelyMutableState>(&mut self) -> &mut Self;
+
     /// Sets up a type implementing [`ComputedStates`].
     ///
     /// This method is idempotent: it has no effect when called again using the same generic type.
@@ -95,60 +124,118 @@ fn warn_if_no_states_plugin_installed(app: &SubApp) {
     }
 }
 
-impl AppExtStates for SubApp {
-    fn init_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self {
-        warn_if_no_states_plugin_installed(self);
-        if !self.world().contains_resource::<State<S>>() {
-            self.init_resource::<State<S>>()
-                .init_resource::<NextState<S>>()
-                .add_event::<StateTransitionEvent<S>>();
-            let schedule = self.get_schedule_mut(StateTransition).expect(
-                "The `StateTransition` schedule is missing. Did you forget to add StatesPlugin or DefaultPlugins before calling init_state?"
-            );
-            S::register_state(schedule);
-            let state = self.world().resource::<State<S>>().get().clone();
-            self.world_mut().write_event(StateTransitionEvent {
-                exited: None,
-                entered: Some(state),
-            });
-            if S::SCOPED_ENTITIES_ENABLED {
-                self.enable_state_scoped_entities::<S>();
-            }
-        } else {
-            let name = core::any::type_name::<S>();
-            warn!("State {name} is already initialized.");
+/// Shared setup of [`AppExtStates::init_state`] and [`AppExtStates::init_stacked_state`].
+///
+/// `method` is the name of the calling method, which is mentioned if the setup fails.
+fn init_freely_mutable_state<S: FreelyMutableState + FromWorld>(
+    app: &mut SubApp,
+    stacked: bool,
+    method: &str,
+) {
+    warn_if_no_states_plugin_installed(app);
+    if !app.world().contains_resource::<State<S>>() {
+        let state = S::from_world(app.world_mut());
+        setup_freely_mutable_state(app, state, stacked, method);
+    } else {
+        let name = core::any::type_name::<S>();
+        warn!("State {name} is already initialized.");
+    }
+}
+
+/// Shared setup of [`AppExtStates::insert_state`] and [`AppExtStates::insert_stacked_state`].
+///
+/// `method` is the name of the calling method, which is mentioned if the setup fails.
+fn insert_freely_mutable_state<S: FreelyMutableState>(
+    app: &mut SubApp,
+    state: S,
+    stacked: bool,
+    method: &str,
+) {
+    warn_if_no_states_plugin_installed(app);
+    if !app.world().contains_resource::<State<S>>() {
+        setup_freely_mutable_state(app, state, stacked, method);
+    } else {
+        // Overwrite previous state, stack and initial event
+        app.insert_resource::<State<S>>(State::new(state.clone()));
+        if stacked && let Some(mut stack) = app.world_mut().get_resource_mut::<StateStack<S>>() {
+            *stack = StateStack::default();
         }
+        app.world_mut()
+            .resource_mut::<Events<StateTransitionEvent<S>>>()
+            .clear();
+        app.world_mut().write_event(StateTransitionEvent {
+            exited: None,
+            entered: Some(state),
+        });
+    }
+}
+
+/// Adds the resources, events and transition systems of a state that starts in `state`.
+fn setup_freely_mutable_state<S: FreelyMutableState>(
+    app: &mut SubApp,
+    state: S,
+    stacked: bool,
+    method: &str,
+) {
+    app.insert_resource::<State<S>>(State::new(state.clone()))
+        .init_resource::<NextState<S>>()
+        .add_event::<StateTransitionEvent<S>>();
+    if stacked {
+        app.init_resource::<StateStack<S>>()
+            .init_resource::<NextStateStack<S>>();
+    }
+    let schedule = app.get_schedule_mut(StateTransition).unwrap_or_else(|| {
+        panic!(
+            "The `StateTransition` schedule is missing. Did you forget to add StatesPlugin or DefaultPlugins before calling {method}?"
+        )
+    });
+    if stacked {
+        register_stacked_state::<S>(schedule);
+    } else {
+        S::register_state(schedule);
+    }
+    app.world_mut().write_event(StateTransitionEvent {
+        exited: None,
+        entered: Some(state),
+    });
+    if S::SCOPED_ENTITIES_ENABLED {
+        app.enable_state_scoped_entities::<S>();
+    }
+}
 
+impl AppExtStates for SubApp {
+    fn init_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self {
+        init_freely_mutable_state::<S>(self, false, "init_state");
         self
     }
 
     fn insert_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self {
-        warn_if_no_states_plugin_installed(self);
-        if !self.world().contains_resource::<State<S>>() {
-            self.insert_resource::<State<S>>(State::new(state.clone()))
-                .init_resource::<NextState<S>>()
-                .add_event::<StateTransitionEvent<S>>();
+        insert_freely_mutable_state(self, state, false, "insert_state");
+        self
+    }
+
+    fn init_stacked_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self {
+        init_freely_mutable_state::<S>(self, true, "init_stacked_state");
+        self
+    }
+
+    fn insert_stacked_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self {
+        insert_freely_mutable_state(self, state, true, "insert_stacked_state");
+        self
+    }
+
+    fn enable_gated_transitions<S: FreelyMutableState>(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<NextState<S>>() {
+            let name = core::any::type_name::<S>();
+            warn!("Gated transitions are enabled for state `{name}`, but the state isn't installed in the app!");
+        }
+        if !self.world().contains_resource::<TransitionGate<S>>() {
+            self.init_resource::<TransitionGate<S>>()
+                .add_event::<StateTransitionStarted<S>>();
             let schedule = self.get_schedule_mut(StateTransition).expect(
-                "The `StateTransition` schedule is missing. Did you forget to add StatesPlugin or DefaultPlugins before calling insert_state?"
+                "The `StateTransition` schedule is missing. Did you forget to add StatesPlugin or DefaultPlugins before calling enable_gated_transitions?"
             );
-            S::register_state(schedule);
-            self.world_mut().write_event(StateTransitionEvent {
-                exited: None,
-                entered: Some(state),
-            });
-            if S::SCOPED_ENTITIES_ENABLED {
-                self.enable_state_scoped_entities::<S>();
-            }
-        } else {
-            // Overwrite previous state and initial event
-            self.insert_resource::<State<S>>(State::new(state.clone()));
-            self.world_mut()
-                .resource_mut::<Events<StateTransitionEvent<S>>>()
-                .clear();
-            self.world_mut().write_event(StateTransitionEvent {
-                exited: None,
-                entered: Some(state),
-            });
+            register_transition_gate::<S>(schedule);
         }
 
         self
@@ -277,6 +364,21 @@ impl AppExtStates for App {
         self
     }
 
+    fn init_stacked_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self {
+        self.main_mut().init_stacked_state::<S>();
+        self
+    }
+
+    fn insert_stacked_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self {
+        self.main_mut().insert_stacked_state::<S>(state);
+        self
+    }
+
+    fn enable_gated_transitions<S: FreelyMutableState>(&mut self) -> &mut Self {
+        self.main_mut().enable_gated_transitions::<S>();
+        self
+    }
+
     fn add_computed_state<S: ComputedStates>(&mut self) -> &mut Self {
         self.main_mut().add_computed_state::<S>();
         self
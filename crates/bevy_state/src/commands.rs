@@ -1,7 +1,7 @@
 use bevy_ecs::{system::Commands, world::World};
 use log::debug;
 
-use crate::state::{FreelyMutableState, NextState};
+use crate::state::{FreelyMutableState, NextState, NextStateStack};
 
 /// Extension trait for [`Commands`] adding `bevy_state` helpers.
 pub trait CommandsStatesExt {
@@ -13,6 +13,20 @@ pub trait CommandsStatesExt {
     /// Note that commands introduce sync points to the ECS schedule, so modifying `NextState`
     /// directly may be more efficient depending on your use-case.
     fn set_state<S: FreelyMutableState>(&mut self, state: S);
+
+    /// Pauses the current state of a stacked state and enters `state` on top of it.
+    ///
+    /// Internally this schedules a command that updates the [`NextStateStack<S>`](crate::prelude::NextStateStack)
+    /// resource, which must have been installed with
+    /// [`init_stacked_state`](crate::app::AppExtStates::init_stacked_state) or
+    /// [`insert_stacked_state`](crate::app::AppExtStates::insert_stacked_state).
+    fn push_state<S: FreelyMutableState>(&mut self, state: S);
+
+    /// Exits the current state of a stacked state and resumes the state below it.
+    ///
+    /// Internally this schedules a command that updates the [`NextStateStack<S>`](crate::prelude::NextStateStack)
+    /// resource.
+    fn pop_state<S: FreelyMutableState>(&mut self);
 }
 
 impl CommandsStatesExt for Commands<'_, '_> {
@@ -27,4 +41,16 @@ impl CommandsStatesExt for Commands<'_, '_> {
             next.set(state);
         });
     }
+
+    fn push_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.queue(move |w: &mut World| {
+            w.resource_mut::<NextStateStack<S>>().push(state);
+        });
+    }
+
+    fn pop_state<S: FreelyMutableState>(&mut self) {
+        self.queue(|w: &mut World| {
+            w.resource_mut::<NextStateStack<S>>().pop();
+        });
+    }
 }
@@ -1,4 +1,4 @@
-use crate::state::{State, States};
+use crate::state::{State, StateStack, States, TransitionGate};
 use bevy_ecs::{change_detection::DetectChanges, system::Res};
 
 /// A [`SystemCondition`](bevy_ecs::prelude::SystemCondition)-satisfying system that returns `true`
@@ -107,6 +107,134 @@ pub fn in_state<S: States>(state: S) -> impl FnMut(Option<Res<State<S>>>) -> boo
     }
 }
 
+/// Generates a [`SystemCondition`](bevy_ecs::prelude::SystemCondition)-satisfying closure that returns `true`
+/// if the state machine is currently in `state`, or if `state` is paused on its [`StateStack`].
+///
+/// This is useful for systems which should keep running while their state is covered by
+/// another one, such as rendering the game world underneath a pause menu.
+///
+/// Will return `false` if the state does not exist. For states which aren't stacked, this is
+/// equivalent to [`in_state`].
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_state::prelude::*;
+/// # use bevy_app::{App, Update};
+/// # use bevy_state::app::StatesPlugin;
+/// # #[derive(Resource, Default)]
+/// # struct Counter(u8);
+/// # let mut app = App::new();
+/// # app
+/// #   .init_resource::<Counter>()
+/// #   .add_plugins(StatesPlugin);
+/// #[derive(States, Clone, Copy, Default, Eq, PartialEq, Hash, Debug)]
+/// enum GameState {
+///     #[default]
+///     Playing,
+///     Paused,
+/// }
+///
+/// app
+///     .init_stacked_state::<GameState>()
+///     .add_systems(Update, my_system.run_if(in_state_stack(GameState::Playing)));
+///
+/// fn my_system(mut counter: ResMut<Counter>) {
+///     counter.0 += 1;
+/// }
+///
+/// app.update();
+/// assert_eq!(app.world().resource::<Counter>().0, 1);
+///
+/// app.world_mut()
+///     .resource_mut::<NextStateStack<GameState>>()
+///     .push(GameState::Paused);
+///
+/// // `GameState::Playing` is paused, but still on the stack
+/// app.update();
+/// assert_eq!(app.world().resource::<Counter>().0, 2);
+/// ```
+pub fn in_state_stack<S: States>(
+    state: S,
+) -> impl FnMut(Option<Res<State<S>>>, Option<Res<StateStack<S>>>) -> bool + Clone {
+    move |current_state: Option<Res<State<S>>>, stack: Option<Res<StateStack<S>>>| {
+        current_state.is_some_and(|current_state| *current_state == state)
+            || stack.is_some_and(|stack| stack.is_paused(&state))
+    }
+}
+
+/// A [`SystemCondition`](bevy_ecs::prelude::SystemCondition)-satisfying system that returns `true`
+/// while a gated transition of the state machine is in flight.
+///
+/// Returns `false` if gated transitions aren't enabled for the state. See
+/// [`TransitionGate`] for more information.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_state::prelude::*;
+/// # use bevy_app::{App, Update};
+/// # use bevy_state::app::StatesPlugin;
+/// # #[derive(Resource, Default)]
+/// # struct Counter(u8);
+/// # let mut app = App::new();
+/// # app
+/// #   .init_resource::<Counter>()
+/// #   .add_plugins(StatesPlugin);
+/// #[derive(States, Clone, Copy, Default, Eq, PartialEq, Hash, Debug)]
+/// enum GameState {
+///     #[default]
+///     MainMenu,
+///     InGame,
+/// }
+///
+/// app
+///     .init_state::<GameState>()
+///     .enable_gated_transitions::<GameState>()
+///     .add_systems(Update, loading_screen.run_if(in_transition::<GameState>));
+///
+/// fn loading_screen(mut counter: ResMut<Counter>) {
+///     counter.0 += 1;
+/// }
+///
+/// app.world_mut()
+///     .resource_mut::<TransitionGate<GameState>>()
+///     .hold("level assets");
+/// app.world_mut()
+///     .resource_mut::<NextState<GameState>>()
+///     .set(GameState::InGame);
+///
+/// // The transition is held open, so `loading_screen` runs
+/// app.update();
+/// assert_eq!(app.world().resource::<Counter>().0, 1);
+///
+/// app.world_mut()
+///     .resource_mut::<TransitionGate<GameState>>()
+///     .release("level assets");
+///
+/// // The transition completes before `Update`, so `loading_screen` doesn't run
+/// app.update();
+/// assert_eq!(app.world().resource::<Counter>().0, 1);
+/// ```
+pub fn in_transition<S: States>(gate: Option<Res<TransitionGate<S>>>) -> bool {
+    gate.is_some_and(|gate| gate.pending().is_some())
+}
+
+/// Generates a [`SystemCondition`](bevy_ecs::prelude::SystemCondition)-satisfying closure that returns `true`
+/// while a gated transition of the state machine into `state` is in flight.
+///
+/// Will return `false` if gated transitions aren't enabled for the state. See
+/// [`in_transition`] for a condition matching any transition.
+pub fn transitioning_to<S: States>(
+    state: S,
+) -> impl FnMut(Option<Res<TransitionGate<S>>>) -> bool + Clone {
+    move |gate: Option<Res<TransitionGate<S>>>| {
+        gate.is_some_and(|gate| gate.pending() == Some(&state))
+    }
+}
+
 /// A [`SystemCondition`](bevy_ecs::prelude::SystemCondition)-satisfying system that returns `true`
 /// if the state machine changed state.
 ///
@@ -192,6 +320,9 @@ mod tests {
             (test_system, test_system)
                 .distributive_run_if(state_exists::<TestState>)
                 .distributive_run_if(in_state(TestState::A).or(in_state(TestState::B)))
+                .distributive_run_if(in_state_stack(TestState::A))
+                .distributive_run_if(in_transition::<TestState>)
+                .distributive_run_if(transitioning_to(TestState::B))
                 .distributive_run_if(state_changed::<TestState>),
         );
     }
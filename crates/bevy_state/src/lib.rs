@@ -25,7 +25,11 @@
 //!
 //! - 3 Transition Schedules - [`OnEnter<S>`](crate::state::OnEnter), [`OnExit<S>`](crate::state::OnExit) and [`OnTransition<S>`](crate::state::OnTransition) - which are used
 //!   to trigger systems specifically during matching transitions.
+//! - A [`StateStack<S>`](crate::state::StateStack) for states that need push/pop semantics, such as pause menus,
+//!   along with the [`OnPause<S>`](crate::state::OnPause) and [`OnResume<S>`](crate::state::OnResume) schedules.
 //! - A [`StateTransitionEvent<S>`](crate::state::StateTransitionEvent) that gets fired when a given state changes.
+//! - A [`TransitionGate<S>`](crate::state::TransitionGate) that keeps transitions in flight until conditions such as
+//!   asset loading are met, along with the [`OnTransitionStart<S>`](crate::state::OnTransitionStart) schedule.
 //! - The [`in_state<S>`](crate::condition::in_state) and [`state_changed<S>`](crate::condition::state_changed) run conditions - which are used
 //!   to determine whether a system should run based on the current state.
 //!
@@ -87,9 +91,10 @@ pub mod prelude {
         commands::CommandsStatesExt,
         condition::*,
         state::{
-            last_transition, ComputedStates, EnterSchedules, ExitSchedules, NextState, OnEnter,
-            OnExit, OnTransition, State, StateSet, StateTransition, StateTransitionEvent, States,
-            SubStates, TransitionSchedules,
+            last_transition, ComputedStates, EnterSchedules, ExitSchedules, NextState,
+            NextStateStack, OnEnter, OnExit, OnPause, OnResume, OnTransition, OnTransitionStart,
+            State, StateSet, StateStack, StateTransition, StateTransitionEvent,
+            StateTransitionStarted, States, SubStates, TransitionGate, TransitionSchedules,
         },
         state_scoped::{DespawnOnEnterState, DespawnOnExitState},
     };
@@ -0,0 +1,432 @@
+use alloc::vec::Vec;
+
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    event::EventWriter,
+    resource::Resource,
+    schedule::{IntoScheduleConfigs, Schedule, ScheduleLabel},
+    system::{Commands, In, IntoSystem, ResMut},
+    world::World,
+};
+use log::warn;
+
+use super::{
+    freely_mutable_state::FreelyMutableState, states::States, take_next_state, transitions::*,
+    NextState, State,
+};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::prelude::ReflectResource;
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::prelude::ReflectDefault;
+
+/// The label of a [`Schedule`] that **only** runs whenever a stacked [`State<S>`] is paused in
+/// the provided state, because another state was pushed on top of it.
+///
+/// This runs instead of [`OnExit`] for the paused state.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct OnPause<S: States>(pub S);
+
+/// The label of a [`Schedule`] that **only** runs whenever a stacked [`State<S>`] resumes the
+/// provided state, because the state on top of it was popped.
+///
+/// This runs instead of [`OnEnter`] for the resumed state.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct OnResume<S: States>(pub S);
+
+/// The kind of the last transition applied to a stacked state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum StackTransition {
+    /// The current state was replaced through [`NextState`], or the state was just initialized.
+    #[default]
+    Replace,
+    /// A state was pushed on top of the stack.
+    Push,
+    /// The state on top of the stack was popped.
+    Pop,
+}
+
+/// The states which are paused underneath the current [`State<S>`] of a stacked state.
+///
+/// Stacked states are installed with [`init_stacked_state`] or [`insert_stacked_state`].
+/// They behave like ordinary [`FreelyMutableState`]s, so [`NextState<S>`] replaces the current
+/// state, but they can also be pushed and popped through [`NextStateStack<S>`].
+///
+/// Pushing a state pauses the current one: [`OnPause`] runs for it instead of [`OnExit`], and
+/// its [state-scoped entities](crate::state_scoped) are kept around. Popping the current state
+/// exits it as usual and resumes the state below it, running [`OnResume`] instead of [`OnEnter`].
+///
+/// Replacing the current state through [`NextState<S>`] leaves the paused states untouched: they
+/// stay paused underneath the new state, and the next pop resumes the top one of them.
+///
+/// [`init_stacked_state`]: crate::app::AppExtStates::init_stacked_state
+/// [`insert_stacked_state`]: crate::app::AppExtStates::insert_stacked_state
+#[derive(Resource, Debug)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(bevy_reflect::Reflect),
+    reflect(Resource, Debug)
+)]
+pub struct StateStack<S: States> {
+    paused: Vec<S>,
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
+    transition: StackTransition,
+}
+
+impl<S: States> Default for StateStack<S> {
+    fn default() -> Self {
+        Self {
+            paused: Vec::new(),
+            transition: StackTransition::Replace,
+        }
+    }
+}
+
+impl<S: States> StateStack<S> {
+    /// Returns the paused states, from the bottom of the stack to the top.
+    ///
+    /// The current state is not included, it is stored in [`State<S>`].
+    pub fn paused(&self) -> &[S] {
+        &self.paused
+    }
+
+    /// Returns the number of paused states.
+    pub fn depth(&self) -> usize {
+        self.paused.len()
+    }
+
+    /// Returns `true` if `state` is one of the paused states.
+    pub fn is_paused(&self, state: &S) -> bool {
+        self.paused.contains(state)
+    }
+
+    /// Returns `true` if the last transition paused the previous state.
+    pub(crate) fn pushed(&self) -> bool {
+        self.transition == StackTransition::Push
+    }
+
+    /// Returns `true` if the last transition resumed a paused state.
+    pub(crate) fn popped(&self) -> bool {
+        self.transition == StackTransition::Pop
+    }
+}
+
+/// The next push or pop to apply to the [`StateStack<S>`] of a stacked state.
+///
+/// Like [`NextState<S>`], only the value of this resource during the
+/// [`StateTransition`] schedule matters, so a second operation queued in the same frame
+/// overrides the first. A pending stack operation takes precedence over [`NextState<S>`],
+/// which stays pending until the next time the schedule runs.
+#[derive(Resource, Debug, Default, Clone)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(bevy_reflect::Reflect),
+    reflect(Resource, Default, Debug)
+)]
+pub enum NextStateStack<S: FreelyMutableState> {
+    /// No stack operation is pending
+    #[default]
+    Unchanged,
+    /// Pause the current state and enter the given one, unless it is the current state
+    Push(S),
+    /// Exit the current state and resume the one below it
+    Pop,
+}
+
+impl<S: FreelyMutableState> NextStateStack<S> {
+    /// Queue pushing `state` on top of the stack.
+    ///
+    /// Pushing the current state does nothing, since it can't be paused and entered at once.
+    pub fn push(&mut self, state: S) {
+        *self = Self::Push(state);
+    }
+
+    /// Queue popping the current state off the stack.
+    pub fn pop(&mut self) {
+        *self = Self::Pop;
+    }
+
+    /// Remove any pending stack operation.
+    pub fn reset(&mut self) {
+        *self = Self::Unchanged;
+    }
+}
+
+/// Registers the systems which apply state changes and run transition schedules for a stacked
+/// state, in place of [`FreelyMutableState::register_state`].
+pub fn register_stacked_state<S: FreelyMutableState>(schedule: &mut Schedule) {
+    schedule.configure_sets((
+        ApplyStateTransition::<S>::default().in_set(StateTransitionSystems::DependentTransitions),
+        ExitSchedules::<S>::default().in_set(StateTransitionSystems::ExitSchedules),
+        TransitionSchedules::<S>::default().in_set(StateTransitionSystems::TransitionSchedules),
+        EnterSchedules::<S>::default().in_set(StateTransitionSystems::EnterSchedules),
+    ));
+
+    schedule
+        .add_systems(
+            apply_stacked_state_transition::<S>.in_set(ApplyStateTransition::<S>::default()),
+        )
+        .add_systems(
+            last_transition::<S>
+                .pipe(run_stacked_exit::<S>)
+                .in_set(ExitSchedules::<S>::default()),
+        )
+        .add_systems(
+            last_transition::<S>
+                .pipe(run_transition::<S>)
+                .in_set(TransitionSchedules::<S>::default()),
+        )
+        .add_systems(
+            last_transition::<S>
+                .pipe(run_stacked_enter::<S>)
+                .in_set(EnterSchedules::<S>::default()),
+        );
+}
+
+fn apply_stacked_state_transition<S: FreelyMutableState>(
+    event: EventWriter<StateTransitionEvent<S>>,
+    commands: Commands,
+    current_state: Option<ResMut<State<S>>>,
+    next_state: Option<ResMut<NextState<S>>>,
+    next_stack: Option<ResMut<NextStateStack<S>>>,
+    stack: Option<ResMut<StateStack<S>>>,
+) {
+    let (Some(current_state), Some(mut stack)) = (current_state, stack) else {
+        return;
+    };
+
+    let next_stack = next_stack.and_then(|mut next_stack| {
+        match core::mem::take(next_stack.bypass_change_detection()) {
+            NextStateStack::Unchanged => None,
+            operation => {
+                next_stack.set_changed();
+                Some(operation)
+            }
+        }
+    });
+
+    let (transition, entered) = match next_stack {
+        Some(NextStateStack::Push(entered)) => {
+            if entered == *current_state.get() {
+                warn!(
+                    "Tried to push {:?} onto {}, which is already the current state.",
+                    entered,
+                    core::any::type_name::<S>()
+                );
+                return;
+            }
+            stack.paused.push(current_state.get().clone());
+            (StackTransition::Push, entered)
+        }
+        Some(NextStateStack::Pop) => {
+            let Some(resumed) = stack.paused.pop() else {
+                warn!(
+                    "Tried to pop the last state of {}, which has nothing to resume.",
+                    core::any::type_name::<S>()
+                );
+                return;
+            };
+            (StackTransition::Pop, resumed)
+        }
+        _ => {
+            let Some(entered) = take_next_state(next_state) else {
+                return;
+            };
+            (StackTransition::Replace, entered)
+        }
+    };
+    stack.transition = transition;
+    internal_apply_state_transition(event, commands, Some(current_state), Some(entered));
+}
+
+fn run_stacked_exit<S: States>(transition: In<Option<StateTransitionEvent<S>>>, world: &mut World) {
+    let Some(transition) = transition.0 else {
+        return;
+    };
+    if transition.entered == transition.exited {
+        return;
+    }
+    let Some(exited) = transition.exited else {
+        return;
+    };
+
+    let _ = match world
+        .get_resource::<StateStack<S>>()
+        .is_some_and(StateStack::pushed)
+    {
+        true => world.try_run_schedule(OnPause(exited)),
+        false => world.try_run_schedule(OnExit(exited)),
+    };
+}
+
+fn run_stacked_enter<S: States>(
+    transition: In<Option<StateTransitionEvent<S>>>,
+    world: &mut World,
+) {
+    let Some(transition) = transition.0 else {
+        return;
+    };
+    if transition.entered == transition.exited {
+        return;
+    }
+    let Some(entered) = transition.entered else {
+        return;
+    };
+
+    let _ = match world
+        .get_resource::<StateStack<S>>()
+        .is_some_and(StateStack::popped)
+    {
+        true => world.try_run_schedule(OnResume(entered)),
+        false => world.try_run_schedule(OnEnter(entered)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_state_macros::States;
+
+    use crate::{app::StatesPlugin, prelude::*};
+
+    #[derive(States, PartialEq, Eq, Debug, Default, Hash, Clone)]
+    enum Mode {
+        #[default]
+        Game,
+        Pause,
+        Options,
+    }
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    fn log(entry: &'static str) -> impl Fn(ResMut<Log>) {
+        move |mut log: ResMut<Log>| log.0.push(entry)
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_resource::<Log>()
+            .init_stacked_state::<Mode>()
+            .add_systems(OnEnter(Mode::Game), log("enter game"))
+            .add_systems(OnExit(Mode::Game), log("exit game"))
+            .add_systems(OnPause(Mode::Game), log("pause game"))
+            .add_systems(OnResume(Mode::Game), log("resume game"))
+            .add_systems(OnEnter(Mode::Pause), log("enter pause"))
+            .add_systems(OnExit(Mode::Pause), log("exit pause"))
+            .add_systems(OnPause(Mode::Pause), log("pause pause"))
+            .add_systems(OnResume(Mode::Pause), log("resume pause"))
+            .add_systems(OnEnter(Mode::Options), log("enter options"))
+            .add_systems(OnExit(Mode::Options), log("exit options"));
+        app.update();
+        app.world_mut().resource_mut::<Log>().0.clear();
+        app
+    }
+
+    fn take_log(app: &mut App) -> Vec<&'static str> {
+        core::mem::take(&mut app.world_mut().resource_mut::<Log>().0)
+    }
+
+    fn push(app: &mut App, mode: Mode) {
+        app.world_mut()
+            .resource_mut::<NextStateStack<Mode>>()
+            .push(mode);
+        app.update();
+    }
+
+    fn pop(app: &mut App) {
+        app.world_mut().resource_mut::<NextStateStack<Mode>>().pop();
+        app.update();
+    }
+
+    #[test]
+    fn push_and_pop_run_pause_and_resume_schedules() {
+        let mut app = app();
+
+        push(&mut app, Mode::Pause);
+        assert_eq!(take_log(&mut app), vec!["pause game", "enter pause"]);
+        push(&mut app, Mode::Options);
+        assert_eq!(take_log(&mut app), vec!["pause pause", "enter options"]);
+        assert_eq!(
+            app.world().resource::<StateStack<Mode>>().paused(),
+            &[Mode::Game, Mode::Pause]
+        );
+
+        pop(&mut app);
+        assert_eq!(take_log(&mut app), vec!["exit options", "resume pause"]);
+        pop(&mut app);
+        assert_eq!(take_log(&mut app), vec!["exit pause", "resume game"]);
+        assert_eq!(*app.world().resource::<State<Mode>>().get(), Mode::Game);
+
+        // There is nothing left to resume.
+        pop(&mut app);
+        assert!(take_log(&mut app).is_empty());
+        assert_eq!(*app.world().resource::<State<Mode>>().get(), Mode::Game);
+    }
+
+    #[test]
+    fn pushing_the_current_state_does_nothing() {
+        let mut app = app();
+
+        push(&mut app, Mode::Game);
+        assert!(take_log(&mut app).is_empty());
+        assert_eq!(app.world().resource::<StateStack<Mode>>().depth(), 0);
+
+        push(&mut app, Mode::Pause);
+        push(&mut app, Mode::Pause);
+        assert_eq!(take_log(&mut app), vec!["pause game", "enter pause"]);
+        assert_eq!(
+            app.world().resource::<StateStack<Mode>>().paused(),
+            &[Mode::Game]
+        );
+
+        pop(&mut app);
+        assert_eq!(take_log(&mut app), vec!["exit pause", "resume game"]);
+        assert_eq!(app.world().resource::<StateStack<Mode>>().depth(), 0);
+    }
+
+    #[test]
+    fn next_state_replaces_top_of_stack() {
+        let mut app = app();
+
+        push(&mut app, Mode::Pause);
+        take_log(&mut app);
+
+        // The stack operation goes first, the replacement waits for the next transition.
+        app.world_mut()
+            .resource_mut::<NextState<Mode>>()
+            .set(Mode::Options);
+        push(&mut app, Mode::Options);
+        assert_eq!(take_log(&mut app), vec!["pause pause", "enter options"]);
+        app.update();
+        assert!(take_log(&mut app).is_empty());
+        assert_eq!(*app.world().resource::<State<Mode>>().get(), Mode::Options);
+
+        app.world_mut()
+            .resource_mut::<NextState<Mode>>()
+            .set(Mode::Pause);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["exit options", "enter pause"]);
+        assert_eq!(app.world().resource::<StateStack<Mode>>().depth(), 2);
+    }
+
+    #[test]
+    fn paused_state_keeps_scoped_entities() {
+        let mut app = app();
+        let game = app.world_mut().spawn(DespawnOnExitState(Mode::Game)).id();
+        let pause = app.world_mut().spawn(DespawnOnExitState(Mode::Pause)).id();
+
+        push(&mut app, Mode::Pause);
+        assert!(app.world().get_entity(game).is_ok());
+        assert!(app.world().get_entity(pause).is_ok());
+
+        pop(&mut app);
+        assert!(app.world().get_entity(game).is_ok());
+        assert!(app.world().get_entity(pause).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    event::{BufferedEvent, Events},
+    resource::Resource,
+    schedule::{IntoScheduleConfigs, Schedule, ScheduleLabel},
+    world::World,
+};
+
+use super::{
+    freely_mutable_state::FreelyMutableState, states::States, transitions::*, NextState, State,
+};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::prelude::ReflectResource;
+
+/// The label of a [`Schedule`](bevy_ecs::schedule::Schedule) that **only** runs when a gated
+/// transition of [`State<S>`] from `exited` to `entered` is requested.
+///
+/// Systems in this schedule can place holds on the [`TransitionGate<S>`], which keep the
+/// transition in flight until they are released. If no holds are placed, the transition
+/// completes right away, in the same [`StateTransition`] run.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct OnTransitionStart<S: States> {
+    /// The state being exited.
+    pub exited: S,
+    /// The state that will be entered once the transition completes.
+    pub entered: S,
+}
+
+/// A [`BufferedEvent`] sent when a gated transition of `S` starts.
+///
+/// The matching [`StateTransitionEvent`](super::StateTransitionEvent) is sent once the
+/// transition completes, which may be several frames later.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, BufferedEvent)]
+pub struct StateTransitionStarted<S: States> {
+    /// The state being exited.
+    pub exited: S,
+    /// The state that will be entered once the transition completes.
+    pub entered: S,
+}
+
+/// Holds state transitions of `S` open until some conditions are met, such as assets being
+/// loaded or a fade-out finishing.
+///
+/// Gated transitions are enabled with
+/// [`enable_gated_transitions`](crate::app::AppExtStates::enable_gated_transitions).
+/// When a new state is requested through [`NextState<S>`], the transition starts but the
+/// [`State<S>`] keeps its current value while any hold is placed on the gate. Once every hold
+/// is released, the transition completes as usual, running [`OnExit`](super::OnExit),
+/// [`OnTransition`](super::OnTransition) and [`OnEnter`](super::OnEnter).
+///
+/// Holds are identified by name, so the system which places one doesn't need to remember
+/// anything to release it. A hold placed while no transition is in flight gates the next one.
+///
+/// Requesting another state while a transition is in flight retargets it: the holds of the
+/// previous transition are released and the new transition starts. Requesting the current
+/// state cancels the transition in flight, releasing its holds as well.
+///
+/// ```
+/// use bevy_state::prelude::*;
+/// use bevy_ecs::prelude::*;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+/// enum GameState {
+///     #[default]
+///     MainMenu,
+///     InGame,
+/// }
+///
+/// // Added to `OnTransitionStart { exited: GameState::MainMenu, entered: GameState::InGame }`
+/// fn start_loading_level(mut gate: ResMut<TransitionGate<GameState>>) {
+///     gate.hold("level assets");
+/// }
+///
+/// fn finish_loading_level(mut gate: ResMut<TransitionGate<GameState>>) {
+///     # let level_loaded = true;
+///     if level_loaded {
+///         gate.release("level assets");
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(bevy_reflect::Reflect),
+    reflect(Resource, Debug)
+)]
+pub struct TransitionGate<S: States> {
+    holds: Vec<Cow<'static, str>>,
+    pending: Option<S>,
+}
+
+impl<S: States> Default for TransitionGate<S> {
+    fn default() -> Self {
+        Self {
+            holds: Vec::new(),
+            pending: None,
+        }
+    }
+}
+
+impl<S: States> TransitionGate<S> {
+    /// Places a hold named `name` on the gate, keeping transitions in flight until it is
+    /// released. Placing a hold which is already placed has no effect.
+    pub fn hold(&mut self, name: impl Into<Cow<'static, str>>) {
+        let name = name.into();
+        if !self.holds.contains(&name) {
+            self.holds.push(name);
+        }
+    }
+
+    /// Releases the hold named `name`, returning `false` if there was no such hold.
+    pub fn release(&mut self, name: &str) -> bool {
+        let len = self.holds.len();
+        self.holds.retain(|hold| hold != name);
+        self.holds.len() != len
+    }
+
+    /// Releases every hold.
+    pub fn release_all(&mut self) {
+        self.holds.clear();
+    }
+
+    /// Returns `true` if any hold is placed on the gate.
+    pub fn is_held(&self) -> bool {
+        !self.holds.is_empty()
+    }
+
+    /// Returns the names of the holds placed on the gate.
+    pub fn holds(&self) -> impl Iterator<Item = &str> {
+        self.holds.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns the state which the transition in flight will enter, if any.
+    ///
+    /// The state being exited is the current value of [`State<S>`].
+    pub fn pending(&self) -> Option<&S> {
+        self.pending.as_ref()
+    }
+}
+
+/// Adds the system gating transitions of `S` to the [`StateTransition`] schedule.
+pub(crate) fn register_transition_gate<S: FreelyMutableState>(schedule: &mut Schedule) {
+    schedule.add_systems(
+        gate_state_transition::<S>
+            .in_set(StateTransitionSystems::DependentTransitions)
+            .before(ApplyStateTransition::<S>::default()),
+    );
+}
+
+/// Starts gated transitions requested through [`NextState<S>`], and hands them over to the
+/// regular transition systems once the [`TransitionGate<S>`] is released.
+fn gate_state_transition<S: FreelyMutableState>(world: &mut World) {
+    let Some(current) = world
+        .get_resource::<State<S>>()
+        .map(|state| state.get().clone())
+    else {
+        return;
+    };
+    let requested = world
+        .get_resource_mut::<NextState<S>>()
+        .and_then(
+            |mut next_state| match core::mem::take(next_state.bypass_change_detection()) {
+                NextState::Pending(state) => {
+                    next_state.set_changed();
+                    Some(state)
+                }
+                NextState::Unchanged => None,
+            },
+        );
+
+    if let Some(entered) = requested {
+        let in_flight = world
+            .get_resource::<TransitionGate<S>>()
+            .is_some_and(|gate| gate.pending.is_some());
+        // Identity transitions have nothing to wait for, unless they cancel a transition in flight.
+        if entered == current && !in_flight {
+            world.resource_mut::<NextState<S>>().set(entered);
+            return;
+        }
+        let Some(mut gate) = world.get_resource_mut::<TransitionGate<S>>() else {
+            return;
+        };
+        if entered == current {
+            gate.pending = None;
+            gate.holds.clear();
+            return;
+        }
+        // Holds belong to the transition in flight, so they don't carry over to its retarget.
+        if gate.pending.replace(entered.clone()).is_some() {
+            gate.holds.clear();
+        }
+
+        if let Some(mut events) = world.get_resource_mut::<Events<StateTransitionStarted<S>>>() {
+            events.write(StateTransitionStarted {
+                exited: current.clone(),
+                entered: entered.clone(),
+            });
+        }
+        let _ = world.try_run_schedule(OnTransitionStart {
+            exited: current,
+            entered,
+        });
+    }
+
+    let Some(mut gate) = world.get_resource_mut::<TransitionGate<S>>() else {
+        return;
+    };
+    if gate.is_held() {
+        return;
+    }
+    if let Some(entered) = gate.pending.take() {
+        world.resource_mut::<NextState<S>>().set(entered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_state_macros::States;
+
+    use crate::{app::StatesPlugin, prelude::*};
+
+    #[derive(States, PartialEq, Eq, Debug, Default, Hash, Clone)]
+    enum Screen {
+        #[default]
+        Menu,
+        Game,
+        Credits,
+    }
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_resource::<Log>()
+            .init_state::<Screen>()
+            .enable_gated_transitions::<Screen>()
+            .add_systems(
+                OnTransitionStart {
+                    exited: Screen::Menu,
+                    entered: Screen::Game,
+                },
+                |mut gate: ResMut<TransitionGate<Screen>>, mut log: ResMut<Log>| {
+                    gate.hold("loading");
+                    log.0.push("start");
+                },
+            )
+            .add_systems(OnExit(Screen::Menu), |mut log: ResMut<Log>| {
+                log.0.push("exit menu");
+            })
+            .add_systems(OnEnter(Screen::Game), |mut log: ResMut<Log>| {
+                log.0.push("enter game");
+            });
+        app.update();
+        app
+    }
+
+    fn take_log(app: &mut App) -> Vec<&'static str> {
+        core::mem::take(&mut app.world_mut().resource_mut::<Log>().0)
+    }
+
+    #[test]
+    fn transition_waits_for_holds() {
+        let mut app = app();
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Game);
+        app.update();
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["start"]);
+        assert_eq!(*app.world().resource::<State<Screen>>().get(), Screen::Menu);
+        assert_eq!(
+            app.world().resource::<TransitionGate<Screen>>().pending(),
+            Some(&Screen::Game)
+        );
+
+        app.world_mut()
+            .resource_mut::<TransitionGate<Screen>>()
+            .release("loading");
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["exit menu", "enter game"]);
+        assert_eq!(*app.world().resource::<State<Screen>>().get(), Screen::Game);
+        assert_eq!(
+            app.world().resource::<TransitionGate<Screen>>().pending(),
+            None
+        );
+    }
+
+    #[test]
+    fn transition_without_holds_is_immediate() {
+        let mut app = app();
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Credits);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["exit menu"]);
+        assert_eq!(
+            *app.world().resource::<State<Screen>>().get(),
+            Screen::Credits
+        );
+    }
+
+    #[test]
+    fn transition_phase_is_exposed() {
+        #[derive(Resource, Default)]
+        struct Transitioning(u32, u32);
+
+        let mut app = app();
+        app.init_resource::<Transitioning>().add_systems(
+            bevy_app::Update,
+            (
+                (|mut counter: ResMut<Transitioning>| counter.0 += 1)
+                    .run_if(in_transition::<Screen>),
+                (|mut counter: ResMut<Transitioning>| counter.1 += 1)
+                    .run_if(transitioning_to(Screen::Game)),
+            ),
+        );
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Game);
+        app.update();
+        let events = app
+            .world()
+            .resource::<Events<StateTransitionStarted<Screen>>>();
+        assert_eq!(
+            events.iter_current_update_events().last(),
+            Some(&StateTransitionStarted {
+                exited: Screen::Menu,
+                entered: Screen::Game,
+            })
+        );
+        app.update();
+        app.world_mut()
+            .resource_mut::<TransitionGate<Screen>>()
+            .release_all();
+        app.update();
+        app.update();
+
+        let counter = app.world().resource::<Transitioning>();
+        assert_eq!((counter.0, counter.1), (2, 2));
+    }
+
+    #[test]
+    fn retarget_releases_previous_holds() {
+        let mut app = app();
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Game);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["start"]);
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Credits);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["exit menu"]);
+        assert_eq!(
+            *app.world().resource::<State<Screen>>().get(),
+            Screen::Credits
+        );
+        let gate = app.world().resource::<TransitionGate<Screen>>();
+        assert!(!gate.is_held());
+        assert_eq!(gate.pending(), None);
+    }
+
+    #[test]
+    fn cancel_releases_holds() {
+        let mut app = app();
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Game);
+        app.update();
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Menu);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["start"]);
+        assert_eq!(*app.world().resource::<State<Screen>>().get(), Screen::Menu);
+        assert!(!app.world().resource::<TransitionGate<Screen>>().is_held());
+
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Credits);
+        app.update();
+        assert_eq!(take_log(&mut app), vec!["exit menu"]);
+        assert_eq!(
+            *app.world().resource::<State<Screen>>().get(),
+            Screen::Credits
+        );
+    }
+}
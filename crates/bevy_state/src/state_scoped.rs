@@ -4,12 +4,12 @@ use bevy_ecs::{
     component::Component,
     entity::Entity,
     event::EventReader,
-    system::{Commands, Query},
+    system::{Commands, Query, Res},
 };
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::prelude::*;
 
-use crate::state::{StateTransitionEvent, States};
+use crate::state::{StateStack, StateTransitionEvent, States};
 
 /// Entities marked with this component will be removed
 /// when the world's state of the matching type no longer matches the supplied value.
@@ -65,10 +65,13 @@ where
 
 /// Despawns entities marked with [`DespawnOnExitState<S>`] when their state no
 /// longer matches the world state.
+///
+/// Entities of a state which is paused by pushing another on its [`StateStack<S>`] are kept.
 pub fn despawn_entities_on_exit_state<S: States>(
     mut commands: Commands,
     mut transitions: EventReader<StateTransitionEvent<S>>,
     query: Query<(Entity, &DespawnOnExitState<S>)>,
+    stack: Option<Res<StateStack<S>>>,
 ) {
     // We use the latest event, because state machine internals generate at most 1
     // transition event (per type) each frame. No event means no change happened
@@ -76,7 +79,7 @@ pub fn despawn_entities_on_exit_state<S: States>(
     let Some(transition) = transitions.read().last() else {
         return;
     };
-    if transition.entered == transition.exited {
+    if transition.entered == transition.exited || stack.is_some_and(|stack| stack.pushed()) {
         return;
     }
     let Some(exited) = &transition.exited else {
@@ -134,10 +137,13 @@ pub struct DespawnOnEnterState<S: States>(pub S);
 
 /// Despawns entities marked with [`DespawnOnEnterState<S>`] when their state
 /// matches the world state.
+///
+/// Resuming a paused state of a [`StateStack<S>`] does not count as entering it.
 pub fn despawn_entities_on_enter_state<S: States>(
     mut commands: Commands,
     mut transitions: EventReader<StateTransitionEvent<S>>,
     query: Query<(Entity, &DespawnOnEnterState<S>)>,
+    stack: Option<Res<StateStack<S>>>,
 ) {
     // We use the latest event, because state machine internals generate at most 1
     // transition event (per type) each frame. No event means no change happened
@@ -145,7 +151,7 @@ pub fn despawn_entities_on_enter_state<S: States>(
     let Some(transition) = transitions.read().last() else {
         return;
     };
-    if transition.entered == transition.exited {
+    if transition.entered == transition.exited || stack.is_some_and(|stack| stack.popped()) {
         return;
     }
     let Some(entered) = &transition.entered else {
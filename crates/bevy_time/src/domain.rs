@@ -0,0 +1,311 @@
+use alloc::vec::Vec;
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::reflect::ReflectComponent;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    hierarchy::ChildOf,
+    system::{Query, Res, SystemParam},
+};
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::Reflect;
+use core::time::Duration;
+
+use crate::{time::Time, virt::Virtual};
+
+/// A clock shared by a group of entities, with its own speed and pause state.
+///
+/// A time domain is an entity with this component. Other entities opt into it with
+/// [`InTimeDomain`], which also applies to their descendants. The domain advances alongside
+/// [`Time<Virtual>`], scaled by its own [`relative_speed`](Self::relative_speed), so pausing the
+/// virtual clock pauses every domain too.
+///
+/// Time domains make it possible to slow down only some entities, such as the enemies during a
+/// slow-motion effect, or to pause gameplay while the UI keeps animating. Systems read the time of
+/// an entity's domain through [`DomainTime`], and animation players and audio playback follow the
+/// domain of their entity.
+///
+/// Domains are advanced once per update, in [`TimeSystems`](crate::TimeSystems). Their delta is
+/// not meaningful inside [`FixedMain`](bevy_app::FixedMain) schedules.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_time::{InTimeDomain, TimeDomain};
+/// # let mut world = World::new();
+/// let enemies = world.spawn((Name::new("Enemies"), TimeDomain::default())).id();
+/// world.spawn(InTimeDomain(enemies));
+///
+/// // Slow motion, for the enemies only.
+/// world
+///     .get_mut::<TimeDomain>(enemies)
+///     .unwrap()
+///     .set_relative_speed(0.25);
+/// ```
+#[derive(Component, Debug, Clone)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect(Component, Clone))]
+pub struct TimeDomain {
+    time: Time,
+    paused: bool,
+    relative_speed: f64,
+    effective_speed: f64,
+}
+
+impl Default for TimeDomain {
+    fn default() -> Self {
+        Self {
+            time: Time::default(),
+            paused: false,
+            relative_speed: 1.0,
+            effective_speed: 1.0,
+        }
+    }
+}
+
+impl TimeDomain {
+    /// Creates a time domain advancing at `ratio` times the speed of [`Time<Virtual>`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is negative or not finite.
+    pub fn with_relative_speed(ratio: f32) -> Self {
+        let mut domain = Self::default();
+        domain.set_relative_speed(ratio);
+        domain
+    }
+
+    /// Returns the clock of this domain.
+    ///
+    /// Its [`delta`](Time::delta) is the time the domain advanced by during the current update.
+    #[inline]
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// Returns how much time the domain advanced by during the current update.
+    #[inline]
+    pub fn delta(&self) -> Duration {
+        self.time.delta()
+    }
+
+    /// Returns how much time the domain advanced by during the current update, as [`f32`] seconds.
+    #[inline]
+    pub fn delta_secs(&self) -> f32 {
+        self.time.delta_secs()
+    }
+
+    /// Returns how much time the domain advanced by since it was created.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.time.elapsed()
+    }
+
+    /// Returns the speed of the domain relative to [`Time<Virtual>`], as [`f32`].
+    ///
+    /// This ignores whether the domain is paused.
+    #[inline]
+    pub fn relative_speed(&self) -> f32 {
+        self.relative_speed as f32
+    }
+
+    /// Returns the speed the domain advanced at relative to [`Time<Virtual>`] during the current
+    /// update, as [`f32`].
+    ///
+    /// This is `0.0` if the domain was paused, and doesn't include the speed of
+    /// [`Time<Virtual>`] itself.
+    #[inline]
+    pub fn effective_speed(&self) -> f32 {
+        self.effective_speed as f32
+    }
+
+    /// Sets the speed of the domain relative to [`Time<Virtual>`].
+    ///
+    /// This takes effect from the next update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is negative or not finite.
+    pub fn set_relative_speed(&mut self, ratio: f32) {
+        assert!(ratio.is_finite(), "tried to go infinitely fast");
+        assert!(ratio >= 0.0, "tried to go back in time");
+        self.relative_speed = ratio as f64;
+    }
+
+    /// Stops the domain, so its delta is zero from the next update.
+    #[inline]
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the domain.
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the domain is paused.
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Advances the domain by the delta of the virtual clock, scaled by its speed.
+    fn advance(&mut self, virtual_delta: Duration) {
+        self.effective_speed = if self.paused {
+            0.0
+        } else {
+            self.relative_speed
+        };
+        let delta = if self.effective_speed != 1.0 {
+            virtual_delta.mul_f64(self.effective_speed)
+        } else {
+            virtual_delta
+        };
+        self.time.advance_by(delta);
+    }
+}
+
+/// Places an entity and its descendants in the [`TimeDomain`] of the given entity.
+///
+/// This is a [`Relationship`](bevy_ecs::relationship::Relationship), and pairs with the
+/// [`TimeDomainMembers`] component on the domain.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = TimeDomainMembers)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, PartialEq, Debug, Clone)
+)]
+pub struct InTimeDomain(pub Entity);
+
+/// The entities which opted into a [`TimeDomain`] with [`InTimeDomain`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = InTimeDomain)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect(Component, Debug))]
+pub struct TimeDomainMembers(Vec<Entity>);
+
+/// Advances every [`TimeDomain`] based on [`Time<Virtual>`].
+pub fn update_time_domains(virtual_time: Res<Time<Virtual>>, mut domains: Query<&mut TimeDomain>) {
+    let delta = virtual_time.delta();
+    for mut domain in &mut domains {
+        domain.advance(delta);
+    }
+}
+
+/// A [`SystemParam`] giving the time of the [`TimeDomain`] of an entity.
+///
+/// Entities outside of any domain use the generic [`Time`] resource instead. This is the
+/// preferred way to tick a [`Timer`](crate::Timer) or a [`Stopwatch`](crate::Stopwatch) which
+/// belongs to an entity:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_time::{DomainTime, Timer};
+/// #[derive(Component)]
+/// struct Cooldown(Timer);
+///
+/// fn tick_cooldowns(domain_time: DomainTime, mut query: Query<(Entity, &mut Cooldown)>) {
+///     for (entity, mut cooldown) in &mut query {
+///         cooldown.0.tick(domain_time.delta(entity));
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(tick_cooldowns);
+/// ```
+#[derive(SystemParam)]
+pub struct DomainTime<'w, 's> {
+    time: Res<'w, Time>,
+    domains: Query<'w, 's, &'static TimeDomain>,
+    members: Query<'w, 's, &'static InTimeDomain>,
+    parents: Query<'w, 's, &'static ChildOf>,
+}
+
+impl DomainTime<'_, '_> {
+    /// Returns the [`TimeDomain`] of `entity`, which is the one of the entity itself or its
+    /// closest ancestor with an [`InTimeDomain`] component.
+    pub fn domain(&self, entity: Entity) -> Option<&TimeDomain> {
+        let member = core::iter::once(entity)
+            .chain(self.parents.iter_ancestors(entity))
+            .find_map(|entity| self.members.get(entity).ok())?;
+        self.domains.get(member.0).ok()
+    }
+
+    /// Returns how much time passed for `entity` during the current update.
+    pub fn delta(&self, entity: Entity) -> Duration {
+        self.domain(entity)
+            .map_or(self.time.delta(), TimeDomain::delta)
+    }
+
+    /// Returns how much time passed for `entity` during the current update, as [`f32`] seconds.
+    pub fn delta_secs(&self, entity: Entity) -> f32 {
+        self.domain(entity)
+            .map_or(self.time.delta_secs(), TimeDomain::delta_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TimePlugin, TimeUpdateStrategy};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Deltas(Vec<Duration>);
+
+    #[test]
+    fn domains_scale_virtual_time() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .init_resource::<Deltas>();
+
+        let slow = app
+            .world_mut()
+            .spawn(TimeDomain::with_relative_speed(0.5))
+            .id();
+        let paused = app.world_mut().spawn(TimeDomain::default()).id();
+        app.world_mut()
+            .get_mut::<TimeDomain>(paused)
+            .unwrap()
+            .pause();
+
+        let enemy = app.world_mut().spawn(InTimeDomain(slow)).id();
+        let weapon = app.world_mut().spawn(ChildOf(enemy)).id();
+        let menu = app.world_mut().spawn(InTimeDomain(paused)).id();
+        let player = app.world_mut().spawn_empty().id();
+
+        app.add_systems(
+            bevy_app::Update,
+            move |domain_time: DomainTime, mut deltas: ResMut<Deltas>| {
+                deltas.0 = [enemy, weapon, menu, player]
+                    .into_iter()
+                    .map(|entity| domain_time.delta(entity))
+                    .collect();
+            },
+        );
+
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world().resource::<Deltas>().0,
+            [
+                Duration::from_millis(50),
+                Duration::from_millis(50),
+                Duration::ZERO,
+                Duration::from_millis(100),
+            ]
+        );
+        assert_eq!(
+            app.world()
+                .get::<TimeDomain>(slow)
+                .unwrap()
+                .effective_speed(),
+            0.5
+        );
+
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        app.update();
+        assert_eq!(app.world().resource::<Deltas>().0, [Duration::ZERO; 4]);
+    }
+}
@@ -0,0 +1,268 @@
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::reflect::ReflectComponent;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::EntityEvent,
+    system::{Commands, Query, Res},
+};
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use core::time::Duration;
+
+use crate::{DomainTime, Fixed, Real, Stopwatch, Time, Timer, TimerMode};
+
+/// The clock driving an [`EntityTimer`] or an [`EntityStopwatch`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Default, PartialEq, Hash, Debug, Clone)
+)]
+pub enum TimerClock {
+    /// Ticks once per update with [`Time<Virtual>`](crate::Virtual), or with the
+    /// [`TimeDomain`](crate::TimeDomain) of the entity if it is in one.
+    #[default]
+    Virtual,
+    /// Ticks once per update with [`Time<Real>`], ignoring pauses and speed changes.
+    Real,
+    /// Ticks once per fixed timestep with [`Time<Fixed>`].
+    Fixed,
+}
+
+/// A [`Timer`] which is ticked automatically by the [`TimePlugin`](crate::TimePlugin).
+///
+/// Whenever the timer finishes, a [`TimerFinished`] event is triggered on its entity. It is
+/// triggered at most once per tick: a repeating timer that wraps around several times during a
+/// tick triggers a single event, whose [`times_finished`](TimerFinished::times_finished) counts
+/// the wraps.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_time::{EntityTimer, TimerFinished, TimerMode};
+/// # let mut world = World::new();
+/// world
+///     .spawn(EntityTimer::from_seconds(2.0, TimerMode::Once))
+///     .observe(|trigger: On<TimerFinished>, mut commands: Commands| {
+///         commands.entity(trigger.target()).despawn();
+///     });
+/// ```
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, PartialEq, Debug, Clone)
+)]
+pub struct EntityTimer {
+    /// The ticked timer.
+    pub timer: Timer,
+    /// The clock ticking the timer.
+    pub clock: TimerClock,
+}
+
+impl EntityTimer {
+    /// Creates a new timer ticked by [`TimerClock::Virtual`].
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Self {
+            timer: Timer::new(duration, mode),
+            clock: TimerClock::Virtual,
+        }
+    }
+
+    /// Creates a new timer ticked by [`TimerClock::Virtual`], with a duration in seconds.
+    pub fn from_seconds(duration: f32, mode: TimerMode) -> Self {
+        Self {
+            timer: Timer::from_seconds(duration, mode),
+            clock: TimerClock::Virtual,
+        }
+    }
+
+    /// Returns this timer ticked by `clock` instead.
+    pub fn with_clock(mut self, clock: TimerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn tick(&mut self, entity: Entity, delta: Duration, commands: &mut Commands) {
+        self.timer.tick(delta);
+        if self.timer.just_finished() {
+            commands.trigger_targets(
+                TimerFinished {
+                    times_finished: self.timer.times_finished_this_tick(),
+                },
+                entity,
+            );
+        }
+    }
+}
+
+/// A [`Stopwatch`] which is ticked automatically by the [`TimePlugin`](crate::TimePlugin).
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, PartialEq, Debug, Clone)
+)]
+pub struct EntityStopwatch {
+    /// The ticked stopwatch.
+    pub stopwatch: Stopwatch,
+    /// The clock ticking the stopwatch.
+    pub clock: TimerClock,
+}
+
+impl EntityStopwatch {
+    /// Creates a new stopwatch ticked by [`TimerClock::Virtual`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this stopwatch ticked by `clock` instead.
+    pub fn with_clock(mut self, clock: TimerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+/// An [`EntityEvent`] triggered on the entity of an [`EntityTimer`] when it finishes.
+#[derive(EntityEvent, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerFinished {
+    /// How many times the timer finished during the last tick.
+    ///
+    /// This is always `1` for [`TimerMode::Once`] timers, and can be greater for
+    /// [`TimerMode::Repeating`] ones if their duration is shorter than a tick.
+    pub times_finished: u32,
+}
+
+/// Ticks the [`EntityTimer`]s and [`EntityStopwatch`]es which run once per update.
+pub fn tick_entity_timers(
+    mut commands: Commands,
+    domain_time: DomainTime,
+    real_time: Res<Time<Real>>,
+    mut timers: Query<(Entity, &mut EntityTimer)>,
+    mut stopwatches: Query<(Entity, &mut EntityStopwatch)>,
+) {
+    let delta = |entity, clock| match clock {
+        TimerClock::Virtual => Some(domain_time.delta(entity)),
+        TimerClock::Real => Some(real_time.delta()),
+        TimerClock::Fixed => None,
+    };
+
+    for (entity, mut timer) in &mut timers {
+        if let Some(delta) = delta(entity, timer.clock) {
+            timer.tick(entity, delta, &mut commands);
+        }
+    }
+    for (entity, mut stopwatch) in &mut stopwatches {
+        if let Some(delta) = delta(entity, stopwatch.clock) {
+            stopwatch.stopwatch.tick(delta);
+        }
+    }
+}
+
+/// Ticks the [`EntityTimer`]s and [`EntityStopwatch`]es which run once per fixed timestep.
+pub fn tick_fixed_entity_timers(
+    mut commands: Commands,
+    fixed_time: Res<Time<Fixed>>,
+    mut timers: Query<(Entity, &mut EntityTimer)>,
+    mut stopwatches: Query<&mut EntityStopwatch>,
+) {
+    let delta = fixed_time.delta();
+    for (entity, mut timer) in &mut timers {
+        if timer.clock == TimerClock::Fixed {
+            timer.tick(entity, delta, &mut commands);
+        }
+    }
+    for mut stopwatch in &mut stopwatches {
+        if stopwatch.clock == TimerClock::Fixed {
+            stopwatch.stopwatch.tick(delta);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TimePlugin, TimeUpdateStrategy};
+    use alloc::{vec, vec::Vec};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+
+    #[derive(Resource, Default)]
+    struct Finished(Vec<(Entity, u32)>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .init_resource::<Finished>()
+            .add_observer(
+                |trigger: On<TimerFinished>, mut finished: ResMut<Finished>| {
+                    finished.0.push((trigger.target(), trigger.times_finished));
+                },
+            );
+        // The first update has no delta.
+        app.update();
+        app
+    }
+
+    #[test]
+    fn timers_trigger_finished() {
+        let mut app = app();
+        let once = app
+            .world_mut()
+            .spawn(EntityTimer::new(
+                Duration::from_millis(150),
+                TimerMode::Once,
+            ))
+            .id();
+        let repeating = app
+            .world_mut()
+            .spawn(EntityTimer::new(
+                Duration::from_millis(40),
+                TimerMode::Repeating,
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(app.world().resource::<Finished>().0, vec![(repeating, 2)]);
+
+        app.world_mut().resource_mut::<Finished>().0.clear();
+        app.update();
+        assert_eq!(
+            app.world().resource::<Finished>().0,
+            vec![(once, 1), (repeating, 3)]
+        );
+
+        app.world_mut().resource_mut::<Finished>().0.clear();
+        app.update();
+        assert_eq!(app.world().resource::<Finished>().0, vec![(repeating, 2)]);
+    }
+
+    #[test]
+    fn clocks_drive_stopwatches() {
+        let mut app = app();
+        let virtual_stopwatch = app.world_mut().spawn(EntityStopwatch::new()).id();
+        let real_stopwatch = app
+            .world_mut()
+            .spawn(EntityStopwatch::new().with_clock(TimerClock::Real))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<Time<crate::Virtual>>()
+            .pause();
+        app.update();
+        app.update();
+
+        let elapsed = |entity| {
+            app.world()
+                .get::<EntityStopwatch>(entity)
+                .unwrap()
+                .stopwatch
+                .elapsed()
+        };
+        assert_eq!(elapsed(virtual_stopwatch), Duration::ZERO);
+        assert_eq!(elapsed(real_stopwatch), Duration::from_millis(200));
+    }
+}
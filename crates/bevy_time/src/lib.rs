@@ -14,6 +14,8 @@ extern crate alloc;
 
 /// Common run conditions
 pub mod common_conditions;
+mod domain;
+mod entity_timer;
 mod fixed;
 mod real;
 mod stopwatch;
@@ -21,6 +23,8 @@ mod time;
 mod timer;
 mod virt;
 
+pub use domain::*;
+pub use entity_timer::*;
 pub use fixed::*;
 pub use real::*;
 pub use stopwatch::*;
@@ -33,7 +37,10 @@ pub use virt::*;
 /// This includes the most common types in this crate, re-exported for your convenience.
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{Fixed, Real, Time, Timer, TimerMode, Virtual};
+    pub use crate::{
+        DomainTime, EntityStopwatch, EntityTimer, Fixed, InTimeDomain, Real, Time, TimeDomain,
+        Timer, TimerClock, TimerFinished, TimerMode, Virtual,
+    };
 }
 
 use bevy_app::{prelude::*, RunFixedMainLoop};
@@ -76,15 +83,24 @@ impl Plugin for TimePlugin {
             app.register_type::<Time>()
                 .register_type::<Time<Real>>()
                 .register_type::<Time<Virtual>>()
-                .register_type::<Time<Fixed>>();
+                .register_type::<Time<Fixed>>()
+                .register_type::<TimeDomain>()
+                .register_type::<InTimeDomain>()
+                .register_type::<TimeDomainMembers>()
+                .register_type::<EntityTimer>()
+                .register_type::<EntityStopwatch>();
         }
 
         app.add_systems(
             First,
-            time_system
-                .in_set(TimeSystems)
-                .ambiguous_with(event_update_system),
+            (
+                time_system.ambiguous_with(event_update_system),
+                update_time_domains.after(time_system),
+            )
+                .in_set(TimeSystems),
         )
+        .add_systems(First, tick_entity_timers.after(TimeSystems))
+        .add_systems(FixedFirst, tick_fixed_entity_timers)
         .add_systems(
             RunFixedMainLoop,
             run_fixed_main_schedule.in_set(RunFixedMainLoopSystems::FixedMainLoop),
@@ -0,0 +1,338 @@
+//! Smooths the motion of entities moved in [`FixedUpdate`] by interpolating their [`Transform`]
+//! between fixed timesteps.
+//!
+//! Gameplay code running in [`FixedUpdate`] moves entities in discrete steps, while frames are
+//! usually rendered at a different rate. Without interpolation, an entity appears to stand still
+//! on frames where no fixed timestep ran and to jump on the others, which is visible as stutter.
+//!
+//! Adding [`TransformInterpolation`] to an entity records its [`Transform`] at the end of each
+//! fixed timestep. Every frame, the [`Transform`] is then replaced by a visual transform blended
+//! from the last two recorded ones, using [`Time<Fixed>::overstep_fraction`]. Before the next fixed
+//! timestep, the recorded [`Transform`] is restored, so systems in [`FixedUpdate`] never observe
+//! the visual transform.
+//!
+//! [`FixedUpdate`]: bevy_app::FixedUpdate
+
+use crate::{components::Transform, plugins::TransformSystems};
+use bevy_app::{App, FixedFirst, FixedLast, Plugin, PostUpdate};
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    component::Component,
+    schedule::{IntoScheduleConfigs, SystemSet},
+    system::{Query, Res},
+};
+use bevy_time::{Fixed, Time};
+
+#[cfg(feature = "bevy_reflect")]
+use {bevy_ecs::reflect::ReflectComponent, bevy_reflect::prelude::*};
+
+/// Set enum for the systems interpolating [`Transform`]s between fixed timesteps.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum TransformInterpolationSystems {
+    /// Restores the [`Transform`]s recorded at the end of the previous fixed timestep.
+    ///
+    /// Runs in [`FixedFirst`].
+    Restore,
+    /// Records the [`Transform`]s at the end of each fixed timestep.
+    ///
+    /// Runs in [`FixedLast`].
+    Record,
+    /// Writes the visual [`Transform`]s.
+    ///
+    /// Runs in [`PostUpdate`], before [`TransformSystems::Propagate`].
+    Interpolate,
+}
+
+/// Adds systems interpolating the [`Transform`] of entities with a [`TransformInterpolation`]
+/// component between fixed timesteps.
+///
+/// This plugin requires the [`Time<Fixed>`] resource, which is added by `TimePlugin`.
+#[derive(Default)]
+pub struct TransformInterpolationPlugin;
+
+impl Plugin for TransformInterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedFirst,
+            restore_fixed_transforms.in_set(TransformInterpolationSystems::Restore),
+        )
+        .add_systems(
+            FixedLast,
+            record_fixed_transforms.in_set(TransformInterpolationSystems::Record),
+        )
+        .add_systems(
+            PostUpdate,
+            interpolate_transforms
+                .in_set(TransformInterpolationSystems::Interpolate)
+                .before(TransformSystems::Propagate),
+        );
+    }
+}
+
+/// Smooths the [`Transform`] of an entity moved in [`FixedUpdate`](bevy_app::FixedUpdate).
+///
+/// Requires the [`TransformInterpolationPlugin`].
+///
+/// The [`Transform`] seen outside of the fixed timestep schedules is a visual transform, derived
+/// from the ones recorded at the end of the last two fixed timesteps. Writing to the [`Transform`]
+/// outside of the fixed timestep schedules, or calling [`TransformHistory::teleport`], moves the
+/// entity without smoothing, so it doesn't smear across the discontinuity.
+#[derive(Component, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[require(TransformHistory)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, PartialEq, Debug, Clone)
+)]
+pub enum TransformInterpolation {
+    /// Blends from the second to last recorded [`Transform`] to the last one.
+    ///
+    /// The visual transform lags up to one fixed timestep behind the recorded one, but always
+    /// lies on the path the entity actually took.
+    #[default]
+    Interpolate,
+    /// Blends past the last recorded [`Transform`], continuing the motion of the last fixed
+    /// timestep.
+    ///
+    /// The visual transform doesn't lag behind the recorded one, but overshoots when the motion
+    /// of the entity changes.
+    Extrapolate,
+}
+
+/// The [`Transform`]s of an entity with [`TransformInterpolation`], recorded at the end of the
+/// last two fixed timesteps.
+#[derive(Component, Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, PartialEq, Debug, Clone)
+)]
+pub struct TransformHistory {
+    previous: Transform,
+    current: Transform,
+    /// The visual transform written to the entity, if it hasn't been restored since.
+    visual: Option<Transform>,
+    teleported: bool,
+}
+
+impl Default for TransformHistory {
+    fn default() -> Self {
+        Self {
+            previous: Transform::IDENTITY,
+            current: Transform::IDENTITY,
+            visual: None,
+            teleported: true,
+        }
+    }
+}
+
+impl TransformHistory {
+    /// Returns the [`Transform`] recorded at the end of the second to last fixed timestep.
+    #[inline]
+    pub fn previous(&self) -> Transform {
+        self.previous
+    }
+
+    /// Returns the [`Transform`] recorded at the end of the last fixed timestep.
+    #[inline]
+    pub fn current(&self) -> Transform {
+        self.current
+    }
+
+    /// Discards the recorded history, so the entity moves without smoothing to its current
+    /// [`Transform`].
+    ///
+    /// Call this after moving an entity to a distant location from within
+    /// [`FixedUpdate`](bevy_app::FixedUpdate). Writes to the [`Transform`] from other schedules
+    /// are detected automatically.
+    #[inline]
+    pub fn teleport(&mut self) {
+        self.teleported = true;
+    }
+
+    /// Returns the visual transform `overstep` fixed timesteps past the previous one.
+    fn blend(&self, overstep: f32) -> Transform {
+        Transform {
+            translation: self
+                .previous
+                .translation
+                .lerp(self.current.translation, overstep),
+            rotation: self
+                .previous
+                .rotation
+                .slerp(self.current.rotation, overstep),
+            scale: self.previous.scale.lerp(self.current.scale, overstep),
+        }
+    }
+}
+
+/// Restores the [`Transform`] recorded at the end of the last fixed timestep, unless the visual
+/// transform was overwritten since.
+pub fn restore_fixed_transforms(mut query: Query<(&mut Transform, &mut TransformHistory)>) {
+    for (mut transform, mut history) in &mut query {
+        if let Some(visual) = history.visual.take() {
+            if *transform == visual {
+                let current = history.current;
+                transform.set_if_neq(current);
+            } else {
+                history.teleport();
+            }
+        }
+        // Smooth the first fixed timestep after a teleport from its starting point.
+        if history.teleported {
+            history.previous = *transform;
+            history.current = *transform;
+            history.teleported = false;
+        }
+    }
+}
+
+/// Records the [`Transform`] at the end of each fixed timestep.
+pub fn record_fixed_transforms(mut query: Query<(&Transform, &mut TransformHistory)>) {
+    for (transform, mut history) in &mut query {
+        history.previous = if history.teleported {
+            *transform
+        } else {
+            history.current
+        };
+        history.current = *transform;
+        history.teleported = false;
+    }
+}
+
+/// Writes the visual [`Transform`] of entities with [`TransformInterpolation`].
+pub fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(
+        &TransformInterpolation,
+        &mut Transform,
+        &mut TransformHistory,
+    )>,
+) {
+    let overstep = fixed_time.overstep_fraction();
+    for (interpolation, mut transform, mut history) in &mut query {
+        // Anything other than the transform we last wrote or restored was written by a system
+        // outside of the fixed timestep schedules.
+        if *transform != history.visual.unwrap_or(history.current) {
+            history.teleport();
+        }
+        if history.teleported {
+            history.previous = *transform;
+            history.current = *transform;
+            history.visual = Some(*transform);
+            continue;
+        }
+
+        let visual = match interpolation {
+            TransformInterpolation::Interpolate => history.blend(overstep),
+            TransformInterpolation::Extrapolate => history.blend(1.0 + overstep),
+        };
+        transform.set_if_neq(visual);
+        history.visual = Some(visual);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use bevy_app::prelude::*;
+    use bevy_ecs::prelude::*;
+    use bevy_math::{vec3, Vec3};
+    use bevy_time::{Fixed, Time, TimePlugin, TimeUpdateStrategy};
+
+    use crate::{components::Transform, interpolation::*};
+
+    #[derive(Component)]
+    struct Velocity(Vec3);
+
+    fn app(interpolation: TransformInterpolation) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, TransformInterpolationPlugin))
+            .insert_resource(Time::<Fixed>::from_seconds(0.1))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                25,
+            )))
+            .add_systems(
+                FixedUpdate,
+                |mut query: Query<(&mut Transform, &Velocity)>| {
+                    for (mut transform, velocity) in &mut query {
+                        transform.translation += velocity.0;
+                    }
+                },
+            );
+        let entity = app
+            .world_mut()
+            .spawn((Transform::default(), Velocity(Vec3::X), interpolation))
+            .id();
+        (app, entity)
+    }
+
+    fn translation(app: &App, entity: Entity) -> Vec3 {
+        app.world().get::<Transform>(entity).unwrap().translation
+    }
+
+    #[test]
+    fn interpolates_between_fixed_timesteps() {
+        let (mut app, entity) = app(TransformInterpolation::Interpolate);
+
+        // Run until the first fixed timestep.
+        while app.world().resource::<Time<Fixed>>().elapsed() == Duration::ZERO {
+            app.update();
+        }
+        let history = *app.world().get::<TransformHistory>(entity).unwrap();
+        assert_eq!(history.previous().translation, Vec3::ZERO);
+        assert_eq!(history.current().translation, Vec3::X);
+
+        let overstep = app.world().resource::<Time<Fixed>>().overstep_fraction();
+        assert_eq!(translation(&app, entity), vec3(overstep, 0.0, 0.0));
+
+        app.update();
+        let overstep = app.world().resource::<Time<Fixed>>().overstep_fraction();
+        assert!(overstep > 0.0);
+        assert_eq!(translation(&app, entity), vec3(overstep, 0.0, 0.0));
+
+        // Fixed timesteps keep seeing the recorded transform.
+        while app.world().resource::<Time<Fixed>>().elapsed() < Duration::from_millis(200) {
+            app.update();
+        }
+        let history = *app.world().get::<TransformHistory>(entity).unwrap();
+        assert_eq!(history.current().translation, vec3(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn extrapolates_past_last_fixed_timestep() {
+        let (mut app, entity) = app(TransformInterpolation::Extrapolate);
+
+        while app.world().resource::<Time<Fixed>>().elapsed() == Duration::ZERO {
+            app.update();
+        }
+        app.update();
+        let overstep = app.world().resource::<Time<Fixed>>().overstep_fraction();
+        assert_eq!(translation(&app, entity), vec3(1.0 + overstep, 0.0, 0.0));
+    }
+
+    #[test]
+    fn external_writes_teleport() {
+        let (mut app, entity) = app(TransformInterpolation::Interpolate);
+
+        while app.world().resource::<Time<Fixed>>().elapsed() == Duration::ZERO {
+            app.update();
+        }
+        app.world_mut()
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation = vec3(100.0, 0.0, 0.0);
+
+        // The teleported transform is kept as is, rather than blended with the old position.
+        while app.world().resource::<Time<Fixed>>().elapsed() < Duration::from_millis(200) {
+            app.update();
+            assert!(translation(&app, entity).x >= 100.0);
+        }
+        let history = *app.world().get::<TransformHistory>(entity).unwrap();
+        assert_eq!(history.previous().translation, vec3(100.0, 0.0, 0.0));
+        assert_eq!(history.current().translation, vec3(101.0, 0.0, 0.0));
+        let overstep = app.world().resource::<Time<Fixed>>().overstep_fraction();
+        assert_eq!(translation(&app, entity), vec3(100.0 + overstep, 0.0, 0.0));
+    }
+}
@@ -24,6 +24,9 @@ pub mod traits;
 #[cfg(feature = "bevy-support")]
 pub mod plugins;
 
+#[cfg(feature = "bevy-support")]
+pub mod interpolation;
+
 /// [`GlobalTransform`]: components::GlobalTransform
 /// Helpers related to computing global transforms
 #[cfg(feature = "bevy-support")]
@@ -45,6 +48,7 @@ pub mod prelude {
     pub use crate::{
         commands::BuildChildrenTransformExt,
         helper::TransformHelper,
+        interpolation::{TransformInterpolation, TransformInterpolationPlugin},
         plugins::{TransformPlugin, TransformSystems},
         traits::TransformPoint,
     };
@@ -0,0 +1,210 @@
+use crate::{ui_transform::UiGlobalTransform, ComputedNode};
+use bevy_camera::visibility::InheritedVisibility;
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    entity::{Entities, Entity, EntityHashSet},
+    hierarchy::{ChildOf, Children},
+    lifecycle::RemovedComponents,
+    query::{Added, Changed, Or, With},
+    system::{Query, Res, ResMut},
+};
+use bevy_input_focus::{
+    directional_navigation::{
+        AutoDirectionalNavigation, AutoNavigationConfig, DirectionalNavigationMap, FocusableArea,
+    },
+    tab_navigation::TabGroup,
+};
+use bevy_math::Vec2;
+
+/// Regenerates the automatic edges of the [`DirectionalNavigationMap`] from the layout of UI nodes
+/// marked with [`AutoDirectionalNavigation`].
+///
+/// Nodes are only connected to other nodes that share the same nearest [`TabGroup`] ancestor.
+/// Hidden and zero-sized nodes are skipped.
+/// The edges are only rebuilt when one of the marked nodes was moved, resized, hidden, reparented
+/// or removed, when one of their ancestors was reparented, when a [`TabGroup`] was added or
+/// removed, or when the [`AutoNavigationConfig`] changed.
+///
+/// Marked nodes that were despawned are removed from the map entirely, along with their
+/// hand-authored edges and [blocked directions](DirectionalNavigationMap::block_direction).
+pub fn update_auto_directional_navigation(
+    mut map: ResMut<DirectionalNavigationMap>,
+    config: Res<AutoNavigationConfig>,
+    nodes: Query<
+        (
+            Entity,
+            &ComputedNode,
+            &UiGlobalTransform,
+            Option<&InheritedVisibility>,
+        ),
+        With<AutoDirectionalNavigation>,
+    >,
+    changed_nodes: Query<
+        (),
+        (
+            With<AutoDirectionalNavigation>,
+            Or<(
+                Changed<ComputedNode>,
+                Changed<UiGlobalTransform>,
+                Changed<InheritedVisibility>,
+                Changed<ChildOf>,
+                Added<AutoDirectionalNavigation>,
+            )>,
+        ),
+    >,
+    added_groups: Query<(), Added<TabGroup>>,
+    reparented: Query<Entity, Changed<ChildOf>>,
+    mut removed: RemovedComponents<AutoDirectionalNavigation>,
+    mut removed_groups: RemovedComponents<TabGroup>,
+    parents: Query<&ChildOf>,
+    children: Query<&Children>,
+    groups: Query<(), With<TabGroup>>,
+    entities: &Entities,
+) {
+    // Always drain the removal events, so that stale ones don't trigger a rebuild later on.
+    let removed = removed.read().collect::<EntityHashSet>();
+    let any_removed_groups = removed_groups.read().count() > 0;
+    if removed.is_empty()
+        && !any_removed_groups
+        && !config.is_changed()
+        && changed_nodes.is_empty()
+        && added_groups.is_empty()
+        // Reparenting an ancestor of a node can move it into another group.
+        && !reparented.iter().any(|entity| {
+            children
+                .iter_descendants(entity)
+                .any(|descendant| nodes.contains(descendant))
+        })
+    {
+        return;
+    }
+
+    let mut areas = nodes
+        .iter()
+        .filter(|(_, node, _, visibility)| {
+            !node.is_empty() && visibility.is_none_or(|visibility| visibility.get())
+        })
+        .map(|(entity, node, transform, _)| {
+            let group = parents
+                .iter_ancestors(entity)
+                .find(|ancestor| groups.contains(*ancestor));
+            // UI coordinates are y-down, while compass directions are y-up.
+            let center = transform.translation * node.inverse_scale_factor();
+            let area = FocusableArea {
+                entity,
+                position: Vec2::new(center.x, -center.y),
+                size: node.size() * node.inverse_scale_factor(),
+            };
+            (group, area)
+        })
+        .collect::<Vec<_>>();
+    areas.sort_by_key(|(group, _)| *group);
+
+    let despawned = removed
+        .into_iter()
+        .filter(|entity| !entities.contains(*entity))
+        .collect::<EntityHashSet>();
+    if !despawned.is_empty() {
+        map.remove_multiple(despawned);
+    }
+
+    map.clear_automatic_edges();
+    for group in areas.chunk_by(|(a, _), (b, _)| a == b) {
+        let group = group.iter().map(|(_, area)| *area).collect::<Vec<_>>();
+        map.add_automatic_edges(&group, &config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use bevy_math::{Affine2, CompassOctant};
+
+    fn spawn_node(world: &mut World, x: f32) -> Entity {
+        world
+            .spawn((
+                AutoDirectionalNavigation,
+                ComputedNode {
+                    size: Vec2::splat(50.),
+                    ..Default::default()
+                },
+                UiGlobalTransform::from(Affine2::from_translation(Vec2::new(x, 0.))),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn tab_group_changes_rebuild_edges() {
+        let mut world = World::new();
+        world.init_resource::<DirectionalNavigationMap>();
+        world.init_resource::<AutoNavigationConfig>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_auto_directional_navigation);
+
+        let container = world.spawn_empty().id();
+        let a = spawn_node(&mut world, 0.);
+        let b = spawn_node(&mut world, 100.);
+        world.entity_mut(a).insert(ChildOf(container));
+
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), Some(b));
+
+        // `a` now belongs to another group than `b`, even though no node moved.
+        world.entity_mut(container).insert(TabGroup::new(0));
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), None);
+
+        world.entity_mut(container).remove::<TabGroup>();
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), Some(b));
+
+        // Reparenting an ancestor of `a` moves it into the group of `b`.
+        let group = world.spawn(TabGroup::new(0)).id();
+        world.entity_mut(b).insert(ChildOf(group));
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), None);
+
+        world.entity_mut(container).insert(ChildOf(group));
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), Some(b));
+    }
+
+    #[test]
+    fn despawned_nodes_are_removed_from_the_map() {
+        let mut world = World::new();
+        world.init_resource::<DirectionalNavigationMap>();
+        world.init_resource::<AutoNavigationConfig>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_auto_directional_navigation);
+
+        let a = spawn_node(&mut world, 0.);
+        let b = spawn_node(&mut world, 100.);
+        let c = spawn_node(&mut world, 200.);
+        schedule.run(&mut world);
+
+        let mut map = world.resource_mut::<DirectionalNavigationMap>();
+        map.block_direction(b, CompassOctant::East);
+        map.add_edge(a, b, CompassOctant::North);
+        assert_eq!(map.get_neighbor(b, CompassOctant::East), None);
+
+        // A node that only lost its marker keeps its blocked directions.
+        world.entity_mut(c).remove::<AutoDirectionalNavigation>();
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert!(map.is_direction_blocked(b, CompassOctant::East));
+
+        world.despawn(b);
+        schedule.run(&mut world);
+        let map = world.resource::<DirectionalNavigationMap>();
+        assert!(!map.is_direction_blocked(b, CompassOctant::East));
+        assert_eq!(map.get_automatic_neighbors(b), None);
+        assert_eq!(map.get_neighbor(a, CompassOctant::North), None);
+        assert_eq!(map.get_neighbor(a, CompassOctant::East), None);
+    }
+}
@@ -25,6 +25,7 @@ use bevy_derive::{Deref, DerefMut};
 use bevy_picking::PickingSystems;
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 mod accessibility;
+mod auto_navigation;
 // This module is not re-exported, but is instead made public.
 // This is intended to discourage accidental use of the experimental API.
 pub mod experimental;
@@ -34,6 +35,7 @@ mod layout;
 mod stack;
 mod ui_node;
 
+pub use auto_navigation::update_auto_directional_navigation;
 pub use focus::*;
 pub use geometry::*;
 pub use gradients::*;
@@ -51,7 +53,9 @@ pub mod prelude {
     #[cfg(feature = "bevy_ui_picking_backend")]
     pub use crate::picking_backend::{UiPickingCamera, UiPickingPlugin, UiPickingSettings};
     #[doc(hidden)]
-    pub use crate::widget::{Text, TextShadow, TextUiReader, TextUiWriter};
+    pub use crate::widget::{
+        Text, TextInput, TextInputPlugin, TextInputValue, TextShadow, TextUiReader, TextUiWriter,
+    };
     #[doc(hidden)]
     pub use {
         crate::{
@@ -72,6 +76,7 @@ use bevy_app::{prelude::*, AnimationSystems, HierarchyPropagatePlugin, Propagate
 use bevy_camera::CameraUpdateSystems;
 use bevy_ecs::prelude::*;
 use bevy_input::InputSystems;
+use bevy_input_focus::directional_navigation::{AutoNavigationConfig, DirectionalNavigationMap};
 use bevy_transform::TransformSystems;
 use layout::ui_surface::UiSurface;
 use stack::ui_stack_system;
@@ -216,6 +221,12 @@ impl Plugin for UiPlugin {
                     .in_set(UiSystems::PostLayout)
                     .in_set(AmbiguousWithText)
                     .in_set(AmbiguousWithUpdateText2dLayout),
+                update_auto_directional_navigation
+                    .in_set(UiSystems::PostLayout)
+                    .run_if(
+                        resource_exists::<DirectionalNavigationMap>
+                            .and(resource_exists::<AutoNavigationConfig>),
+                    ),
             ),
         );
 
@@ -0,0 +1,1272 @@
+use core::ops::Range;
+
+use crate::{
+    widget::{text_system, Text},
+    ComputedNode, InteractionDisabled, UiGlobalTransform, UiSystems,
+};
+use accesskit::Role;
+use bevy_a11y::AccessibilityNode;
+use bevy_app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy_color::Color;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_input::{
+    keyboard::{KeyCode, KeyboardInput},
+    ButtonInput, ButtonState,
+};
+use bevy_input_focus::{tab_navigation::TabIndex, FocusedInput, InputFocus, InputFocusSystems};
+use bevy_math::{Rect, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_text::{ComputedTextBlock, CosmicFontSystem, LineBreak, TextLayout};
+use bevy_window::{Ime, PrimaryWindow, Window};
+use cosmic_text::{Action, Attrs, AttrsOwned, Buffer, Cursor, Edit, Editor, Motion, Shaping};
+
+/// Maximum number of undo steps kept by a [`TextInput`].
+const MAX_UNDO_STEPS: usize = 100;
+
+/// Displayed in place of text that would otherwise have no visible line to put the caret on,
+/// such as an empty value or a value ending in a line break.
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Adds editing, caret rendering and IME support for [`TextInput`] widgets.
+///
+/// Keyboard input reaches text inputs through [`FocusedInput`] events, so the
+/// [`InputDispatchPlugin`](bevy_input_focus::InputDispatchPlugin) must also be added.
+/// Add the [`TabNavigationPlugin`](bevy_input_focus::tab_navigation::TabNavigationPlugin)
+/// to move focus between inputs with <kbd>Tab</kbd> or by clicking on them.
+#[derive(Default)]
+pub struct TextInputPlugin;
+
+impl Plugin for TextInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TextInputClipboard>()
+            .add_observer(text_input_on_key_input)
+            .add_systems(
+                PreUpdate,
+                apply_text_input_edits.after(InputFocusSystems::Dispatch),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    update_text_input_cursor_layout
+                        .in_set(UiSystems::PostLayout)
+                        .after(text_system),
+                    update_text_input_ime.after(update_text_input_cursor_layout),
+                ),
+            );
+
+        #[cfg(feature = "bevy_picking")]
+        app.add_observer(text_input_on_pointer_press);
+    }
+}
+
+/// An editable text field.
+///
+/// A text input is a UI [`Text`] node whose contents are generated from its [`TextInputValue`].
+/// While it has [`InputFocus`] it reacts to keyboard and IME input, and draws a caret and the
+/// current selection using its [`TextInputStyle`].
+///
+/// The text is edited with the caret motions of the `cosmic-text` editor, so word jumps, line
+/// wrapping and vertical movement behave the same way the text is laid out. The usual shortcuts are
+/// supported: <kbd>Ctrl</kbd>/<kbd>Cmd</kbd> with <kbd>A</kbd> selects everything, <kbd>C</kbd>,
+/// <kbd>X</kbd> and <kbd>V</kbd> go through the [`TextInputClipboard`], and <kbd>Z</kbd> and
+/// <kbd>Y</kbd> undo and redo.
+///
+/// Only the root [`Text`] span is used; don't add [`TextSpan`](bevy_text::TextSpan) children.
+///
+/// ```
+/// # use bevy_ecs::world::World;
+/// # use bevy_ui::{Node, Val, widget::{TextInput, TextInputValue}};
+/// # let mut world = World::default();
+/// world.spawn((
+///     TextInput::default(),
+///     TextInputValue::new("Player 1"),
+///     Node {
+///         width: Val::Px(200.),
+///         ..Default::default()
+///     },
+/// ));
+///
+/// // A password field.
+/// world.spawn(TextInput {
+///     mask: Some('•'),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default, Debug, PartialEq, Clone)]
+#[require(
+    Text,
+    TextInputValue,
+    TextInputState,
+    TextInputStyle,
+    TextCursorLayout,
+    TabIndex,
+    AccessibilityNode(accesskit::Node::new(Role::TextInput))
+)]
+pub struct TextInput {
+    /// If true, <kbd>Enter</kbd> inserts a line break and the text wraps according to its
+    /// [`TextLayout`].
+    ///
+    /// Otherwise the text is kept on a single line and <kbd>Enter</kbd> triggers a
+    /// [`TextInputSubmit`] event.
+    pub multiline: bool,
+    /// If set, every character of the value is displayed as this character, and the value can't
+    /// be copied or cut to the clipboard.
+    pub mask: Option<char>,
+}
+
+/// The text being edited by a [`TextInput`].
+///
+/// Changes made by the user are written here, and the displayed text is updated when this
+/// component is changed by other systems.
+#[derive(Component, Debug, Default, Clone, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Component, Default, Debug, PartialEq, Clone)]
+pub struct TextInputValue(pub String);
+
+impl TextInputValue {
+    /// Makes a new text input value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+}
+
+/// The appearance of the caret and selection of a [`TextInput`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default, Debug, PartialEq, Clone)]
+pub struct TextInputStyle {
+    /// Color of the caret, also used to underline IME preedit text.
+    pub caret_color: Color,
+    /// Width of the caret in logical pixels.
+    pub caret_width: f32,
+    /// Color drawn behind selected text.
+    pub selection_color: Color,
+}
+
+impl Default for TextInputStyle {
+    fn default() -> Self {
+        Self {
+            caret_color: Color::WHITE,
+            caret_width: 2.,
+            selection_color: Color::srgba(0.3, 0.5, 1., 0.4),
+        }
+    }
+}
+
+/// Editing state of a [`TextInput`]: caret, selection, IME composition and undo history.
+///
+/// Positions are character (not byte) indices into the [`TextInputValue`].
+#[derive(Component, Debug, Default, Clone)]
+pub struct TextInputState {
+    cursor: usize,
+    anchor: Option<usize>,
+    preedit: Option<Preedit>,
+    pending: Vec<TextInputAction>,
+    undo: Vec<EditSnapshot>,
+    redo: Vec<EditSnapshot>,
+    last_edit: Option<EditKind>,
+}
+
+impl TextInputState {
+    /// Returns the character index of the caret.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the caret to the given character index and clears the selection.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+        self.anchor = None;
+        self.last_edit = None;
+    }
+
+    /// Returns the selected range of characters, if any.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.anchor
+            .filter(|&anchor| anchor != self.cursor)
+            .map(|anchor| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /// Selects the given range of characters, leaving the caret at its end.
+    pub fn select(&mut self, range: Range<usize>) {
+        self.anchor = Some(range.start);
+        self.cursor = range.end;
+        self.last_edit = None;
+    }
+
+    /// Returns the text currently being composed by an input method, if any.
+    ///
+    /// Preedit text is displayed at the caret but isn't part of the [`TextInputValue`] until the
+    /// input method commits it.
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_ref().map(|preedit| preedit.text.as_str())
+    }
+
+    /// Discards the undo and redo history.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+    }
+
+    /// Saves an undo step before an edit is applied to `value`.
+    ///
+    /// Consecutive insertions or deletions are merged into a single step so that undo doesn't
+    /// work one character at a time.
+    fn record_edit(&mut self, value: &str, kind: EditKind) {
+        if kind == EditKind::Other || self.last_edit != Some(kind) {
+            if self.undo.len() == MAX_UNDO_STEPS {
+                self.undo.remove(0);
+            }
+            self.undo.push(self.snapshot(value));
+        }
+        self.redo.clear();
+        self.last_edit = Some(kind);
+    }
+
+    fn snapshot(&self, value: &str) -> EditSnapshot {
+        EditSnapshot {
+            value: value.into(),
+            cursor: self.cursor,
+            anchor: self.anchor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: EditSnapshot, value: &mut String) {
+        *value = snapshot.value;
+        self.cursor = snapshot.cursor;
+        self.anchor = snapshot.anchor;
+        self.last_edit = None;
+    }
+
+    /// Replaces a range of characters in `value` and places the caret after the new text.
+    fn replace(&mut self, value: &mut String, range: Range<usize>, text: &str) {
+        let start = byte_index(value, range.start);
+        let end = byte_index(value, range.end);
+        value.replace_range(start..end, text);
+        self.cursor = range.start + text.chars().count();
+        self.anchor = None;
+    }
+}
+
+/// Text being composed by an input method.
+#[derive(Debug, Clone)]
+struct Preedit {
+    text: String,
+    /// Byte index of the caret within `text`, or `None` if the caret should be hidden.
+    cursor: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct EditSnapshot {
+    value: String,
+    cursor: usize,
+    anchor: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+/// Edits queued by input observers and applied by [`apply_text_input_edits`].
+#[derive(Debug, Clone)]
+enum TextInputAction {
+    Motion { motion: Motion, select: bool },
+    Click { position: Vec2, select: bool },
+    Insert(String),
+    Backspace,
+    Delete,
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    Redo,
+    Escape,
+    Submit,
+}
+
+/// Triggered on a single-line [`TextInput`] when <kbd>Enter</kbd> is pressed.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct TextInputSubmit {
+    /// The value of the input when it was submitted.
+    pub value: String,
+}
+
+/// Caret, selection and IME preedit geometry of a focused [`TextInput`], used for rendering.
+///
+/// Rects are in physical pixels, relative to the top-left corner of the node.
+/// Everything is empty while the input doesn't have focus.
+#[derive(Component, Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default, Debug, PartialEq, Clone)]
+pub struct TextCursorLayout {
+    /// The caret, if it should be drawn.
+    pub caret: Option<Rect>,
+    /// One rect per laid-out line of the selection.
+    pub selection: Vec<Rect>,
+    /// Underlines for the text being composed by an input method.
+    pub preedit: Vec<Rect>,
+}
+
+/// Access to a clipboard for copying and pasting text.
+///
+/// Bevy doesn't access the system clipboard itself. Implement this for your platform's clipboard
+/// and insert it as the [`TextInputClipboard`] resource to share text with other applications.
+pub trait ClipboardProvider: Send + Sync + 'static {
+    /// Returns the text on the clipboard, if there is any.
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Puts text on the clipboard.
+    fn set_text(&mut self, text: String);
+}
+
+/// A clipboard that only shares text between text inputs in this app.
+#[derive(Debug, Default, Clone)]
+pub struct LocalClipboard {
+    text: Option<String>,
+}
+
+impl ClipboardProvider for LocalClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
+
+/// The clipboard used by the copy, cut and paste shortcuts of [`TextInput`].
+///
+/// Defaults to a [`LocalClipboard`].
+#[derive(Resource, Deref, DerefMut)]
+pub struct TextInputClipboard(pub Box<dyn ClipboardProvider>);
+
+impl TextInputClipboard {
+    /// Creates a clipboard resource backed by the given provider.
+    pub fn new(provider: impl ClipboardProvider) -> Self {
+        Self(Box::new(provider))
+    }
+}
+
+impl Default for TextInputClipboard {
+    fn default() -> Self {
+        Self::new(LocalClipboard::default())
+    }
+}
+
+/// Translates key presses on a focused [`TextInput`] into editing actions.
+///
+/// <kbd>Tab</kbd> and unhandled keys keep bubbling so that tab navigation and other observers
+/// still see them.
+fn text_input_on_key_input(
+    mut trigger: On<FocusedInput<KeyboardInput>>,
+    mut inputs: Query<(&TextInput, &mut TextInputState), Without<InteractionDisabled>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Ok((input, mut state)) = inputs.get_mut(trigger.target()) else {
+        return;
+    };
+    let event = &trigger.event().input;
+    if event.state != ButtonState::Pressed || event.key_code == KeyCode::Tab {
+        return;
+    }
+
+    // While an input method is composing text, it owns the keyboard.
+    if state.preedit.is_some() {
+        trigger.propagate(false);
+        return;
+    }
+
+    // On Windows, AltGr is reported as ControlLeft and AltRight, and types characters like `@`.
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && !keys.pressed(KeyCode::AltRight);
+    let shortcut = control || keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]);
+    let select = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let motion = |motion| TextInputAction::Motion { motion, select };
+
+    let action = match event.key_code {
+        KeyCode::ArrowLeft if shortcut => motion(Motion::LeftWord),
+        KeyCode::ArrowLeft => motion(Motion::Left),
+        KeyCode::ArrowRight if shortcut => motion(Motion::RightWord),
+        KeyCode::ArrowRight => motion(Motion::Right),
+        KeyCode::ArrowUp if input.multiline => motion(Motion::Up),
+        KeyCode::ArrowDown if input.multiline => motion(Motion::Down),
+        KeyCode::PageUp if input.multiline => motion(Motion::PageUp),
+        KeyCode::PageDown if input.multiline => motion(Motion::PageDown),
+        KeyCode::Home if shortcut => motion(Motion::BufferStart),
+        KeyCode::Home => motion(Motion::Home),
+        KeyCode::End if shortcut => motion(Motion::BufferEnd),
+        KeyCode::End => motion(Motion::End),
+        KeyCode::Backspace => {
+            if shortcut && state.selection().is_none() {
+                state.pending.push(TextInputAction::Motion {
+                    motion: Motion::PreviousWord,
+                    select: true,
+                });
+            }
+            TextInputAction::Backspace
+        }
+        KeyCode::Delete => {
+            if shortcut && state.selection().is_none() {
+                state.pending.push(TextInputAction::Motion {
+                    motion: Motion::NextWord,
+                    select: true,
+                });
+            }
+            TextInputAction::Delete
+        }
+        KeyCode::Enter | KeyCode::NumpadEnter if input.multiline => {
+            TextInputAction::Insert("\n".into())
+        }
+        KeyCode::Enter | KeyCode::NumpadEnter => TextInputAction::Submit,
+        KeyCode::Escape if state.selection().is_some() => TextInputAction::Escape,
+        KeyCode::KeyA if shortcut => TextInputAction::SelectAll,
+        KeyCode::KeyC if shortcut => TextInputAction::Copy,
+        KeyCode::KeyX if shortcut => TextInputAction::Cut,
+        KeyCode::KeyV if shortcut => TextInputAction::Paste,
+        KeyCode::KeyZ if shortcut && select => TextInputAction::Redo,
+        KeyCode::KeyZ if shortcut => TextInputAction::Undo,
+        KeyCode::KeyY if shortcut => TextInputAction::Redo,
+        _ => match &event.text {
+            Some(text) if !shortcut && text.chars().any(|c| !c.is_control()) => {
+                TextInputAction::Insert(text.to_string())
+            }
+            _ => return,
+        },
+    };
+
+    state.pending.push(action);
+    trigger.propagate(false);
+}
+
+/// Places the caret where a [`TextInput`] is pressed, extending the selection if shift is held.
+#[cfg(feature = "bevy_picking")]
+fn text_input_on_pointer_press(
+    trigger: On<bevy_picking::events::Pointer<bevy_picking::events::Press>>,
+    mut inputs: Query<
+        (&mut TextInputState, &ComputedNode),
+        (With<TextInput>, Without<InteractionDisabled>),
+    >,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let press = &trigger.event().event;
+    if trigger.target() != trigger.original_target()
+        || press.button != bevy_picking::pointer::PointerButton::Primary
+    {
+        return;
+    }
+    let Ok((mut state, node)) = inputs.get_mut(trigger.target()) else {
+        return;
+    };
+    // The UI picking backend reports hit positions normalized to the node, with the origin at its center.
+    let Some(hit) = press.hit.position else {
+        return;
+    };
+    state.pending.push(TextInputAction::Click {
+        position: (hit.truncate() + 0.5) * node.size(),
+        select: keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+    });
+}
+
+/// Applies queued editing actions and IME events to text inputs, and keeps their displayed
+/// [`Text`] in sync with their [`TextInputValue`].
+pub fn apply_text_input_edits(
+    mut inputs: Query<(
+        Entity,
+        Ref<TextInput>,
+        &mut TextInputValue,
+        &mut TextInputState,
+        &ComputedTextBlock,
+        &mut Text,
+        &mut TextLayout,
+        &mut AccessibilityNode,
+        Has<InteractionDisabled>,
+    )>,
+    focus: Res<InputFocus>,
+    mut ime_events: EventReader<Ime>,
+    mut font_system: ResMut<CosmicFontSystem>,
+    mut clipboard: ResMut<TextInputClipboard>,
+    mut commands: Commands,
+) {
+    for event in ime_events.read() {
+        let Some(entity) = focus.get() else {
+            continue;
+        };
+        let Ok((.., mut state, _, _, _, _, false)) = inputs.get_mut(entity) else {
+            continue;
+        };
+        match event {
+            Ime::Preedit { value, cursor, .. } => {
+                state.preedit = (!value.is_empty()).then(|| Preedit {
+                    text: value.clone(),
+                    cursor: cursor.map(|(start, _)| start),
+                });
+            }
+            Ime::Commit { value, .. } => {
+                state.preedit = None;
+                state.pending.push(TextInputAction::Insert(value.clone()));
+            }
+            Ime::Disabled { .. } => state.preedit = None,
+            Ime::Enabled { .. } => {}
+        }
+    }
+
+    for (
+        entity,
+        input,
+        mut value,
+        mut state,
+        computed,
+        mut text,
+        mut layout,
+        mut accessibility,
+        disabled,
+    ) in &mut inputs
+    {
+        if input.is_changed() && !input.multiline && layout.linebreak != LineBreak::NoWrap {
+            layout.linebreak = LineBreak::NoWrap;
+        }
+
+        if !state.pending.is_empty() {
+            let actions = core::mem::take(&mut state.pending);
+            if !disabled {
+                let state = state.as_mut();
+                let mut editor = None;
+                for action in actions {
+                    apply_action(
+                        action,
+                        entity,
+                        &input,
+                        &mut value,
+                        state,
+                        &mut editor,
+                        computed.buffer(),
+                        &mut font_system,
+                        &mut clipboard,
+                        &mut commands,
+                    );
+                }
+            }
+        }
+
+        if !(input.is_changed() || value.is_changed() || state.is_changed()) {
+            continue;
+        }
+
+        // The value may have been replaced by another system.
+        let len = value.chars().count();
+        if state.cursor > len || state.anchor.is_some_and(|anchor| anchor > len) {
+            state.cursor = state.cursor.min(len);
+            state.anchor = state.anchor.map(|anchor| anchor.min(len));
+        }
+
+        let preedit = state
+            .preedit
+            .as_ref()
+            .map(|preedit| (state.cursor, preedit.text.as_str()));
+        text.set_if_neq(Text(display_text(&value, input.mask, preedit)));
+
+        if input.is_changed() || value.is_changed() {
+            accessibility.set_role(match *input {
+                TextInput { mask: Some(_), .. } => Role::PasswordInput,
+                TextInput {
+                    multiline: true, ..
+                } => Role::MultilineTextInput,
+                TextInput { .. } => Role::TextInput,
+            });
+            if input.mask.is_none() {
+                accessibility.set_value(value.as_str());
+            } else {
+                accessibility.clear_value();
+            }
+        }
+    }
+}
+
+fn apply_action(
+    action: TextInputAction,
+    entity: Entity,
+    input: &TextInput,
+    value: &mut Mut<TextInputValue>,
+    state: &mut TextInputState,
+    editor: &mut Option<Editor<'static>>,
+    buffer: &Buffer,
+    font_system: &mut CosmicFontSystem,
+    clipboard: &mut TextInputClipboard,
+    commands: &mut Commands,
+) {
+    match action {
+        TextInputAction::Motion { motion, select } => {
+            state.last_edit = None;
+            // Without shift, moving sideways collapses the selection to the corresponding end.
+            if !select && let Some(selection) = state.selection() {
+                match motion {
+                    Motion::Left | Motion::Previous => {
+                        state.set_cursor(selection.start);
+                        return;
+                    }
+                    Motion::Right | Motion::Next => {
+                        state.set_cursor(selection.end);
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+            let moved = motion_target(editor, buffer, value, input, font_system, state, motion);
+            move_cursor(state, moved, select);
+        }
+        TextInputAction::Click { position, select } => {
+            state.last_edit = None;
+            let editor = sync_editor(editor, buffer, value, input.mask, font_system);
+            if let Some(moved) = editor.with_buffer(|buffer| {
+                buffer
+                    .hit(position.x, position.y)
+                    .map(|cursor| cursor_to_char(buffer, cursor))
+            }) {
+                move_cursor(state, moved.min(value.chars().count()), select);
+            }
+        }
+        TextInputAction::Insert(text) => insert_text(input, value, state, &text, EditKind::Insert),
+        TextInputAction::Paste => {
+            if let Some(text) = clipboard.get_text() {
+                insert_text(input, value, state, &text, EditKind::Other);
+            }
+        }
+        // Deleting moves by grapheme clusters, so that combining sequences and emoji are
+        // deleted as a whole.
+        TextInputAction::Backspace => {
+            let range = match state.selection() {
+                None if state.cursor > 0 => {
+                    let motion = Motion::Previous;
+                    let start =
+                        motion_target(editor, buffer, value, input, font_system, state, motion);
+                    // A caret inside a cluster still deletes at least one character.
+                    Some(start.min(state.cursor - 1)..state.cursor)
+                }
+                selection => selection,
+            };
+            if let Some(range) = range {
+                state.record_edit(value, EditKind::Delete);
+                state.replace(value, range, "");
+            }
+        }
+        TextInputAction::Delete => {
+            let range = match state.selection() {
+                None if state.cursor < value.chars().count() => {
+                    let motion = Motion::Next;
+                    let end =
+                        motion_target(editor, buffer, value, input, font_system, state, motion);
+                    Some(state.cursor..end.max(state.cursor + 1))
+                }
+                selection => selection,
+            };
+            if let Some(range) = range {
+                state.record_edit(value, EditKind::Delete);
+                state.replace(value, range, "");
+            }
+        }
+        TextInputAction::SelectAll => state.select(0..value.chars().count()),
+        TextInputAction::Copy | TextInputAction::Cut => {
+            if input.mask.is_some() {
+                return;
+            }
+            let Some(selection) = state.selection() else {
+                return;
+            };
+            let start = byte_index(value, selection.start);
+            let end = byte_index(value, selection.end);
+            clipboard.set_text(value.as_str()[start..end].into());
+            if matches!(action, TextInputAction::Cut) {
+                state.record_edit(value, EditKind::Other);
+                state.replace(value, selection, "");
+            }
+        }
+        TextInputAction::Undo => {
+            if let Some(snapshot) = state.undo.pop() {
+                let current = state.snapshot(value);
+                state.redo.push(current);
+                state.restore(snapshot, value);
+            }
+        }
+        TextInputAction::Redo => {
+            if let Some(snapshot) = state.redo.pop() {
+                let current = state.snapshot(value);
+                state.undo.push(current);
+                state.restore(snapshot, value);
+            }
+        }
+        TextInputAction::Escape => state.anchor = None,
+        TextInputAction::Submit => {
+            commands.trigger_targets(
+                TextInputSubmit {
+                    value: value.0.clone(),
+                },
+                entity,
+            );
+        }
+    }
+}
+
+fn insert_text(
+    input: &TextInput,
+    value: &mut Mut<TextInputValue>,
+    state: &mut TextInputState,
+    text: &str,
+    kind: EditKind,
+) {
+    let text: String = text
+        .chars()
+        .filter(|&c| !c.is_control() || (input.multiline && c == '\n'))
+        .collect();
+    if text.is_empty() {
+        return;
+    }
+    let range = state.selection().unwrap_or(state.cursor..state.cursor);
+    state.record_edit(value, kind);
+    state.replace(value, range, &text);
+}
+
+/// Returns the character index that the caret reaches by applying `motion` to it.
+fn motion_target(
+    editor: &mut Option<Editor<'static>>,
+    buffer: &Buffer,
+    value: &str,
+    input: &TextInput,
+    font_system: &mut CosmicFontSystem,
+    state: &TextInputState,
+    motion: Motion,
+) -> usize {
+    let editor = sync_editor(editor, buffer, value, input.mask, font_system);
+    let cursor = editor.with_buffer(|buffer| char_to_cursor(buffer, state.cursor));
+    editor.set_cursor(cursor);
+    editor.action(&mut font_system.0, Action::Motion(motion));
+    let cursor = editor.cursor();
+    let moved = editor.with_buffer(|buffer| cursor_to_char(buffer, cursor));
+    moved.min(value.chars().count())
+}
+
+fn move_cursor(state: &mut TextInputState, cursor: usize, select: bool) {
+    if select {
+        state.anchor.get_or_insert(state.cursor);
+    } else {
+        state.anchor = None;
+    }
+    state.cursor = cursor;
+}
+
+/// Returns an editor whose buffer holds the displayed form of `value`.
+///
+/// The editor starts as a copy of the laid-out text block, and its text is replaced whenever
+/// an earlier action this frame has edited the value.
+fn sync_editor<'a>(
+    editor: &'a mut Option<Editor<'static>>,
+    buffer: &Buffer,
+    value: &str,
+    mask: Option<char>,
+    font_system: &mut CosmicFontSystem,
+) -> &'a mut Editor<'static> {
+    let editor = editor.get_or_insert_with(|| Editor::new(buffer.clone()));
+    let display = display_text(value, mask, None);
+    editor.with_buffer_mut(|buffer| {
+        let mut lines = display.split('\n');
+        let up_to_date = buffer
+            .lines
+            .iter()
+            .all(|line| lines.next() == Some(line.text()))
+            && lines.next().is_none();
+        if !up_to_date {
+            let attrs = buffer.lines.first().map_or_else(
+                || AttrsOwned::new(&Attrs::new()),
+                |line| AttrsOwned::new(&line.attrs_list().get_span(0)),
+            );
+            buffer.set_text(
+                &mut font_system.0,
+                &display,
+                &attrs.as_attrs(),
+                Shaping::Advanced,
+            );
+        }
+    });
+    editor
+}
+
+/// Builds the text displayed for a value, masking it and inserting IME preedit text at the
+/// given character index.
+fn display_text(value: &str, mask: Option<char>, preedit: Option<(usize, &str)>) -> String {
+    let mut display: String = match mask {
+        Some(mask) => value.chars().map(|_| mask).collect(),
+        None => value.into(),
+    };
+    if let Some((cursor, preedit)) = preedit {
+        display.insert_str(byte_index(&display, cursor), preedit);
+    }
+    if display.is_empty() || display.ends_with('\n') {
+        display.push(ZERO_WIDTH_SPACE);
+    }
+    display
+}
+
+fn byte_index(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn char_to_cursor(buffer: &Buffer, mut char_index: usize) -> Cursor {
+    for (line_index, line) in buffer.lines.iter().enumerate() {
+        let text = line.text();
+        let len = text.chars().count();
+        if char_index <= len {
+            return Cursor::new(line_index, byte_index(text, char_index));
+        }
+        char_index -= len + 1;
+    }
+    buffer.lines.last().map_or(Cursor::new(0, 0), |line| {
+        Cursor::new(buffer.lines.len() - 1, line.text().len())
+    })
+}
+
+fn cursor_to_char(buffer: &Buffer, cursor: Cursor) -> usize {
+    let preceding_lines: usize = buffer
+        .lines
+        .iter()
+        .take(cursor.line)
+        .map(|line| line.text().chars().count() + 1)
+        .sum();
+    let in_line = buffer
+        .lines
+        .get(cursor.line)
+        .and_then(|line| line.text().get(..cursor.index))
+        .map_or(0, |text| text.chars().count());
+    preceding_lines + in_line
+}
+
+/// Returns the top of the caret and its height, if the cursor is on a laid-out line.
+fn caret_position(buffer: &Buffer, cursor: Cursor) -> Option<(Vec2, f32)> {
+    buffer.layout_runs().find_map(|run| {
+        if run.line_i != cursor.line {
+            return None;
+        }
+        let x = match run
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.start <= cursor.index && cursor.index < glyph.end)
+        {
+            Some(glyph) => {
+                let offset = glyph.w * (cursor.index - glyph.start) as f32
+                    / (glyph.end - glyph.start) as f32;
+                if glyph.level.is_rtl() {
+                    glyph.x + glyph.w - offset
+                } else {
+                    glyph.x + offset
+                }
+            }
+            None => match run.glyphs.last() {
+                Some(glyph) if glyph.end == cursor.index => {
+                    if glyph.level.is_rtl() {
+                        glyph.x
+                    } else {
+                        glyph.x + glyph.w
+                    }
+                }
+                // The cursor is on another wrapped run of the same line.
+                Some(_) => return None,
+                None => 0.,
+            },
+        };
+        Some((Vec2::new(x, run.line_top), run.line_height))
+    })
+}
+
+/// Returns one rect per laid-out run covered by the range between two cursors.
+fn range_rects(buffer: &Buffer, start: Cursor, end: Cursor) -> Vec<Rect> {
+    buffer
+        .layout_runs()
+        .filter(|run| start.line <= run.line_i && run.line_i <= end.line)
+        .filter_map(|run| {
+            let run_start = if run.line_i == start.line {
+                start
+            } else {
+                Cursor::new(run.line_i, 0)
+            };
+            let run_end = if run.line_i == end.line {
+                end
+            } else {
+                Cursor::new(run.line_i, run.text.len())
+            };
+            let (x, width) = run.highlight(run_start, run_end)?;
+            Some(Rect::new(
+                x,
+                run.line_top,
+                x + width,
+                run.line_top + run.line_height,
+            ))
+        })
+        .collect()
+}
+
+/// Computes the [`TextCursorLayout`] of the focused text input from its laid-out text.
+pub fn update_text_input_cursor_layout(
+    mut inputs: Query<
+        (
+            Entity,
+            &TextInputState,
+            &TextInputStyle,
+            &ComputedTextBlock,
+            &ComputedNode,
+            &mut TextCursorLayout,
+        ),
+        With<TextInput>,
+    >,
+    focus: Res<InputFocus>,
+) {
+    for (entity, state, style, computed, node, mut cursor_layout) in &mut inputs {
+        if focus.get() != Some(entity) {
+            cursor_layout.set_if_neq(TextCursorLayout::default());
+            continue;
+        }
+
+        let buffer = computed.buffer();
+        let scale_factor = node.inverse_scale_factor().recip();
+        let mut new_layout = TextCursorLayout::default();
+
+        let mut caret = state.cursor;
+        if let Some(preedit) = &state.preedit {
+            let len = preedit.text.chars().count();
+            let start = char_to_cursor(buffer, state.cursor);
+            let end = char_to_cursor(buffer, state.cursor + len);
+            let thickness = scale_factor.max(1.);
+            new_layout.preedit = range_rects(buffer, start, end)
+                .into_iter()
+                .map(|rect| Rect::new(rect.min.x, rect.max.y - thickness, rect.max.x, rect.max.y))
+                .collect();
+            caret += match preedit.cursor {
+                Some(index) => preedit
+                    .text
+                    .get(..index)
+                    .map_or(len, |text| text.chars().count()),
+                None => len,
+            };
+        } else if let Some(selection) = state.selection() {
+            let start = char_to_cursor(buffer, selection.start);
+            let end = char_to_cursor(buffer, selection.end);
+            new_layout.selection = range_rects(buffer, start, end);
+        }
+
+        let show_caret = state
+            .preedit
+            .as_ref()
+            .is_none_or(|preedit| preedit.cursor.is_some());
+        if show_caret
+            && let Some((top, height)) = caret_position(buffer, char_to_cursor(buffer, caret))
+        {
+            let width = style.caret_width * scale_factor;
+            let left = (top.x - 0.5 * width).max(0.);
+            new_layout.caret = Some(Rect::new(left, top.y, left + width, top.y + height));
+        }
+
+        cursor_layout.set_if_neq(new_layout);
+    }
+}
+
+/// Enables IME on the primary window while a text input has focus, and positions the
+/// candidate box below the caret.
+pub fn update_text_input_ime(
+    inputs: Query<(&TextCursorLayout, &ComputedNode, &UiGlobalTransform), With<TextInput>>,
+    focus: Res<InputFocus>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut ime_enabled: Local<bool>,
+) {
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+
+    let Some((cursor_layout, node, transform)) =
+        focus.get().and_then(|entity| inputs.get(entity).ok())
+    else {
+        if *ime_enabled {
+            window.ime_enabled = false;
+            *ime_enabled = false;
+        }
+        return;
+    };
+
+    if !*ime_enabled {
+        window.ime_enabled = true;
+        *ime_enabled = true;
+    }
+
+    if let Some(caret) = cursor_layout.caret {
+        let bottom_left = Vec2::new(caret.min.x, caret.max.y) - 0.5 * node.size();
+        let position = transform.transform_point2(bottom_left) / window.scale_factor();
+        if window.ime_position != position {
+            window.ime_position = position;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_input::{
+        keyboard::{Key, NativeKey},
+        InputPlugin,
+    };
+    use bevy_input_focus::InputDispatchPlugin;
+    use cosmic_text::{AttrsList, BufferLine, LineEnding, Metrics};
+
+    use super::*;
+
+    /// Returns an app with a focused [`TextInput`] holding `value`, and the input entity.
+    fn app(value: &str) -> (App, Entity) {
+        // Caret motions need the text to be shaped with some font.
+        let mut font_system = CosmicFontSystem::default();
+        font_system
+            .db_mut()
+            .load_font_data(bevy_text::DEFAULT_FONT_DATA.to_vec());
+
+        let mut app = App::new();
+        app.add_plugins((InputPlugin, InputDispatchPlugin, TextInputPlugin))
+            .insert_resource(font_system)
+            .add_event::<Ime>();
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let input = app
+            .world_mut()
+            .spawn((TextInput::default(), TextInputValue::new(value)))
+            .id();
+        app.update();
+        *app.world_mut().resource_mut::<InputFocus>() = InputFocus::from_entity(input);
+        (app, input)
+    }
+
+    /// Presses and releases a key, typing `text` if it has some.
+    fn type_key(app: &mut App, key_code: KeyCode, text: Option<&str>) {
+        press(app, key_code, text);
+        release(app, key_code);
+    }
+
+    fn press(app: &mut App, key_code: KeyCode, text: Option<&str>) {
+        send_key(app, key_code, text, ButtonState::Pressed);
+    }
+
+    fn release(app: &mut App, key_code: KeyCode) {
+        send_key(app, key_code, None, ButtonState::Released);
+    }
+
+    fn send_key(app: &mut App, key_code: KeyCode, text: Option<&str>, state: ButtonState) {
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world())
+            .unwrap();
+        app.world_mut().write_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            text: text.map(Into::into),
+            repeat: false,
+            window,
+        });
+        app.update();
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            type_key(app, KeyCode::KeyA, Some(c.encode_utf8(&mut [0; 4])));
+        }
+    }
+
+    fn value(app: &App, input: Entity) -> &str {
+        app.world().get::<TextInputValue>(input).unwrap()
+    }
+
+    fn state(app: &App, input: Entity) -> &TextInputState {
+        app.world().get::<TextInputState>(input).unwrap()
+    }
+
+    fn buffer(lines: &[&str]) -> Buffer {
+        let mut buffer = Buffer::new_empty(Metrics::new(10., 12.));
+        let attrs = AttrsList::new(&Attrs::new());
+        buffer.lines = lines
+            .iter()
+            .map(|&line| BufferLine::new(line, LineEnding::Lf, attrs.clone(), Shaping::Advanced))
+            .collect();
+        buffer
+    }
+
+    #[test]
+    fn display_text_masks_and_inserts_preedit() {
+        assert_eq!(display_text("héllo", None, None), "héllo");
+        assert_eq!(display_text("héllo", Some('*'), None), "*****");
+        assert_eq!(display_text("hé", None, Some((1, "ab"))), "habé");
+        assert_eq!(display_text("hé", Some('*'), Some((2, "ab"))), "**ab");
+
+        // Empty values and trailing line breaks keep a line for the caret.
+        assert_eq!(display_text("", None, None), ZERO_WIDTH_SPACE.to_string());
+        assert_eq!(
+            display_text("a\n", None, None),
+            format!("a\n{ZERO_WIDTH_SPACE}")
+        );
+    }
+
+    #[test]
+    fn byte_index_counts_characters() {
+        let text = "aé€b";
+        assert_eq!(byte_index(text, 0), 0);
+        assert_eq!(byte_index(text, 1), 1);
+        assert_eq!(byte_index(text, 2), 3);
+        assert_eq!(byte_index(text, 3), 6);
+        assert_eq!(byte_index(text, 4), text.len());
+        assert_eq!(byte_index(text, 10), text.len());
+    }
+
+    #[test]
+    fn char_to_cursor_crosses_lines() {
+        let buffer = buffer(&["ab", "çd"]);
+        assert_eq!(char_to_cursor(&buffer, 0), Cursor::new(0, 0));
+        assert_eq!(char_to_cursor(&buffer, 2), Cursor::new(0, 2));
+        // The line break counts as one character.
+        assert_eq!(char_to_cursor(&buffer, 3), Cursor::new(1, 0));
+        assert_eq!(char_to_cursor(&buffer, 4), Cursor::new(1, 2));
+        assert_eq!(char_to_cursor(&buffer, 5), Cursor::new(1, 3));
+        // Indices past the end are clamped to it.
+        assert_eq!(char_to_cursor(&buffer, 9), Cursor::new(1, 3));
+
+        for index in 0..=5 {
+            assert_eq!(
+                cursor_to_char(&buffer, char_to_cursor(&buffer, index)),
+                index
+            );
+        }
+    }
+
+    #[test]
+    fn consecutive_edits_of_a_kind_are_merged() {
+        let mut state = TextInputState::default();
+        let mut value = String::new();
+        let mut edit = |state: &mut TextInputState, kind: EditKind, text: &str| {
+            state.record_edit(&value, kind);
+            let cursor = state.cursor;
+            match text {
+                "" => state.replace(&mut value, cursor - 1..cursor, ""),
+                text => state.replace(&mut value, cursor..cursor, text),
+            }
+        };
+
+        edit(&mut state, EditKind::Insert, "a");
+        edit(&mut state, EditKind::Insert, "b");
+        edit(&mut state, EditKind::Insert, "c");
+        assert_eq!(state.undo.len(), 1);
+        edit(&mut state, EditKind::Delete, "");
+        edit(&mut state, EditKind::Delete, "");
+        assert_eq!(state.undo.len(), 2);
+        // Other edits, such as pastes, are never merged.
+        edit(&mut state, EditKind::Other, "de");
+        edit(&mut state, EditKind::Other, "f");
+        assert_eq!(state.undo.len(), 4);
+        // Moving the caret starts a new step.
+        state.set_cursor(0);
+        edit(&mut state, EditKind::Insert, "g");
+        edit(&mut state, EditKind::Insert, "h");
+        assert_eq!(state.undo.len(), 5);
+        assert_eq!(value, "ghadef");
+
+        let undone: Vec<_> = state.undo.iter().map(|step| step.value.as_str()).collect();
+        assert_eq!(undone, ["", "abc", "a", "ade", "adef"]);
+    }
+
+    #[test]
+    fn typing_edits_multi_byte_text() {
+        let (mut app, input) = app("");
+
+        type_text(&mut app, "héllo");
+        assert_eq!(value(&app, input), "héllo");
+        assert_eq!(state(&app, input).cursor(), 5);
+
+        type_key(&mut app, KeyCode::ArrowLeft, None);
+        type_key(&mut app, KeyCode::ArrowLeft, None);
+        type_text(&mut app, "日本");
+        assert_eq!(value(&app, input), "hél日本lo");
+        assert_eq!(state(&app, input).cursor(), 5);
+
+        type_key(&mut app, KeyCode::Backspace, None);
+        assert_eq!(value(&app, input), "hél日lo");
+        type_key(&mut app, KeyCode::Delete, None);
+        assert_eq!(value(&app, input), "hél日o");
+        assert_eq!(state(&app, input).cursor(), 4);
+        assert_eq!(
+            app.world().get::<Text>(input).unwrap().0,
+            value(&app, input)
+        );
+    }
+
+    #[test]
+    fn typing_replaces_the_selection() {
+        let (mut app, input) = app("añb");
+        type_key(&mut app, KeyCode::End, None);
+        assert_eq!(state(&app, input).cursor(), 3);
+
+        press(&mut app, KeyCode::ShiftLeft, None);
+        type_key(&mut app, KeyCode::ArrowLeft, None);
+        type_key(&mut app, KeyCode::ArrowLeft, None);
+        release(&mut app, KeyCode::ShiftLeft);
+        assert_eq!(state(&app, input).cursor(), 1);
+        assert_eq!(state(&app, input).selection(), Some(1..3));
+
+        type_text(&mut app, "€");
+        assert_eq!(value(&app, input), "a€");
+        assert_eq!(state(&app, input).selection(), None);
+
+        press(&mut app, KeyCode::ControlLeft, None);
+        type_key(&mut app, KeyCode::KeyA, None);
+        release(&mut app, KeyCode::ControlLeft);
+        assert_eq!(state(&app, input).selection(), Some(0..2));
+        type_key(&mut app, KeyCode::Backspace, None);
+        assert_eq!(value(&app, input), "");
+    }
+
+    #[test]
+    fn altgr_characters_are_typed() {
+        let (mut app, input) = app("");
+
+        press(&mut app, KeyCode::ControlLeft, None);
+        press(&mut app, KeyCode::AltRight, None);
+        type_key(&mut app, KeyCode::KeyQ, Some("@"));
+        release(&mut app, KeyCode::AltRight);
+        release(&mut app, KeyCode::ControlLeft);
+        assert_eq!(value(&app, input), "@");
+        assert_eq!(state(&app, input).cursor(), 1);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_values() {
+        let (mut app, input) = app("");
+
+        type_text(&mut app, "ab");
+        type_key(&mut app, KeyCode::Backspace, None);
+        assert_eq!(value(&app, input), "a");
+
+        press(&mut app, KeyCode::ControlLeft, None);
+        type_key(&mut app, KeyCode::KeyZ, None);
+        assert_eq!(value(&app, input), "ab");
+        type_key(&mut app, KeyCode::KeyZ, None);
+        assert_eq!(value(&app, input), "");
+        assert_eq!(state(&app, input).cursor(), 0);
+        type_key(&mut app, KeyCode::KeyY, None);
+        assert_eq!(value(&app, input), "ab");
+        assert_eq!(state(&app, input).cursor(), 2);
+        release(&mut app, KeyCode::ControlLeft);
+    }
+
+    #[test]
+    fn undo_history_is_bounded() {
+        let mut state = TextInputState::default();
+        for _ in 0..MAX_UNDO_STEPS + 10 {
+            state.record_edit("", EditKind::Other);
+        }
+        assert_eq!(state.undo.len(), MAX_UNDO_STEPS);
+    }
+}